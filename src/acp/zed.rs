@@ -22,8 +22,8 @@ use vtcode_core::config::types::{AgentConfig as CoreAgentConfig, CapabilityLevel
 use vtcode_core::config::{AgentClientProtocolZedConfig, ToolsConfig, VTCodeConfig};
 use vtcode_core::llm::factory::{create_provider_for_model, create_provider_with_config};
 use vtcode_core::llm::provider::{
-    FinishReason, LLMRequest, LLMStreamEvent, Message, ToolCall as ProviderToolCall, ToolChoice,
-    ToolDefinition,
+    Attachment, FinishReason, LLMRequest, LLMStreamEvent, Message, ToolCall as ProviderToolCall,
+    ToolChoice, ToolDefinition,
 };
 use vtcode_core::prompts::read_system_prompt_from_md;
 use vtcode_core::tools::file_ops::FileOpsTool;
@@ -1698,8 +1698,9 @@ impl ZedAgent {
         &self,
         session_id: &acp::SessionId,
         prompt: &[acp::ContentBlock],
-    ) -> Result<String, acp::Error> {
+    ) -> Result<(String, Vec<Attachment>), acp::Error> {
         let mut aggregated = String::new();
+        let mut attachments = Vec::new();
 
         for block in prompt {
             match block {
@@ -1715,6 +1716,15 @@ impl ZedAgent {
                         Self::append_segment(&mut aggregated, &rendered);
                     }
                     acp::EmbeddedResourceResource::BlobResourceContents(blob) => {
+                        if let Some(mut attachment) = blob
+                            .mime_type
+                            .as_deref()
+                            .and_then(|mime| Attachment::from_media_type(mime, blob.blob.clone()))
+                        {
+                            attachment.name = Some(blob.uri.clone());
+                            attachments.push(attachment);
+                            continue;
+                        }
                         warn!(
                             uri = blob.uri,
                             "Ignoring unsupported embedded blob resource"
@@ -1727,6 +1737,14 @@ impl ZedAgent {
                         Self::append_segment(&mut aggregated, &rendered);
                     }
                 },
+                acp::ContentBlock::Image(image)
+                    if !image.data.is_empty()
+                        && Attachment::kind_for_media_type(&image.mime_type).is_some() =>
+                {
+                    let mut attachment = Attachment::image(&image.mime_type, image.data.clone());
+                    attachment.name = image.uri.clone();
+                    attachments.push(attachment);
+                }
                 acp::ContentBlock::Image(image) => {
                     let identifier = image.uri.as_deref().unwrap_or(image.mime_type.as_str());
                     let placeholder = format!(
@@ -1745,7 +1763,7 @@ impl ZedAgent {
             }
         }
 
-        Ok(aggregated)
+        Ok((aggregated, attachments))
     }
 
     async fn render_resource_link(
@@ -1850,6 +1868,7 @@ impl acp::Agent for ZedAgent {
 
         let mut capabilities = acp::AgentCapabilities::default();
         capabilities.prompt_capabilities.embedded_context = true;
+        capabilities.prompt_capabilities.image = true;
 
         Ok(acp::InitializeResponse {
             protocol_version: acp::V1,
@@ -1887,8 +1906,12 @@ impl acp::Agent for ZedAgent {
 
         session.cancel_flag.set(false);
 
-        let user_message = self.resolve_prompt(&args.session_id, &args.prompt).await?;
-        self.push_message(&session, Message::user(user_message.clone()));
+        let (user_message, attachments) =
+            self.resolve_prompt(&args.session_id, &args.prompt).await?;
+        self.push_message(
+            &session,
+            Message::user_with_attachments(user_message.clone(), attachments),
+        );
 
         let provider = match create_provider_for_model(
             &self.config.model,
@@ -1965,6 +1988,7 @@ impl acp::Agent for ZedAgent {
                 parallel_tool_calls: None,
                 parallel_tool_config: None,
                 reasoning_effort,
+                response_format: None,
             };
 
             let mut stream = provider
@@ -2060,6 +2084,7 @@ impl acp::Agent for ZedAgent {
                     parallel_tool_calls: None,
                     parallel_tool_config: None,
                     reasoning_effort,
+                    response_format: None,
                 };
 
                 let response = provider
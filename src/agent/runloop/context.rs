@@ -1,7 +1,11 @@
 use vtcode_core::config::constants::context as context_defaults;
 use vtcode_core::config::loader::VTCodeConfig;
+use vtcode_core::core::tokenizer::Tokenizer;
 use vtcode_core::llm::provider as uni;
 
+/// Providers bill images and document pages at roughly this many tokens each
+const ATTACHMENT_TOKEN_ESTIMATE: usize = 1_600;
+
 #[derive(Clone, Copy)]
 pub(crate) struct ContextTrimConfig {
     pub(crate) max_tokens: usize,
@@ -85,6 +89,7 @@ pub(crate) fn apply_aggressive_trim_unified(
 pub(crate) fn enforce_unified_context_window(
     history: &mut Vec<uni::Message>,
     config: ContextTrimConfig,
+    tokenizer: &dyn Tokenizer,
 ) -> ContextTrimOutcome {
     if history.is_empty() {
         return ContextTrimOutcome::default();
@@ -92,7 +97,7 @@ pub(crate) fn enforce_unified_context_window(
 
     let tokens_per_message: Vec<usize> = history
         .iter()
-        .map(approximate_unified_message_tokens)
+        .map(|message| unified_message_tokens(message, tokenizer))
         .collect();
     let mut total_tokens: usize = tokens_per_message.iter().sum();
 
@@ -163,29 +168,32 @@ pub(crate) fn load_context_trim_config(vt_cfg: Option<&VTCodeConfig>) -> Context
     }
 }
 
-fn approximate_unified_message_tokens(message: &uni::Message) -> usize {
-    let mut total_chars = message.content.len();
-    total_chars += message.role.as_generic_str().len();
+/// Count the tokens a message contributes to the prompt using the active
+/// provider's tokenizer.
+pub(crate) fn unified_message_tokens(message: &uni::Message, tokenizer: &dyn Tokenizer) -> usize {
+    let mut total = tokenizer.count(&message.content);
+    total += tokenizer.count(message.role.as_generic_str());
 
     if let Some(tool_calls) = &message.tool_calls {
         for call in tool_calls {
-            total_chars += call.id.len();
-            total_chars += call.call_type.len();
-            total_chars += call.function.name.len();
-            total_chars += call.function.arguments.len();
+            total += tokenizer.count(&call.id);
+            total += tokenizer.count(&call.call_type);
+            total += tokenizer.count(&call.function.name);
+            total += tokenizer.count(&call.function.arguments);
         }
     }
 
     if let Some(tool_call_id) = &message.tool_call_id {
-        total_chars += tool_call_id.len();
+        total += tokenizer.count(tool_call_id);
     }
 
-    total_chars.div_ceil(context_defaults::CHAR_PER_TOKEN_APPROX)
+    total + message.attachments.len() * ATTACHMENT_TOKEN_ESTIMATE
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use vtcode_core::core::tokenizer::EstimatingTokenizer;
 
     #[test]
     fn test_enforce_unified_context_window_trims_and_preserves_latest() {
@@ -199,12 +207,17 @@ mod tests {
             preserve_recent_turns: 3,
         };
 
-        let outcome = enforce_unified_context_window(&mut history, config);
+        let tokenizer = EstimatingTokenizer::new("test", 4.0);
+
+        let outcome = enforce_unified_context_window(&mut history, config, &tokenizer);
 
         assert!(outcome.is_trimmed());
         assert_eq!(original_len - history.len(), outcome.removed_messages);
 
-        let remaining_tokens: usize = history.iter().map(approximate_unified_message_tokens).sum();
+        let remaining_tokens: usize = history
+            .iter()
+            .map(|message| unified_message_tokens(message, &tokenizer))
+            .sum();
         assert!(remaining_tokens <= config.max_tokens);
 
         let last_content = history
@@ -19,6 +19,7 @@ pub async fn run_single_agent_loop(
     config: &CoreAgentConfig,
     skip_confirmations: bool,
     full_auto: bool,
+    plan_mode: bool,
 ) -> Result<()> {
     let mut vt_cfg = ConfigManager::load_from_workspace(&config.workspace)
         .ok()
@@ -26,7 +27,8 @@ pub async fn run_single_agent_loop(
 
     apply_runtime_overrides(vt_cfg.as_mut(), config);
 
-    unified::run_single_agent_loop_unified(config, vt_cfg, skip_confirmations, full_auto).await
+    unified::run_single_agent_loop_unified(config, vt_cfg, skip_confirmations, full_auto, plan_mode)
+        .await
 }
 
 pub(crate) fn is_context_overflow_error(message: &str) -> bool {
@@ -216,7 +216,10 @@ impl ModelPickerState {
                 }
                 InlineListSelection::Theme(_)
                 | InlineListSelection::Session(_)
-                | InlineListSelection::SlashCommand(_) => Ok(ModelPickerProgress::InProgress),
+                | InlineListSelection::SlashCommand(_)
+                | InlineListSelection::McpChoice(_)
+                | InlineListSelection::PlanChoice(_)
+                | InlineListSelection::HunkChoice(_) => Ok(ModelPickerProgress::InProgress),
             },
             PickerStep::AwaitReasoning => match choice {
                 InlineListSelection::Reasoning(level) => {
@@ -232,7 +235,10 @@ impl ModelPickerState {
                 }
                 InlineListSelection::Theme(_)
                 | InlineListSelection::Session(_)
-                | InlineListSelection::SlashCommand(_) => Ok(ModelPickerProgress::InProgress),
+                | InlineListSelection::SlashCommand(_)
+                | InlineListSelection::McpChoice(_)
+                | InlineListSelection::PlanChoice(_)
+                | InlineListSelection::HunkChoice(_) => Ok(ModelPickerProgress::InProgress),
             },
             PickerStep::AwaitApiKey => {
                 renderer.line(
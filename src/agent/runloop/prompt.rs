@@ -2,6 +2,7 @@ use std::collections::HashSet;
 
 use vtcode_core::config::loader::VTCodeConfig;
 use vtcode_core::config::types::AgentConfig as CoreAgentConfig;
+use vtcode_core::core::cost_tracker::SharedCostTracker;
 use vtcode_core::llm::{factory::create_provider_with_config, provider as uni};
 
 const MIN_PROMPT_LENGTH_FOR_REFINEMENT: usize = 20;
@@ -15,6 +16,7 @@ pub(crate) async fn refine_user_prompt_if_enabled(
     raw: &str,
     cfg: &CoreAgentConfig,
     vt_cfg: Option<&VTCodeConfig>,
+    cost_tracker: &SharedCostTracker,
 ) -> String {
     if std::env::var("VTCODE_PROMPT_REFINER_STUB").is_ok() {
         return format!("[REFINED] {}", raw);
@@ -26,7 +28,7 @@ pub(crate) async fn refine_user_prompt_if_enabled(
         return raw.to_string();
     }
 
-    if !should_attempt_refinement(raw) {
+    if !should_attempt_refinement(raw) || cost_tracker.lock().budget_exceeded().is_some() {
         return raw.to_string();
     }
 
@@ -65,7 +67,7 @@ pub(crate) async fn refine_user_prompt_if_enabled(
         messages: vec![uni::Message::user(raw.to_string())],
         system_prompt: None,
         tools: None,
-        model: refiner_model,
+        model: refiner_model.clone(),
         max_tokens: Some(800),
         temperature: Some(0.3),
         stream: false,
@@ -73,13 +75,17 @@ pub(crate) async fn refine_user_prompt_if_enabled(
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort,
+        response_format: None,
     };
 
-    match refiner
-        .generate(req)
-        .await
-        .map(|response| response.content.unwrap_or_default())
-    {
+    match refiner.generate(req).await.map(|response| {
+        if let Some(usage) = response.usage.as_ref() {
+            cost_tracker
+                .lock()
+                .record(&provider_name, &refiner_model, usage);
+        }
+        response.content.unwrap_or_default()
+    }) {
         Ok(text) if should_accept_refinement(raw, &text) => text,
         _ => raw.to_string(),
     }
@@ -162,6 +168,7 @@ mod tests {
     use vtcode_core::config::types::{
         ModelSelectionSource, ReasoningEffortLevel, UiSurfacePreference,
     };
+    use vtcode_core::core::cost_tracker::CostTracker;
 
     #[tokio::test]
     async fn test_prompt_refinement_applies_to_gemini_when_flag_disabled() {
@@ -189,7 +196,8 @@ mod tests {
         vt.agent.refine_prompts_enabled = true;
 
         let raw = "make me a list of files";
-        let out = refine_user_prompt_if_enabled(raw, &cfg, Some(&vt)).await;
+        let cost_tracker = CostTracker::new(&vt.cost, None).into_shared();
+        let out = refine_user_prompt_if_enabled(raw, &cfg, Some(&vt), &cost_tracker).await;
 
         assert!(out.starts_with("[REFINED] "));
 
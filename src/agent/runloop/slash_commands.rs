@@ -1,11 +1,14 @@
 use anyhow::Result;
 use chrono::Local;
 use serde_json::{Map, Value};
+use std::path::PathBuf;
 use std::time::Duration;
+use vtcode_core::project_memory::MemoryScope;
 use vtcode_core::ui::slash::SLASH_COMMANDS;
 use vtcode_core::ui::theme;
 use vtcode_core::utils::ansi::{AnsiRenderer, MessageStyle};
 use vtcode_core::utils::session_archive;
+use vtcode_core::utils::session_export::ExportFormat;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ThemePaletteMode {
@@ -13,6 +16,23 @@ pub enum ThemePaletteMode {
     Inspect,
 }
 
+pub enum MemoryCommand {
+    List,
+    Add {
+        scope: MemoryScope,
+        text: String,
+    },
+    Edit {
+        scope: MemoryScope,
+        index: usize,
+        text: String,
+    },
+    Remove {
+        scope: MemoryScope,
+        index: usize,
+    },
+}
+
 pub enum SlashCommandOutcome {
     Handled,
     ThemeChanged(String),
@@ -25,6 +45,24 @@ pub enum SlashCommandOutcome {
         force: bool,
     },
     ShowConfig,
+    ShowStatus,
+    Compact {
+        focus: Option<String>,
+    },
+    SetSessionBudget {
+        limit_usd: Option<f64>,
+    },
+    SetPlanMode {
+        enabled: Option<bool>,
+    },
+    ManageMemory(MemoryCommand),
+    ExportTranscript {
+        format: ExportFormat,
+        path: Option<PathBuf>,
+    },
+    ManageBranches {
+        switch_to: Option<usize>,
+    },
     Exit,
     StartModelSelection,
     StartThemePalette {
@@ -109,10 +147,13 @@ pub fn handle_slash_command(
                 } else {
                     " "
                 };
-                let label = theme::theme_label(id).unwrap_or(id);
+                let label = theme::theme_label(&id).unwrap_or_else(|| id.clone());
+                let source = theme::custom_theme_path(&id)
+                    .map(|path| format!(" - {}", path.display()))
+                    .unwrap_or_default();
                 renderer.line(
                     MessageStyle::Info,
-                    &format!("{} {} ({})", marker, id, label),
+                    &format!("{} {} ({}){}", marker, id, label, source),
                 )?;
             }
             Ok(SlashCommandOutcome::Handled)
@@ -151,7 +192,82 @@ pub fn handle_slash_command(
             Ok(SlashCommandOutcome::InitializeWorkspace { force })
         }
         "config" => Ok(SlashCommandOutcome::ShowConfig),
+        "status" => Ok(SlashCommandOutcome::ShowStatus),
+        "compact" => {
+            let focus = parts.collect::<Vec<_>>().join(" ");
+            Ok(SlashCommandOutcome::Compact {
+                focus: (!focus.is_empty()).then_some(focus),
+            })
+        }
+        "budget" => {
+            let Some(value) = parts.next() else {
+                return Ok(SlashCommandOutcome::ShowStatus);
+            };
+            if matches!(value.to_ascii_lowercase().as_str(), "off" | "none") {
+                return Ok(SlashCommandOutcome::SetSessionBudget { limit_usd: None });
+            }
+            match value.trim_start_matches('$').parse::<f64>() {
+                Ok(limit) if limit.is_finite() && limit > 0.0 => {
+                    Ok(SlashCommandOutcome::SetSessionBudget {
+                        limit_usd: Some(limit),
+                    })
+                }
+                _ => {
+                    renderer.line(
+                        MessageStyle::Error,
+                        "Usage: /budget [usd|off] (for example: /budget 5)",
+                    )?;
+                    Ok(SlashCommandOutcome::Handled)
+                }
+            }
+        }
+        "plan" => match parts.next().map(|value| value.to_ascii_lowercase()) {
+            None => Ok(SlashCommandOutcome::SetPlanMode { enabled: None }),
+            Some(value) if value == "on" => Ok(SlashCommandOutcome::SetPlanMode {
+                enabled: Some(true),
+            }),
+            Some(value) if value == "off" => Ok(SlashCommandOutcome::SetPlanMode {
+                enabled: Some(false),
+            }),
+            Some(_) => {
+                renderer.line(MessageStyle::Error, "Usage: /plan [on|off]")?;
+                Ok(SlashCommandOutcome::Handled)
+            }
+        },
+        "memory" => {
+            let remaining: Vec<&str> = parts.collect();
+            match parse_memory_command(&remaining) {
+                Some(command) => Ok(SlashCommandOutcome::ManageMemory(command)),
+                None => {
+                    renderer.line(
+                        MessageStyle::Error,
+                        "Usage: /memory [add|edit <n>|remove <n>] [--user] [text]",
+                    )?;
+                    Ok(SlashCommandOutcome::Handled)
+                }
+            }
+        }
         "model" => Ok(SlashCommandOutcome::StartModelSelection),
+        "export" => {
+            let remaining: Vec<&str> = parts.collect();
+            match parse_export_command(&remaining) {
+                Some((format, path)) => Ok(SlashCommandOutcome::ExportTranscript { format, path }),
+                None => {
+                    renderer.line(MessageStyle::Error, "Usage: /export [md|html|json] [path]")?;
+                    Ok(SlashCommandOutcome::Handled)
+                }
+            }
+        }
+        "branches" => match (parts.next().map(str::parse::<usize>), parts.next()) {
+            (None, _) => Ok(SlashCommandOutcome::ManageBranches { switch_to: None }),
+            (Some(Ok(id)), None) => Ok(SlashCommandOutcome::ManageBranches {
+                switch_to: Some(id),
+            }),
+            _ => {
+                renderer.line(MessageStyle::Error, "Usage: /branches [number]")?;
+                Ok(SlashCommandOutcome::Handled)
+            }
+        },
         "sessions" => {
             let limit = parts
                 .next()
@@ -239,6 +355,75 @@ pub fn handle_slash_command(
     }
 }
 
+/// Parse `/export` arguments; without an explicit format the path extension decides.
+fn parse_export_command(args: &[&str]) -> Option<(ExportFormat, Option<PathBuf>)> {
+    let (format, rest) = match args.split_first() {
+        Some((first, rest)) => match ExportFormat::parse(first) {
+            Some(format) => (Some(format), rest),
+            None => (None, args),
+        },
+        None => (None, args),
+    };
+    let path = match rest {
+        [] => None,
+        [path] => Some(PathBuf::from(path)),
+        _ => return None,
+    };
+    let format = format
+        .or_else(|| {
+            path.as_ref()
+                .and_then(|path| path.extension())
+                .and_then(|ext| ext.to_str())
+                .and_then(ExportFormat::parse)
+        })
+        .unwrap_or(ExportFormat::Markdown);
+    Some((format, path))
+}
+
+fn parse_memory_command(args: &[&str]) -> Option<MemoryCommand> {
+    let Some((action, rest)) = args.split_first() else {
+        return Some(MemoryCommand::List);
+    };
+    let mut scope = MemoryScope::Project;
+    let rest: Vec<&str> = rest
+        .iter()
+        .copied()
+        .filter(|token| match *token {
+            "--user" | "-u" => {
+                scope = MemoryScope::User;
+                false
+            }
+            "--project" | "-p" => {
+                scope = MemoryScope::Project;
+                false
+            }
+            _ => true,
+        })
+        .collect();
+
+    match action.to_ascii_lowercase().as_str() {
+        "list" | "ls" if rest.is_empty() => Some(MemoryCommand::List),
+        "add" if !rest.is_empty() => Some(MemoryCommand::Add {
+            scope,
+            text: rest.join(" "),
+        }),
+        "edit" | "update" => {
+            let (index, text) = rest.split_first()?;
+            let index = index.parse().ok()?;
+            (!text.is_empty()).then(|| MemoryCommand::Edit {
+                scope,
+                index,
+                text: text.join(" "),
+            })
+        }
+        "remove" | "rm" | "prune" if rest.len() == 1 => Some(MemoryCommand::Remove {
+            scope,
+            index: rest[0].parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
 fn format_duration_label(duration: Duration) -> String {
     let total_seconds = duration.as_secs();
     let hours = total_seconds / 3600;
@@ -1,12 +1,17 @@
 use anstyle::{AnsiColor, Color, Style};
 use anyhow::{Context, Result};
+use chrono::Utc;
 use serde_json::Value;
 use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
 use vtcode_core::config::ToolOutputMode;
 use vtcode_core::config::constants::{defaults, tools};
 use vtcode_core::config::loader::VTCodeConfig;
-use vtcode_core::tools::{PlanCompletionState, StepStatus, TaskPlan};
+use vtcode_core::tools::{CommandOutputLine, PlanCompletionState, StepStatus, TaskPlan};
+use vtcode_core::ui::tui::InlineBlockKind;
 use vtcode_core::utils::ansi::{AnsiRenderer, MessageStyle};
+use vtcode_core::utils::dot_config::get_dot_manager;
 
 pub(crate) fn render_tool_output(
     renderer: &mut AnsiRenderer,
@@ -14,6 +19,8 @@ pub(crate) fn render_tool_output(
     val: &Value,
     vt_config: Option<&VTCodeConfig>,
 ) -> Result<()> {
+    renderer.begin_block(InlineBlockKind::ToolResult, tool_name, None);
+
     if tool_name == Some(tools::UPDATE_PLAN) {
         render_plan_update(renderer, val)?;
         return Ok(());
@@ -76,6 +83,65 @@ pub(crate) fn render_tool_output(
     Ok(())
 }
 
+/// Tail of a command's output shown while it runs. The final tool output
+/// replaces it once the command exits.
+pub(crate) struct LiveCommandOutput {
+    tail: VecDeque<CommandOutputLine>,
+    limit: usize,
+    rendered: usize,
+}
+
+impl LiveCommandOutput {
+    pub(crate) fn new(vt_config: Option<&VTCodeConfig>) -> Self {
+        Self {
+            tail: VecDeque::new(),
+            limit: resolve_stdout_tail_limit(vt_config),
+            rendered: 0,
+        }
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        renderer: &mut AnsiRenderer,
+        tool_name: &str,
+        line: CommandOutputLine,
+    ) -> Result<()> {
+        if self.tail.is_empty() {
+            renderer.begin_block(InlineBlockKind::ToolResult, Some(tool_name), None);
+        }
+        if self.tail.len() == self.limit {
+            self.tail.pop_front();
+            renderer.retract_lines(self.rendered);
+            self.rendered = 0;
+            for previous in &self.tail {
+                render_live_line(renderer, previous)?;
+                self.rendered += 1;
+            }
+        }
+        render_live_line(renderer, &line)?;
+        self.rendered += 1;
+        self.tail.push_back(line);
+        Ok(())
+    }
+
+    /// Remove the preview from the transcript.
+    pub(crate) fn clear(&mut self, renderer: &mut AnsiRenderer) {
+        renderer.retract_lines(self.rendered);
+        self.rendered = 0;
+        self.tail.clear();
+    }
+}
+
+fn render_live_line(renderer: &mut AnsiRenderer, line: &CommandOutputLine) -> Result<()> {
+    let (style, text) = match line {
+        CommandOutputLine::Stdout(text) => (MessageStyle::Output, text),
+        CommandOutputLine::Stderr(text) => (MessageStyle::Error, text),
+    };
+    // Progress bars redraw with carriage returns; only the latest frame matters
+    let text = text.rsplit('\r').next().unwrap_or_default();
+    renderer.line(style, text)
+}
+
 fn render_plan_update(renderer: &mut AnsiRenderer, val: &Value) -> Result<()> {
     let heading = if val.get("error").is_some() {
         val.get("message")
@@ -514,6 +580,40 @@ fn select_stream_lines<'a>(
     (tail, total, truncated)
 }
 
+/// Save an untruncated output stream under the tool output cache so the
+/// transcript can load it on demand.
+fn spill_full_output(tool_name: Option<&str>, title: &str, content: &str) -> Option<PathBuf> {
+    let dir = get_dot_manager()
+        .lock()
+        .map(|manager| manager.cache_dir("tool-output"))
+        .ok()?;
+    let tool: String = tool_name
+        .unwrap_or("tool")
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let path = dir.join(format!(
+        "{}-{}-{}.log",
+        Utc::now().format("%Y%m%dT%H%M%S%3f"),
+        tool,
+        title
+    ));
+    let written = fs::create_dir_all(&dir).and_then(|_| fs::write(&path, content));
+    match written {
+        Ok(()) => Some(path),
+        Err(error) => {
+            tracing::debug!(%error, path = %path.display(), "failed to save full tool output");
+            None
+        }
+    }
+}
+
 fn render_write_file_preview(
     renderer: &mut AnsiRenderer,
     payload: &Value,
@@ -586,6 +686,7 @@ fn render_write_file_preview(
     }
 
     if !diff_content.is_empty() {
+        renderer.begin_block(InlineBlockKind::Diff, Some(path), None);
         renderer.line(MessageStyle::Tool, "[diff]")?;
         for line in diff_content.lines() {
             let display = format!("  {line}");
@@ -632,19 +733,34 @@ fn render_stream_section(
     fallback_style: MessageStyle,
 ) -> Result<()> {
     let is_mcp_tool = tool_name.map_or(false, |name| name.starts_with("mcp_"));
-    let prefer_full = renderer.prefers_untruncated_output();
-    let (lines, total, truncated) = select_stream_lines(content, mode, tail_limit, prefer_full);
+    let (lines, total, truncated) = select_stream_lines(content, mode, tail_limit, false);
 
     if lines.is_empty() {
         return Ok(());
     }
 
+    let full_output = if truncated {
+        spill_full_output(tool_name, title, content)
+    } else {
+        None
+    };
+    let block_title = format!("{} {}", tool_name.unwrap_or("tool"), title);
+    renderer.begin_block(
+        InlineBlockKind::ToolResult,
+        Some(&block_title),
+        full_output.as_deref(),
+    );
+
     if truncated {
         let summary_prefix = if is_mcp_tool { "" } else { "  " };
+        let saved = full_output
+            .as_ref()
+            .map(|path| format!(" (full output: {})", path.display()))
+            .unwrap_or_default();
         renderer.line(
             MessageStyle::Info,
             &format!(
-                "{summary_prefix}... showing last {}/{} {} lines",
+                "{summary_prefix}... showing last {}/{} {} lines{saved}",
                 lines.len(),
                 total,
                 title
@@ -0,0 +1,119 @@
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use vtcode_core::llm::provider::Attachment;
+
+/// Attachments referenced in user input via `@path/to/file.png`
+#[derive(Default)]
+pub(crate) struct InputAttachments {
+    pub(crate) attachments: Vec<Attachment>,
+    pub(crate) errors: Vec<String>,
+}
+
+/// Collect `@path` mentions that point at images or PDFs inside the workspace.
+///
+/// Mentions of other file types are expanded as text context by `mentions`;
+/// unreadable or oversized attachments are reported as errors.
+pub(crate) fn collect_input_attachments(input: &str, workspace: &Path) -> InputAttachments {
+    let mut result = InputAttachments::default();
+    let mut seen: Vec<PathBuf> = Vec::new();
+
+    for raw in mention_tokens(input) {
+        let Some(path) = resolve_mention(raw, workspace) else {
+            continue;
+        };
+        if seen.contains(&path) {
+            continue;
+        }
+
+        match Attachment::from_path(&path) {
+            Ok(attachment) => result.attachments.push(attachment),
+            Err(err) => result.errors.push(err.to_string()),
+        }
+        seen.push(path);
+    }
+
+    result
+}
+
+/// `@` mentions in `input` without the `@`, allowing leading punctuation such as "(@diagram.pdf)".
+pub(crate) fn mention_tokens(input: &str) -> impl Iterator<Item = &str> {
+    input.split_whitespace().filter_map(|token| {
+        token
+            .trim_start_matches(['(', '[', '"', '\''])
+            .strip_prefix('@')
+    })
+}
+
+/// The mention as written and without trailing punctuation such as "see @shot.png,".
+pub(crate) fn mention_variants(raw: &str) -> [&str; 2] {
+    [
+        raw,
+        raw.trim_end_matches(|ch: char| {
+            matches!(
+                ch,
+                ',' | ';' | ':' | ')' | ']' | '"' | '\'' | '!' | '?' | '.'
+            )
+        }),
+    ]
+}
+
+fn resolve_mention(raw: &str, workspace: &Path) -> Option<PathBuf> {
+    mention_variants(raw).into_iter().find_map(|candidate| {
+        let relative = Path::new(candidate);
+        Attachment::media_type_for_path(relative)?;
+        let path = if relative.is_absolute() {
+            relative.to_path_buf()
+        } else {
+            workspace.join(relative)
+        };
+        let canonical = path.canonicalize().ok()?;
+        let workspace = workspace.canonicalize().ok()?;
+        (canonical.starts_with(&workspace) && canonical.is_file()).then_some(canonical)
+    })
+}
+
+/// Remove an `attachment` payload from a tool result so the base64 data is
+/// neither rendered nor sent as tool text.
+pub(crate) fn take_tool_attachment(tool_output: &mut Value) -> Option<Attachment> {
+    let payload = tool_output.as_object_mut()?.remove("attachment")?;
+    serde_json::from_value(payload).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vtcode_core::llm::provider::AttachmentKind;
+
+    #[test]
+    fn collects_image_and_pdf_mentions_inside_workspace() {
+        let workspace = tempfile::tempdir().unwrap();
+        std::fs::write(workspace.path().join("shot.png"), [0x89, b'P', b'N', b'G']).unwrap();
+        std::fs::create_dir(workspace.path().join("docs")).unwrap();
+        std::fs::write(workspace.path().join("docs/spec.pdf"), b"%PDF-1.4").unwrap();
+        std::fs::write(workspace.path().join("notes.md"), b"# notes").unwrap();
+
+        let input =
+            "compare @shot.png, with (@docs/spec.pdf). and @notes.md @shot.png @missing.png";
+        let collected = collect_input_attachments(input, workspace.path());
+
+        assert!(collected.errors.is_empty());
+        assert_eq!(collected.attachments.len(), 2);
+        assert_eq!(collected.attachments[0].kind, AttachmentKind::Image);
+        assert_eq!(collected.attachments[0].media_type, "image/png");
+        assert_eq!(collected.attachments[1].kind, AttachmentKind::Document);
+        assert_eq!(collected.attachments[1].name.as_deref(), Some("spec.pdf"));
+    }
+
+    #[test]
+    fn ignores_paths_outside_workspace() {
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret.png"), b"png").unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+
+        let input = format!("@{}", outside.path().join("secret.png").display());
+        let collected = collect_input_attachments(&input, workspace.path());
+
+        assert!(collected.attachments.is_empty());
+    }
+}
@@ -0,0 +1,494 @@
+use anyhow::{Result, bail};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use vtcode_core::llm::provider as uni;
+use vtcode_core::tools::edit_review::ProposedChange;
+use vtcode_core::utils::session_archive::{SessionBranch, SessionFileEdit};
+
+use super::export::ResponseDetails;
+
+const PROMPT_PREVIEW_CHARS: usize = 60;
+
+/// A prompt sent on a branch, which can later be edited to fork a new branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PromptMark {
+    /// Position of the user message in the conversation history
+    index: usize,
+    turn: u64,
+    /// What the user typed, restored into the input for editing
+    input: String,
+    /// The prompt as shown in the transcript
+    display: String,
+}
+
+impl PromptMark {
+    pub(crate) fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+struct Branch {
+    id: usize,
+    parent: Option<usize>,
+    fork_at: usize,
+    /// Messages of the branch while another one is active
+    messages: Vec<uni::Message>,
+    prompts: Vec<PromptMark>,
+    edits: Vec<SessionFileEdit>,
+}
+
+/// Files touched while moving between branches.
+#[derive(Debug, Default)]
+pub(crate) struct BranchFileChanges {
+    restored: BTreeSet<PathBuf>,
+    /// Files changed outside the agent since it edited them, left as they are
+    skipped: BTreeSet<PathBuf>,
+}
+
+impl BranchFileChanges {
+    pub(crate) fn describe(&self, workspace: &Path) -> Vec<String> {
+        let list = |paths: &BTreeSet<PathBuf>| {
+            paths
+                .iter()
+                .map(|path| {
+                    path.strip_prefix(workspace)
+                        .unwrap_or(path)
+                        .display()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut lines = Vec::new();
+        if !self.restored.is_empty() {
+            lines.push(format!("Restored files: {}", list(&self.restored)));
+        }
+        if !self.skipped.is_empty() {
+            lines.push(format!(
+                "Left unchanged because they were modified since the agent edited them: {}",
+                list(&self.skipped)
+            ));
+        }
+        lines
+    }
+}
+
+/// Pre-images of the files a write tool is about to change.
+pub(crate) struct FilePreImages(Vec<(PathBuf, Option<String>)>);
+
+impl FilePreImages {
+    pub(crate) fn capture(workspace: &Path, changes: &[ProposedChange]) -> Self {
+        let paths: BTreeSet<PathBuf> = changes
+            .iter()
+            .flat_map(|change| std::iter::once(&change.path).chain(change.source_path.as_ref()))
+            .map(|path| workspace.join(path))
+            .collect();
+        Self(
+            paths
+                .into_iter()
+                .filter_map(|path| {
+                    let content = read_state(&path).ok()?;
+                    Some((path, content))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Branches of the conversation created by editing an earlier prompt and
+/// regenerating. Each branch keeps the file edits the agent made on it so that
+/// switching branches also moves the workspace files along.
+pub(crate) struct ConversationBranches {
+    branches: Vec<Branch>,
+    active: usize,
+    turn: u64,
+    next_edit_id: u64,
+}
+
+impl Default for ConversationBranches {
+    fn default() -> Self {
+        Self {
+            branches: vec![Branch {
+                id: 1,
+                parent: None,
+                fork_at: 0,
+                messages: Vec::new(),
+                prompts: Vec::new(),
+                edits: Vec::new(),
+            }],
+            active: 0,
+            turn: 0,
+            next_edit_id: 1,
+        }
+    }
+}
+
+impl ConversationBranches {
+    /// Remember the prompt just pushed to `history` so it can be edited later.
+    pub(crate) fn record_prompt(&mut self, history: &[uni::Message], input: &str, display: &str) {
+        let Some(index) = history.len().checked_sub(1) else {
+            return;
+        };
+        self.turn += 1;
+        let turn = self.turn;
+        self.branches[self.active].prompts.push(PromptMark {
+            index,
+            turn,
+            input: input.to_string(),
+            display: display.trim().to_string(),
+        });
+    }
+
+    /// Forget the latest prompt after it was dropped from the history.
+    pub(crate) fn discard_last_prompt(&mut self) {
+        self.branches[self.active].prompts.pop();
+    }
+
+    /// The latest prompt of the active branch shown as `text` in the transcript.
+    pub(crate) fn find_prompt(&self, text: &str) -> Option<PromptMark> {
+        let text = text.trim();
+        self.branches[self.active]
+            .prompts
+            .iter()
+            .rev()
+            .find(|mark| mark.display == text)
+            .cloned()
+    }
+
+    /// Record what the current turn's write tool changed in the captured files.
+    pub(crate) fn record_file_edits(&mut self, pre_images: FilePreImages) {
+        for (path, before) in pre_images.0 {
+            let Ok(after) = read_state(&path) else {
+                continue;
+            };
+            if after == before {
+                continue;
+            }
+            let id = self.next_edit_id;
+            self.next_edit_id += 1;
+            self.branches[self.active].edits.push(SessionFileEdit {
+                id,
+                turn: self.turn,
+                path,
+                before,
+                after,
+            });
+        }
+    }
+
+    /// Keep the active branch and start a new one just before `mark`, undoing the
+    /// file edits made from that prompt onwards.
+    pub(crate) fn fork(
+        &mut self,
+        mark: &PromptMark,
+        history: &mut Vec<uni::Message>,
+    ) -> Result<(usize, BranchFileChanges)> {
+        let still_present = history.get(mark.index).is_some_and(|message| {
+            message.role == uni::MessageRole::User && message.content.starts_with(&mark.display)
+        });
+        if !still_present {
+            bail!(
+                "That prompt is no longer in the conversation history, most likely because it was compacted."
+            );
+        }
+
+        let mut changes = BranchFileChanges::default();
+        let id = self.branches.len() + 1;
+        let current = &mut self.branches[self.active];
+        for edit in current.edits.iter().rev() {
+            if edit.turn >= mark.turn {
+                revert(edit, &mut changes);
+            }
+        }
+        current.messages = history.clone();
+        let branch = Branch {
+            id,
+            parent: Some(current.id),
+            fork_at: mark.index,
+            messages: Vec::new(),
+            prompts: current
+                .prompts
+                .iter()
+                .filter(|prompt| prompt.turn < mark.turn)
+                .cloned()
+                .collect(),
+            edits: current
+                .edits
+                .iter()
+                .filter(|edit| edit.turn < mark.turn)
+                .cloned()
+                .collect(),
+        };
+        history.truncate(mark.index);
+        self.branches.push(branch);
+        self.active = self.branches.len() - 1;
+        Ok((id, changes))
+    }
+
+    /// Make branch `id` active, swapping its messages into `history` and moving
+    /// the files it edited to the state it left them in.
+    pub(crate) fn switch(
+        &mut self,
+        id: usize,
+        history: &mut Vec<uni::Message>,
+    ) -> Result<BranchFileChanges> {
+        let Some(target) = self.branches.iter().position(|branch| branch.id == id) else {
+            bail!("There is no branch {}. Use /branches to list them.", id);
+        };
+        if target == self.active {
+            bail!("Branch {} is already active.", id);
+        }
+
+        let mut changes = BranchFileChanges::default();
+        let current = &self.branches[self.active];
+        let next = &self.branches[target];
+        let shared = current
+            .edits
+            .iter()
+            .zip(&next.edits)
+            .take_while(|(left, right)| left.id == right.id)
+            .count();
+        for edit in current.edits[shared..].iter().rev() {
+            revert(edit, &mut changes);
+        }
+        for edit in &next.edits[shared..] {
+            reapply(edit, &mut changes);
+        }
+
+        self.branches[self.active].messages = std::mem::take(history);
+        *history = std::mem::take(&mut self.branches[target].messages);
+        self.active = target;
+        Ok(changes)
+    }
+
+    pub(crate) fn active_id(&self) -> usize {
+        self.branches[self.active].id
+    }
+
+    /// One line per branch for `/branches`.
+    pub(crate) fn describe(&self, history: &[uni::Message]) -> Vec<String> {
+        self.branches
+            .iter()
+            .enumerate()
+            .map(|(index, branch)| {
+                let active = index == self.active;
+                let messages = if active {
+                    history.len()
+                } else {
+                    branch.messages.len()
+                };
+                let mut line = format!(
+                    "{} {}: {} messages",
+                    if active { "*" } else { " " },
+                    branch.id,
+                    messages
+                );
+                if let Some(parent) = branch.parent {
+                    line.push_str(&format!(
+                        ", from branch {} at message {}",
+                        parent,
+                        branch.fork_at + 1
+                    ));
+                }
+                if !branch.edits.is_empty() {
+                    line.push_str(&format!(", {} file edits", branch.edits.len()));
+                }
+                if let Some(prompt) = branch.prompts.last() {
+                    line.push_str(&format!(" · “{}”", preview(&prompt.display)));
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// Branches for the session archive; empty until a prompt has been edited.
+    pub(crate) fn session_branches(
+        &self,
+        history: &[uni::Message],
+        details: &ResponseDetails,
+    ) -> Vec<SessionBranch> {
+        if self.branches.len() < 2 {
+            return Vec::new();
+        }
+        self.branches
+            .iter()
+            .enumerate()
+            .map(|(index, branch)| {
+                let active = index == self.active;
+                let messages = if active { history } else { &branch.messages };
+                SessionBranch {
+                    id: branch.id,
+                    parent: branch.parent,
+                    fork_at: branch.fork_at,
+                    active,
+                    messages: details.session_messages(messages),
+                    file_edits: branch.edits.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn preview(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.chars().count() > PROMPT_PREVIEW_CHARS {
+        let mut truncated: String = line.chars().take(PROMPT_PREVIEW_CHARS - 1).collect();
+        truncated.push('…');
+        truncated
+    } else {
+        line.to_string()
+    }
+}
+
+/// Undo `edit` when the file still holds what the agent wrote.
+fn revert(edit: &SessionFileEdit, changes: &mut BranchFileChanges) {
+    move_file_state(edit, edit.after.as_deref(), edit.before.as_deref(), changes);
+}
+
+/// Redo `edit` when the file still holds what it replaced.
+fn reapply(edit: &SessionFileEdit, changes: &mut BranchFileChanges) {
+    move_file_state(edit, edit.before.as_deref(), edit.after.as_deref(), changes);
+}
+
+fn move_file_state(
+    edit: &SessionFileEdit,
+    expected: Option<&str>,
+    target: Option<&str>,
+    changes: &mut BranchFileChanges,
+) {
+    let unchanged = read_state(&edit.path).is_ok_and(|current| current.as_deref() == expected);
+    if unchanged && write_state(&edit.path, target).is_ok() {
+        changes.restored.insert(edit.path.clone());
+    } else {
+        changes.skipped.insert(edit.path.clone());
+    }
+}
+
+fn read_state(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn write_state(path: &Path, content: Option<&str>) -> io::Result<()> {
+    match content {
+        Some(content) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, content)
+        }
+        None => match fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(path: &str) -> ProposedChange {
+        ProposedChange {
+            path: path.to_string(),
+            source_path: None,
+            old_content: String::new(),
+            new_content: String::new(),
+            delete: false,
+        }
+    }
+
+    fn send(branches: &mut ConversationBranches, history: &mut Vec<uni::Message>, prompt: &str) {
+        history.push(uni::Message::user(prompt.to_string()));
+        branches.record_prompt(history, prompt, prompt);
+    }
+
+    fn write_turn(
+        branches: &mut ConversationBranches,
+        history: &mut Vec<uni::Message>,
+        workspace: &Path,
+        content: &str,
+    ) {
+        let pre_images = FilePreImages::capture(workspace, &[change("notes.txt")]);
+        fs::write(workspace.join("notes.txt"), content).unwrap();
+        branches.record_file_edits(pre_images);
+        history.push(uni::Message::assistant(format!("wrote {content}")));
+    }
+
+    #[test]
+    fn forking_and_switching_moves_history_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path();
+        let file = workspace.join("notes.txt");
+        let mut branches = ConversationBranches::default();
+        let mut history = Vec::new();
+
+        send(&mut branches, &mut history, "create notes");
+        write_turn(&mut branches, &mut history, workspace, "first");
+        send(&mut branches, &mut history, "make them shorter");
+        write_turn(&mut branches, &mut history, workspace, "short");
+
+        let mark = branches.find_prompt("make them shorter").unwrap();
+        assert_eq!(mark.input(), "make them shorter");
+        let (id, changes) = branches.fork(&mark, &mut history).unwrap();
+        assert_eq!(id, 2);
+        assert_eq!(changes.restored.len(), 1);
+        assert_eq!(history.len(), 2);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "first");
+
+        send(&mut branches, &mut history, "make them longer");
+        write_turn(&mut branches, &mut history, workspace, "much longer");
+
+        let changes = branches.switch(1, &mut history).unwrap();
+        assert!(changes.skipped.is_empty());
+        assert_eq!(history.len(), 4);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "short");
+
+        branches.switch(2, &mut history).unwrap();
+        assert_eq!(history[2].content, "make them longer");
+        assert_eq!(fs::read_to_string(&file).unwrap(), "much longer");
+
+        let listing = branches.describe(&history);
+        assert_eq!(listing.len(), 2);
+        assert!(listing[1].starts_with("* 2: 4 messages, from branch 1 at message 3"));
+    }
+
+    #[test]
+    fn files_changed_outside_the_agent_are_left_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path();
+        let file = workspace.join("notes.txt");
+        let mut branches = ConversationBranches::default();
+        let mut history = Vec::new();
+
+        send(&mut branches, &mut history, "create notes");
+        write_turn(&mut branches, &mut history, workspace, "first");
+        fs::write(&file, "edited by hand").unwrap();
+
+        let mark = branches.find_prompt("create notes").unwrap();
+        let (_, changes) = branches.fork(&mark, &mut history).unwrap();
+        assert!(history.is_empty());
+        assert!(changes.restored.is_empty());
+        assert_eq!(changes.skipped.len(), 1);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "edited by hand");
+    }
+
+    #[test]
+    fn compacted_prompts_cannot_be_forked() {
+        let mut branches = ConversationBranches::default();
+        let mut history = Vec::new();
+        send(&mut branches, &mut history, "first");
+        send(&mut branches, &mut history, "second");
+        history.remove(0);
+
+        let mark = branches.find_prompt("second").unwrap();
+        assert!(branches.fork(&mark, &mut history).is_err());
+        assert_eq!(history.len(), 1);
+    }
+}
@@ -0,0 +1,617 @@
+use anyhow::{Context, Result, anyhow};
+use std::collections::{BTreeSet, HashSet};
+use tokio::sync::RwLock;
+
+use vtcode_core::config::constants::tools as tool_names;
+use vtcode_core::config::loader::VTCodeConfig;
+use vtcode_core::core::cost_tracker::SharedCostTracker;
+use vtcode_core::core::decision_tracker::{Action, DecisionTracker};
+use vtcode_core::core::tokenizer::Tokenizer;
+use vtcode_core::llm::provider as uni;
+use vtcode_core::tools::apply_patch::{Patch, PatchOperation};
+use vtcode_core::tools::{PlanCompletionState, TaskPlan};
+use vtcode_core::utils::ansi::{AnsiRenderer, MessageStyle};
+
+use crate::agent::runloop::context::{ContextTrimConfig, unified_message_tokens};
+
+/// First line of every summary message so later compactions can recognise it
+const SUMMARY_HEADER: &str = "[Conversation summary]";
+const LEDGER_HEADING: &str = "## Decision ledger";
+const PLAN_HEADING: &str = "## Open plan";
+const FILES_HEADING: &str = "## Touched files";
+const TOOL_OUTPUT_CHAR_LIMIT: usize = 2_000;
+const MESSAGE_CHAR_LIMIT: usize = 6_000;
+const TOOL_ARGS_CHAR_LIMIT: usize = 400;
+const SUMMARY_SYSTEM_PROMPT: &str = "You compress coding-agent conversations so the agent can keep working without the original messages. \
+Write a dense handoff summary in Markdown with these sections: Goal, Progress so far, Key findings (errors, root causes, commands and their results, \
+APIs or code facts learned), Decisions and constraints, Next steps. Keep exact file paths, identifiers and error messages. \
+Do not invent details and do not address the user.";
+
+/// Compaction knobs resolved from `[context.compaction]` and the trim window.
+#[derive(Clone, Copy)]
+pub(crate) struct CompactionSettings {
+    pub(crate) enabled: bool,
+    pub(crate) trigger_tokens: usize,
+    pub(crate) max_summary_tokens: u32,
+    pub(crate) preserve_recent_messages: usize,
+    /// Ledger entries to carry over, `None` when the ledger is disabled or not preserved
+    pub(crate) ledger_entries: Option<usize>,
+}
+
+impl CompactionSettings {
+    pub(crate) fn from_config(vt_cfg: Option<&VTCodeConfig>, trim: ContextTrimConfig) -> Self {
+        let context_cfg = vt_cfg.map(|cfg| &cfg.context);
+        let compaction = context_cfg
+            .map(|cfg| cfg.compaction.clone())
+            .unwrap_or_default();
+        let trigger_percent = compaction.trigger_percent.clamp(10, 100) as usize;
+        let ledger = context_cfg
+            .map(|cfg| cfg.ledger.clone())
+            .unwrap_or_default();
+        let ledger_entries =
+            (ledger.enabled && ledger.preserve_in_compression).then_some(ledger.max_entries);
+
+        Self {
+            enabled: compaction.enabled,
+            trigger_tokens: trim.max_tokens * trigger_percent / 100,
+            max_summary_tokens: compaction.max_summary_tokens.max(256),
+            preserve_recent_messages: trim.preserve_recent_turns,
+            ledger_entries,
+        }
+    }
+
+    /// Whether the history has grown past the automatic compaction threshold.
+    pub(crate) fn should_auto_compact(
+        &self,
+        history: &[uni::Message],
+        tokenizer: &dyn Tokenizer,
+    ) -> bool {
+        self.enabled
+            && history_tokens(history, tokenizer) > self.trigger_tokens
+            && compaction_boundary(history, self.preserve_recent_messages).is_some()
+    }
+}
+
+/// Everything a compaction needs from the running session.
+pub(crate) struct CompactionSession<'a> {
+    pub(crate) provider: &'a dyn uni::LLMProvider,
+    pub(crate) model: &'a str,
+    pub(crate) tokenizer: &'a dyn Tokenizer,
+    pub(crate) settings: &'a CompactionSettings,
+    pub(crate) ledger: &'a RwLock<DecisionTracker>,
+    pub(crate) cost_tracker: &'a SharedCostTracker,
+    pub(crate) plan: TaskPlan,
+}
+
+pub(crate) struct CompactionOutcome {
+    pub(crate) replaced_messages: usize,
+    pub(crate) tokens_before: usize,
+    pub(crate) tokens_after: usize,
+}
+
+impl CompactionOutcome {
+    pub(crate) fn describe(&self) -> String {
+        format!(
+            "Compacted {} earlier messages into a summary (~{} → ~{} tokens).",
+            self.replaced_messages, self.tokens_before, self.tokens_after
+        )
+    }
+}
+
+/// Automatic compaction at the token threshold. Failures are reported and leave the
+/// history untouched so the regular trimming can take over; returns `false` in that case.
+pub(crate) async fn auto_compact(
+    history: &mut Vec<uni::Message>,
+    session: CompactionSession<'_>,
+    renderer: &mut AnsiRenderer,
+) -> Result<bool> {
+    match compact_history(history, session, None, "context threshold reached").await {
+        Ok(Some(outcome)) => {
+            renderer.line(MessageStyle::Info, &outcome.describe())?;
+            Ok(true)
+        }
+        Ok(None) => Ok(true),
+        Err(err) => {
+            renderer.line(
+                MessageStyle::Error,
+                &format!("Automatic compaction failed, trimming instead: {:#}", err),
+            )?;
+            Ok(false)
+        }
+    }
+}
+
+/// Summarize everything older than the preserved window with the active model and
+/// swap the summary into `history`. Returns `None` when there is nothing to compact.
+pub(crate) async fn compact_history(
+    history: &mut Vec<uni::Message>,
+    session: CompactionSession<'_>,
+    focus: Option<&str>,
+    reason: &str,
+) -> Result<Option<CompactionOutcome>> {
+    let Some(boundary) = compaction_boundary(history, session.settings.preserve_recent_messages)
+    else {
+        return Ok(None);
+    };
+
+    let tokens_before = history_tokens(history, session.tokenizer);
+    let older = &history[..boundary];
+    let summary = request_summary(&session, older, focus).await?;
+    let ledger_brief = match session.settings.ledger_entries {
+        Some(entries) => {
+            let ledger = session.ledger.read().await;
+            (!ledger.get_decisions().is_empty()).then(|| ledger.render_ledger_brief(entries))
+        }
+        None => None,
+    };
+    let message = render_summary_message(
+        &summary,
+        ledger_brief.as_deref(),
+        &session.plan,
+        &touched_files(older),
+    );
+
+    history.splice(..boundary, [uni::Message::user(message)]);
+    let tokens_after = history_tokens(history, session.tokenizer);
+    let outcome = CompactionOutcome {
+        replaced_messages: boundary,
+        tokens_before,
+        tokens_after,
+    };
+
+    session.ledger.write().await.record_decision(
+        format!("Compacted {} messages ({})", boundary, reason),
+        Action::ContextCompression {
+            reason: reason.to_string(),
+            compression_ratio: if tokens_before == 0 {
+                1.0
+            } else {
+                tokens_after as f64 / tokens_before as f64
+            },
+        },
+        None,
+    );
+
+    Ok(Some(outcome))
+}
+
+async fn request_summary(
+    session: &CompactionSession<'_>,
+    older: &[uni::Message],
+    focus: Option<&str>,
+) -> Result<String> {
+    let mut prompt = String::from("Summarize this earlier part of our session.\n");
+    if let Some(focus) = focus.map(str::trim).filter(|focus| !focus.is_empty()) {
+        prompt.push_str(&format!("Pay particular attention to: {}\n", focus));
+    }
+    prompt.push_str("\n<transcript>\n");
+    prompt.push_str(&render_transcript(older));
+    prompt.push_str("</transcript>");
+
+    if let Some(limit) = session.cost_tracker.lock().budget_exceeded() {
+        return Err(anyhow!("Summarization skipped: {}", limit));
+    }
+
+    let request = uni::LLMRequest {
+        messages: vec![uni::Message::user(prompt)],
+        system_prompt: Some(SUMMARY_SYSTEM_PROMPT.to_string()),
+        tools: None,
+        model: session.model.to_string(),
+        max_tokens: Some(session.settings.max_summary_tokens),
+        temperature: Some(0.2),
+        stream: false,
+        tool_choice: Some(uni::ToolChoice::none()),
+        parallel_tool_calls: None,
+        parallel_tool_config: None,
+        reasoning_effort: None,
+        response_format: None,
+    };
+
+    let response = session
+        .provider
+        .generate(request)
+        .await
+        .map_err(|err| anyhow!("{}", err))
+        .context("Summarization request failed")?;
+    if let Some(usage) = response.usage.as_ref() {
+        session
+            .cost_tracker
+            .lock()
+            .record(session.provider.name(), session.model, usage);
+    }
+    let summary = response.content.unwrap_or_default().trim().to_string();
+    if summary.is_empty() {
+        return Err(anyhow!("The model returned an empty summary"));
+    }
+    Ok(summary)
+}
+
+/// Index of the first message to keep verbatim. The boundary never separates a tool
+/// response from the assistant message that requested it.
+fn compaction_boundary(history: &[uni::Message], preserve_recent: usize) -> Option<usize> {
+    let mut boundary = history.len().saturating_sub(preserve_recent.max(1));
+    while boundary > 0 && history[boundary].is_tool_response() {
+        boundary -= 1;
+    }
+
+    let only_previous_summary = boundary == 1 && is_summary_message(&history[0]);
+    if boundary == 0 || only_previous_summary {
+        return None;
+    }
+    Some(boundary)
+}
+
+fn is_summary_message(message: &uni::Message) -> bool {
+    message.content.starts_with(SUMMARY_HEADER)
+}
+
+fn history_tokens(history: &[uni::Message], tokenizer: &dyn Tokenizer) -> usize {
+    history
+        .iter()
+        .map(|message| unified_message_tokens(message, tokenizer))
+        .sum()
+}
+
+fn render_transcript(messages: &[uni::Message]) -> String {
+    let mut out = String::new();
+    for message in messages {
+        let (label, limit) = match message.role {
+            uni::MessageRole::System => ("System", MESSAGE_CHAR_LIMIT),
+            uni::MessageRole::User => ("User", MESSAGE_CHAR_LIMIT),
+            uni::MessageRole::Assistant => ("Assistant", MESSAGE_CHAR_LIMIT),
+            uni::MessageRole::Tool => ("Tool result", TOOL_OUTPUT_CHAR_LIMIT),
+        };
+        if !message.content.trim().is_empty() {
+            out.push_str(&format!(
+                "{}: {}\n",
+                label,
+                truncate_chars(message.content.trim(), limit)
+            ));
+        }
+        if !message.attachments.is_empty() {
+            out.push_str(&format!(
+                "({} attachment(s) omitted)\n",
+                message.attachments.len()
+            ));
+        }
+        for call in message.tool_calls.iter().flatten() {
+            out.push_str(&format!(
+                "Tool call: {}({})\n",
+                call.function.name,
+                truncate_chars(&call.function.arguments, TOOL_ARGS_CHAR_LIMIT)
+            ));
+        }
+    }
+    out
+}
+
+fn truncate_chars(text: &str, limit: usize) -> String {
+    match text.char_indices().nth(limit) {
+        Some((index, _)) => format!("{}… [truncated]", &text[..index]),
+        None => text.to_string(),
+    }
+}
+
+/// Files written by tool calls in `messages`, plus those listed by earlier summaries.
+fn touched_files(messages: &[uni::Message]) -> BTreeSet<String> {
+    let mut files = BTreeSet::new();
+    // srgn only reports the files it rewrote in its response
+    let mut srgn_calls = HashSet::new();
+    for message in messages {
+        if is_summary_message(message) {
+            let listed = message
+                .content
+                .split_once(FILES_HEADING)
+                .map(|(_, rest)| rest)
+                .unwrap_or_default();
+            files.extend(
+                listed
+                    .lines()
+                    .take_while(|line| !line.starts_with("## "))
+                    .filter_map(|line| line.strip_prefix("- "))
+                    .map(|path| path.trim().to_string()),
+            );
+        }
+
+        if let Some(call_id) = message.tool_call_id.as_ref()
+            && srgn_calls.contains(call_id)
+            && let Ok(result) = serde_json::from_str::<serde_json::Value>(&message.content)
+            && let Some(modified) = result.get("modified_files").and_then(|v| v.as_array())
+        {
+            files.extend(
+                modified
+                    .iter()
+                    .filter_map(|path| path.as_str())
+                    .map(str::to_string),
+            );
+        }
+
+        for call in message.tool_calls.iter().flatten() {
+            let Ok(args) = call.parsed_arguments() else {
+                continue;
+            };
+            match call.function.name.as_str() {
+                tool_names::WRITE_FILE
+                | tool_names::EDIT_FILE
+                | tool_names::CREATE_FILE
+                | tool_names::DELETE_FILE => {
+                    if let Some(path) = ["path", "file_path"]
+                        .iter()
+                        .find_map(|key| args.get(*key).and_then(|value| value.as_str()))
+                    {
+                        files.insert(path.to_string());
+                    }
+                }
+                tool_names::APPLY_PATCH => {
+                    let Some(patch) = args
+                        .get("input")
+                        .and_then(|value| value.as_str())
+                        .and_then(|input| Patch::parse(input).ok())
+                    else {
+                        continue;
+                    };
+                    for operation in patch.operations {
+                        match operation {
+                            PatchOperation::AddFile { path, .. }
+                            | PatchOperation::DeleteFile { path } => {
+                                files.insert(path);
+                            }
+                            PatchOperation::UpdateFile { path, new_path, .. } => {
+                                files.insert(path);
+                                files.extend(new_path);
+                            }
+                        }
+                    }
+                }
+                tool_names::SRGN => {
+                    srgn_calls.insert(call.id.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+    files
+}
+
+fn render_summary_message(
+    summary: &str,
+    ledger_brief: Option<&str>,
+    plan: &TaskPlan,
+    files: &BTreeSet<String>,
+) -> String {
+    let mut out = format!(
+        "{}\nEarlier messages were compacted into this summary.\n\n{}\n",
+        SUMMARY_HEADER, summary
+    );
+
+    if let Some(brief) = ledger_brief {
+        out.push_str(&format!("\n{}\n{}\n", LEDGER_HEADING, brief.trim_end()));
+    }
+
+    if plan.summary.status == PlanCompletionState::InProgress {
+        out.push_str(&format!("\n{}\n", PLAN_HEADING));
+        if let Some(explanation) = plan.explanation.as_deref() {
+            out.push_str(&format!("{}\n", explanation));
+        }
+        for step in &plan.steps {
+            out.push_str(&format!(
+                "- {} {}{}\n",
+                step.status.checkbox(),
+                step.step,
+                step.status.status_note().unwrap_or_default()
+            ));
+        }
+    }
+
+    if !files.is_empty() {
+        out.push_str(&format!("\n{}\n", FILES_HEADING));
+        for file in files {
+            out.push_str(&format!("- {}\n", file));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use vtcode_core::config::cost::CostConfig;
+    use vtcode_core::core::cost_tracker::CostTracker;
+    use vtcode_core::core::tokenizer::EstimatingTokenizer;
+    use vtcode_core::llm::provider::{FinishReason, LLMError, LLMRequest, LLMResponse};
+    use vtcode_core::tools::{PlanManager, PlanStep, StepStatus, UpdatePlanArgs};
+
+    struct SummaryProvider {
+        prompts: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl uni::LLMProvider for SummaryProvider {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn generate(&self, request: LLMRequest) -> Result<LLMResponse, LLMError> {
+            self.prompts
+                .lock()
+                .unwrap()
+                .push(request.messages[0].content.clone());
+            Ok(LLMResponse {
+                content: Some("Goal: fix the flaky parser test.".to_string()),
+                tool_calls: None,
+                usage: None,
+                finish_reason: FinishReason::Stop,
+                reasoning: None,
+            })
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            vec!["mock-model".to_string()]
+        }
+
+        fn validate_request(&self, _request: &LLMRequest) -> Result<(), LLMError> {
+            Ok(())
+        }
+    }
+
+    fn settings(preserve_recent_messages: usize) -> CompactionSettings {
+        CompactionSettings {
+            enabled: true,
+            trigger_tokens: 10,
+            max_summary_tokens: 500,
+            preserve_recent_messages,
+            ledger_entries: Some(5),
+        }
+    }
+
+    fn edit_call(id: &str, path: &str) -> uni::Message {
+        uni::Message::assistant_with_tools(
+            String::new(),
+            vec![uni::ToolCall::function(
+                id.to_string(),
+                tool_names::EDIT_FILE.to_string(),
+                serde_json::json!({ "path": path, "old_str": "a", "new_str": "b" }).to_string(),
+            )],
+        )
+    }
+
+    #[test]
+    fn boundary_keeps_tool_responses_with_their_calls() {
+        let history = vec![
+            uni::Message::user("start".to_string()),
+            edit_call("call_1", "src/lib.rs"),
+            uni::Message::tool_response("call_1".to_string(), "{}".to_string()),
+            uni::Message::assistant("done".to_string()),
+        ];
+        assert_eq!(compaction_boundary(&history, 2), Some(1));
+        assert_eq!(compaction_boundary(&history, 4), None);
+    }
+
+    #[test]
+    fn touched_files_include_patch_headers_and_srgn_results() {
+        let patch = "*** Begin Patch\n*** Add File: src/new.rs\n+fn main() {}\n*** Update File: src/old.rs\n*** Move to: src/moved.rs\n@@\n-a\n+b\n*** Delete File: src/gone.rs\n*** End Patch";
+        let history = vec![
+            uni::Message::assistant_with_tools(
+                String::new(),
+                vec![
+                    uni::ToolCall::function(
+                        "call_1".to_string(),
+                        tool_names::APPLY_PATCH.to_string(),
+                        serde_json::json!({ "input": patch }).to_string(),
+                    ),
+                    uni::ToolCall::function(
+                        "call_2".to_string(),
+                        tool_names::SRGN.to_string(),
+                        serde_json::json!({ "path": "src/*.rs", "scope": "foo" }).to_string(),
+                    ),
+                ],
+            ),
+            uni::Message::tool_response("call_1".to_string(), "{\"success\":true}".to_string()),
+            uni::Message::tool_response(
+                "call_2".to_string(),
+                serde_json::json!({ "success": true, "modified_files": ["src/lib.rs"] })
+                    .to_string(),
+            ),
+        ];
+
+        let files: Vec<_> = touched_files(&history).into_iter().collect();
+        assert_eq!(
+            files,
+            [
+                "src/gone.rs",
+                "src/lib.rs",
+                "src/moved.rs",
+                "src/new.rs",
+                "src/old.rs"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn compaction_swaps_summary_and_carries_state_forward() {
+        let mut history = vec![
+            uni::Message::user("The parser test is flaky".to_string()),
+            edit_call("call_1", "src/parser.rs"),
+            uni::Message::tool_response("call_1".to_string(), "{\"success\":true}".to_string()),
+            uni::Message::assistant("Patched the parser".to_string()),
+            uni::Message::user("Now run the tests".to_string()),
+            uni::Message::assistant("Running them".to_string()),
+        ];
+        let provider = SummaryProvider {
+            prompts: Mutex::new(Vec::new()),
+        };
+        let tokenizer = EstimatingTokenizer::new("test", 4.0);
+        let settings = settings(2);
+        let ledger = RwLock::new(DecisionTracker::new());
+        ledger
+            .write()
+            .await
+            .record_goal("Fix the flaky parser test".to_string());
+        let plan = PlanManager::new()
+            .update_plan(UpdatePlanArgs {
+                explanation: None,
+                plan: vec![
+                    PlanStep {
+                        step: "Patch parser".to_string(),
+                        status: StepStatus::Completed,
+                    },
+                    PlanStep {
+                        step: "Run tests".to_string(),
+                        status: StepStatus::InProgress,
+                    },
+                ],
+            })
+            .unwrap();
+        let cost_tracker = CostTracker::new(&CostConfig::default(), None).into_shared();
+        let session = |plan: TaskPlan| CompactionSession {
+            provider: &provider,
+            model: "mock-model",
+            tokenizer: &tokenizer,
+            settings: &settings,
+            ledger: &ledger,
+            cost_tracker: &cost_tracker,
+            plan,
+        };
+
+        let outcome = compact_history(
+            &mut history,
+            session(plan.clone()),
+            Some("parser"),
+            "manual",
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(outcome.replaced_messages, 4);
+        assert_eq!(history.len(), 3);
+        let summary = &history[0].content;
+        assert!(summary.starts_with(SUMMARY_HEADER));
+        assert!(summary.contains("Goal: fix the flaky parser test."));
+        assert!(summary.contains("Decision Ledger"));
+        assert!(summary.contains("Run tests _(in progress)_"));
+        assert!(summary.contains("- src/parser.rs"));
+        assert_eq!(history[1].content, "Now run the tests");
+        let prompt = provider.prompts.lock().unwrap()[0].clone();
+        assert!(prompt.contains("Pay particular attention to: parser"));
+        assert!(prompt.contains("Tool call: edit_file"));
+
+        history.push(edit_call("call_2", "tests/parser.rs"));
+        history.push(uni::Message::tool_response(
+            "call_2".to_string(),
+            "{}".to_string(),
+        ));
+        history.push(uni::Message::assistant("Tests pass".to_string()));
+        history.push(uni::Message::user("Thanks".to_string()));
+        compact_history(&mut history, session(TaskPlan::default()), None, "auto")
+            .await
+            .unwrap()
+            .unwrap();
+        let summary = &history[0].content;
+        assert!(summary.contains("- src/parser.rs"));
+        assert!(summary.contains("- tests/parser.rs"));
+        assert!(!summary.contains(PLAN_HEADING));
+    }
+}
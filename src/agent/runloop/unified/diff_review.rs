@@ -0,0 +1,273 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use vtcode_core::tools::edit_review::{
+    HunkDecision, ProposedChange, ReviewHunk, ReviewedChange, split_hunks,
+};
+use vtcode_core::ui::diff_renderer::DiffRenderer;
+use vtcode_core::ui::notifications::{NotificationEvent, Notifier};
+use vtcode_core::ui::tui::{InlineEvent, InlineHandle, InlineListItem, InlineListSelection};
+use vtcode_core::utils::ansi::{AnsiRenderer, MessageStyle};
+
+use super::mcp_interaction::next_event;
+use super::turn::CtrlCState;
+
+const CONTEXT_LINES: usize = 3;
+const CHOICE_ACCEPT: &str = "accept";
+const CHOICE_REJECT: &str = "reject";
+const CHOICE_EDIT: &str = "edit";
+const CHOICE_ACCEPT_REST: &str = "accept_rest";
+const CHOICE_REJECT_REST: &str = "reject_rest";
+
+pub(crate) enum ChangeReviewOutcome {
+    /// Every hunk was accepted, so the tool can run unchanged.
+    AcceptAll,
+    Reviewed(Vec<ReviewedChange>),
+    Exit,
+    Interrupted,
+}
+
+enum HunkStep {
+    Decided(HunkDecision),
+    DecideRest(HunkDecision),
+    Exit,
+    Interrupted,
+}
+
+/// Walk the user through every hunk of the proposed changes before they are written.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn review_changes(
+    changes: Vec<ProposedChange>,
+    renderer: &mut AnsiRenderer,
+    handle: &InlineHandle,
+    events: &mut UnboundedReceiver<InlineEvent>,
+    ctrl_c_state: &Arc<CtrlCState>,
+    ctrl_c_notify: &Arc<Notify>,
+    default_placeholder: Option<String>,
+    notifier: &Notifier,
+) -> Result<ChangeReviewOutcome> {
+    let diff_renderer = DiffRenderer::new(true, CONTEXT_LINES, false);
+    let mut reviewed: Vec<ReviewedChange> = changes
+        .into_iter()
+        .map(|change| {
+            let diff =
+                diff_renderer.generate_diff(&change.old_content, &change.new_content, &change.path);
+            ReviewedChange {
+                hunks: split_hunks(&diff, CONTEXT_LINES),
+                decisions: Vec::new(),
+                change,
+            }
+        })
+        .collect();
+
+    let total = reviewed.iter().map(|item| item.hunks.len()).sum::<usize>();
+    if total == 0 {
+        return Ok(ChangeReviewOutcome::AcceptAll);
+    }
+
+    renderer.line_if_not_empty(MessageStyle::Info)?;
+    renderer.line(
+        MessageStyle::Info,
+        &format!("Review {} proposed hunk(s) before they are written.", total),
+    )?;
+    notifier.notify(&NotificationEvent::InputRequested {
+        message: format!("{} proposed hunk(s) are waiting for review.", total),
+    });
+
+    let mut position = 0;
+    let mut rest: Option<HunkDecision> = None;
+    for item in &mut reviewed {
+        for hunk in &item.hunks {
+            position += 1;
+            if let Some(decision) = rest.clone() {
+                item.decisions.push(decision);
+                continue;
+            }
+
+            let title = format!("Review {} (hunk {}/{})", item.change.path, position, total);
+            let step = review_hunk(
+                &title,
+                &item.change,
+                hunk,
+                &diff_renderer,
+                renderer,
+                handle,
+                events,
+                ctrl_c_state,
+                ctrl_c_notify,
+            )
+            .await?;
+            handle.set_placeholder(default_placeholder.clone());
+            match step {
+                HunkStep::Decided(decision) => item.decisions.push(decision),
+                HunkStep::DecideRest(decision) => {
+                    item.decisions.push(decision.clone());
+                    rest = Some(decision);
+                }
+                HunkStep::Exit => return Ok(ChangeReviewOutcome::Exit),
+                HunkStep::Interrupted => return Ok(ChangeReviewOutcome::Interrupted),
+            }
+        }
+    }
+
+    if reviewed.iter().all(ReviewedChange::all_accepted) {
+        return Ok(ChangeReviewOutcome::AcceptAll);
+    }
+    Ok(ChangeReviewOutcome::Reviewed(reviewed))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn review_hunk(
+    title: &str,
+    change: &ProposedChange,
+    hunk: &ReviewHunk,
+    diff_renderer: &DiffRenderer,
+    renderer: &mut AnsiRenderer,
+    handle: &InlineHandle,
+    events: &mut UnboundedReceiver<InlineEvent>,
+    ctrl_c_state: &Arc<CtrlCState>,
+    ctrl_c_notify: &Arc<Notify>,
+) -> Result<HunkStep> {
+    loop {
+        handle.show_list_modal(
+            title.to_string(),
+            hunk_review_lines(change, hunk, diff_renderer),
+            hunk_choices(),
+            Some(InlineListSelection::HunkChoice(CHOICE_ACCEPT.to_string())),
+        );
+
+        let choice = match next_event(events, ctrl_c_state, ctrl_c_notify).await {
+            InlineEvent::ListModalSubmit(InlineListSelection::HunkChoice(choice)) => choice,
+            InlineEvent::ListModalCancel | InlineEvent::Cancel => CHOICE_REJECT.to_string(),
+            InlineEvent::Exit => {
+                handle.close_modal();
+                return Ok(HunkStep::Exit);
+            }
+            InlineEvent::Interrupt => {
+                handle.close_modal();
+                return Ok(HunkStep::Interrupted);
+            }
+            _ => continue,
+        };
+        handle.close_modal();
+
+        match choice.as_str() {
+            CHOICE_ACCEPT => return Ok(HunkStep::Decided(HunkDecision::Accepted)),
+            CHOICE_ACCEPT_REST => return Ok(HunkStep::DecideRest(HunkDecision::Accepted)),
+            CHOICE_REJECT_REST => return Ok(HunkStep::DecideRest(HunkDecision::Rejected)),
+            CHOICE_EDIT => {
+                renderer.line(
+                    MessageStyle::Info,
+                    "Editing the hunk in the input. Shift+Enter or Alt+Enter starts a new line, Enter applies the edit and Esc returns to the review.",
+                )?;
+                handle.set_placeholder(Some(
+                    "Replacement lines for this hunk - Esc to go back".to_string(),
+                ));
+                handle.set_input(hunk.added.join("\n"));
+                loop {
+                    match next_event(events, ctrl_c_state, ctrl_c_notify).await {
+                        InlineEvent::Submit(text) => {
+                            handle.clear_input();
+                            return Ok(HunkStep::Decided(HunkDecision::Edited(text)));
+                        }
+                        InlineEvent::Cancel => {
+                            handle.clear_input();
+                            break;
+                        }
+                        InlineEvent::Exit => return Ok(HunkStep::Exit),
+                        InlineEvent::Interrupt => return Ok(HunkStep::Interrupted),
+                        _ => {}
+                    }
+                }
+            }
+            _ => return Ok(HunkStep::Decided(HunkDecision::Rejected)),
+        }
+    }
+}
+
+fn hunk_review_lines(
+    change: &ProposedChange,
+    hunk: &ReviewHunk,
+    diff_renderer: &DiffRenderer,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    let heading = if change.delete {
+        format!("Delete {}", change.path)
+    } else if let Some(source) = change.source_path.as_deref() {
+        format!("Move {} -> {}", source, change.path)
+    } else if change.old_content.is_empty() {
+        format!("Create {}", change.path)
+    } else {
+        format!(
+            "Edit {} (-{} +{})",
+            change.path,
+            hunk.removed.len(),
+            hunk.added.len()
+        )
+    };
+    lines.push(heading);
+    lines.push(String::new());
+    lines.extend(diff_renderer.render_lines(&hunk.lines));
+    lines
+}
+
+fn hunk_choices() -> Vec<InlineListItem> {
+    vec![
+        choice_item("Accept", "Write this hunk as proposed", CHOICE_ACCEPT),
+        choice_item("Reject", "Keep the current lines", CHOICE_REJECT),
+        choice_item("Edit", "Rewrite the hunk before it is written", CHOICE_EDIT),
+        choice_item(
+            "Accept remaining",
+            "Accept this and every remaining hunk",
+            CHOICE_ACCEPT_REST,
+        ),
+        choice_item(
+            "Reject remaining",
+            "Reject this and every remaining hunk",
+            CHOICE_REJECT_REST,
+        ),
+    ]
+}
+
+fn choice_item(title: &str, subtitle: &str, value: &str) -> InlineListItem {
+    InlineListItem {
+        title: title.to_string(),
+        subtitle: Some(subtitle.to_string()),
+        badge: None,
+        indent: 0,
+        selection: Some(InlineListSelection::HunkChoice(value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn review_lines_show_heading_and_hunk() {
+        let change = ProposedChange {
+            path: "src/lib.rs".to_string(),
+            source_path: None,
+            old_content: "a\nb\nc\n".to_string(),
+            new_content: "a\nB\nc\n".to_string(),
+            delete: false,
+        };
+        let diff_renderer = DiffRenderer::new(false, CONTEXT_LINES, false);
+        let diff = diff_renderer.generate_diff(&change.old_content, &change.new_content, "x");
+        let hunks = split_hunks(&diff, CONTEXT_LINES);
+        let lines = hunk_review_lines(&change, &hunks[0], &diff_renderer);
+        assert_eq!(
+            lines,
+            vec![
+                "Edit src/lib.rs (-1 +1)".to_string(),
+                String::new(),
+                " a".to_string(),
+                "- b".to_string(),
+                "+ B".to_string(),
+                " c".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use vtcode_core::llm::provider as uni;
+use vtcode_core::utils::session_archive::{
+    SessionArchiveMetadata, SessionMessage, SessionSnapshot, SessionUsage,
+};
+use vtcode_core::utils::session_export::{self, ExportFormat};
+use vtcode_core::utils::transcript;
+
+/// Reasoning and usage of each model response, kept beside the conversation so
+/// archives and exports can show them. Entries are keyed by the first tool call id
+/// or the response text, which survive history trimming and compaction.
+#[derive(Default)]
+pub(crate) struct ResponseDetails {
+    entries: HashMap<String, (Option<String>, Option<SessionUsage>)>,
+}
+
+impl ResponseDetails {
+    pub(crate) fn record(
+        &mut self,
+        tool_calls: &[uni::ToolCall],
+        content: Option<&str>,
+        reasoning: Option<String>,
+        usage: Option<SessionUsage>,
+    ) {
+        if reasoning.is_none() && usage.is_none() {
+            return;
+        }
+        let key = match tool_calls.first() {
+            Some(call) => call.id.clone(),
+            None => match content {
+                Some(text) => text.to_string(),
+                None => return,
+            },
+        };
+        self.entries.insert(key, (reasoning, usage));
+    }
+
+    pub(crate) fn session_messages(&self, history: &[uni::Message]) -> Vec<SessionMessage> {
+        history
+            .iter()
+            .map(|message| {
+                let session_message = SessionMessage::from(message);
+                if message.role != uni::MessageRole::Assistant {
+                    return session_message;
+                }
+                let key = match message.tool_calls.as_ref().and_then(|calls| calls.first()) {
+                    Some(call) => call.id.as_str(),
+                    None => message.content.as_str(),
+                };
+                match self.entries.get(key) {
+                    Some((reasoning, usage)) => {
+                        session_message.with_response_details(reasoning.clone(), *usage)
+                    }
+                    None => session_message,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The conversation so far, shaped like a finalized session archive.
+pub(crate) fn conversation_snapshot(
+    metadata: &SessionArchiveMetadata,
+    started_at: DateTime<Utc>,
+    history: &[uni::Message],
+    details: &ResponseDetails,
+    distinct_tools: Vec<String>,
+) -> SessionSnapshot {
+    SessionSnapshot {
+        metadata: metadata.clone(),
+        started_at,
+        ended_at: Utc::now(),
+        total_messages: history.len(),
+        distinct_tools,
+        transcript: transcript::snapshot(),
+        messages: details.session_messages(history),
+        branches: Vec::new(),
+    }
+}
+
+/// Write `snapshot` to `path`, or to a timestamped file in the workspace.
+pub(crate) fn export_snapshot(
+    snapshot: &SessionSnapshot,
+    workspace: &Path,
+    format: ExportFormat,
+    path: Option<PathBuf>,
+) -> Result<PathBuf> {
+    let path = match path {
+        Some(path) if path.is_relative() => workspace.join(path),
+        Some(path) => path,
+        None => workspace.join(session_export::default_file_name(snapshot, format)),
+    };
+    session_export::write_session(snapshot, format, &path)?;
+    Ok(path)
+}
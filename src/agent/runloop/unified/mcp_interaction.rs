@@ -0,0 +1,417 @@
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::task;
+
+use vtcode_core::mcp_interaction::{
+    CreateElicitationResult, ElicitationField, ElicitationFieldKind, ElicitationForm,
+    McpInteraction, SamplingSummary, elicitation_accepted, elicitation_cancelled,
+    elicitation_declined,
+};
+use vtcode_core::ui::notifications::{NotificationEvent, Notifier};
+use vtcode_core::ui::tui::{InlineEvent, InlineHandle, InlineListItem, InlineListSelection};
+use vtcode_core::utils::ansi::{AnsiRenderer, MessageStyle};
+
+use super::turn::{CtrlCState, PlaceholderGuard};
+
+const ELICITATION_TITLE: &str = "MCP input request";
+const SAMPLING_TITLE: &str = "MCP sampling request";
+const CHOICE_ACCEPT: &str = "accept";
+const CHOICE_DECLINE: &str = "decline";
+const CHOICE_CANCEL: &str = "cancel";
+// NUL prefix keeps the sentinel distinct from schema-provided enum values.
+const CHOICE_SKIP: &str = "\u{0}skip";
+const PREVIEW_LIMIT: usize = 160;
+
+/// How the session should continue after an MCP interaction was answered
+pub(crate) enum McpInteractionOutcome {
+    Handled,
+    Exit,
+    Interrupted,
+}
+
+enum FormStep {
+    Value(Option<Value>),
+    Cancelled,
+    Exit,
+    Interrupted,
+}
+
+/// Wait for the next provider request, or forever when MCP is not active
+pub(crate) async fn next_mcp_interaction(
+    receiver: &mut Option<UnboundedReceiver<McpInteraction>>,
+) -> Option<McpInteraction> {
+    match receiver {
+        Some(receiver) => receiver.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn handle_mcp_interaction(
+    interaction: McpInteraction,
+    renderer: &mut AnsiRenderer,
+    handle: &InlineHandle,
+    events: &mut UnboundedReceiver<InlineEvent>,
+    ctrl_c_state: &Arc<CtrlCState>,
+    ctrl_c_notify: &Arc<Notify>,
+    default_placeholder: Option<String>,
+    notifier: &Notifier,
+) -> Result<McpInteractionOutcome> {
+    match interaction {
+        McpInteraction::Elicitation {
+            provider,
+            message,
+            form,
+            responder,
+        } => {
+            renderer.line_if_not_empty(MessageStyle::Info)?;
+            renderer.line(
+                MessageStyle::McpStatus,
+                &format!("MCP: {} is requesting input", provider),
+            )?;
+            notifier.notify(&NotificationEvent::InputRequested {
+                message: format!("MCP server '{}' is requesting input.", provider),
+            });
+            renderer.line(MessageStyle::Info, &message)?;
+
+            let mut lines = vec![message.clone()];
+            if !form.fields.is_empty() {
+                lines.push(String::new());
+                lines.extend(form.fields.iter().map(describe_field));
+            }
+            let items = vec![
+                choice_item("Respond", "Fill in the requested fields", CHOICE_ACCEPT),
+                choice_item(
+                    "Decline",
+                    "Refuse, but let the tool continue",
+                    CHOICE_DECLINE,
+                ),
+                choice_item("Cancel", "Abort the request", CHOICE_CANCEL),
+            ];
+            handle.show_list_modal(
+                ELICITATION_TITLE.to_string(),
+                lines,
+                items,
+                Some(InlineListSelection::McpChoice(CHOICE_ACCEPT.to_string())),
+            );
+
+            let (result, outcome) = match next_event(events, ctrl_c_state, ctrl_c_notify).await {
+                InlineEvent::ListModalSubmit(InlineListSelection::McpChoice(choice))
+                    if choice == CHOICE_ACCEPT =>
+                {
+                    collect_form(
+                        &form,
+                        renderer,
+                        handle,
+                        events,
+                        ctrl_c_state,
+                        ctrl_c_notify,
+                        default_placeholder,
+                    )
+                    .await?
+                }
+                InlineEvent::ListModalSubmit(InlineListSelection::McpChoice(choice))
+                    if choice == CHOICE_DECLINE =>
+                {
+                    (elicitation_declined(), McpInteractionOutcome::Handled)
+                }
+                InlineEvent::Exit => (elicitation_cancelled(), McpInteractionOutcome::Exit),
+                InlineEvent::Interrupt => {
+                    (elicitation_cancelled(), McpInteractionOutcome::Interrupted)
+                }
+                _ => (elicitation_cancelled(), McpInteractionOutcome::Handled),
+            };
+            handle.close_modal();
+
+            let status = if result.content.is_some() {
+                "Sent response to"
+            } else {
+                "Declined input request from"
+            };
+            renderer.line(MessageStyle::Info, &format!("{} {}.", status, provider))?;
+            let _ = responder.send(result);
+            Ok(outcome)
+        }
+        McpInteraction::SamplingApproval {
+            provider,
+            summary,
+            responder,
+        } => {
+            renderer.line_if_not_empty(MessageStyle::Info)?;
+            renderer.line(
+                MessageStyle::McpStatus,
+                &format!(
+                    "MCP: {} wants to run a completion with {}",
+                    provider, summary.model
+                ),
+            )?;
+            notifier.notify(&NotificationEvent::InputRequested {
+                message: format!("MCP server '{}' wants to run a completion.", provider),
+            });
+
+            let items = vec![
+                choice_item("Allow", "Forward this request to the model", CHOICE_ACCEPT),
+                choice_item("Deny", "Reject this request", CHOICE_DECLINE),
+            ];
+            handle.show_list_modal(
+                SAMPLING_TITLE.to_string(),
+                describe_sampling(&provider, &summary),
+                items,
+                Some(InlineListSelection::McpChoice(CHOICE_DECLINE.to_string())),
+            );
+
+            let (approved, outcome) = match next_event(events, ctrl_c_state, ctrl_c_notify).await {
+                InlineEvent::ListModalSubmit(InlineListSelection::McpChoice(choice)) => {
+                    (choice == CHOICE_ACCEPT, McpInteractionOutcome::Handled)
+                }
+                InlineEvent::Exit => (false, McpInteractionOutcome::Exit),
+                InlineEvent::Interrupt => (false, McpInteractionOutcome::Interrupted),
+                _ => (false, McpInteractionOutcome::Handled),
+            };
+            handle.close_modal();
+
+            let status = if approved { "Allowed" } else { "Denied" };
+            renderer.line(
+                MessageStyle::Info,
+                &format!("{} sampling request from {}.", status, provider),
+            )?;
+            let _ = responder.send(approved);
+            Ok(outcome)
+        }
+        // Progress is shown on the running tool's spinner; updates arriving later are stale
+        McpInteraction::Progress(_) => Ok(McpInteractionOutcome::Handled),
+    }
+}
+
+async fn collect_form(
+    form: &ElicitationForm,
+    renderer: &mut AnsiRenderer,
+    handle: &InlineHandle,
+    events: &mut UnboundedReceiver<InlineEvent>,
+    ctrl_c_state: &Arc<CtrlCState>,
+    ctrl_c_notify: &Arc<Notify>,
+    default_placeholder: Option<String>,
+) -> Result<(CreateElicitationResult, McpInteractionOutcome)> {
+    let _placeholder_guard = PlaceholderGuard::new(handle, default_placeholder);
+    let mut content = Map::new();
+
+    for field in &form.fields {
+        let step = match &field.kind {
+            ElicitationFieldKind::Enum { .. } | ElicitationFieldKind::Boolean => {
+                choose_field_value(field, handle, events, ctrl_c_state, ctrl_c_notify).await
+            }
+            _ => {
+                type_field_value(field, renderer, handle, events, ctrl_c_state, ctrl_c_notify)
+                    .await?
+            }
+        };
+
+        match step {
+            FormStep::Value(Some(value)) => {
+                content.insert(field.name.clone(), value);
+            }
+            FormStep::Value(None) => {}
+            FormStep::Cancelled => {
+                return Ok((elicitation_cancelled(), McpInteractionOutcome::Handled));
+            }
+            FormStep::Exit => return Ok((elicitation_cancelled(), McpInteractionOutcome::Exit)),
+            FormStep::Interrupted => {
+                return Ok((elicitation_cancelled(), McpInteractionOutcome::Interrupted));
+            }
+        }
+    }
+
+    if let Err(err) = form.validate(&content) {
+        renderer.line(MessageStyle::Error, &err.to_string())?;
+        return Ok((elicitation_cancelled(), McpInteractionOutcome::Handled));
+    }
+
+    Ok((
+        elicitation_accepted(content),
+        McpInteractionOutcome::Handled,
+    ))
+}
+
+async fn choose_field_value(
+    field: &ElicitationField,
+    handle: &InlineHandle,
+    events: &mut UnboundedReceiver<InlineEvent>,
+    ctrl_c_state: &Arc<CtrlCState>,
+    ctrl_c_notify: &Arc<Notify>,
+) -> FormStep {
+    let mut items: Vec<InlineListItem> = match &field.kind {
+        ElicitationFieldKind::Enum { values, labels } => values
+            .iter()
+            .zip(labels.iter())
+            .map(|(value, label)| choice_item(label, value, value))
+            .collect(),
+        _ => vec![
+            choice_item("Yes", "true", "true"),
+            choice_item("No", "false", "false"),
+        ],
+    };
+    if !field.required && field.default.is_none() {
+        items.push(choice_item("Skip", "Leave this field empty", CHOICE_SKIP));
+    }
+
+    let selected = field
+        .default
+        .as_ref()
+        .map(|value| match value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        })
+        .map(InlineListSelection::McpChoice);
+    handle.show_list_modal(
+        ELICITATION_TITLE.to_string(),
+        vec![describe_field(field)],
+        items,
+        selected,
+    );
+
+    let step = match next_event(events, ctrl_c_state, ctrl_c_notify).await {
+        InlineEvent::ListModalSubmit(InlineListSelection::McpChoice(choice)) => {
+            if choice == CHOICE_SKIP {
+                FormStep::Value(None)
+            } else {
+                match field.parse_input(&choice) {
+                    Ok(value) => FormStep::Value(value),
+                    Err(_) => FormStep::Cancelled,
+                }
+            }
+        }
+        InlineEvent::Exit => FormStep::Exit,
+        InlineEvent::Interrupt => FormStep::Interrupted,
+        _ => FormStep::Cancelled,
+    };
+    handle.close_modal();
+    step
+}
+
+async fn type_field_value(
+    field: &ElicitationField,
+    renderer: &mut AnsiRenderer,
+    handle: &InlineHandle,
+    events: &mut UnboundedReceiver<InlineEvent>,
+    ctrl_c_state: &Arc<CtrlCState>,
+    ctrl_c_notify: &Arc<Notify>,
+) -> Result<FormStep> {
+    renderer.line(MessageStyle::Info, &describe_field(field))?;
+    let hint = if field.required {
+        format!("{} ({}) - Esc to cancel", field.label(), field.type_hint())
+    } else {
+        format!(
+            "{} ({}, optional) - Enter to skip, Esc to cancel",
+            field.label(),
+            field.type_hint()
+        )
+    };
+    handle.set_placeholder(Some(hint));
+    task::yield_now().await;
+
+    loop {
+        match next_event(events, ctrl_c_state, ctrl_c_notify).await {
+            InlineEvent::Submit(input) => {
+                handle.clear_input();
+                match field.parse_input(&input) {
+                    Ok(value) => return Ok(FormStep::Value(value)),
+                    Err(err) => renderer.line(MessageStyle::Error, &err.to_string())?,
+                }
+            }
+            InlineEvent::Cancel => {
+                handle.clear_input();
+                return Ok(FormStep::Cancelled);
+            }
+            InlineEvent::Exit => return Ok(FormStep::Exit),
+            InlineEvent::Interrupt => return Ok(FormStep::Interrupted),
+            InlineEvent::Queue(_)
+            | InlineEvent::Steer(_)
+            | InlineEvent::EditQueued
+            | InlineEvent::EditMessage(_)
+            | InlineEvent::ListModalSubmit(_)
+            | InlineEvent::ListModalCancel
+            | InlineEvent::TogglePlanMode
+            | InlineEvent::ScrollLineUp
+            | InlineEvent::ScrollLineDown
+            | InlineEvent::ScrollPageUp
+            | InlineEvent::ScrollPageDown => {}
+        }
+    }
+}
+
+pub(super) async fn next_event(
+    events: &mut UnboundedReceiver<InlineEvent>,
+    ctrl_c_state: &Arc<CtrlCState>,
+    ctrl_c_notify: &Arc<Notify>,
+) -> InlineEvent {
+    if ctrl_c_state.is_cancel_requested() {
+        return InlineEvent::Interrupt;
+    }
+
+    let notify = ctrl_c_notify.clone();
+    let maybe_event = tokio::select! {
+        _ = notify.notified(), if !ctrl_c_state.is_cancel_requested() => None,
+        event = events.recv() => event,
+    };
+
+    match maybe_event {
+        Some(event) => {
+            ctrl_c_state.disarm_exit();
+            event
+        }
+        None if ctrl_c_state.is_cancel_requested() => InlineEvent::Interrupt,
+        None => InlineEvent::Exit,
+    }
+}
+
+fn choice_item(title: &str, subtitle: &str, value: &str) -> InlineListItem {
+    InlineListItem {
+        title: title.to_string(),
+        subtitle: Some(subtitle.to_string()),
+        badge: None,
+        indent: 0,
+        selection: Some(InlineListSelection::McpChoice(value.to_string())),
+    }
+}
+
+fn describe_field(field: &ElicitationField) -> String {
+    let mut line = format!("• {} ({})", field.label(), field.type_hint());
+    if field.required {
+        line.push_str(" *");
+    }
+    if let Some(description) = &field.description {
+        line.push_str(" - ");
+        line.push_str(description);
+    }
+    line
+}
+
+fn describe_sampling(provider: &str, summary: &SamplingSummary) -> Vec<String> {
+    let mut lines = vec![
+        format!("Provider: {}", provider),
+        format!("Model: {}", summary.model),
+        format!(
+            "Messages: {} • Max tokens: {}",
+            summary.message_count, summary.max_tokens
+        ),
+    ];
+    if let Some(system) = &summary.system_prompt {
+        lines.push(format!("System: {}", preview(system)));
+    }
+    if let Some(last) = &summary.last_message {
+        lines.push(format!("Last message: {}", preview(last)));
+    }
+    lines
+}
+
+fn preview(text: &str) -> String {
+    let condensed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if condensed.chars().count() <= PREVIEW_LIMIT {
+        return condensed;
+    }
+    let truncated: String = condensed.chars().take(PREVIEW_LIMIT).collect();
+    format!("{}…", truncated)
+}
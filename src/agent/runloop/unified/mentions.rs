@@ -0,0 +1,351 @@
+//! `@` mentions of workspace files and symbols in the inline input
+
+use anyhow::{Context, Result, anyhow};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tree_sitter::Tree;
+
+use vtcode_core::llm::provider::Attachment;
+use vtcode_core::tools::file_search::{FileSearchConfig, FileSearcher};
+use vtcode_core::tools::tree_sitter::{SymbolInfo, SymbolKind, TreeSitterAnalyzer};
+use vtcode_core::ui::tui::{InlineMentionCandidate, InlineMentionKind};
+use vtcode_core::utils::vtcodegitignore::VTCodeGitignore;
+
+use super::attachments::{mention_tokens, mention_variants};
+
+const MAX_INDEXED_FILES: usize = 20_000;
+const MAX_SYMBOL_FILES: usize = 2_000;
+const MAX_SYMBOL_FILE_BYTES: u64 = 256 * 1024;
+const MAX_CONTEXT_BYTES: usize = 64 * 1024;
+const SYMBOL_SEPARATOR: char = '#';
+const CONTEXT_OPEN: &str = "<context";
+const CONTEXT_CLOSE: &str = "</context>";
+
+/// Text context gathered from `@path` and `@path#symbol` mentions.
+#[derive(Default)]
+pub(crate) struct MentionContext {
+    pub(crate) blocks: Vec<String>,
+    pub(crate) attached: Vec<String>,
+    pub(crate) errors: Vec<String>,
+}
+
+impl MentionContext {
+    /// The user message with every attached block after the typed text.
+    pub(crate) fn append_to(&self, message: &str) -> String {
+        if self.blocks.is_empty() {
+            return message.to_string();
+        }
+        let mut combined = message.to_string();
+        for block in &self.blocks {
+            combined.push_str("\n\n");
+            combined.push_str(block);
+        }
+        combined
+    }
+}
+
+/// Workspace files and the symbols defined in them, for `@` completion.
+pub(crate) fn build_mention_candidates(workspace: &Path) -> Vec<InlineMentionCandidate> {
+    let config = FileSearchConfig {
+        max_results: MAX_INDEXED_FILES,
+        ..FileSearchConfig::default()
+    };
+    let files = match FileSearcher::new(workspace.to_path_buf(), config).search_files(None) {
+        Ok(files) => files,
+        Err(error) => {
+            tracing::warn!(%error, "failed to index workspace files for @ mentions");
+            return Vec::new();
+        }
+    };
+
+    let mut analyzer = TreeSitterAnalyzer::new().ok();
+    let mut parsed_files = 0;
+    let mut candidates = Vec::new();
+    let mut symbols = Vec::new();
+    for file in files.iter().filter(|file| !file.is_dir) {
+        let relative = relative_path(workspace, &file.path);
+        // Mentions end at whitespace, so such paths could never be resolved.
+        if relative.contains(char::is_whitespace) {
+            continue;
+        }
+        candidates.push(InlineMentionCandidate {
+            kind: InlineMentionKind::File,
+            label: relative.clone(),
+            insert: relative.clone(),
+            detail: None,
+        });
+
+        if parsed_files >= MAX_SYMBOL_FILES || file.size > MAX_SYMBOL_FILE_BYTES {
+            continue;
+        }
+        let Some(analyzer) = analyzer.as_mut() else {
+            continue;
+        };
+        if analyzer.detect_language_from_path(&file.path).is_err() {
+            continue;
+        }
+        parsed_files += 1;
+        let Ok(source) = std::fs::read_to_string(&file.path) else {
+            continue;
+        };
+        let Ok((_, file_symbols)) = definitions(analyzer, &file.path, &source) else {
+            continue;
+        };
+        let mut seen = HashSet::new();
+        for symbol in file_symbols {
+            if !is_mentionable(&symbol.kind) || !seen.insert(symbol.name.clone()) {
+                continue;
+            }
+            symbols.push(InlineMentionCandidate {
+                kind: InlineMentionKind::Symbol,
+                insert: format!("{}{}{}", relative, SYMBOL_SEPARATOR, symbol.name),
+                detail: Some(format!("{}:{}", relative, symbol.position.row + 1)),
+                label: symbol.name,
+            });
+        }
+    }
+
+    candidates.extend(symbols);
+    candidates
+}
+
+/// Expand `@path` and `@path#symbol` mentions of workspace text files into context blocks.
+///
+/// Images and PDFs are left to `collect_input_attachments`; mentions that do not
+/// name a workspace file are treated as plain text. Files excluded by
+/// `.vtcodegitignore` are reported as errors rather than attached.
+pub(crate) async fn collect_mention_context(input: &str, workspace: &Path) -> MentionContext {
+    let mut context = MentionContext::default();
+    let Ok(root) = workspace.canonicalize() else {
+        return context;
+    };
+    let ignore = match VTCodeGitignore::from_directory(&root).await {
+        Ok(ignore) => Some(ignore),
+        Err(error) => {
+            tracing::warn!(%error, "failed to load .vtcodegitignore for @ mentions");
+            None
+        }
+    };
+    let mut seen: HashSet<(PathBuf, Option<String>)> = HashSet::new();
+
+    for raw in mention_tokens(input) {
+        let Some((path, symbol)) = mention_variants(raw)
+            .into_iter()
+            .find_map(|candidate| resolve_mention(candidate, &root))
+        else {
+            continue;
+        };
+        if Attachment::media_type_for_path(&path).is_some()
+            || !seen.insert((path.clone(), symbol.clone()))
+        {
+            continue;
+        }
+
+        let relative = relative_path(&root, &path);
+        let name = match symbol.as_deref() {
+            Some(symbol) => format!("{}{}{}", relative, SYMBOL_SEPARATOR, symbol),
+            None => relative,
+        };
+        if ignore
+            .as_ref()
+            .is_some_and(|ignore| ignore.should_exclude(&path))
+        {
+            context
+                .errors
+                .push(format!("{}: excluded by .vtcodegitignore", name));
+            continue;
+        }
+        match render_mention(&path, symbol.as_deref(), &name) {
+            Ok(block) => {
+                context.blocks.push(block);
+                context.attached.push(name);
+            }
+            Err(error) => context.errors.push(format!("{}: {}", name, error)),
+        }
+    }
+
+    context
+}
+
+fn resolve_mention(candidate: &str, root: &Path) -> Option<(PathBuf, Option<String>)> {
+    let (path, symbol) = match candidate.split_once(SYMBOL_SEPARATOR) {
+        Some((path, symbol)) if !symbol.is_empty() => (path, Some(symbol.to_string())),
+        _ => (candidate, None),
+    };
+    if path.is_empty() {
+        return None;
+    }
+    let path = Path::new(path);
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        root.join(path)
+    };
+    let canonical = joined.canonicalize().ok()?;
+    (canonical.starts_with(root) && canonical.is_file()).then_some((canonical, symbol))
+}
+
+fn render_mention(path: &Path, symbol: Option<&str>, name: &str) -> Result<String> {
+    let bytes = std::fs::read(path).with_context(|| format!("failed to read {}", name))?;
+    let source = String::from_utf8(bytes).map_err(|_| anyhow!("not a text file"))?;
+    let mut uri = format!("file://{}", path.display());
+    let body = match symbol {
+        Some(symbol) => {
+            let (start, end, body) = symbol_source(path, &source, symbol)?;
+            uri.push_str(&format!("#L{}-L{}", start, end));
+            body
+        }
+        None => source,
+    };
+    Ok(context_block(name, &uri, &truncate(&body)))
+}
+
+/// Source of the first definition named `name`, with its 1-based line range.
+fn symbol_source(path: &Path, source: &str, name: &str) -> Result<(usize, usize, String)> {
+    let mut analyzer = TreeSitterAnalyzer::new()?;
+    let (tree, symbols) = definitions(&mut analyzer, path, source)?;
+    let symbol = symbols
+        .iter()
+        .find(|symbol| symbol.name == name && is_mentionable(&symbol.kind))
+        .ok_or_else(|| anyhow!("no definition named '{}'", name))?;
+
+    let offset = symbol.position.byte_offset;
+    let mut node = tree
+        .root_node()
+        .descendant_for_byte_range(offset, offset)
+        .ok_or_else(|| anyhow!("no syntax node for '{}'", name))?;
+    // Climb to the outermost node that starts at the definition, stopping below the file root.
+    while let Some(parent) = node.parent() {
+        if parent.start_byte() != offset || parent.parent().is_none() {
+            break;
+        }
+        node = parent;
+    }
+    Ok((
+        node.start_position().row + 1,
+        node.end_position().row + 1,
+        source[node.byte_range()].to_string(),
+    ))
+}
+
+fn definitions(
+    analyzer: &mut TreeSitterAnalyzer,
+    path: &Path,
+    source: &str,
+) -> Result<(Tree, Vec<SymbolInfo>)> {
+    let language = analyzer.detect_language_from_path(path)?;
+    let tree = analyzer.parse(source, language)?;
+    let symbols = analyzer.extract_symbols(&tree, source, language)?;
+    Ok((tree, symbols))
+}
+
+fn is_mentionable(kind: &SymbolKind) -> bool {
+    !matches!(kind, SymbolKind::Import | SymbolKind::Variable)
+}
+
+fn truncate(body: &str) -> String {
+    if body.len() <= MAX_CONTEXT_BYTES {
+        return body.to_string();
+    }
+    let mut end = MAX_CONTEXT_BYTES;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}\n… truncated ({} of {} bytes shown)",
+        &body[..end],
+        end,
+        body.len()
+    )
+}
+
+/// Same framing the ACP bridge uses for resource links.
+fn context_block(name: &str, uri: &str, body: &str) -> String {
+    let mut rendered = format!("{} uri=\"{}\" name=\"{}\">\n", CONTEXT_OPEN, uri, name);
+    rendered.push_str(body);
+    if !body.ends_with('\n') {
+        rendered.push('\n');
+    }
+    rendered.push_str(CONTEXT_CLOSE);
+    rendered
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn expands_file_and_symbol_mentions() {
+        let workspace = tempfile::tempdir().unwrap();
+        std::fs::create_dir(workspace.path().join("src")).unwrap();
+        std::fs::write(
+            workspace.path().join("src/lib.rs"),
+            "use std::fmt;\n\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\nfn other() {}\n",
+        )
+        .unwrap();
+        std::fs::write(workspace.path().join("notes.md"), "# Notes\n").unwrap();
+        std::fs::write(workspace.path().join("shot.png"), [0x89, b'P', b'N', b'G']).unwrap();
+
+        let input = "see @notes.md, @src/lib.rs#add and @shot.png @src/lib.rs#missing @someone";
+        let context = collect_mention_context(input, workspace.path()).await;
+
+        assert_eq!(context.attached, vec!["notes.md", "src/lib.rs#add"]);
+        assert_eq!(context.errors.len(), 1);
+        assert!(context.errors[0].contains("missing"));
+        assert!(context.blocks[0].ends_with("name=\"notes.md\">\n# Notes\n</context>"));
+        assert!(context.blocks[1].contains("#L3-L5\" name=\"src/lib.rs#add\">"));
+        assert!(
+            context.blocks[1]
+                .contains("\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n</context>")
+        );
+
+        let message = context.append_to("see");
+        assert!(message.starts_with("see\n\n<context uri=\"file://"));
+    }
+
+    #[test]
+    fn candidates_cover_files_and_symbols() {
+        let workspace = tempfile::tempdir().unwrap();
+        std::fs::write(
+            workspace.path().join("main.rs"),
+            "struct Config;\nfn main() {}\n",
+        )
+        .unwrap();
+        std::fs::write(workspace.path().join(".vtcodegitignore"), "secret.txt\n").unwrap();
+        std::fs::write(workspace.path().join("secret.txt"), "hidden").unwrap();
+
+        let candidates = build_mention_candidates(workspace.path());
+        let inserts: Vec<&str> = candidates.iter().map(|c| c.insert.as_str()).collect();
+
+        assert!(inserts.contains(&"main.rs"));
+        assert!(inserts.contains(&"main.rs#main"));
+        assert!(inserts.contains(&"main.rs#Config"));
+        assert!(!inserts.contains(&"secret.txt"));
+    }
+
+    #[tokio::test]
+    async fn skips_mentions_excluded_by_vtcodegitignore() {
+        let workspace = tempfile::tempdir().unwrap();
+        std::fs::write(workspace.path().join(".vtcodegitignore"), "secret.txt\n").unwrap();
+        std::fs::write(workspace.path().join("secret.txt"), "hidden").unwrap();
+        std::fs::write(workspace.path().join("notes.md"), "# Notes\n").unwrap();
+
+        let context =
+            collect_mention_context("read @secret.txt and @notes.md", workspace.path()).await;
+
+        assert_eq!(context.attached, vec!["notes.md"]);
+        assert_eq!(context.blocks.len(), 1);
+        assert!(!context.blocks[0].contains("hidden"));
+        assert_eq!(
+            context.errors,
+            vec!["secret.txt: excluded by .vtcodegitignore".to_string()]
+        );
+    }
+}
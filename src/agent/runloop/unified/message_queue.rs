@@ -0,0 +1,352 @@
+use std::collections::{HashSet, VecDeque};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use vtcode_core::llm::provider as uni;
+use vtcode_core::ui::tui::{InlineEvent, InlineHandle};
+
+const MESSAGE_SEPARATOR: &str = "\n\n";
+
+/// How a running turn should stop after the user acted on the input.
+enum TurnInterruption {
+    Cancel,
+    /// Abandon the turn and submit this message instead.
+    Replace(String),
+}
+
+/// Messages typed while the agent is working, mirrored in the TUI above the input.
+#[derive(Default)]
+pub(crate) struct MessageQueue {
+    messages: Vec<String>,
+    deferred: VecDeque<InlineEvent>,
+}
+
+impl MessageQueue {
+    /// Handle every event that arrived while the agent was busy and report whether the
+    /// running turn should stop. A steered message is left in `replacement`.
+    pub(crate) fn interrupts_turn(
+        &mut self,
+        events: &mut UnboundedReceiver<InlineEvent>,
+        handle: &InlineHandle,
+        replacement: &mut Option<String>,
+    ) -> bool {
+        match self.drain(events, handle) {
+            Some(TurnInterruption::Replace(text)) => {
+                *replacement = Some(text);
+                true
+            }
+            Some(TurnInterruption::Cancel) => true,
+            None => false,
+        }
+    }
+
+    fn drain(
+        &mut self,
+        events: &mut UnboundedReceiver<InlineEvent>,
+        handle: &InlineHandle,
+    ) -> Option<TurnInterruption> {
+        while let Ok(event) = events.try_recv() {
+            if let Some(interruption) = self.handle_event(event, handle) {
+                return Some(interruption);
+            }
+        }
+        None
+    }
+
+    fn handle_event(
+        &mut self,
+        event: InlineEvent,
+        handle: &InlineHandle,
+    ) -> Option<TurnInterruption> {
+        match event {
+            InlineEvent::Submit(text) | InlineEvent::Queue(text) => self.push(text, handle),
+            InlineEvent::EditQueued => {
+                if let Some(text) = self.messages.pop() {
+                    handle.set_input(text);
+                    self.sync(handle);
+                }
+            }
+            InlineEvent::Steer(text) => {
+                self.push(text, handle);
+                return Some(match self.take_all(handle) {
+                    Some(replacement) => TurnInterruption::Replace(replacement),
+                    None => TurnInterruption::Cancel,
+                });
+            }
+            InlineEvent::Cancel | InlineEvent::Interrupt => {
+                return Some(TurnInterruption::Cancel);
+            }
+            InlineEvent::Exit => {
+                self.deferred.push_back(event);
+                return Some(TurnInterruption::Cancel);
+            }
+            InlineEvent::TogglePlanMode => self.deferred.push_back(event),
+            InlineEvent::EditMessage(_)
+            | InlineEvent::ListModalSubmit(_)
+            | InlineEvent::ListModalCancel
+            | InlineEvent::ScrollLineUp
+            | InlineEvent::ScrollLineDown
+            | InlineEvent::ScrollPageUp
+            | InlineEvent::ScrollPageDown => {}
+        }
+        None
+    }
+
+    /// Events that only make sense between turns, such as toggling plan mode.
+    pub(crate) fn take_deferred(&mut self) -> Option<InlineEvent> {
+        self.deferred.pop_front()
+    }
+
+    /// Queued guidance for the model, leaving slash commands for after the turn.
+    pub(crate) fn take_guidance(&mut self, handle: &InlineHandle) -> Option<String> {
+        let (commands, guidance): (Vec<String>, Vec<String>) = std::mem::take(&mut self.messages)
+            .into_iter()
+            .partition(|message| message.starts_with('/'));
+        self.messages = commands;
+        if guidance.is_empty() {
+            return None;
+        }
+        self.sync(handle);
+        Some(guidance.join(MESSAGE_SEPARATOR))
+    }
+
+    /// The next message to submit once the agent is idle: a slash command on its
+    /// own, or the queued guidance up to the next command.
+    pub(crate) fn take_submission(&mut self, handle: &InlineHandle) -> Option<String> {
+        let first = self.messages.first()?;
+        let count = if first.starts_with('/') {
+            1
+        } else {
+            self.messages
+                .iter()
+                .take_while(|message| !message.starts_with('/'))
+                .count()
+        };
+        let submission = self
+            .messages
+            .drain(..count)
+            .collect::<Vec<_>>()
+            .join(MESSAGE_SEPARATOR);
+        self.sync(handle);
+        Some(submission)
+    }
+
+    /// Everything still queued, as a single message.
+    pub(crate) fn take_all(&mut self, handle: &InlineHandle) -> Option<String> {
+        if self.messages.is_empty() {
+            return None;
+        }
+        let combined = std::mem::take(&mut self.messages).join(MESSAGE_SEPARATOR);
+        self.sync(handle);
+        Some(combined)
+    }
+
+    fn push(&mut self, text: String, handle: &InlineHandle) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        handle.record_history(text.to_string());
+        self.messages.push(text.to_string());
+        self.sync(handle);
+    }
+
+    fn sync(&self, handle: &InlineHandle) {
+        handle.set_queued_messages(self.messages.clone());
+    }
+}
+
+/// Length of the longest prefix of `history` whose tool calls all have responses, so
+/// a steered turn can keep the work it finished without leaving dangling calls.
+pub(crate) fn completed_exchange_len(history: &[uni::Message]) -> usize {
+    let mut pending: HashSet<&str> = HashSet::new();
+    let mut completed = 0;
+    for (index, message) in history.iter().enumerate() {
+        if let Some(calls) = &message.tool_calls {
+            pending.extend(calls.iter().map(|call| call.id.as_str()));
+        }
+        if message.role == uni::MessageRole::Tool
+            && let Some(id) = message.tool_call_id.as_deref()
+            && pending.remove(id)
+            && pending.is_empty()
+        {
+            completed = index + 1;
+        }
+    }
+    completed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+    use vtcode_core::ui::tui::InlineCommand;
+
+    struct Harness {
+        queue: MessageQueue,
+        handle: InlineHandle,
+        commands: UnboundedReceiver<InlineCommand>,
+        sender: UnboundedSender<InlineEvent>,
+        events: UnboundedReceiver<InlineEvent>,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            let (command_tx, commands) = unbounded_channel();
+            let (sender, events) = unbounded_channel();
+            Self {
+                queue: MessageQueue::default(),
+                handle: InlineHandle::new(command_tx),
+                commands,
+                sender,
+                events,
+            }
+        }
+
+        fn send(&self, event: InlineEvent) {
+            self.sender.send(event).unwrap();
+        }
+
+        fn interrupts(&mut self, replacement: &mut Option<String>) -> bool {
+            self.queue
+                .interrupts_turn(&mut self.events, &self.handle, replacement)
+        }
+
+        fn restored_input(&mut self) -> Option<String> {
+            let mut input = None;
+            while let Ok(command) = self.commands.try_recv() {
+                if let InlineCommand::SetInput(content) = command {
+                    input = Some(content);
+                }
+            }
+            input
+        }
+    }
+
+    #[test]
+    fn queued_messages_do_not_interrupt() {
+        let mut harness = Harness::new();
+        harness.send(InlineEvent::Queue("first".to_string()));
+        harness.send(InlineEvent::Submit("  second  ".to_string()));
+        harness.send(InlineEvent::Queue("   ".to_string()));
+
+        let mut replacement = None;
+        assert!(!harness.interrupts(&mut replacement));
+        assert!(replacement.is_none());
+        assert_eq!(harness.queue.messages, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn guidance_leaves_slash_commands_queued() {
+        let mut harness = Harness::new();
+        for text in ["look at tests", "/model", "also docs"] {
+            harness.send(InlineEvent::Queue(text.to_string()));
+        }
+        let mut replacement = None;
+        harness.interrupts(&mut replacement);
+
+        assert_eq!(
+            harness.queue.take_guidance(&harness.handle).as_deref(),
+            Some("look at tests\n\nalso docs")
+        );
+        assert_eq!(harness.queue.messages, vec!["/model"]);
+        assert!(harness.queue.take_guidance(&harness.handle).is_none());
+    }
+
+    #[test]
+    fn submissions_split_at_slash_commands() {
+        let mut harness = Harness::new();
+        for text in ["one", "two", "/clear", "/status", "three"] {
+            harness.send(InlineEvent::Queue(text.to_string()));
+        }
+        let mut replacement = None;
+        harness.interrupts(&mut replacement);
+
+        let handle = harness.handle.clone();
+        let mut submissions = Vec::new();
+        while let Some(submission) = harness.queue.take_submission(&handle) {
+            submissions.push(submission);
+        }
+        assert_eq!(
+            submissions,
+            vec!["one\n\ntwo", "/clear", "/status", "three"]
+        );
+    }
+
+    #[test]
+    fn steer_replaces_turn_with_everything_queued() {
+        let mut harness = Harness::new();
+        harness.send(InlineEvent::Queue("first".to_string()));
+        harness.send(InlineEvent::Steer("actually this".to_string()));
+        harness.send(InlineEvent::Queue("later".to_string()));
+
+        let mut replacement = None;
+        assert!(harness.interrupts(&mut replacement));
+        assert_eq!(replacement.as_deref(), Some("first\n\nactually this"));
+        // Events after the steer stay in the channel for the next drain.
+        assert!(!harness.interrupts(&mut replacement));
+        assert_eq!(harness.queue.messages, vec!["later"]);
+    }
+
+    #[test]
+    fn empty_steer_cancels_without_replacement() {
+        let mut harness = Harness::new();
+        harness.send(InlineEvent::Steer(" ".to_string()));
+
+        let mut replacement = None;
+        assert!(harness.interrupts(&mut replacement));
+        assert!(replacement.is_none());
+    }
+
+    #[test]
+    fn exit_and_plan_toggle_are_deferred() {
+        let mut harness = Harness::new();
+        harness.send(InlineEvent::TogglePlanMode);
+
+        let mut replacement = None;
+        assert!(!harness.interrupts(&mut replacement));
+        assert!(matches!(
+            harness.queue.take_deferred(),
+            Some(InlineEvent::TogglePlanMode)
+        ));
+
+        harness.send(InlineEvent::Exit);
+        assert!(harness.interrupts(&mut replacement));
+        assert!(matches!(
+            harness.queue.take_deferred(),
+            Some(InlineEvent::Exit)
+        ));
+        assert!(harness.queue.take_deferred().is_none());
+    }
+
+    #[test]
+    fn edit_queued_moves_last_message_back_to_input() {
+        let mut harness = Harness::new();
+        harness.send(InlineEvent::Queue("keep".to_string()));
+        harness.send(InlineEvent::Queue("fix typo".to_string()));
+        harness.send(InlineEvent::EditQueued);
+
+        let mut replacement = None;
+        assert!(!harness.interrupts(&mut replacement));
+        assert_eq!(harness.queue.messages, vec!["keep"]);
+        assert_eq!(harness.restored_input().as_deref(), Some("fix typo"));
+    }
+
+    #[test]
+    fn completed_exchanges_exclude_unanswered_calls() {
+        let call = |id: &str| {
+            uni::ToolCall::function(id.to_string(), "read_file".to_string(), "{}".to_string())
+        };
+        let history = vec![
+            uni::Message::user("start".to_string()),
+            uni::Message::assistant_with_tools(String::new(), vec![call("a"), call("b")]),
+            uni::Message::tool_response("a".to_string(), "ok".to_string()),
+            uni::Message::tool_response("b".to_string(), "ok".to_string()),
+            uni::Message::user("guidance".to_string()),
+            uni::Message::assistant_with_tools(String::new(), vec![call("c"), call("d")]),
+            uni::Message::tool_response("c".to_string(), "ok".to_string()),
+        ];
+
+        assert_eq!(completed_exchange_len(&history), 4);
+        assert_eq!(completed_exchange_len(&history[..1]), 0);
+    }
+}
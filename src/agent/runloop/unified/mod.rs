@@ -1,4 +1,13 @@
+mod attachments;
+mod branches;
+mod compaction;
+mod diff_review;
 mod display;
+mod export;
+mod mcp_interaction;
+mod mentions;
+mod message_queue;
+mod plan_mode;
 mod prompts;
 mod session_setup;
 mod shell;
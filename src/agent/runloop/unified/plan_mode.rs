@@ -0,0 +1,173 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use vtcode_core::tools::TaskPlan;
+use vtcode_core::ui::notifications::{NotificationEvent, Notifier};
+use vtcode_core::ui::tui::{InlineEvent, InlineHandle, InlineListItem, InlineListSelection};
+use vtcode_core::utils::ansi::{AnsiRenderer, MessageStyle};
+
+use super::mcp_interaction::next_event;
+use super::turn::CtrlCState;
+
+const REVIEW_TITLE: &str = "Review plan";
+const CHOICE_APPROVE: &str = "approve";
+const CHOICE_EDIT: &str = "edit";
+const CHOICE_REJECT: &str = "reject";
+
+/// Appended to the system prompt while plan mode is active.
+pub(crate) const PLAN_MODE_INSTRUCTIONS: &str = "## PLAN MODE\nPlan mode is active: only read-only tools are available and nothing may be changed yet. Explore the workspace as needed, then record the proposed steps with `update_plan` (all `pending`) and finish with a short summary of the approach, risks and open questions. The user will approve, edit or reject the plan before any edits are allowed.";
+
+/// Sent back to the model when it finishes a plan-mode turn without recording a plan.
+pub(crate) const PLAN_MODE_REMINDER: &str = "Plan mode is still active and no plan has been recorded. Call `update_plan` with the proposed steps now instead of making changes.";
+
+/// Submitted on the user's behalf once the plan is approved.
+pub(crate) const PLAN_APPROVED_MESSAGE: &str =
+    "The plan is approved. Implement it now and keep `update_plan` current as steps complete.";
+
+pub(crate) enum PlanReviewDecision {
+    Approved,
+    Edit,
+    Rejected,
+    Exit,
+    Interrupted,
+}
+
+/// Show the recorded plan in a modal and wait for the user's verdict.
+pub(crate) async fn review_plan(
+    plan: &TaskPlan,
+    renderer: &mut AnsiRenderer,
+    handle: &InlineHandle,
+    events: &mut UnboundedReceiver<InlineEvent>,
+    ctrl_c_state: &Arc<CtrlCState>,
+    ctrl_c_notify: &Arc<Notify>,
+    notifier: &Notifier,
+) -> Result<PlanReviewDecision> {
+    renderer.line_if_not_empty(MessageStyle::Info)?;
+    renderer.line(
+        MessageStyle::Info,
+        "Plan ready for review. Approve it to leave plan mode and start editing.",
+    )?;
+    notifier.notify(&NotificationEvent::InputRequested {
+        message: "The plan is waiting for review.".to_string(),
+    });
+
+    let items = vec![
+        choice_item(
+            "Approve",
+            "Leave plan mode and implement the plan",
+            CHOICE_APPROVE,
+        ),
+        choice_item(
+            "Edit",
+            "Describe changes and get a revised plan",
+            CHOICE_EDIT,
+        ),
+        choice_item(
+            "Reject",
+            "Discard the plan and stay in plan mode",
+            CHOICE_REJECT,
+        ),
+    ];
+    handle.show_list_modal(
+        REVIEW_TITLE.to_string(),
+        plan_review_lines(plan),
+        items,
+        Some(InlineListSelection::PlanChoice(CHOICE_APPROVE.to_string())),
+    );
+
+    let decision = match next_event(events, ctrl_c_state, ctrl_c_notify).await {
+        InlineEvent::ListModalSubmit(InlineListSelection::PlanChoice(choice)) => {
+            match choice.as_str() {
+                CHOICE_APPROVE => PlanReviewDecision::Approved,
+                CHOICE_EDIT => PlanReviewDecision::Edit,
+                _ => PlanReviewDecision::Rejected,
+            }
+        }
+        InlineEvent::Exit => PlanReviewDecision::Exit,
+        InlineEvent::Interrupt => PlanReviewDecision::Interrupted,
+        _ => PlanReviewDecision::Rejected,
+    };
+    handle.close_modal();
+    Ok(decision)
+}
+
+pub(crate) fn plan_review_lines(plan: &TaskPlan) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(explanation) = plan.explanation.as_deref() {
+        lines.push(explanation.to_string());
+        lines.push(String::new());
+    }
+    lines.extend(
+        plan.steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| format!("{}. {}", index + 1, step.step)),
+    );
+    lines
+}
+
+/// Pre-filled input for the Edit choice, so the user only changes what they disagree with.
+pub(crate) fn plan_revision_draft(plan: &TaskPlan) -> String {
+    let steps = plan
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(index, step)| format!("{}. {}", index + 1, step.step))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!("Revise the plan: {}", steps)
+}
+
+fn choice_item(title: &str, subtitle: &str, value: &str) -> InlineListItem {
+    InlineListItem {
+        title: title.to_string(),
+        subtitle: Some(subtitle.to_string()),
+        badge: None,
+        indent: 0,
+        selection: Some(InlineListSelection::PlanChoice(value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vtcode_core::tools::{PlanManager, PlanStep, StepStatus, UpdatePlanArgs};
+
+    fn sample_plan() -> TaskPlan {
+        PlanManager::new()
+            .update_plan(UpdatePlanArgs {
+                explanation: Some("Split the parser".to_string()),
+                plan: vec![
+                    PlanStep {
+                        step: "Extract tokenizer".to_string(),
+                        status: StepStatus::Pending,
+                    },
+                    PlanStep {
+                        step: "Add tests".to_string(),
+                        status: StepStatus::Pending,
+                    },
+                ],
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn review_lines_number_the_steps() {
+        let lines = plan_review_lines(&sample_plan());
+        assert_eq!(
+            lines,
+            vec![
+                "Split the parser".to_string(),
+                String::new(),
+                "1. Extract tokenizer".to_string(),
+                "2. Add tests".to_string(),
+            ]
+        );
+        assert_eq!(
+            plan_revision_draft(&sample_plan()),
+            "Revise the plan: 1. Extract tokenizer 2. Add tests"
+        );
+    }
+}
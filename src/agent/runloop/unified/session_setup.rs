@@ -6,13 +6,16 @@ use vtcode_core::config::types::AgentConfig as CoreAgentConfig;
 use vtcode_core::core::context_curator::{
     ContextCurationConfig as RuntimeContextCurationConfig, ContextCurator,
 };
+use vtcode_core::core::cost_tracker::{CostTracker, SharedCostTracker};
 use vtcode_core::core::decision_tracker::DecisionTracker;
 use vtcode_core::core::token_budget::{
     TokenBudgetConfig as RuntimeTokenBudgetConfig, TokenBudgetManager,
 };
+use vtcode_core::core::tokenizer::ProviderTokenizer;
 use vtcode_core::core::trajectory::TrajectoryLogger;
+use vtcode_core::llm::fallback::{FallbackProvider, FallbackStatus, create_fallback_targets};
 use vtcode_core::llm::{factory::create_provider_with_config, provider as uni};
-use vtcode_core::models::ModelId;
+use vtcode_core::models::{ModelId, Provider};
 use vtcode_core::tools::ToolRegistry;
 use vtcode_core::tools::build_function_declarations_with_mode;
 
@@ -24,11 +27,16 @@ use crate::agent::runloop::telemetry::build_trajectory_logger;
 use crate::agent::runloop::welcome::{SessionBootstrap, prepare_session_bootstrap};
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
 use vtcode_core::mcp_client::McpClient;
+use vtcode_core::mcp_interaction::{McpInteraction, McpSamplingBackend};
 
 pub(crate) struct SessionState {
     pub session_bootstrap: SessionBootstrap,
     pub provider_client: Box<dyn uni::LLMProvider>,
+    pub fallback_status: Option<Arc<FallbackStatus>>,
+    pub fallback_warnings: Vec<String>,
+    pub cost_tracker: SharedCostTracker,
     pub tool_registry: ToolRegistry,
     pub tools: Vec<uni::ToolDefinition>,
     pub trim_config: ContextTrimConfig,
@@ -38,6 +46,7 @@ pub(crate) struct SessionState {
     pub base_system_prompt: String,
     pub full_auto_allowlist: Option<Vec<String>>,
     pub mcp_client: Option<Arc<McpClient>>,
+    pub mcp_interactions: Option<UnboundedReceiver<McpInteraction>>,
     pub mcp_panel_state: mcp_events::McpPanelState,
     pub token_budget: Arc<TokenBudgetManager>,
     pub token_budget_enabled: bool,
@@ -130,6 +139,26 @@ pub(crate) async fn initialize_session(
     )
     .context("Failed to initialize provider client")?;
 
+    let (provider_client, fallback_status, fallback_warnings) =
+        wrap_with_fallback(provider_client, &provider_name, config, vt_cfg);
+    let cost_tracker = CostTracker::new(
+        &vt_cfg.map(|cfg| cfg.cost.clone()).unwrap_or_default(),
+        CostTracker::default_ledger_path(),
+    )
+    .into_shared();
+
+    // Route provider-initiated elicitation and sampling requests to the inline UI
+    let mcp_interactions = if let Some(mcp_client) = &mcp_client {
+        let (interaction_tx, interaction_rx) = mpsc::unbounded_channel();
+        mcp_client.set_interaction_sender(interaction_tx);
+
+        install_sampling_backend(mcp_client, &provider_name, config, vt_cfg, &cost_tracker);
+
+        Some(interaction_rx)
+    } else {
+        None
+    };
+
     let mut full_auto_allowlist = None;
 
     let mut declarations = build_function_declarations_with_mode(todo_planning_enabled);
@@ -179,7 +208,16 @@ pub(crate) async fn initialize_session(
         token_budget_config.compaction_threshold = cfg.token_budget.compaction_threshold;
         token_budget_config.detailed_tracking = cfg.token_budget.detailed_tracking;
     }
-    let token_budget = Arc::new(TokenBudgetManager::new(token_budget_config));
+    // Count with the active provider's tokenizer rather than the configured
+    // budget model so trimming tracks what the provider actually bills.
+    let tokenizer = ProviderTokenizer::shared(
+        provider_name.parse::<Provider>().unwrap_or_default(),
+        &config.model,
+    );
+    let token_budget = Arc::new(TokenBudgetManager::with_tokenizer(
+        token_budget_config,
+        tokenizer,
+    ));
 
     let decision_ledger = Arc::new(RwLock::new(DecisionTracker::new()));
     let mut curator_config = RuntimeContextCurationConfig::default();
@@ -252,6 +290,9 @@ pub(crate) async fn initialize_session(
     Ok(SessionState {
         session_bootstrap,
         provider_client,
+        fallback_status,
+        fallback_warnings,
+        cost_tracker,
         tool_registry,
         tools,
         trim_config,
@@ -261,9 +302,78 @@ pub(crate) async fn initialize_session(
         base_system_prompt,
         full_auto_allowlist,
         mcp_client: mcp_client.clone(),
+        mcp_interactions,
         mcp_panel_state,
         token_budget,
         token_budget_enabled,
         curator,
     })
 }
+
+/// Wrap the provider so rate limits and outages fail over along the configured chain.
+///
+/// Returns the provider to use, the status handle of the chain when one is active, and
+/// warnings about chain entries that could not be initialized.
+pub(crate) fn wrap_with_fallback(
+    provider_client: Box<dyn uni::LLMProvider>,
+    provider_name: &str,
+    config: &CoreAgentConfig,
+    vt_cfg: Option<&VTCodeConfig>,
+) -> (
+    Box<dyn uni::LLMProvider>,
+    Option<Arc<FallbackStatus>>,
+    Vec<String>,
+) {
+    let Some(fallback_cfg) = vt_cfg
+        .map(|cfg| &cfg.fallback)
+        .filter(|cfg| cfg.is_active())
+    else {
+        return (provider_client, None, Vec::new());
+    };
+
+    let (targets, warnings) = create_fallback_targets(
+        fallback_cfg,
+        provider_name,
+        &config.model,
+        &config.custom_api_keys,
+        Some(config.prompt_cache.clone()),
+    );
+    if targets.is_empty() {
+        return (provider_client, None, warnings);
+    }
+
+    info!(
+        "Provider fallback chain enabled with {} targets",
+        targets.len()
+    );
+    let wrapped = FallbackProvider::new(provider_client, &config.model, targets, fallback_cfg);
+    let status = wrapped.status();
+    (Box::new(wrapped), Some(status), warnings)
+}
+
+/// Point MCP sampling requests at the active provider and model when sampling is enabled.
+pub(crate) fn install_sampling_backend(
+    mcp_client: &McpClient,
+    provider_name: &str,
+    config: &CoreAgentConfig,
+    vt_cfg: Option<&VTCodeConfig>,
+    cost_tracker: &SharedCostTracker,
+) {
+    if !vt_cfg.is_some_and(|cfg| cfg.mcp.sampling.enabled) {
+        return;
+    }
+    match create_provider_with_config(
+        provider_name,
+        Some(config.api_key.clone()),
+        None,
+        Some(config.model.clone()),
+        Some(config.prompt_cache.clone()),
+    ) {
+        Ok(sampling_provider) => mcp_client.set_sampling_backend(McpSamplingBackend {
+            provider: Arc::from(sampling_provider),
+            model: config.model.clone(),
+            cost_tracker: Some(Arc::clone(cost_tracker)),
+        }),
+        Err(err) => warn!("Failed to initialize MCP sampling provider: {}", err),
+    }
+}
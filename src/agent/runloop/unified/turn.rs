@@ -1,13 +1,13 @@
 use anyhow::{Context, Result, anyhow};
-use chrono::Local;
+use chrono::{Local, Utc};
 use futures::StreamExt;
 use indicatif::ProgressStyle;
 use std::collections::{BTreeSet, HashSet};
 use std::fmt::Write as FmtWrite;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::Notify;
 use tokio::sync::mpsc::UnboundedReceiver;
@@ -28,29 +28,41 @@ use vtcode_core::core::context_curator::{
     ConversationPhase, CuratedContext, Message as CuratorMessage,
     ToolDefinition as CuratorToolDefinition,
 };
+use vtcode_core::core::cost_tracker::{BudgetLimit, CostTracker, SharedCostTracker, format_usd};
 use vtcode_core::core::decision_tracker::{Action as DTAction, DecisionOutcome};
 use vtcode_core::core::router::{Router, TaskClass};
 use vtcode_core::core::token_budget::{ContextComponent, TokenBudgetManager};
+use vtcode_core::core::tokenizer::{ProviderTokenizer, Tokenizer};
 use vtcode_core::llm::error_display;
 use vtcode_core::llm::factory::create_provider_with_config;
+use vtcode_core::llm::fallback::{FallbackNotice, FallbackStatus};
 use vtcode_core::llm::provider::{self as uni, LLMStreamEvent};
 use vtcode_core::llm::rig_adapter::{reasoning_parameters_for, verify_model_with_rig};
+use vtcode_core::mcp_client::McpClient;
+use vtcode_core::mcp_interaction::McpInteraction;
+use vtcode_core::project_memory::{MemoryScope, MemoryStore};
 use vtcode_core::tool_policy::ToolPolicy;
-use vtcode_core::tools::registry::{ToolErrorType, ToolExecutionError, ToolPermissionDecision};
+use vtcode_core::tools::registry::{
+    ToolErrorType, ToolExecutionError, ToolPermissionDecision, ToolRegistry,
+};
+use vtcode_core::ui::notifications::{NotificationEvent, Notifier};
 use vtcode_core::ui::slash::{SLASH_COMMANDS, SlashCommandInfo};
 use vtcode_core::ui::theme;
 use vtcode_core::ui::tui::{
-    InlineEvent, InlineHandle, InlineListItem, InlineListSelection, InlineTextStyle,
+    InlineBlockKind, InlineEvent, InlineHandle, InlineListItem, InlineListSelection,
+    InlineMessageKind, InlineSegment, InlineTextStyle, Keymap, PromptHistory,
     convert_style as convert_ui_style, spawn_session, theme_from_styles,
 };
 use vtcode_core::utils::ansi::{AnsiRenderer, MessageStyle};
 use vtcode_core::utils::session_archive::{
-    self, SessionArchive, SessionArchiveMetadata, SessionListing, SessionMessage,
+    self, SessionArchive, SessionArchiveMetadata, SessionListing, SessionUsage,
 };
 use vtcode_core::utils::transcript;
 
+use super::compaction::{CompactionSession, CompactionSettings, auto_compact, compact_history};
 use crate::agent::runloop::context::{
     apply_aggressive_trim_unified, enforce_unified_context_window, prune_unified_tool_responses,
+    unified_message_tokens,
 };
 use crate::agent::runloop::git::confirm_changes_with_git_diff;
 use crate::agent::runloop::is_context_overflow_error;
@@ -59,14 +71,27 @@ use crate::agent::runloop::model_picker::{
 };
 use crate::agent::runloop::prompt::refine_user_prompt_if_enabled;
 use crate::agent::runloop::slash_commands::{
-    SlashCommandOutcome, ThemePaletteMode, handle_slash_command,
+    MemoryCommand, SlashCommandOutcome, ThemePaletteMode, handle_slash_command,
 };
 use crate::agent::runloop::text_tools::detect_textual_tool_call;
-use crate::agent::runloop::tool_output::render_tool_output;
+use crate::agent::runloop::tool_output::{LiveCommandOutput, render_tool_output};
 use crate::agent::runloop::ui::{build_inline_header_context, render_session_banner};
 
+use super::attachments::{collect_input_attachments, take_tool_attachment};
+use super::branches::{ConversationBranches, FilePreImages, PromptMark};
+use super::diff_review::{ChangeReviewOutcome, review_changes};
 use super::display::{display_user_message, ensure_turn_bottom_gap, persist_theme_preference};
-use super::session_setup::{SessionState, initialize_session};
+use super::export::{ResponseDetails, conversation_snapshot, export_snapshot};
+use super::mcp_interaction::{McpInteractionOutcome, handle_mcp_interaction, next_mcp_interaction};
+use super::mentions::{build_mention_candidates, collect_mention_context};
+use super::message_queue::{MessageQueue, completed_exchange_len};
+use super::plan_mode::{
+    PLAN_APPROVED_MESSAGE, PLAN_MODE_INSTRUCTIONS, PLAN_MODE_REMINDER, PlanReviewDecision,
+    plan_revision_draft, review_plan,
+};
+use super::session_setup::{
+    SessionState, initialize_session, install_sampling_backend, wrap_with_fallback,
+};
 use super::shell::{derive_recent_tool_output, should_short_circuit_shell};
 use crate::agent::runloop::mcp_events;
 use crate::agent::runloop::welcome::SessionBootstrap;
@@ -125,14 +150,18 @@ fn show_theme_palette(renderer: &mut AnsiRenderer, mode: ThemePaletteMode) -> Re
     let mut items = Vec::new();
 
     for id in theme::available_themes() {
-        let label = theme::theme_label(id).unwrap_or(id);
+        let label = theme::theme_label(&id).unwrap_or_else(|| id.clone());
         let badge = (id == current_id).then(|| THEME_ACTIVE_BADGE.to_string());
+        let subtitle = match theme::custom_theme_path(&id) {
+            Some(path) => format!("ID: {} • {}", id, path.display()),
+            None => format!("ID: {}", id),
+        };
         items.push(InlineListItem {
-            title: label.to_string(),
-            subtitle: Some(format!("ID: {}", id)),
+            title: label,
+            subtitle: Some(subtitle),
             badge,
             indent: 0,
-            selection: Some(InlineListSelection::Theme(id.to_string())),
+            selection: Some(InlineListSelection::Theme(id)),
         });
     }
 
@@ -342,7 +371,7 @@ fn handle_palette_selection(
                     Ok(None)
                 }
                 ThemePaletteMode::Inspect => {
-                    let label = theme::theme_label(&theme_id).unwrap_or_else(|| theme_id.as_str());
+                    let label = theme::theme_label(&theme_id).unwrap_or_else(|| theme_id.clone());
                     renderer.line(
                         MessageStyle::Info,
                         &format!("Theme {} ({}) is available.", label, theme_id),
@@ -435,17 +464,50 @@ fn describe_phase(phase: ConversationPhase) -> Option<String> {
     }
 }
 
-fn resolve_mode_label(preference: UiSurfacePreference, full_auto: bool) -> String {
+fn resolve_mode_label(preference: UiSurfacePreference, full_auto: bool, plan_mode: bool) -> String {
     let base = match preference {
         UiSurfacePreference::Alternate => ui::HEADER_MODE_ALTERNATE,
         UiSurfacePreference::Inline => ui::HEADER_MODE_INLINE,
         UiSurfacePreference::Auto => ui::HEADER_MODE_AUTO,
     };
+    let mut label = base.to_string();
     if full_auto {
-        format!("{}{}", base, ui::HEADER_MODE_FULL_AUTO_SUFFIX)
-    } else {
-        base.to_string()
+        label.push_str(ui::HEADER_MODE_FULL_AUTO_SUFFIX);
+    }
+    if plan_mode {
+        label.push_str(ui::HEADER_MODE_PLAN_SUFFIX);
     }
+    label
+}
+
+/// Switch plan mode on or off, keeping the tool registry and header in sync.
+fn set_plan_mode(
+    enabled: bool,
+    tool_registry: &mut vtcode_core::tools::registry::ToolRegistry,
+    renderer: &mut AnsiRenderer,
+    handle: &InlineHandle,
+    mode_label: &mut String,
+    preference: UiSurfacePreference,
+    full_auto: bool,
+) -> Result<()> {
+    if enabled && !tool_registry.has_tool(tool_names::UPDATE_PLAN) {
+        renderer.line(
+            MessageStyle::Error,
+            "Plan mode needs the update_plan tool. Enable `todo_planning_mode` under [agent] in vtcode.toml.",
+        )?;
+        return Ok(());
+    }
+
+    tool_registry.set_plan_mode(enabled);
+    *mode_label = resolve_mode_label(preference, full_auto, enabled);
+    handle.set_header_mode(mode_label.clone());
+    let message = if enabled {
+        "Plan mode on: only read-only tools run until you approve a plan (Shift+Tab or /plan to leave)."
+    } else {
+        "Plan mode off: the agent may edit files again."
+    };
+    renderer.line(MessageStyle::Info, message)?;
+    Ok(())
 }
 
 fn format_provider_label(value: &str) -> String {
@@ -605,6 +667,160 @@ fn build_curator_tools(tools: &[uni::ToolDefinition]) -> Vec<CuratorToolDefiniti
         .collect()
 }
 
+/// Explain which spend limit paused the agent and how to resume
+fn budget_pause_hint(limit: BudgetLimit) -> String {
+    match limit {
+        BudgetLimit::Session { .. } => format!(
+            "{}. Raise it with /budget <usd> or remove it with /budget off to continue.",
+            limit
+        ),
+        BudgetLimit::Daily { .. } => format!(
+            "{}. Raise `cost.max_daily_cost_usd` in vtcode.toml to continue today.",
+            limit
+        ),
+    }
+}
+
+fn render_session_status(
+    renderer: &mut AnsiRenderer,
+    config: &CoreAgentConfig,
+    provider_name: &str,
+    fallback: Option<&FallbackNotice>,
+    cost_tracker: &CostTracker,
+) -> Result<()> {
+    let (provider, model) = match fallback {
+        Some(notice) => (
+            format!("{} (fallback)", format_provider_label(&notice.provider)),
+            notice.model.clone(),
+        ),
+        None if config.provider.trim().is_empty() => {
+            (format_provider_label(provider_name), config.model.clone())
+        }
+        None => (
+            format_provider_label(&config.provider),
+            config.model.clone(),
+        ),
+    };
+    renderer.line(
+        MessageStyle::Info,
+        &format!("Model: {} via {}", model, provider),
+    )?;
+
+    if !cost_tracker.is_enabled() {
+        renderer.line(
+            MessageStyle::Info,
+            "Cost tracking is disabled (`cost.enabled = false`).",
+        )?;
+        return Ok(());
+    }
+
+    let snapshot = cost_tracker.snapshot();
+    renderer.line(
+        MessageStyle::Info,
+        &format!(
+            "Tokens: {} prompt · {} completion · {} cache read · {} cache write",
+            snapshot.tokens.prompt,
+            snapshot.tokens.completion,
+            snapshot.tokens.cache_read,
+            snapshot.tokens.cache_write
+        ),
+    )?;
+    renderer.line(
+        MessageStyle::Info,
+        &format!(
+            "Cost: last turn {} · session {} · today {}",
+            format_usd(snapshot.turn_usd),
+            format_usd(snapshot.session_usd),
+            format_usd(snapshot.daily_usd)
+        ),
+    )?;
+    let describe_limit =
+        |limit: Option<f64>| limit.map(format_usd).unwrap_or_else(|| "none".to_string());
+    renderer.line(
+        MessageStyle::Info,
+        &format!(
+            "Limits: session {} · daily {}",
+            describe_limit(cost_tracker.session_limit_usd()),
+            describe_limit(cost_tracker.daily_limit_usd())
+        ),
+    )?;
+
+    let unpriced: Vec<&str> = cost_tracker.unpriced_models().collect();
+    if !unpriced.is_empty() {
+        renderer.line(
+            MessageStyle::Info,
+            &format!(
+                "No price known for {}; add entries under [cost.prices] to count them.",
+                unpriced.join(", ")
+            ),
+        )?;
+    }
+    if let Some(limit) = cost_tracker.budget_exceeded() {
+        renderer.line(
+            MessageStyle::Error,
+            &format!("Paused: {}", budget_pause_hint(limit)),
+        )?;
+    }
+    Ok(())
+}
+
+/// Announce a switch to or from a fallback provider and reflect it in the header
+#[allow(clippy::too_many_arguments)]
+fn render_fallback_change(
+    renderer: &mut AnsiRenderer,
+    handle: &InlineHandle,
+    config: &CoreAgentConfig,
+    session_bootstrap: &SessionBootstrap,
+    notice: Option<&FallbackNotice>,
+    primary_provider: &str,
+    mode_label: String,
+    reasoning_label: String,
+) -> Result<()> {
+    let primary_label = if config.provider.trim().is_empty() {
+        format_provider_label(primary_provider)
+    } else {
+        format_provider_label(&config.provider)
+    };
+
+    let (provider_label, model_label) = match notice {
+        Some(notice) => {
+            let fallback_label = format_provider_label(&notice.provider);
+            renderer.line(
+                MessageStyle::Info,
+                &format!(
+                    "{} unavailable ({}); continuing with {} via {}.",
+                    primary_label, notice.reason, notice.model, fallback_label
+                ),
+            )?;
+            (
+                format!("{} (fallback)", fallback_label),
+                notice.model.clone(),
+            )
+        }
+        None => {
+            renderer.line(
+                MessageStyle::Info,
+                &format!(
+                    "{} is available again; switched back to {}.",
+                    primary_label, config.model
+                ),
+            )?;
+            (primary_label, config.model.clone())
+        }
+    };
+
+    let header_context = build_inline_header_context(
+        config,
+        session_bootstrap,
+        provider_label,
+        model_label,
+        mode_label,
+        reasoning_label,
+    )?;
+    handle.set_header_context(header_context);
+    Ok(())
+}
+
 fn finalize_model_selection(
     renderer: &mut AnsiRenderer,
     picker: &ModelPickerState,
@@ -612,9 +828,13 @@ fn finalize_model_selection(
     config: &mut CoreAgentConfig,
     vt_cfg: &mut Option<VTCodeConfig>,
     provider_client: &mut Box<dyn uni::LLMProvider>,
+    fallback_status: &mut Option<Arc<FallbackStatus>>,
+    mcp_client: Option<&McpClient>,
+    cost_tracker: &SharedCostTracker,
+    token_budget: &TokenBudgetManager,
     session_bootstrap: &SessionBootstrap,
     handle: &InlineHandle,
-    full_auto: bool,
+    mode_label: &str,
 ) -> Result<()> {
     let workspace = config.workspace.clone();
 
@@ -663,17 +883,20 @@ fn finalize_model_selection(
     let updated_cfg = picker.persist_selection(&workspace, &selection)?;
     *vt_cfg = Some(updated_cfg);
 
+    let mut new_client = None;
     if let Some(provider_enum) = selection.provider_enum {
-        let provider_name = selection.provider.clone();
-        let new_client = create_provider_with_config(
-            &provider_name,
-            Some(api_key.clone()),
-            None,
-            Some(selection.model.clone()),
-            Some(config.prompt_cache.clone()),
-        )
-        .context("Failed to initialize provider for the selected model")?;
-        *provider_client = new_client;
+        new_client = Some(
+            create_provider_with_config(
+                &selection.provider,
+                Some(api_key.clone()),
+                None,
+                Some(selection.model.clone()),
+                Some(config.prompt_cache.clone()),
+            )
+            .context("Failed to initialize provider for the selected model")?,
+        );
+        // Budgets and compaction thresholds must count with the new provider's vocabulary
+        token_budget.set_tokenizer(ProviderTokenizer::shared(provider_enum, &selection.model));
         config.provider = provider_enum.to_string();
     } else {
         renderer.line(
@@ -695,6 +918,29 @@ fn finalize_model_selection(
         config.custom_api_keys.remove(&selection.provider);
     }
 
+    // Keep the fallback chain and MCP sampling on the newly selected model
+    if let Some(new_client) = new_client {
+        let (wrapped, status, warnings) =
+            wrap_with_fallback(new_client, &selection.provider, config, vt_cfg.as_ref());
+        *provider_client = wrapped;
+        *fallback_status = status;
+        for warning in warnings {
+            renderer.line(
+                MessageStyle::Info,
+                &format!("Provider fallback: {}", warning),
+            )?;
+        }
+        if let Some(mcp_client) = mcp_client {
+            install_sampling_backend(
+                mcp_client,
+                &selection.provider,
+                config,
+                vt_cfg.as_ref(),
+                cost_tracker,
+            );
+        }
+    }
+
     if let Some(provider_enum) = selection.provider_enum {
         if selection.reasoning_supported {
             if let Some(payload) = reasoning_parameters_for(provider_enum, selection.reasoning) {
@@ -707,13 +953,12 @@ fn finalize_model_selection(
     }
 
     let reasoning_label = selection.reasoning.as_str().to_string();
-    let mode_label = resolve_mode_label(config.ui_surface, full_auto);
     let header_context = build_inline_header_context(
         config,
         session_bootstrap,
         selection.provider_label.clone(),
         selection.model.clone(),
-        mode_label,
+        mode_label.to_string(),
         reasoning_label.clone(),
     )?;
     handle.set_header_context(header_context);
@@ -813,7 +1058,7 @@ enum ToolPermissionFlow {
 }
 
 #[derive(Default)]
-struct CtrlCState {
+pub(super) struct CtrlCState {
     cancel_requested: AtomicBool,
     exit_requested: AtomicBool,
     exit_armed: AtomicBool,
@@ -845,7 +1090,7 @@ impl CtrlCState {
         self.exit_armed.store(true, Ordering::SeqCst);
     }
 
-    fn is_cancel_requested(&self) -> bool {
+    pub(super) fn is_cancel_requested(&self) -> bool {
         self.cancel_requested.load(Ordering::SeqCst)
     }
 
@@ -853,18 +1098,18 @@ impl CtrlCState {
         self.exit_requested.load(Ordering::SeqCst)
     }
 
-    fn disarm_exit(&self) {
+    pub(super) fn disarm_exit(&self) {
         self.exit_armed.store(false, Ordering::SeqCst);
     }
 }
 
-struct PlaceholderGuard {
+pub(super) struct PlaceholderGuard {
     handle: InlineHandle,
     restore: Option<String>,
 }
 
 impl PlaceholderGuard {
-    fn new(handle: &InlineHandle, restore: Option<String>) -> Self {
+    pub(super) fn new(handle: &InlineHandle, restore: Option<String>) -> Self {
         Self {
             handle: handle.clone(),
             restore,
@@ -884,6 +1129,7 @@ fn render_tool_call_summary(
     args: &Value,
 ) -> Result<()> {
     let (headline, used_keys) = describe_tool_action(tool_name, args);
+    renderer.begin_block(InlineBlockKind::ToolCall, Some(&headline), None);
     renderer.line(MessageStyle::Info, &format!("→ {}", headline))?;
 
     let bullets = derive_tool_argument_bullets(args, &used_keys);
@@ -925,6 +1171,59 @@ fn derive_tool_argument_bullets(args: &Value, skip_keys: &HashSet<String>) -> Ve
     }
 }
 
+/// Apply a `/memory` command and report whether the memory files changed.
+fn run_memory_command(
+    renderer: &mut AnsiRenderer,
+    store: &MemoryStore,
+    command: MemoryCommand,
+) -> Result<bool> {
+    match command {
+        MemoryCommand::List => {
+            for scope in [MemoryScope::Project, MemoryScope::User] {
+                let Some(path) = store.path(scope) else {
+                    continue;
+                };
+                let entries = store.entries(scope)?;
+                renderer.line(
+                    MessageStyle::Info,
+                    &format!("{} memory ({}):", scope, path.display()),
+                )?;
+                if entries.is_empty() {
+                    renderer.line(MessageStyle::Info, "  (empty)")?;
+                }
+                for (index, entry) in entries.iter().enumerate() {
+                    renderer.line(MessageStyle::Info, &format!("  {}. {}", index + 1, entry))?;
+                }
+            }
+            Ok(false)
+        }
+        MemoryCommand::Add { scope, text } => {
+            let index = store.add(scope, &text)?;
+            renderer.line(
+                MessageStyle::Info,
+                &format!("Saved as {} memory entry #{}.", scope, index),
+            )?;
+            Ok(true)
+        }
+        MemoryCommand::Edit { scope, index, text } => {
+            store.update(scope, index, &text)?;
+            renderer.line(
+                MessageStyle::Info,
+                &format!("Updated {} memory entry #{}.", scope, index),
+            )?;
+            Ok(true)
+        }
+        MemoryCommand::Remove { scope, index } => {
+            let removed = store.remove(scope, index)?;
+            renderer.line(
+                MessageStyle::Info,
+                &format!("Removed {} memory entry: {}", scope, removed),
+            )?;
+            Ok(true)
+        }
+    }
+}
+
 fn describe_tool_action(tool_name: &str, args: &Value) -> (String, HashSet<String>) {
     match tool_name {
         tool_names::RUN_TERMINAL_CMD | tool_names::BASH => describe_shell_command(args)
@@ -947,12 +1246,18 @@ fn describe_tool_action(tool_name: &str, args: &Value) -> (String, HashSet<Strin
         tool_names::CURL => {
             describe_curl(args).unwrap_or_else(|| ("Fetch URL".to_string(), HashSet::new()))
         }
+        tool_names::WEB_FETCH => {
+            describe_curl(args).unwrap_or_else(|| ("Fetch page".to_string(), HashSet::new()))
+        }
+        tool_names::WEB_SEARCH => describe_web_search(args)
+            .unwrap_or_else(|| ("Search the web".to_string(), HashSet::new())),
         tool_names::SIMPLE_SEARCH => describe_simple_search(args)
             .unwrap_or_else(|| ("Search workspace".to_string(), HashSet::new())),
         tool_names::SRGN => describe_srgn(args)
             .unwrap_or_else(|| ("Search and replace".to_string(), HashSet::new())),
         tool_names::APPLY_PATCH => ("Apply workspace patch".to_string(), HashSet::new()),
         tool_names::UPDATE_PLAN => ("Update task plan".to_string(), HashSet::new()),
+        tool_names::REMEMBER => ("Update memory".to_string(), HashSet::new()),
         _ => (
             format!("Use {}", humanize_tool_name(tool_name)),
             HashSet::new(),
@@ -1108,6 +1413,16 @@ fn describe_curl(args: &Value) -> Option<(String, HashSet<String>)> {
     None
 }
 
+fn describe_web_search(args: &Value) -> Option<(String, HashSet<String>)> {
+    let query = lookup_string(args, "query")?;
+    let mut used = HashSet::new();
+    used.insert("query".to_string());
+    Some((
+        format!("Search the web for \"{}\"", truncate_middle(&query, 60)),
+        used,
+    ))
+}
+
 fn lookup_string(args: &Value, key: &str) -> Option<String> {
     args.as_object()
         .and_then(|map| map.get(key))
@@ -1280,7 +1595,13 @@ async fn prompt_tool_permission(
                     "Respond with 'yes' to approve or 'no' to deny.",
                 )?;
             }
-            InlineEvent::ListModalSubmit(_) | InlineEvent::ListModalCancel => {
+            InlineEvent::Queue(_)
+            | InlineEvent::Steer(_)
+            | InlineEvent::EditQueued
+            | InlineEvent::EditMessage(_)
+            | InlineEvent::ListModalSubmit(_)
+            | InlineEvent::ListModalCancel
+            | InlineEvent::TogglePlanMode => {
                 continue;
             }
             InlineEvent::Cancel => {
@@ -1366,6 +1687,29 @@ fn apply_prompt_style(handle: &InlineHandle) {
     handle.set_prompt("❯ ".to_string(), style);
 }
 
+/// Re-apply a custom theme whenever its file is saved while it is selected.
+fn spawn_theme_watcher(handle: InlineHandle) -> task::JoinHandle<()> {
+    task::spawn(async move {
+        loop {
+            sleep(Duration::from_millis(ui::THEME_RELOAD_INTERVAL_MS)).await;
+            match theme::reload_active_custom_theme() {
+                Ok(true) => {
+                    handle.set_theme(theme_from_styles(&theme::active_styles()));
+                    apply_prompt_style(&handle);
+                }
+                Ok(false) => {}
+                Err(err) => handle.append_line(
+                    InlineMessageKind::Error,
+                    vec![InlineSegment {
+                        text: format!("Theme not reloaded: {:#}", err),
+                        style: InlineTextStyle::default(),
+                    }],
+                ),
+            }
+        }
+    })
+}
+
 const SPINNER_UPDATE_INTERVAL_MS: u64 = 120;
 
 struct SpinnerFrameGenerator {
@@ -1392,6 +1736,7 @@ struct PlaceholderSpinner {
     handle: InlineHandle,
     restore_hint: Option<String>,
     active: Arc<AtomicBool>,
+    detail: Arc<Mutex<Option<String>>>,
     task: task::JoinHandle<()>,
 }
 
@@ -1412,32 +1757,58 @@ impl PlaceholderSpinner {
         restore_hint: Option<String>,
         message: impl Into<String>,
     ) -> Self {
-        let message = message.into();
+        Self::start(handle, restore_hint, message.into(), None)
+    }
+
+    /// Spinner that also shows how long has passed since `started`.
+    fn with_elapsed(
+        handle: &InlineHandle,
+        restore_hint: Option<String>,
+        message: impl Into<String>,
+        started: Instant,
+    ) -> Self {
+        Self::start(handle, restore_hint, message.into(), Some(started))
+    }
+
+    fn start(
+        handle: &InlineHandle,
+        restore_hint: Option<String>,
+        message: String,
+        started: Option<Instant>,
+    ) -> Self {
         let active = Arc::new(AtomicBool::new(true));
+        let detail = Arc::new(Mutex::new(None::<String>));
         let spinner_active = active.clone();
+        let spinner_detail = detail.clone();
         let spinner_handle = handle.clone();
         let restore_on_stop = restore_hint.clone();
         let spinner_style = spinner_placeholder_style();
         let spinner_message = message;
 
-        spinner_handle.set_input_enabled(false);
-        spinner_handle.set_cursor_visible(false);
+        // Keep the input editable so the user can queue or steer while the agent works
+        spinner_handle.set_busy(true);
         let task = task::spawn(async move {
             let mut frames = SpinnerFrameGenerator::new();
             while spinner_active.load(Ordering::SeqCst) {
                 let frame = frames.next_frame();
-                let display = if spinner_message.is_empty() {
+                let mut display = if spinner_message.is_empty() {
                     frame.to_string()
                 } else {
                     format!("{frame} {spinner_message}")
                 };
+                if let Some(started) = started {
+                    display.push_str(&format!(" ({})", format_duration_label(started.elapsed())));
+                }
+                if let Some(detail) = spinner_detail.lock().ok().and_then(|d| d.clone()) {
+                    display.push_str(" · ");
+                    display.push_str(&detail);
+                }
                 spinner_handle
                     .set_placeholder_with_style(Some(display), Some(spinner_style.clone()));
                 sleep(Duration::from_millis(SPINNER_UPDATE_INTERVAL_MS)).await;
             }
 
-            spinner_handle.set_cursor_visible(true);
-            spinner_handle.set_input_enabled(true);
+            spinner_handle.set_busy(false);
             spinner_handle.set_placeholder_with_style(restore_on_stop, None);
         });
 
@@ -1445,16 +1816,23 @@ impl PlaceholderSpinner {
             handle: handle.clone(),
             restore_hint,
             active,
+            detail,
             task,
         }
     }
 
+    /// Show `detail` after the message, e.g. progress reported by the running tool.
+    fn set_detail(&self, detail: String) {
+        if let Ok(mut current) = self.detail.lock() {
+            *current = Some(detail);
+        }
+    }
+
     fn finish(&self) {
         if self.active.swap(false, Ordering::SeqCst) {
             self.handle
                 .set_placeholder_with_style(self.restore_hint.clone(), None);
-            self.handle.set_input_enabled(true);
-            self.handle.set_cursor_visible(true);
+            self.handle.set_busy(false);
         }
     }
 }
@@ -1509,6 +1887,15 @@ fn stream_plain_response_delta(
     Ok(())
 }
 
+/// Show a reasoning trace that arrived in one piece as a folded thinking block.
+fn render_reasoning_block(renderer: &mut AnsiRenderer, reasoning: Option<&str>) -> Result<()> {
+    let Some(reasoning) = reasoning.map(str::trim).filter(|text| !text.is_empty()) else {
+        return Ok(());
+    };
+    renderer.begin_block(InlineBlockKind::Reasoning, None, None);
+    renderer.line(MessageStyle::Reasoning, reasoning)
+}
+
 async fn stream_and_render_response(
     provider: &dyn uni::LLMProvider,
     request: uni::LLMRequest,
@@ -1532,11 +1919,23 @@ async fn stream_and_render_response(
         }
     };
     let mut emitted_tokens = false;
+    let reasoning_style = MessageStyle::Reasoning;
+    let mut streamed_reasoning = false;
+    let mut reasoning_needs_indent = true;
 
     while let Some(event_result) = stream.next().await {
         match event_result {
             Ok(LLMStreamEvent::Token { delta }) => {
                 finish_spinner(&mut spinner_active);
+                if streamed_reasoning
+                    && !emitted_tokens
+                    && !supports_streaming_markdown
+                    && !reasoning_needs_indent
+                {
+                    renderer
+                        .inline_with_style(reasoning_style, "\n")
+                        .map_err(|err| map_render_error(provider_name, err))?;
+                }
                 aggregated.push_str(&delta);
                 if supports_streaming_markdown {
                     rendered_line_count = renderer
@@ -1554,7 +1953,23 @@ async fn stream_and_render_response(
                 }
                 emitted_tokens = true;
             }
-            Ok(LLMStreamEvent::Reasoning { .. }) => {}
+            Ok(LLMStreamEvent::Reasoning { delta }) => {
+                if emitted_tokens {
+                    continue;
+                }
+                if !streamed_reasoning {
+                    renderer.begin_block(InlineBlockKind::Reasoning, None, None);
+                    streamed_reasoning = true;
+                }
+                stream_plain_response_delta(
+                    renderer,
+                    reasoning_style,
+                    reasoning_style.indent(),
+                    &mut reasoning_needs_indent,
+                    &delta,
+                )
+                .map_err(|err| map_render_error(provider_name, err))?;
+            }
             Ok(LLMStreamEvent::Completed { response }) => {
                 final_response = Some(response);
             }
@@ -1575,6 +1990,11 @@ async fn stream_and_render_response(
         uni::LLMError::Provider(formatted_error)
     })?;
 
+    if !streamed_reasoning && !emitted_tokens {
+        render_reasoning_block(renderer, response.reasoning.as_deref())
+            .map_err(|err| map_render_error(provider_name, err))?;
+    }
+
     if aggregated.is_empty() {
         if let Some(content) = response.content.clone() {
             if !content.is_empty() {
@@ -1607,7 +2027,7 @@ async fn stream_and_render_response(
 
 enum TurnLoopResult {
     Completed,
-    Aborted,
+    Aborted(String),
     Cancelled,
 }
 
@@ -1724,6 +2144,7 @@ pub(crate) async fn run_single_agent_loop_unified(
     mut vt_cfg: Option<VTCodeConfig>,
     skip_confirmations: bool,
     full_auto: bool,
+    plan_mode: bool,
 ) -> Result<()> {
     // Set up panic handler to ensure MCP cleanup on panic
     let original_hook = std::panic::take_hook();
@@ -1737,6 +2158,9 @@ pub(crate) async fn run_single_agent_loop_unified(
     let SessionState {
         session_bootstrap,
         mut provider_client,
+        mut fallback_status,
+        fallback_warnings,
+        cost_tracker,
         mut tool_registry,
         tools,
         trim_config,
@@ -1747,13 +2171,35 @@ pub(crate) async fn run_single_agent_loop_unified(
         full_auto_allowlist,
         #[allow(unused_variables)]
         mcp_client,
+        mut mcp_interactions,
         mut mcp_panel_state,
         token_budget,
         token_budget_enabled,
         mut curator,
     } = initialize_session(&config, vt_cfg.as_ref(), full_auto).await?;
+    // Preview terminal command output in the transcript while the command runs
+    let (command_output_tx, mut command_output) = tokio::sync::mpsc::unbounded_channel();
+    tool_registry.set_command_output_sender(command_output_tx);
+    let notifier = Arc::new(Notifier::new(
+        vt_cfg
+            .as_ref()
+            .map(|cfg| cfg.ui.notifications.clone())
+            .unwrap_or_default(),
+    ));
+    tool_registry.set_notifier(notifier.clone());
+    // Tool attachments are moved into the next user message by take_tool_attachment
+    tool_registry.set_attachments_enabled(true);
 
     let curator_tool_catalog = build_curator_tools(&tools);
+    let mut tokenizer = token_budget.tokenizer();
+    let compaction_settings = CompactionSettings::from_config(vt_cfg.as_ref(), trim_config);
+    let memory_store = MemoryStore::for_workspace(&config.workspace);
+    let memory_config = vt_cfg
+        .as_ref()
+        .map(|cfg| cfg.memory.clone())
+        .unwrap_or_default();
+    let mut memory_prompt = memory_store.prompt_section(&memory_config, &tokenizer);
+    let tool_definitions_json = serde_json::to_string(&tools).unwrap_or_default();
 
     let active_styles = theme::active_styles();
     let theme_spec = theme_from_styles(&active_styles);
@@ -1766,15 +2212,35 @@ pub(crate) async fn run_single_agent_loop_unified(
         .as_ref()
         .map(|cfg| cfg.ui.show_timeline_pane)
         .unwrap_or(ui::INLINE_SHOW_TIMELINE_PANE);
+    let mut keymap_error: Option<String> = None;
+    let keymap = match vt_cfg.as_ref().map(|cfg| Keymap::from_config(&cfg.ui)) {
+        Some(Ok(keymap)) => keymap,
+        Some(Err(err)) => {
+            keymap_error = Some(format!("{:#}", err));
+            Keymap::default().with_vi_mode(vt_cfg.as_ref().is_some_and(|cfg| cfg.ui.vi_mode))
+        }
+        None => Keymap::default(),
+    };
     let session = spawn_session(
         theme_spec.clone(),
         default_placeholder.clone(),
         config.ui_surface,
         inline_rows,
         show_timeline_pane,
+        PromptHistory::for_workspace(&config.workspace),
+        keymap,
     )
     .context("failed to launch inline session")?;
     let handle = session.handle.clone();
+    {
+        let handle = handle.clone();
+        let workspace = config.workspace.clone();
+        // Indexing walks the whole workspace, so completions arrive after the session starts.
+        tokio::task::spawn_blocking(move || {
+            handle.set_mention_candidates(build_mention_candidates(&workspace));
+        });
+    }
+    let theme_watcher = spawn_theme_watcher(handle.clone());
     let highlight_config = vt_cfg
         .as_ref()
         .map(|cfg| cfg.syntax_highlighting.clone())
@@ -1804,6 +2270,8 @@ pub(crate) async fn run_single_agent_loop_unified(
         config.theme.clone(),
         config.reasoning_effort.as_str().to_string(),
     );
+    let export_metadata = archive_metadata.clone();
+    let session_started_at = Utc::now();
     let mut session_archive_error: Option<String> = None;
     let mut session_archive = match SessionArchive::new(archive_metadata) {
         Ok(archive) => Some(archive),
@@ -1829,13 +2297,18 @@ pub(crate) async fn run_single_agent_loop_unified(
         &config.model,
         &reasoning_label,
     )?;
-    let mode_label = resolve_mode_label(config.ui_surface, full_auto);
+    let mut mode_label = resolve_mode_label(config.ui_surface, full_auto, false);
+    let review_file_changes = !skip_confirmations
+        && vt_cfg
+            .as_ref()
+            .map(|cfg| cfg.security.review_file_changes)
+            .unwrap_or(true);
     let header_context = build_inline_header_context(
         &config,
         &session_bootstrap,
         header_provider_label,
         config.model.clone(),
-        mode_label,
+        mode_label.clone(),
         reasoning_label.clone(),
     )?;
     handle.set_header_context(header_context);
@@ -1846,6 +2319,13 @@ pub(crate) async fn run_single_agent_loop_unified(
 
     // MCP events are now rendered as message blocks in the conversation history
 
+    if let Some(message) = keymap_error.take() {
+        renderer.line(
+            MessageStyle::Error,
+            &format!("Ignoring [ui.keybindings], using default keys: {}", message),
+        )?;
+        renderer.line_if_not_empty(MessageStyle::Output)?;
+    }
     if let Some(message) = session_archive_error.take() {
         renderer.line(
             MessageStyle::Info,
@@ -1854,6 +2334,21 @@ pub(crate) async fn run_single_agent_loop_unified(
         renderer.line_if_not_empty(MessageStyle::Output)?;
     }
 
+    for warning in &fallback_warnings {
+        renderer.line(
+            MessageStyle::Info,
+            &format!("Provider fallback: {}", warning),
+        )?;
+    }
+    let mut displayed_fallback: Option<FallbackNotice> = None;
+
+    {
+        let tracker = cost_tracker.lock();
+        if tracker.is_enabled() {
+            handle.set_header_cost(tracker.header_label());
+        }
+    }
+
     if full_auto {
         if let Some(allowlist) = full_auto_allowlist.as_ref() {
             if allowlist.is_empty() {
@@ -1873,6 +2368,18 @@ pub(crate) async fn run_single_agent_loop_unified(
         }
     }
 
+    if plan_mode {
+        set_plan_mode(
+            true,
+            &mut tool_registry,
+            &mut renderer,
+            &handle,
+            &mut mode_label,
+            config.ui_surface,
+            full_auto,
+        )?;
+    }
+
     let ctrl_c_state = Arc::new(CtrlCState::new());
     let ctrl_c_notify = Arc::new(Notify::new());
     let mcp_client_for_signal = mcp_client.clone();
@@ -1918,16 +2425,34 @@ pub(crate) async fn run_single_agent_loop_unified(
     let mut palette_state: Option<ActivePalette> = None;
     let mut events = session.events;
     let mut last_forced_redraw = Instant::now();
+    // Input sent on the user's behalf, such as the go-ahead after a plan is approved
+    let mut pending_submission: Option<String> = None;
+    let mut message_queue = MessageQueue::default();
+    let mut response_details = ResponseDetails::default();
+    let mut branches = ConversationBranches::default();
+    // Earlier prompt being edited; submitting forks a new branch just before it
+    let mut pending_edit: Option<PromptMark> = None;
     loop {
         if ctrl_c_state.is_exit_requested() {
             break;
         }
 
-        let maybe_event = tokio::select! {
-            biased;
+        let mut synthesized = pending_submission.is_some();
+        let maybe_event = if let Some(text) = pending_submission.take() {
+            Some(InlineEvent::Submit(text))
+        } else if let Some(event) = message_queue.take_deferred() {
+            Some(event)
+        } else if let Some(text) = message_queue.take_submission(&handle) {
+            // Queued messages were recorded in the prompt history when they were typed
+            synthesized = true;
+            Some(InlineEvent::Submit(text))
+        } else {
+            tokio::select! {
+                biased;
 
-            _ = ctrl_c_notify.notified(), if ctrl_c_state.is_cancel_requested() => None,
-            event = events.recv() => event,
+                _ = ctrl_c_notify.notified(), if ctrl_c_state.is_cancel_requested() => None,
+                event = events.recv() => event,
+            }
         };
 
         if ctrl_c_state.is_cancel_requested() {
@@ -1953,7 +2478,8 @@ pub(crate) async fn run_single_agent_loop_unified(
         ctrl_c_state.disarm_exit();
 
         let submitted = match event {
-            InlineEvent::Submit(text) => text,
+            // Queue and steer events can still arrive just after the agent went idle
+            InlineEvent::Submit(text) | InlineEvent::Queue(text) | InlineEvent::Steer(text) => text,
             InlineEvent::ListModalSubmit(selection) => {
                 if let Some(picker) = model_picker_state.as_mut() {
                     let progress =
@@ -1973,15 +2499,21 @@ pub(crate) async fn run_single_agent_loop_unified(
                                 &mut config,
                                 &mut vt_cfg,
                                 &mut provider_client,
+                                &mut fallback_status,
+                                mcp_client.as_deref(),
+                                &cost_tracker,
+                                &token_budget,
                                 &session_bootstrap,
                                 &handle,
-                                full_auto,
+                                &mode_label,
                             ) {
                                 renderer.line(
                                     MessageStyle::Error,
                                     &format!("Failed to apply model selection: {}", err),
                                 )?;
                             }
+                            tokenizer = token_budget.tokenizer();
+                            displayed_fallback = None;
                         }
                     }
                 }
@@ -2003,10 +2535,37 @@ pub(crate) async fn run_single_agent_loop_unified(
                 continue;
             }
             InlineEvent::Cancel => {
-                renderer.line(
-                    MessageStyle::Info,
-                    "Cancellation request noted. No active run to stop.",
-                )?;
+                if pending_edit.take().is_some() {
+                    handle.clear_input();
+                    renderer.line(
+                        MessageStyle::Info,
+                        "Edit cancelled; the conversation continues on the current branch.",
+                    )?;
+                } else {
+                    renderer.line(
+                        MessageStyle::Info,
+                        "Cancellation request noted. No active run to stop.",
+                    )?;
+                }
+                continue;
+            }
+            InlineEvent::EditMessage(text) => {
+                match branches.find_prompt(&text) {
+                    Some(mark) => {
+                        handle.set_input(mark.input().to_string());
+                        renderer.line(
+                            MessageStyle::Info,
+                            "Editing an earlier prompt. Submit to regenerate from there on a new branch (the current one stays available in /branches), or press Esc to cancel.",
+                        )?;
+                        pending_edit = Some(mark);
+                    }
+                    None => {
+                        renderer.line(
+                            MessageStyle::Error,
+                            "Only prompts sent on the current branch of this session can be edited.",
+                        )?;
+                    }
+                }
                 continue;
             }
             InlineEvent::Exit => {
@@ -2016,7 +2575,21 @@ pub(crate) async fn run_single_agent_loop_unified(
             InlineEvent::Interrupt => {
                 break;
             }
-            InlineEvent::ScrollLineUp
+            InlineEvent::TogglePlanMode => {
+                let enabled = !tool_registry.plan_mode();
+                set_plan_mode(
+                    enabled,
+                    &mut tool_registry,
+                    &mut renderer,
+                    &handle,
+                    &mut mode_label,
+                    config.ui_surface,
+                    full_auto,
+                )?;
+                continue;
+            }
+            InlineEvent::EditQueued
+            | InlineEvent::ScrollLineUp
             | InlineEvent::ScrollLineDown
             | InlineEvent::ScrollPageUp
             | InlineEvent::ScrollPageDown => continue,
@@ -2028,6 +2601,11 @@ pub(crate) async fn run_single_agent_loop_unified(
             continue;
         }
 
+        // Picker input can hold API keys, so only record prompts typed at the main input.
+        if !synthesized && model_picker_state.is_none() {
+            handle.record_history(input_owned.clone());
+        }
+
         match input_owned.as_str() {
             "" => continue,
             "exit" | "quit" => {
@@ -2291,6 +2869,155 @@ pub(crate) async fn run_single_agent_loop_unified(
                                 }
                             }
                         }
+                        SlashCommandOutcome::ShowStatus => {
+                            render_session_status(
+                                &mut renderer,
+                                &config,
+                                provider_client.name(),
+                                displayed_fallback.as_ref(),
+                                &cost_tracker.lock(),
+                            )?;
+                            continue;
+                        }
+                        SlashCommandOutcome::Compact { focus } => {
+                            let session = CompactionSession {
+                                provider: provider_client.as_ref(),
+                                model: &config.model,
+                                tokenizer: tokenizer.as_ref(),
+                                settings: &compaction_settings,
+                                ledger: &decision_ledger,
+                                cost_tracker: &cost_tracker,
+                                plan: tool_registry.current_plan(),
+                            };
+                            match compact_history(
+                                &mut conversation_history,
+                                session,
+                                focus.as_deref(),
+                                "requested with /compact",
+                            )
+                            .await
+                            {
+                                Ok(Some(outcome)) => {
+                                    renderer.line(MessageStyle::Info, &outcome.describe())?;
+                                }
+                                Ok(None) => {
+                                    renderer.line(
+                                        MessageStyle::Info,
+                                        "Nothing to compact yet; the conversation still fits in the preserved window.",
+                                    )?;
+                                }
+                                Err(err) => {
+                                    renderer.line(
+                                        MessageStyle::Error,
+                                        &format!("Compaction failed: {:#}", err),
+                                    )?;
+                                }
+                            }
+                            continue;
+                        }
+                        SlashCommandOutcome::SetSessionBudget { limit_usd } => {
+                            let mut tracker = cost_tracker.lock();
+                            tracker.set_session_limit_usd(limit_usd);
+                            let message = match limit_usd {
+                                Some(limit) => {
+                                    format!("Session cost limit set to {}.", format_usd(limit))
+                                }
+                                None => "Session cost limit removed.".to_string(),
+                            };
+                            renderer.line(MessageStyle::Info, &message)?;
+                            if tracker.is_enabled() {
+                                handle.set_header_cost(tracker.header_label());
+                            }
+                            continue;
+                        }
+                        SlashCommandOutcome::SetPlanMode { enabled } => {
+                            let enabled = enabled.unwrap_or(!tool_registry.plan_mode());
+                            set_plan_mode(
+                                enabled,
+                                &mut tool_registry,
+                                &mut renderer,
+                                &handle,
+                                &mut mode_label,
+                                config.ui_surface,
+                                full_auto,
+                            )?;
+                            continue;
+                        }
+                        SlashCommandOutcome::ManageMemory(command) => {
+                            match run_memory_command(&mut renderer, &memory_store, command) {
+                                Ok(true) => {
+                                    memory_prompt =
+                                        memory_store.prompt_section(&memory_config, &tokenizer);
+                                }
+                                Ok(false) => {}
+                                Err(err) => {
+                                    renderer.line(
+                                        MessageStyle::Error,
+                                        &format!("Memory update failed: {:#}", err),
+                                    )?;
+                                }
+                            }
+                            continue;
+                        }
+                        SlashCommandOutcome::ExportTranscript { format, path } => {
+                            let snapshot = conversation_snapshot(
+                                &export_metadata,
+                                session_started_at,
+                                &conversation_history,
+                                &response_details,
+                                session_stats.sorted_tools(),
+                            );
+                            match export_snapshot(&snapshot, &config.workspace, format, path) {
+                                Ok(path) => {
+                                    renderer.line(
+                                        MessageStyle::Info,
+                                        &format!("Exported conversation to {}", path.display()),
+                                    )?;
+                                }
+                                Err(err) => {
+                                    renderer.line(
+                                        MessageStyle::Error,
+                                        &format!("Export failed: {:#}", err),
+                                    )?;
+                                }
+                            }
+                            continue;
+                        }
+                        SlashCommandOutcome::ManageBranches { switch_to: None } => {
+                            renderer.line(MessageStyle::Info, "Conversation branches:")?;
+                            for line in branches.describe(&conversation_history) {
+                                renderer.line(MessageStyle::Info, &line)?;
+                            }
+                            renderer.line(
+                                MessageStyle::Info,
+                                "Focus a prompt with Alt+P and press Alt+R to edit it on a new branch; /branches <number> switches.",
+                            )?;
+                            continue;
+                        }
+                        SlashCommandOutcome::ManageBranches {
+                            switch_to: Some(id),
+                        } => {
+                            match branches.switch(id, &mut conversation_history) {
+                                Ok(changes) => {
+                                    pending_edit = None;
+                                    renderer.line(
+                                        MessageStyle::Info,
+                                        &format!(
+                                            "Switched to branch {} ({} messages). The transcript above still shows earlier branches.",
+                                            branches.active_id(),
+                                            conversation_history.len()
+                                        ),
+                                    )?;
+                                    for line in changes.describe(&config.workspace) {
+                                        renderer.line(MessageStyle::Info, &line)?;
+                                    }
+                                }
+                                Err(err) => {
+                                    renderer.line(MessageStyle::Error, &format!("{:#}", err))?;
+                                }
+                            }
+                            continue;
+                        }
                         SlashCommandOutcome::Exit => {
                             renderer.line(MessageStyle::Info, "Goodbye!")?;
                             break;
@@ -2319,15 +3046,21 @@ pub(crate) async fn run_single_agent_loop_unified(
                         &mut config,
                         &mut vt_cfg,
                         &mut provider_client,
+                        &mut fallback_status,
+                        mcp_client.as_deref(),
+                        &cost_tracker,
+                        &token_budget,
                         &session_bootstrap,
                         &handle,
-                        full_auto,
+                        &mode_label,
                     ) {
                         renderer.line(
                             MessageStyle::Error,
                             &format!("Failed to apply model selection: {}", err),
                         )?;
                     }
+                    tokenizer = token_budget.tokenizer();
+                    displayed_fallback = None;
                     continue;
                 }
             }
@@ -2335,16 +3068,97 @@ pub(crate) async fn run_single_agent_loop_unified(
 
         let input = input_owned.as_str();
 
-        let refined_user = refine_user_prompt_if_enabled(input, &config, vt_cfg.as_ref()).await;
+        let budget_limit = cost_tracker.lock().budget_exceeded();
+        if let Some(limit) = budget_limit {
+            renderer.line(
+                MessageStyle::Error,
+                &format!("Message not sent: {}", budget_pause_hint(limit)),
+            )?;
+            continue;
+        }
+        if let Some(mark) = pending_edit.take() {
+            match branches.fork(&mark, &mut conversation_history) {
+                Ok((id, changes)) => {
+                    renderer.line(
+                        MessageStyle::Info,
+                        &format!(
+                            "Started branch {} from the edited prompt. Use /branches to switch back.",
+                            id
+                        ),
+                    )?;
+                    for line in changes.describe(&config.workspace) {
+                        renderer.line(MessageStyle::Info, &line)?;
+                    }
+                }
+                Err(err) => {
+                    renderer.line(MessageStyle::Error, &format!("{:#}", err))?;
+                    continue;
+                }
+            }
+        }
+        cost_tracker.lock().start_turn();
+
+        let refined_user =
+            refine_user_prompt_if_enabled(input, &config, vt_cfg.as_ref(), &cost_tracker).await;
         // Display the user message with inline border decoration
         display_user_message(&mut renderer, &refined_user)?;
-        conversation_history.push(uni::Message::user(refined_user));
-        let _pruned_tools = prune_unified_tool_responses(
+        let input_attachments = collect_input_attachments(input, &config.workspace);
+        for error in &input_attachments.errors {
+            renderer.line(
+                MessageStyle::Error,
+                &format!("Attachment skipped: {}", error),
+            )?;
+        }
+        for attachment in &input_attachments.attachments {
+            renderer.line(
+                MessageStyle::Info,
+                &format!(
+                    "Attached {} ({})",
+                    attachment.file_name(),
+                    attachment.media_type
+                ),
+            )?;
+        }
+        let mention_context = collect_mention_context(input, &config.workspace).await;
+        for error in &mention_context.errors {
+            renderer.line(MessageStyle::Error, &format!("Mention skipped: {}", error))?;
+        }
+        for name in &mention_context.attached {
+            renderer.line(MessageStyle::Info, &format!("Attached context {}", name))?;
+        }
+        conversation_history.push(uni::Message::user_with_attachments(
+            mention_context.append_to(&refined_user),
+            input_attachments.attachments,
+        ));
+        branches.record_prompt(&conversation_history, input, &refined_user);
+        let mut auto_compaction_available = true;
+        if compaction_settings.enabled {
+            // Summaries replace the older turns, so tool payloads are kept until then.
+            if compaction_settings.should_auto_compact(&conversation_history, tokenizer.as_ref()) {
+                let session = CompactionSession {
+                    provider: provider_client.as_ref(),
+                    model: &config.model,
+                    tokenizer: tokenizer.as_ref(),
+                    settings: &compaction_settings,
+                    ledger: &decision_ledger,
+                    cost_tracker: &cost_tracker,
+                    plan: tool_registry.current_plan(),
+                };
+                auto_compaction_available =
+                    auto_compact(&mut conversation_history, session, &mut renderer).await?;
+            }
+        } else {
+            let _pruned_tools = prune_unified_tool_responses(
+                &mut conversation_history,
+                trim_config.preserve_recent_turns,
+            );
+        }
+        // Removed: Tool response pruning message
+        let trim_result = enforce_unified_context_window(
             &mut conversation_history,
-            trim_config.preserve_recent_turns,
+            trim_config,
+            tokenizer.as_ref(),
         );
-        // Removed: Tool response pruning message
-        let trim_result = enforce_unified_context_window(&mut conversation_history, trim_config);
         if trim_result.is_trimmed() {
             renderer.line(
                 MessageStyle::Info,
@@ -2364,15 +3178,24 @@ pub(crate) async fn run_single_agent_loop_unified(
 
         let mut loop_guard = 0usize;
         let mut any_write_effect = false;
+        let plan_version_at_turn_start = tool_registry.current_plan().version;
+        let mut plan_reminder_sent = false;
         let mut last_tool_stdout: Option<String> = None;
         let mut bottom_gap_applied = false;
+        let turn_started = Instant::now();
 
         let turn_result = 'outer: loop {
             if ctrl_c_state.is_cancel_requested() {
                 break TurnLoopResult::Cancelled;
             }
+            if message_queue.interrupts_turn(&mut events, &handle, &mut pending_submission) {
+                break TurnLoopResult::Cancelled;
+            }
             if loop_guard == 0 {
                 renderer.line_if_not_empty(MessageStyle::Output)?;
+            } else if let Some(guidance) = message_queue.take_guidance(&handle) {
+                display_user_message(&mut renderer, &guidance)?;
+                working_history.push(uni::Message::user(guidance));
             }
             loop_guard += 1;
             if loop_guard >= max_tool_loops {
@@ -2388,8 +3211,41 @@ pub(crate) async fn run_single_agent_loop_unified(
                 working_history.push(uni::Message::assistant(notice));
                 break TurnLoopResult::Completed;
             }
+            let budget_limit = cost_tracker.lock().budget_exceeded();
+            if let Some(limit) = budget_limit {
+                if !bottom_gap_applied {
+                    renderer.line(MessageStyle::Output, "")?;
+                }
+                let notice = format!(
+                    "I paused before the next model call: {}",
+                    budget_pause_hint(limit)
+                );
+                renderer.line(MessageStyle::Error, &notice)?;
+                ensure_turn_bottom_gap(&mut renderer, &mut bottom_gap_applied)?;
+                working_history.push(uni::Message::assistant(notice));
+                break TurnLoopResult::Completed;
+            }
 
-            let _ = enforce_unified_context_window(&mut working_history, trim_config);
+            if auto_compaction_available
+                && compaction_settings.should_auto_compact(&working_history, tokenizer.as_ref())
+            {
+                let session = CompactionSession {
+                    provider: provider_client.as_ref(),
+                    model: &config.model,
+                    tokenizer: tokenizer.as_ref(),
+                    settings: &compaction_settings,
+                    ledger: &decision_ledger,
+                    cost_tracker: &cost_tracker,
+                    plan: tool_registry.current_plan(),
+                };
+                auto_compaction_available =
+                    auto_compact(&mut working_history, session, &mut renderer).await?;
+            }
+            let _ = enforce_unified_context_window(
+                &mut working_history,
+                trim_config,
+                tokenizer.as_ref(),
+            );
 
             let decision = if let Some(cfg) = vt_cfg.as_ref().filter(|cfg| cfg.router.enabled) {
                 Router::route_async(cfg, &config, &config.api_key, input).await
@@ -2449,9 +3305,14 @@ pub(crate) async fn run_single_agent_loop_unified(
 
             let mut attempt_history = working_history.clone();
             let mut retry_attempts = 0usize;
+            let mut response_usage: Option<SessionUsage> = None;
             let (response, response_streamed) = loop {
                 retry_attempts += 1;
-                let _ = enforce_unified_context_window(&mut attempt_history, trim_config);
+                let _ = enforce_unified_context_window(
+                    &mut attempt_history,
+                    trim_config,
+                    tokenizer.as_ref(),
+                );
 
                 if token_budget_enabled {
                     token_budget.reset().await;
@@ -2466,6 +3327,14 @@ pub(crate) async fn run_single_agent_loop_unified(
                 let curated_sections = build_curated_sections(&curated_context);
 
                 let mut system_prompt = base_system_prompt.clone();
+                if let Some(section) = memory_prompt.as_deref() {
+                    system_prompt.push_str("\n\n");
+                    system_prompt.push_str(section);
+                }
+                if tool_registry.plan_mode() {
+                    system_prompt.push_str("\n\n");
+                    system_prompt.push_str(PLAN_MODE_INSTRUCTIONS);
+                }
                 if token_budget_enabled {
                     token_budget
                         .count_tokens_for_component(
@@ -2510,7 +3379,15 @@ pub(crate) async fn run_single_agent_loop_unified(
                 let request = uni::LLMRequest {
                     messages: attempt_history.clone(),
                     system_prompt: Some(system_prompt.clone()),
-                    tools: Some(tools.clone()),
+                    tools: Some(if tool_registry.plan_mode() {
+                        tools
+                            .iter()
+                            .filter(|tool| tool_registry.is_read_only_tool(&tool.function.name))
+                            .cloned()
+                            .collect()
+                    } else {
+                        tools.clone()
+                    }),
                     model: active_model.clone(),
                     max_tokens: max_tokens_opt.or(Some(2000)),
                     temperature: Some(0.7),
@@ -2519,11 +3396,17 @@ pub(crate) async fn run_single_agent_loop_unified(
                     parallel_tool_calls: None,
                     parallel_tool_config: parallel_cfg_opt.clone(),
                     reasoning_effort,
+                    response_format: None,
                 };
+                let estimated_prompt_tokens = attempt_history
+                    .iter()
+                    .map(|message| unified_message_tokens(message, tokenizer.as_ref()))
+                    .sum::<usize>()
+                    + tokenizer.count(&system_prompt)
+                    + tokenizer.count(&tool_definitions_json);
 
                 let thinking_spinner =
                     PlaceholderSpinner::new(&handle, default_placeholder.clone(), "Thinking...");
-                let mut spinner_active = true;
                 task::yield_now().await;
                 let result = if use_streaming {
                     let outcome = stream_and_render_response(
@@ -2533,21 +3416,58 @@ pub(crate) async fn run_single_agent_loop_unified(
                         &mut renderer,
                     )
                     .await;
-                    spinner_active = false;
                     outcome
                 } else {
-                    provider_client
-                        .generate(request)
-                        .await
-                        .map(|resp| (resp, false))
+                    let outcome = provider_client.generate(request).await;
+                    thinking_spinner.finish();
+                    if let Ok(response) = outcome.as_ref() {
+                        render_reasoning_block(&mut renderer, response.reasoning.as_deref())?;
+                    }
+                    outcome.map(|resp| (resp, false))
                 };
 
-                if spinner_active {
-                    thinking_spinner.finish();
+                if let Some(status) = fallback_status.as_ref() {
+                    let current = status.active();
+                    if current != displayed_fallback {
+                        render_fallback_change(
+                            &mut renderer,
+                            &handle,
+                            &config,
+                            &session_bootstrap,
+                            current.as_ref(),
+                            provider_client.name(),
+                            mode_label.clone(),
+                            reasoning_label.clone(),
+                        )?;
+                        displayed_fallback = current;
+                    }
                 }
 
                 match result {
                     Ok((result, streamed_tokens)) => {
+                        if let Some(usage) = result.usage.as_ref() {
+                            tokenizer.record_usage(
+                                estimated_prompt_tokens,
+                                usage.prompt_tokens as usize,
+                            );
+                            let (billed_provider, billed_model) = displayed_fallback
+                                .as_ref()
+                                .map(|notice| (notice.provider.as_str(), notice.model.as_str()))
+                                .unwrap_or((provider_client.name(), active_model.as_str()));
+                            let cost = {
+                                let mut tracker = cost_tracker.lock();
+                                let cost = tracker.record(billed_provider, billed_model, usage);
+                                if cost.is_some() {
+                                    handle.set_header_cost(tracker.header_label());
+                                }
+                                cost
+                            };
+                            response_usage = Some(SessionUsage {
+                                prompt_tokens: usage.prompt_tokens,
+                                completion_tokens: usage.completion_tokens,
+                                cost_usd: cost,
+                            });
+                        }
                         working_history = attempt_history.clone();
                         break (result, streamed_tokens);
                     }
@@ -2600,7 +3520,9 @@ pub(crate) async fn run_single_agent_loop_unified(
                                 &format!("Provider error: {error_text}"),
                             )?;
                             ensure_turn_bottom_gap(&mut renderer, &mut bottom_gap_applied)?;
-                            break 'outer TurnLoopResult::Aborted;
+                            break 'outer TurnLoopResult::Aborted(format!(
+                                "Provider error: {error_text}"
+                            ));
                         }
                     }
                 }
@@ -2632,6 +3554,12 @@ pub(crate) async fn run_single_agent_loop_unified(
                 interpreted_textual_call = true;
                 final_text = None;
             }
+            response_details.record(
+                &tool_calls,
+                final_text.as_deref(),
+                response.reasoning.clone(),
+                response_usage,
+            );
 
             if tool_calls.is_empty()
                 && let Some(text) = final_text.clone()
@@ -2647,6 +3575,7 @@ pub(crate) async fn run_single_agent_loop_unified(
                     assistant_text,
                     tool_calls.clone(),
                 ));
+                let mut tool_attachments: Vec<uni::Attachment> = Vec::new();
                 for call in &tool_calls {
                     let name = call.function.name.as_str();
                     let args_val = call
@@ -2695,6 +3624,10 @@ pub(crate) async fn run_single_agent_loop_unified(
                         )
                     };
 
+                    if message_queue.interrupts_turn(&mut events, &handle, &mut pending_submission)
+                    {
+                        break 'outer TurnLoopResult::Cancelled;
+                    }
                     match ensure_tool_permission(
                         &mut tool_registry,
                         name,
@@ -2708,29 +3641,195 @@ pub(crate) async fn run_single_agent_loop_unified(
                     .await
                     {
                         Ok(ToolPermissionFlow::Approved) => {
-                            let tool_spinner = PlaceholderSpinner::new(
+                            // Preview once so the reviewed changes are the ones snapshotted for branches
+                            let previewed_changes = if ToolRegistry::supports_change_review(name) {
+                                match tool_registry.preview_file_changes(name, &args_val).await {
+                                    Ok(changes) => changes,
+                                    // A change the user cannot review must not be written unseen
+                                    Err(error) if review_file_changes => {
+                                        session_stats.record_tool(name);
+                                        let preview_error = ToolExecutionError::new(
+                                            name.to_string(),
+                                            ToolErrorType::ExecutionError,
+                                            format!("Failed to preview file changes: {error}"),
+                                        )
+                                        .to_json_value();
+                                        traj.log_tool_call(
+                                            working_history.len(),
+                                            name,
+                                            &args_val,
+                                            false,
+                                        );
+                                        render_tool_output(
+                                            &mut renderer,
+                                            Some(name),
+                                            &preview_error,
+                                            vt_cfg.as_ref(),
+                                        )?;
+                                        let content = serde_json::to_string(&preview_error)
+                                            .unwrap_or("{}".to_string());
+                                        working_history.push(uni::Message::tool_response(
+                                            call.id.clone(),
+                                            content,
+                                        ));
+                                        {
+                                            let mut ledger = decision_ledger.write().await;
+                                            ledger.record_outcome(
+                                                &dec_id,
+                                                DecisionOutcome::Failure {
+                                                    error: format!(
+                                                        "Failed to preview file changes: {error}"
+                                                    ),
+                                                    recovery_attempts: 0,
+                                                    context_preserved: true,
+                                                },
+                                            );
+                                        }
+                                        continue;
+                                    }
+                                    Err(_) => Vec::new(),
+                                }
+                            } else {
+                                Vec::new()
+                            };
+
+                            // Let the user accept, reject or rewrite each hunk before it is written
+                            let mut reviewed_changes = None;
+                            if review_file_changes && !previewed_changes.is_empty() {
+                                match review_changes(
+                                    previewed_changes.clone(),
+                                    &mut renderer,
+                                    &handle,
+                                    &mut events,
+                                    &ctrl_c_state,
+                                    &ctrl_c_notify,
+                                    default_placeholder.clone(),
+                                    &notifier,
+                                )
+                                .await?
+                                {
+                                    ChangeReviewOutcome::AcceptAll => {}
+                                    ChangeReviewOutcome::Reviewed(reviewed) => {
+                                        reviewed_changes = Some(reviewed);
+                                    }
+                                    ChangeReviewOutcome::Exit => {
+                                        renderer.line(MessageStyle::Info, "Goodbye!")?;
+                                        break 'outer TurnLoopResult::Cancelled;
+                                    }
+                                    ChangeReviewOutcome::Interrupted => {
+                                        break 'outer TurnLoopResult::Cancelled;
+                                    }
+                                }
+                            }
+
+                            // Pre-images let branch switches move the edited files along
+                            let pre_images =
+                                FilePreImages::capture(&config.workspace, &previewed_changes);
+
+                            let tool_started = Instant::now();
+                            let mut tool_spinner = PlaceholderSpinner::with_elapsed(
                                 &handle,
                                 default_placeholder.clone(),
                                 format!("Running tool: {}", name),
+                                tool_started,
                             );
+                            let mut live_output = LiveCommandOutput::new(vt_cfg.as_ref());
 
                             // Force TUI refresh to ensure display stability
                             safe_force_redraw(&handle, &mut last_forced_redraw);
 
-                            match tokio::time::timeout(
-                                tokio::time::Duration::from_secs(300), // 5 minute timeout for long-running tools
-                                tool_registry.execute_tool(name, args_val.clone()),
-                            )
-                            .await
-                            {
-                                Ok(Ok(tool_output)) => {
+                            let execution = async {
+                                match reviewed_changes.as_deref() {
+                                    Some(reviewed) => {
+                                        tool_registry.apply_reviewed_changes(reviewed).await
+                                    }
+                                    None => {
+                                        tool_registry.execute_tool(name, args_val.clone()).await
+                                    }
+                                }
+                            };
+                            tokio::pin!(execution);
+                            // 5 minute timeout for long-running tools, paused while the user
+                            // answers an MCP prompt
+                            let mut deadline =
+                                tokio::time::Instant::now() + Duration::from_secs(300);
+
+                            // Commands stream their output, and MCP providers may report progress
+                            // or ask for user input or sampling while the tool runs
+                            let tool_result = loop {
+                                let interaction = tokio::select! {
+                                    result = &mut execution => break Ok(result),
+                                    _ = tokio::time::sleep_until(deadline) => break Err(()),
+                                    Some(line) = command_output.recv() => {
+                                        live_output.push(&mut renderer, name, line)?;
+                                        continue;
+                                    }
+                                    Some(interaction) = next_mcp_interaction(&mut mcp_interactions) => interaction,
+                                };
+                                if let McpInteraction::Progress(progress) = &interaction {
+                                    tool_spinner.set_detail(progress.label());
+                                    continue;
+                                }
+
+                                tool_spinner.finish();
+                                let prompt_started = tokio::time::Instant::now();
+                                if message_queue.interrupts_turn(
+                                    &mut events,
+                                    &handle,
+                                    &mut pending_submission,
+                                ) {
+                                    break 'outer TurnLoopResult::Cancelled;
+                                }
+                                match handle_mcp_interaction(
+                                    interaction,
+                                    &mut renderer,
+                                    &handle,
+                                    &mut events,
+                                    &ctrl_c_state,
+                                    &ctrl_c_notify,
+                                    default_placeholder.clone(),
+                                    &notifier,
+                                )
+                                .await?
+                                {
+                                    McpInteractionOutcome::Handled => {}
+                                    McpInteractionOutcome::Exit => {
+                                        renderer.line(MessageStyle::Info, "Goodbye!")?;
+                                        break 'outer TurnLoopResult::Cancelled;
+                                    }
+                                    McpInteractionOutcome::Interrupted => {
+                                        break 'outer TurnLoopResult::Cancelled;
+                                    }
+                                }
+                                deadline += prompt_started.elapsed();
+                                tool_spinner = PlaceholderSpinner::with_elapsed(
+                                    &handle,
+                                    default_placeholder.clone(),
+                                    format!("Running tool: {}", name),
+                                    tool_started,
+                                );
+                            };
+                            while command_output.try_recv().is_ok() {}
+                            live_output.clear(&mut renderer);
+
+                            match tool_result {
+                                Ok(Ok(mut tool_output)) => {
                                     tool_spinner.finish();
+                                    branches.record_file_edits(pre_images);
+                                    if let Some(attachment) = take_tool_attachment(&mut tool_output)
+                                    {
+                                        tool_attachments.push(attachment);
+                                    }
 
                                     // Ensure TUI layout is clean after spinner finishes
                                     safe_force_redraw(&handle, &mut last_forced_redraw);
                                     tokio::time::sleep(Duration::from_millis(50)).await;
 
                                     session_stats.record_tool(name);
+                                    if name == tool_names::REMEMBER {
+                                        memory_prompt =
+                                            memory_store.prompt_section(&memory_config, &tokenizer);
+                                    }
                                     traj.log_tool_call(
                                         working_history.len(),
                                         name,
@@ -2967,12 +4066,15 @@ pub(crate) async fn run_single_agent_loop_unified(
                         }
                         Ok(ToolPermissionFlow::Denied) => {
                             session_stats.record_tool(name);
-                            let denial = ToolExecutionError::new(
-                                name.to_string(),
-                                ToolErrorType::PolicyViolation,
-                                format!("Tool '{}' execution denied by policy", name),
-                            )
-                            .to_json_value();
+                            let denial =
+                                ToolExecutionError::new(
+                                    name.to_string(),
+                                    ToolErrorType::PolicyViolation,
+                                    tool_registry.plan_mode_restriction(name).unwrap_or_else(
+                                        || format!("Tool '{}' execution denied by policy", name),
+                                    ),
+                                )
+                                .to_json_value();
                             traj.log_tool_call(working_history.len(), name, &args_val, false);
                             render_tool_output(
                                 &mut renderer,
@@ -3042,6 +4144,19 @@ pub(crate) async fn run_single_agent_loop_unified(
                         }
                     }
                 }
+                // Tool results are text-only for most providers, so media
+                // returned by tools follows the tool responses as user content.
+                if !tool_attachments.is_empty() {
+                    let names = tool_attachments
+                        .iter()
+                        .map(|attachment| attachment.file_name().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    working_history.push(uni::Message::user_with_attachments(
+                        format!("Contents of {} returned by the previous tool calls.", names),
+                        tool_attachments,
+                    ));
+                }
                 continue;
             }
 
@@ -3079,8 +4194,16 @@ pub(crate) async fn run_single_agent_loop_unified(
                                     None
                                 }
                             }),
+                            response_format: None,
                         };
                         let rr = provider_client.generate(review_req).await.ok();
+                        if let Some(usage) = rr.as_ref().and_then(|result| result.usage.as_ref()) {
+                            cost_tracker.lock().record(
+                                provider_client.name(),
+                                &config.model,
+                                usage,
+                            );
+                        }
                         if let Some(r) = rr.and_then(|result| result.content)
                             && !r.trim().is_empty()
                         {
@@ -3111,9 +4234,21 @@ pub(crate) async fn run_single_agent_loop_unified(
             } else {
                 ensure_turn_bottom_gap(&mut renderer, &mut bottom_gap_applied)?;
             }
+            if tool_registry.plan_mode()
+                && !plan_reminder_sent
+                && tool_registry.current_plan().version == plan_version_at_turn_start
+            {
+                plan_reminder_sent = true;
+                working_history.push(uni::Message::user(PLAN_MODE_REMINDER.to_string()));
+                continue;
+            }
             break TurnLoopResult::Completed;
         };
 
+        // Input typed during the final model call is queued for the next turn, and a
+        // steer at this point simply becomes the next submission.
+        let _ = message_queue.interrupts_turn(&mut events, &handle, &mut pending_submission);
+
         match turn_result {
             TurnLoopResult::Cancelled => {
                 if ctrl_c_state.is_exit_requested() {
@@ -3121,29 +4256,58 @@ pub(crate) async fn run_single_agent_loop_unified(
                 }
 
                 renderer.line_if_not_empty(MessageStyle::Output)?;
-                renderer.line(
-                    MessageStyle::Info,
-                    "Interrupted current task. Press Ctrl+C again to exit.",
-                )?;
-                handle.clear_input();
+                if pending_submission.is_some() {
+                    // Keep tool work the steered turn already finished so the model does
+                    // not repeat it for the replacement message.
+                    let completed = completed_exchange_len(&working_history);
+                    if completed > conversation_history.len() {
+                        working_history.truncate(completed);
+                        conversation_history = working_history;
+                    }
+                    renderer.line(
+                        MessageStyle::Info,
+                        "Interrupted current task to send your message.",
+                    )?;
+                } else {
+                    renderer.line(
+                        MessageStyle::Info,
+                        "Interrupted current task. Press Ctrl+C again to exit.",
+                    )?;
+                    // Hand queued messages back for editing instead of sending them
+                    match message_queue.take_all(&handle) {
+                        Some(queued) => handle.set_input(queued),
+                        None => handle.clear_input(),
+                    }
+                }
                 handle.set_placeholder(default_placeholder.clone());
                 ctrl_c_state.clear_cancel();
                 continue;
             }
-            TurnLoopResult::Aborted => {
+            TurnLoopResult::Aborted(message) => {
+                notifier.notify_after(
+                    &NotificationEvent::Error { message },
+                    turn_started.elapsed(),
+                );
                 let _ = conversation_history.pop();
+                branches.discard_last_prompt();
                 continue;
             }
             TurnLoopResult::Completed => {
+                notifier.notify_after(&NotificationEvent::TurnCompleted, turn_started.elapsed());
                 conversation_history = working_history;
 
-                let _pruned_after_turn = prune_unified_tool_responses(
+                if !compaction_settings.enabled {
+                    let _pruned_after_turn = prune_unified_tool_responses(
+                        &mut conversation_history,
+                        trim_config.preserve_recent_turns,
+                    );
+                }
+                // Removed: Tool response pruning message after completion
+                let post_trim = enforce_unified_context_window(
                     &mut conversation_history,
-                    trim_config.preserve_recent_turns,
+                    trim_config,
+                    tokenizer.as_ref(),
                 );
-                // Removed: Tool response pruning message after completion
-                let post_trim =
-                    enforce_unified_context_window(&mut conversation_history, trim_config);
                 if post_trim.is_trimmed() {
                     renderer.line(
                         MessageStyle::Info,
@@ -3169,6 +4333,64 @@ pub(crate) async fn run_single_agent_loop_unified(
                         )?;
                     }
                 }
+
+                let plan = tool_registry.current_plan();
+                if tool_registry.plan_mode()
+                    && plan.version != plan_version_at_turn_start
+                    && !plan.steps.is_empty()
+                {
+                    let decision = review_plan(
+                        &plan,
+                        &mut renderer,
+                        &handle,
+                        &mut events,
+                        &ctrl_c_state,
+                        &ctrl_c_notify,
+                        &notifier,
+                    )
+                    .await?;
+                    match decision {
+                        PlanReviewDecision::Approved => {
+                            set_plan_mode(
+                                false,
+                                &mut tool_registry,
+                                &mut renderer,
+                                &handle,
+                                &mut mode_label,
+                                config.ui_surface,
+                                full_auto,
+                            )?;
+                            pending_submission = Some(PLAN_APPROVED_MESSAGE.to_string());
+                        }
+                        PlanReviewDecision::Edit => {
+                            renderer.line(
+                                MessageStyle::Info,
+                                "Edit the request below and press Enter to get a revised plan.",
+                            )?;
+                            handle.set_input(plan_revision_draft(&plan));
+                        }
+                        PlanReviewDecision::Rejected => {
+                            renderer.line(
+                                MessageStyle::Info,
+                                "Plan rejected. Still in plan mode; describe another approach to get a new plan.",
+                            )?;
+                        }
+                        PlanReviewDecision::Exit => {
+                            renderer.line(MessageStyle::Info, "Goodbye!")?;
+                            break;
+                        }
+                        PlanReviewDecision::Interrupted => {
+                            if ctrl_c_state.is_exit_requested() {
+                                break;
+                            }
+                            renderer.line(
+                                MessageStyle::Info,
+                                "Plan review interrupted. Still in plan mode.",
+                            )?;
+                            ctrl_c_state.clear_cancel();
+                        }
+                    }
+                }
             }
         }
     }
@@ -3177,15 +4399,14 @@ pub(crate) async fn run_single_agent_loop_unified(
     if let Some(archive) = session_archive.take() {
         let distinct_tools = session_stats.sorted_tools();
         let total_messages = conversation_history.len();
-        let session_messages: Vec<SessionMessage> = conversation_history
-            .iter()
-            .map(SessionMessage::from)
-            .collect();
-        match archive.finalize(
+        let session_messages = response_details.session_messages(&conversation_history);
+        let session_branches = branches.session_branches(&conversation_history, &response_details);
+        match archive.finalize_with_branches(
             transcript_lines,
             total_messages,
             distinct_tools,
             session_messages,
+            session_branches,
         ) {
             Ok(path) => {
                 renderer.line(
@@ -3222,6 +4443,7 @@ pub(crate) async fn run_single_agent_loop_unified(
         }
     }
 
+    theme_watcher.abort();
     handle.shutdown();
     Ok(())
 }
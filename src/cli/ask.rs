@@ -2,11 +2,13 @@ use anyhow::{Context, Result};
 use console::style;
 use futures::StreamExt;
 use std::io::{self, Write};
+use std::path::Path;
 use vtcode_core::{
     config::types::AgentConfig as CoreAgentConfig,
     llm::{
         factory::{create_provider_for_model, create_provider_with_config},
-        provider::{LLMRequest, LLMResponse, LLMStreamEvent, Message, ToolChoice},
+        provider::{JsonSchema, LLMRequest, LLMResponse, LLMStreamEvent, Message, ToolChoice},
+        structured::generate_structured,
     },
 };
 
@@ -39,16 +41,48 @@ fn print_final_response(printed_any: bool, response: Option<LLMResponse>) {
     }
 }
 
+/// Load a JSON Schema file, naming it after its `title` or the file stem
+fn load_response_schema(path: &Path) -> Result<JsonSchema> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read schema file {}", path.display()))?;
+    let schema: serde_json::Value = serde_json::from_str(&raw)
+        .with_context(|| format!("Schema file {} is not valid JSON", path.display()))?;
+    if !schema.is_object() {
+        anyhow::bail!("Schema file {} must contain a JSON object", path.display());
+    }
+    let name = schema
+        .get("title")
+        .and_then(|title| title.as_str())
+        .map(str::to_string)
+        .or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default();
+    Ok(JsonSchema::new(name, schema))
+}
+
 /// Handle the ask command - single prompt, no tools
-pub async fn handle_ask_command(config: &CoreAgentConfig, prompt: &str) -> Result<()> {
+///
+/// With `schema_path`, the reply is constrained to the schema, validated and
+/// printed as bare JSON so it can be consumed by other tools.
+pub async fn handle_ask_command(
+    config: &CoreAgentConfig,
+    prompt: &str,
+    schema_path: Option<&Path>,
+) -> Result<()> {
     if prompt.trim().is_empty() {
         anyhow::bail!("No prompt provided. Use: vtcode ask \"Your question here\"");
     }
 
-    println!("{}", style("Single Prompt Mode").blue().bold());
-    println!("Provider: {}", &config.provider);
-    println!("Model: {}", &config.model);
-    println!();
+    let response_format = schema_path.map(load_response_schema).transpose()?;
+
+    if response_format.is_none() {
+        println!("{}", style("Single Prompt Mode").blue().bold());
+        println!("Provider: {}", &config.provider);
+        println!("Model: {}", &config.model);
+        println!();
+    }
 
     let provider = match create_provider_for_model(
         &config.model,
@@ -84,8 +118,17 @@ pub async fn handle_ask_command(config: &CoreAgentConfig, prompt: &str) -> Resul
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort,
+        response_format,
     };
 
+    if request.response_format.is_some() {
+        let structured = generate_structured(provider.as_ref(), request)
+            .await
+            .context("Structured completion failed")?;
+        println!("{}", serde_json::to_string_pretty(&structured.value)?);
+        return Ok(());
+    }
+
     match request_mode {
         AskRequestMode::Streaming => {
             let mut stream = provider
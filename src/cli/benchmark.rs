@@ -13,12 +13,15 @@ use vtcode_core::core::agent::runner::{AgentRunner, ContextItem, Task};
 use vtcode_core::core::agent::types::AgentType;
 use vtcode_core::utils::dot_config::WorkspaceTrustLevel;
 
+use super::swe_bench;
 use crate::workspace_trust::{WorkspaceTrustGateResult, ensure_workspace_trust};
 
 const ERROR_SPEC_REQUIRED: &str =
     "Provide a benchmark specification via --task-file, --task, or STDIN.";
 const ERROR_FULL_AUTO_REQUIRED: &str =
     "Benchmark runs require --full-auto/--auto with [automation.full_auto] enabled.";
+const ERROR_FULL_AUTO_TRUST_REQUIRED: &str =
+    "Benchmark runs require workspace trust level 'full_auto'. Upgrade trust before proceeding.";
 const ERROR_SPEC_EMPTY: &str = "Benchmark specification is empty.";
 const CONTEXT_PREFIX: &str = "ctx";
 const TASK_PREFIX: &str = "task";
@@ -33,12 +36,16 @@ pub struct BenchmarkCommandOptions {
     pub inline_task: Option<String>,
     pub output: Option<PathBuf>,
     pub max_tasks: Option<usize>,
+    /// SWE-bench JSONL dataset; switches to patch-graded evaluation
+    pub swe_bench: Option<PathBuf>,
 }
 
 #[derive(Debug)]
-struct PreparedTask {
-    task: Task,
-    contexts: Vec<ContextItem>,
+pub(crate) struct PreparedTask {
+    pub(crate) task: Task,
+    pub(crate) contexts: Vec<ContextItem>,
+    /// Shell command whose exit status decides whether the task passed
+    pub(crate) verify: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -77,6 +84,8 @@ struct RawTaskSpec {
     context: Option<String>,
     #[serde(default)]
     reference_context: Vec<RawContextEntry>,
+    #[serde(default, alias = "verification", alias = "test_command")]
+    verify: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -137,42 +146,28 @@ pub async fn handle_benchmark_command(
     options: BenchmarkCommandOptions,
     full_auto_requested: bool,
 ) -> Result<()> {
-    match ensure_workspace_trust(&config.workspace, true)? {
-        WorkspaceTrustGateResult::Trusted(level) => {
-            if level != WorkspaceTrustLevel::FullAuto {
-                bail!(
-                    "Benchmark command requires workspace trust level 'full_auto'. Upgrade trust before proceeding."
-                );
-            }
-        }
-        WorkspaceTrustGateResult::Aborted => {
-            return Ok(());
-        }
+    if !ensure_full_auto_ready(config, vt_cfg, full_auto_requested)? {
+        return Ok(());
     }
 
-    if !full_auto_requested {
-        bail!(ERROR_FULL_AUTO_REQUIRED);
+    if let Some(dataset) = options.swe_bench.as_deref() {
+        return swe_bench::run_swe_bench(
+            config,
+            vt_cfg,
+            dataset,
+            options.max_tasks,
+            options.output.as_deref(),
+        )
+        .await;
     }
 
     let automation_cfg = &vt_cfg.automation.full_auto;
-    if !automation_cfg.enabled {
-        bail!(ERROR_FULL_AUTO_REQUIRED);
-    }
-
-    let spec_source = load_spec_source(&options)?;
-    let mut tasks = parse_spec(&spec_source, &config.workspace)?;
-    if tasks.is_empty() {
-        bail!(ERROR_SPEC_EMPTY);
-    }
-
-    if let Some(limit) = options.max_tasks {
-        if limit == 0 {
-            bail!("--max-tasks must be greater than zero when provided.");
-        }
-        if tasks.len() > limit {
-            tasks.truncate(limit);
-        }
-    }
+    let tasks = load_tasks(
+        options.task_file.as_deref(),
+        options.inline_task.as_deref(),
+        options.max_tasks,
+        &config.workspace,
+    )?;
 
     let model_id = ModelId::from_str(&config.model).with_context(|| {
         format!(
@@ -245,15 +240,66 @@ pub async fn handle_benchmark_command(
     Ok(())
 }
 
-fn load_spec_source(options: &BenchmarkCommandOptions) -> Result<String> {
-    if let Some(inline) = &options.inline_task {
+/// Check the workspace trust and full-auto gates shared by unattended task runs.
+/// Returns `false` when the user aborted the trust prompt.
+pub(crate) fn ensure_full_auto_ready(
+    config: &CoreAgentConfig,
+    vt_cfg: &VTCodeConfig,
+    full_auto_requested: bool,
+) -> Result<bool> {
+    match ensure_workspace_trust(&config.workspace, true)? {
+        WorkspaceTrustGateResult::Trusted(level) => {
+            if level != WorkspaceTrustLevel::FullAuto {
+                bail!(ERROR_FULL_AUTO_TRUST_REQUIRED);
+            }
+        }
+        WorkspaceTrustGateResult::Aborted => {
+            return Ok(false);
+        }
+    }
+
+    if !full_auto_requested || !vt_cfg.automation.full_auto.enabled {
+        bail!(ERROR_FULL_AUTO_REQUIRED);
+    }
+
+    Ok(true)
+}
+
+/// Load the benchmark specification from the inline value, a file or STDIN
+/// and convert it into executable tasks.
+pub(crate) fn load_tasks(
+    task_file: Option<&Path>,
+    inline_task: Option<&str>,
+    max_tasks: Option<usize>,
+    workspace: &Path,
+) -> Result<Vec<PreparedTask>> {
+    let spec_source = load_spec_source(task_file, inline_task)?;
+    let mut tasks = parse_spec(&spec_source, workspace)?;
+    if tasks.is_empty() {
+        bail!(ERROR_SPEC_EMPTY);
+    }
+
+    if let Some(limit) = max_tasks {
+        if limit == 0 {
+            bail!("--max-tasks must be greater than zero when provided.");
+        }
+        if tasks.len() > limit {
+            tasks.truncate(limit);
+        }
+    }
+
+    Ok(tasks)
+}
+
+fn load_spec_source(task_file: Option<&Path>, inline_task: Option<&str>) -> Result<String> {
+    if let Some(inline) = inline_task {
         let trimmed = inline.trim();
         if !trimmed.is_empty() {
-            return Ok(inline.clone());
+            return Ok(inline.to_string());
         }
     }
 
-    if let Some(path) = &options.task_file {
+    if let Some(path) = task_file {
         let contents = fs::read_to_string(path).with_context(|| {
             format!(
                 "Failed to read benchmark specification from {}",
@@ -319,6 +365,7 @@ fn parse_spec(source: &str, workspace: &Path) -> Result<Vec<PreparedTask>> {
             instructions: None,
         },
         contexts: Vec::new(),
+        verify: None,
     }])
 }
 
@@ -372,6 +419,11 @@ fn prepare_task(mut raw: RawTaskSpec, index: usize, workspace: &Path) -> Result<
         .filter(|value| !value.is_empty());
 
     let contexts = build_contexts(raw.contexts, raw.reference_context, raw.context, workspace)?;
+    let verify = raw
+        .verify
+        .take()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
 
     let task = Task {
         id: identifier,
@@ -380,7 +432,11 @@ fn prepare_task(mut raw: RawTaskSpec, index: usize, workspace: &Path) -> Result<
         instructions,
     };
 
-    Ok(PreparedTask { task, contexts })
+    Ok(PreparedTask {
+        task,
+        contexts,
+        verify,
+    })
 }
 
 fn build_contexts(
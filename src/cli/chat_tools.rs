@@ -8,6 +8,7 @@ pub async fn handle_chat_command(
     config: &CoreAgentConfig,
     skip_confirmations: bool,
     full_auto: bool,
+    plan_mode: bool,
 ) -> Result<()> {
     match ensure_workspace_trust(&config.workspace, full_auto)? {
         WorkspaceTrustGateResult::Trusted(level) => {
@@ -19,5 +20,6 @@ pub async fn handle_chat_command(
             return Ok(());
         }
     }
-    crate::agent::runloop::run_single_agent_loop(config, skip_confirmations, full_auto).await
+    crate::agent::runloop::run_single_agent_loop(config, skip_confirmations, full_auto, plan_mode)
+        .await
 }
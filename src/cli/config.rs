@@ -3,6 +3,7 @@ use console::style;
 use std::fs;
 use std::io::Write;
 use std::path::Path;
+use vtcode_core::config::loader::flatten_table;
 use vtcode_core::config::{ConfigManager, VTCodeConfig};
 
 /// Handle the config command
@@ -41,6 +42,61 @@ pub async fn handle_config_command(output: Option<&Path>, use_home_dir: bool) ->
     Ok(())
 }
 
+/// Handle `vtcode config show`, printing the merged configuration and,
+/// with `--origin`, the layer that set each key
+pub fn handle_config_show_command(workspace: &Path, show_origin: bool) -> Result<()> {
+    let manager = ConfigManager::load_from_workspace(workspace)?;
+    println!("{}", style("Effective configuration").blue().bold());
+    if let Some(profile) = manager.profile() {
+        println!("Profile: {}", profile);
+    }
+    if manager.layers().is_empty() {
+        println!("Layers: none (defaults only)");
+    } else {
+        println!("Layers (lowest precedence first):");
+        for layer in manager.layers() {
+            println!("  {:<9} {}", layer.kind.as_str(), layer.path.display());
+        }
+    }
+    println!();
+
+    let mut table = toml::Table::try_from(manager.config())?;
+    // API keys captured by the model picker are secrets
+    if let Some(keys) = table
+        .get_mut("agent")
+        .and_then(|agent| agent.get_mut("custom_api_keys"))
+        .and_then(toml::Value::as_table_mut)
+    {
+        for (_, value) in keys.iter_mut() {
+            *value = toml::Value::String("********".to_string());
+        }
+    }
+
+    if !show_origin {
+        println!("{}", toml::to_string_pretty(&table)?);
+        return Ok(());
+    }
+
+    let entries = flatten_table(&table);
+    let width = entries.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    for (key, value) in entries {
+        let origin = manager
+            .origins()
+            .get(&key)
+            .map(ToString::to_string)
+            .unwrap_or_else(|| "default".to_string());
+        println!(
+            "{:<width$} = {}  {}",
+            key,
+            value,
+            style(format!("# {}", origin)).dim(),
+            width = width
+        );
+    }
+
+    Ok(())
+}
+
 /// Generate default configuration content
 /// This function creates a complete configuration by:
 /// 1. Loading existing vtcode.toml if it exists (preserving user customizations)
@@ -48,7 +48,7 @@ pub async fn handle_init_command(workspace: &Path, force: bool, run: bool) -> Re
             model_source: ModelSelectionSource::WorkspaceConfig,
             custom_api_keys: BTreeMap::new(),
         };
-        handle_chat_command(&config, false, false)
+        handle_chat_command(&config, false, false, false)
             .await
             .with_context(|| "failed to start chat session")?;
     }
@@ -13,9 +13,12 @@ pub mod create_project;
 pub mod init;
 pub mod init_project;
 pub mod man;
+pub mod models_compare;
 pub mod performance;
 pub mod revert;
+pub mod sessions;
 pub mod snapshots;
+pub mod swe_bench;
 pub mod trajectory;
 
 // Re-export command handlers for backward compatibility
@@ -27,13 +30,15 @@ pub use benchmark::{BenchmarkCommandOptions, handle_benchmark_command};
 // Use the modular runloop by default
 pub use chat_tools::handle_chat_command;
 pub use compress_context::handle_compress_context_command;
-pub use config::handle_config_command;
+pub use config::{handle_config_command, handle_config_show_command};
 pub use create_project::handle_create_project_command;
 pub use init::handle_init_command;
 pub use init_project::handle_init_project_command;
 pub use man::handle_man_command;
+pub use models_compare::{CompareCommandOptions, handle_models_compare_command};
 pub use performance::handle_performance_command;
 pub use revert::handle_revert_command;
+pub use sessions::handle_sessions_export_command;
 pub use snapshots::{handle_cleanup_snapshots_command, handle_snapshots_command};
 pub use trajectory::handle_trajectory_command as handle_trajectory_logs_command;
 
@@ -46,3 +51,11 @@ pub fn set_workspace_env(path: &Path) {
         std::env::set_var("WORKSPACE_DIR", path);
     }
 }
+
+/// Export the selected configuration profile so every config load applies it
+pub fn set_profile_env(profile: &str) {
+    // SAFETY: Setting a process-local environment variable is safe; the OS copies the value.
+    unsafe {
+        std::env::set_var(vtcode_core::config::loader::PROFILE_ENV_VAR, profile);
+    }
+}
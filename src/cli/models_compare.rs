@@ -0,0 +1,681 @@
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use vtcode_core::config::VTCodeConfig;
+use vtcode_core::config::api_keys::{ApiKeySources, get_api_key};
+use vtcode_core::config::models::ModelPricing;
+use vtcode_core::config::types::AgentConfig as CoreAgentConfig;
+use vtcode_core::core::agent::runner::{ContextItem, Task};
+use vtcode_core::core::cost_tracker::{format_usd, usage_cost};
+use vtcode_core::llm::factory::create_provider_with_config;
+use vtcode_core::llm::fallback::parse_chain_entry;
+use vtcode_core::llm::provider::{self as uni, LLMProvider};
+use vtcode_core::tools::build_function_declarations_with_mode;
+use vtcode_core::tools::registry::ToolRegistry;
+use walkdir::WalkDir;
+
+use super::benchmark::{PreparedTask, ensure_full_auto_ready, load_tasks};
+
+/// Directories that are never copied into the per-task sandboxes
+const SKIPPED_DIRS: [&str; 3] = [".git", "target", "node_modules"];
+const MAX_RESPONSE_TOKENS: u32 = 2000;
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(600);
+/// Characters of verification output kept in the report
+const VERIFY_OUTPUT_TAIL_CHARS: usize = 2000;
+
+/// Options passed from the CLI layer for `vtcode models compare`.
+#[derive(Debug, Clone)]
+pub struct CompareCommandOptions {
+    pub models: Vec<String>,
+    pub task_file: Option<PathBuf>,
+    pub inline_task: Option<String>,
+    pub output: Option<PathBuf>,
+    pub max_tasks: Option<usize>,
+    pub json: bool,
+}
+
+/// A model under evaluation together with the client used to reach it.
+pub(crate) struct CompareTarget {
+    pub(crate) label: String,
+    pub(crate) provider: String,
+    pub(crate) model: String,
+    pub(crate) client: Box<dyn LLMProvider>,
+}
+
+/// Settings shared by every task run.
+pub(crate) struct CompareSettings {
+    pub(crate) system_prompt: String,
+    pub(crate) allowed_tools: Vec<String>,
+    pub(crate) max_tool_loops: usize,
+    /// Per-model price overrides from `[cost.prices]`
+    pub(crate) prices: BTreeMap<String, ModelPricing>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ComparisonReport {
+    workspace: String,
+    task_count: usize,
+    models: Vec<ModelSummary>,
+    runs: Vec<TaskRun>,
+}
+
+#[derive(Debug, Serialize)]
+struct ModelSummary {
+    model: String,
+    provider: String,
+    passed: usize,
+    failed: usize,
+    pass_rate: f64,
+    total_latency_ms: u128,
+    avg_latency_ms: u128,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    cost_usd: Option<f64>,
+    tool_calls: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct TaskRun {
+    task_id: String,
+    model: String,
+    passed: bool,
+    latency_ms: u128,
+    llm_latency_ms: u128,
+    turns: usize,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    cost_usd: Option<f64>,
+    tool_calls: usize,
+    tool_errors: usize,
+    verification: Option<VerificationOutcome>,
+    summary: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct VerificationOutcome {
+    command: String,
+    exit_code: Option<i32>,
+    output: String,
+}
+
+pub async fn handle_models_compare_command(
+    config: &CoreAgentConfig,
+    vt_cfg: &VTCodeConfig,
+    options: CompareCommandOptions,
+    full_auto_requested: bool,
+) -> Result<()> {
+    if !ensure_full_auto_ready(config, vt_cfg, full_auto_requested)? {
+        return Ok(());
+    }
+
+    let tasks = load_tasks(
+        options.task_file.as_deref(),
+        options.inline_task.as_deref(),
+        options.max_tasks,
+        &config.workspace,
+    )?;
+
+    let mut targets = Vec::with_capacity(options.models.len());
+    for entry in options
+        .models
+        .iter()
+        .filter(|entry| !entry.trim().is_empty())
+    {
+        targets.push(create_target(entry, vt_cfg)?);
+    }
+    if targets.is_empty() {
+        bail!("Provide at least one model with --model.");
+    }
+
+    let settings = CompareSettings {
+        system_prompt: vtcode_core::prompts::read_system_prompt_from_md()
+            .unwrap_or_else(|_| vtcode_core::prompts::system::default_system_prompt().to_string()),
+        allowed_tools: vt_cfg.automation.full_auto.allowed_tools.clone(),
+        max_tool_loops: vt_cfg.tools.max_tool_loops.max(1),
+        prices: vt_cfg.cost.prices.clone(),
+    };
+
+    let report = run_comparison(&targets, &tasks, &config.workspace, &settings).await?;
+    let markdown = render_markdown(&report);
+    let json = serde_json::to_string_pretty(&report)
+        .context("Failed to serialize comparison report to JSON")?;
+
+    if let Some(path) = &options.output {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).with_context(|| {
+                format!(
+                    "Failed to create comparison report directory {}",
+                    parent.display()
+                )
+            })?;
+        }
+        let is_json = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        let contents = if is_json { &json } else { &markdown };
+        fs::write(path, contents)
+            .with_context(|| format!("Failed to write comparison report to {}", path.display()))?;
+    }
+
+    println!("{}", if options.json { json } else { markdown });
+    Ok(())
+}
+
+fn create_target(entry: &str, vt_cfg: &VTCodeConfig) -> Result<CompareTarget> {
+    let (provider, model) = parse_chain_entry(entry).map_err(|err| anyhow!("{}", err))?;
+    let api_key = match vt_cfg.agent.custom_api_keys.get(&provider) {
+        Some(key) if !key.trim().is_empty() => key.clone(),
+        _ => get_api_key(&provider, &ApiKeySources::for_provider(&provider))
+            .with_context(|| format!("No API key available for '{}'", entry))?,
+    };
+    let client = create_provider_with_config(
+        &provider,
+        Some(api_key),
+        None,
+        Some(model.clone()),
+        Some(vt_cfg.prompt_cache.clone()),
+    )
+    .map_err(|err| anyhow!("Failed to create provider for '{}': {}", entry, err))?;
+
+    Ok(CompareTarget {
+        label: entry.trim().to_string(),
+        provider,
+        model,
+        client,
+    })
+}
+
+/// Run every task against every target, each in a fresh copy of `workspace`.
+pub(crate) async fn run_comparison(
+    targets: &[CompareTarget],
+    tasks: &[PreparedTask],
+    workspace: &Path,
+    settings: &CompareSettings,
+) -> Result<ComparisonReport> {
+    let tools: Vec<uni::ToolDefinition> = build_function_declarations_with_mode(false)
+        .into_iter()
+        .map(|decl| uni::ToolDefinition::function(decl.name, decl.description, decl.parameters))
+        .collect();
+
+    let mut runs = Vec::with_capacity(targets.len() * tasks.len());
+    let mut models = Vec::with_capacity(targets.len());
+    for target in targets {
+        let mut target_runs = Vec::with_capacity(tasks.len());
+        for prepared in tasks {
+            eprintln!("[compare] {} · {}", target.label, prepared.task.id);
+            let run = run_task(target, prepared, workspace, &tools, settings).await?;
+            eprintln!(
+                "[compare] {} · {}: {}",
+                target.label,
+                prepared.task.id,
+                if run.passed { "pass" } else { "fail" }
+            );
+            target_runs.push(run);
+        }
+        models.push(summarize(target, &target_runs));
+        runs.extend(target_runs);
+    }
+
+    Ok(ComparisonReport {
+        workspace: workspace.display().to_string(),
+        task_count: tasks.len(),
+        models,
+        runs,
+    })
+}
+
+async fn run_task(
+    target: &CompareTarget,
+    prepared: &PreparedTask,
+    workspace: &Path,
+    tools: &[uni::ToolDefinition],
+    settings: &CompareSettings,
+) -> Result<TaskRun> {
+    let sandbox = tempfile::Builder::new()
+        .prefix("vtcode-compare-")
+        .tempdir()
+        .context("Failed to create comparison sandbox")?;
+    copy_workspace(workspace, sandbox.path())?;
+
+    let mut registry = ToolRegistry::new(sandbox.path().to_path_buf());
+    registry.enable_full_auto_mode(&settings.allowed_tools);
+    let pricing = settings
+        .prices
+        .get(&target.model)
+        .copied()
+        .or_else(|| ModelPricing::for_model(&target.model));
+    let cache_reported_separately = target.provider.eq_ignore_ascii_case("anthropic");
+
+    let mut run = TaskRun {
+        task_id: prepared.task.id.clone(),
+        model: target.label.clone(),
+        passed: false,
+        latency_ms: 0,
+        llm_latency_ms: 0,
+        turns: 0,
+        prompt_tokens: 0,
+        completion_tokens: 0,
+        cost_usd: pricing.map(|_| 0.0),
+        tool_calls: 0,
+        tool_errors: 0,
+        verification: None,
+        summary: None,
+        error: None,
+    };
+
+    let started = Instant::now();
+    let mut messages = vec![uni::Message::user(task_prompt(
+        &prepared.task,
+        &prepared.contexts,
+    ))];
+    let mut completed = false;
+    while run.turns < settings.max_tool_loops {
+        run.turns += 1;
+        let request = uni::LLMRequest {
+            messages: messages.clone(),
+            system_prompt: Some(settings.system_prompt.clone()),
+            tools: Some(tools.to_vec()),
+            model: target.model.clone(),
+            max_tokens: Some(MAX_RESPONSE_TOKENS),
+            temperature: Some(0.0),
+            stream: false,
+            tool_choice: Some(uni::ToolChoice::auto()),
+            parallel_tool_calls: None,
+            parallel_tool_config: None,
+            reasoning_effort: None,
+            response_format: None,
+        };
+
+        let llm_started = Instant::now();
+        let response = target.client.generate(request).await;
+        run.llm_latency_ms += llm_started.elapsed().as_millis();
+        let response = match response {
+            Ok(response) => response,
+            Err(err) => {
+                run.error = Some(err.to_string());
+                break;
+            }
+        };
+
+        if let Some(usage) = response.usage.as_ref() {
+            run.prompt_tokens += usage.prompt_tokens as u64;
+            run.completion_tokens += usage.completion_tokens as u64;
+            if let (Some(total), Some(pricing)) = (run.cost_usd.as_mut(), pricing.as_ref()) {
+                *total += usage_cost(pricing, usage, cache_reported_separately);
+            }
+        }
+
+        let content = response.content.unwrap_or_default();
+        let tool_calls = response.tool_calls.unwrap_or_default();
+        if tool_calls.is_empty() {
+            let trimmed = content.trim();
+            run.summary = (!trimmed.is_empty()).then(|| trimmed.to_string());
+            completed = true;
+            break;
+        }
+
+        messages.push(uni::Message::assistant_with_tools(
+            content,
+            tool_calls.clone(),
+        ));
+        for call in tool_calls {
+            run.tool_calls += 1;
+            let output = execute_tool_call(&mut registry, &call).await;
+            if output.get("error").is_some() {
+                run.tool_errors += 1;
+            }
+            messages.push(uni::Message::tool_response(
+                call.id.clone(),
+                serde_json::to_string(&output).unwrap_or_else(|_| output.to_string()),
+            ));
+        }
+    }
+    if !completed && run.error.is_none() {
+        run.error = Some(format!(
+            "Reached the tool loop limit of {}",
+            settings.max_tool_loops
+        ));
+    }
+
+    match prepared.verify.as_deref() {
+        Some(command) => {
+            let outcome = run_verification(command, sandbox.path()).await;
+            run.passed = outcome.exit_code == Some(0);
+            run.verification = Some(outcome);
+        }
+        None => run.passed = completed && run.error.is_none(),
+    }
+    run.latency_ms = started.elapsed().as_millis();
+
+    Ok(run)
+}
+
+async fn execute_tool_call(registry: &mut ToolRegistry, call: &uni::ToolCall) -> Value {
+    let name = call.function.name.as_str();
+    let args = match call.parsed_arguments() {
+        Ok(args) => args,
+        Err(err) => {
+            return serde_json::json!({
+                "error": { "message": format!("Invalid arguments for {}: {}", name, err) }
+            });
+        }
+    };
+
+    // Full-auto pre-approves allow-listed tools so no permission prompt is shown.
+    if let Err(err) = registry.evaluate_tool_policy(name) {
+        return serde_json::json!({ "error": { "message": err.to_string() } });
+    }
+    match registry.execute_tool(name, args).await {
+        Ok(value) => value,
+        Err(err) => serde_json::json!({ "error": { "message": err.to_string() } }),
+    }
+}
+
+fn task_prompt(task: &Task, contexts: &[ContextItem]) -> String {
+    let mut prompt = format!("Task: {}\n\n{}", task.title, task.description);
+    if let Some(instructions) = task.instructions.as_ref() {
+        prompt.push_str("\n\n");
+        prompt.push_str(instructions);
+    }
+    if !contexts.is_empty() {
+        prompt.push_str("\n\nRelevant Context:");
+        for context in contexts {
+            prompt.push_str(&format!("\n[{}]\n{}", context.id, context.content));
+        }
+    }
+    prompt
+}
+
+async fn run_verification(command: &str, sandbox: &Path) -> VerificationOutcome {
+    let mut process = Command::new("sh");
+    process
+        .arg("-c")
+        .arg(command)
+        .current_dir(sandbox)
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+
+    let (exit_code, output) = match tokio::time::timeout(VERIFY_TIMEOUT, process.output()).await {
+        Ok(Ok(output)) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            (output.status.code(), text)
+        }
+        Ok(Err(err)) => (None, format!("Failed to run verification: {}", err)),
+        Err(_) => (
+            None,
+            format!("Verification timed out after {}s", VERIFY_TIMEOUT.as_secs()),
+        ),
+    };
+
+    VerificationOutcome {
+        command: command.to_string(),
+        exit_code,
+        output: tail_chars(output.trim(), VERIFY_OUTPUT_TAIL_CHARS),
+    }
+}
+
+fn tail_chars(text: &str, limit: usize) -> String {
+    let count = text.chars().count();
+    if count <= limit {
+        return text.to_string();
+    }
+    text.chars().skip(count - limit).collect()
+}
+
+/// Copy `source` into `destination`, skipping VCS metadata and build output.
+pub(crate) fn copy_workspace(source: &Path, destination: &Path) -> Result<()> {
+    let walker = WalkDir::new(source).into_iter().filter_entry(|entry| {
+        entry.depth() == 0
+            || !(entry.file_type().is_dir()
+                && SKIPPED_DIRS
+                    .iter()
+                    .any(|skipped| entry.file_name() == *skipped))
+    });
+
+    for entry in walker {
+        let entry = entry.context("Failed to walk workspace for comparison sandbox")?;
+        let relative = entry
+            .path()
+            .strip_prefix(source)
+            .context("Workspace entry outside of the workspace root")?;
+        let target = destination.join(relative);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)
+                .with_context(|| format!("Failed to create {}", target.display()))?;
+        } else if entry.file_type().is_file() {
+            fs::copy(entry.path(), &target).with_context(|| {
+                format!(
+                    "Failed to copy {} into comparison sandbox",
+                    entry.path().display()
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn summarize(target: &CompareTarget, runs: &[TaskRun]) -> ModelSummary {
+    let passed = runs.iter().filter(|run| run.passed).count();
+    let total_latency_ms: u128 = runs.iter().map(|run| run.latency_ms).sum();
+    let cost_usd = runs.iter().map(|run| run.cost_usd).sum::<Option<f64>>();
+    ModelSummary {
+        model: target.label.clone(),
+        provider: target.provider.clone(),
+        passed,
+        failed: runs.len() - passed,
+        pass_rate: if runs.is_empty() {
+            0.0
+        } else {
+            passed as f64 / runs.len() as f64
+        },
+        total_latency_ms,
+        avg_latency_ms: total_latency_ms / runs.len().max(1) as u128,
+        prompt_tokens: runs.iter().map(|run| run.prompt_tokens).sum(),
+        completion_tokens: runs.iter().map(|run| run.completion_tokens).sum(),
+        cost_usd,
+        tool_calls: runs.iter().map(|run| run.tool_calls).sum(),
+    }
+}
+
+fn format_cost(cost: Option<f64>) -> String {
+    cost.map(format_usd).unwrap_or_else(|| "n/a".to_string())
+}
+
+fn format_latency(ms: u128) -> String {
+    format!("{:.1}s", ms as f64 / 1000.0)
+}
+
+pub(crate) fn render_markdown(report: &ComparisonReport) -> String {
+    let mut out = String::from("# Model comparison\n\n");
+    out.push_str(&format!(
+        "Workspace: `{}` · Tasks: {}\n\n",
+        report.workspace, report.task_count
+    ));
+
+    out.push_str("| Model | Passed | Avg latency | Prompt tokens | Completion tokens | Cost | Tool calls |\n");
+    out.push_str("|---|---|---|---|---|---|---|\n");
+    for summary in &report.models {
+        out.push_str(&format!(
+            "| {} | {}/{} ({:.0}%) | {} | {} | {} | {} | {} |\n",
+            summary.model,
+            summary.passed,
+            summary.passed + summary.failed,
+            summary.pass_rate * 100.0,
+            format_latency(summary.avg_latency_ms),
+            summary.prompt_tokens,
+            summary.completion_tokens,
+            format_cost(summary.cost_usd),
+            summary.tool_calls
+        ));
+    }
+
+    out.push_str("\n## Tasks\n\n");
+    out.push_str(
+        "| Task | Model | Result | Latency | Tokens (in/out) | Cost | Tool calls | Notes |\n",
+    );
+    out.push_str("|---|---|---|---|---|---|---|---|\n");
+    for run in &report.runs {
+        let note = run
+            .error
+            .clone()
+            .or_else(|| {
+                run.verification.as_ref().and_then(|outcome| {
+                    (outcome.exit_code != Some(0)).then(|| {
+                        format!(
+                            "`{}` exited with {}",
+                            outcome.command,
+                            outcome
+                                .exit_code
+                                .map(|code| code.to_string())
+                                .unwrap_or_else(|| "no status".to_string())
+                        )
+                    })
+                })
+            })
+            .unwrap_or_default()
+            .replace('|', "\\|")
+            .replace('\n', " ");
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {}/{} | {} | {} ({} failed) | {} |\n",
+            run.task_id,
+            run.model,
+            if run.passed { "pass" } else { "fail" },
+            format_latency(run.latency_ms),
+            run.prompt_tokens,
+            run.completion_tokens,
+            format_cost(run.cost_usd),
+            run.tool_calls,
+            run.tool_errors,
+            note
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use vtcode_core::llm::provider::{FinishReason, LLMError, LLMRequest, LLMResponse, Usage};
+
+    /// Replays canned responses: a `write_file` call followed by a final answer.
+    struct MockProvider {
+        writes: Option<&'static str>,
+        turns: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl LLMProvider for MockProvider {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn generate(&self, _request: LLMRequest) -> Result<LLMResponse, LLMError> {
+            let mut turns = self.turns.lock().unwrap();
+            *turns += 1;
+            let tool_calls = match (self.writes, *turns) {
+                (Some(content), 1) => Some(vec![uni::ToolCall::function(
+                    "call_1".to_string(),
+                    "write_file".to_string(),
+                    serde_json::json!({ "path": "answer.txt", "content": content }).to_string(),
+                )]),
+                _ => None,
+            };
+            Ok(LLMResponse {
+                content: Some("Task completed.".to_string()),
+                tool_calls,
+                usage: Some(Usage {
+                    prompt_tokens: 1000,
+                    completion_tokens: 100,
+                    total_tokens: 1100,
+                    cached_prompt_tokens: None,
+                    cache_creation_tokens: None,
+                    cache_read_tokens: None,
+                }),
+                finish_reason: FinishReason::Stop,
+                reasoning: None,
+            })
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            vec!["gpt-5".to_string()]
+        }
+
+        fn validate_request(&self, _request: &LLMRequest) -> Result<(), LLMError> {
+            Ok(())
+        }
+    }
+
+    fn target(label: &str, writes: Option<&'static str>) -> CompareTarget {
+        CompareTarget {
+            label: label.to_string(),
+            provider: "openai".to_string(),
+            model: "gpt-5".to_string(),
+            client: Box::new(MockProvider {
+                writes,
+                turns: Mutex::new(0),
+            }),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn compares_models_in_isolated_workspaces() {
+        let workspace = tempfile::tempdir().unwrap();
+        fs::write(workspace.path().join("README.md"), "fixture").unwrap();
+        fs::create_dir_all(workspace.path().join("target")).unwrap();
+        fs::write(workspace.path().join("target").join("big.bin"), "skip").unwrap();
+
+        let tasks = vec![PreparedTask {
+            task: Task::new(
+                "write-answer".to_string(),
+                "Write the answer".to_string(),
+                "Create answer.txt containing 42".to_string(),
+            ),
+            contexts: Vec::new(),
+            verify: Some(
+                "grep -q 42 answer.txt && test -f README.md && test ! -d target".to_string(),
+            ),
+        }];
+        let settings = CompareSettings {
+            system_prompt: "You are a test agent.".to_string(),
+            allowed_tools: vec!["write_file".to_string()],
+            max_tool_loops: 5,
+            prices: BTreeMap::new(),
+        };
+        let targets = vec![target("good", Some("42")), target("lazy", None)];
+
+        let report = run_comparison(&targets, &tasks, workspace.path(), &settings)
+            .await
+            .unwrap();
+
+        assert!(!workspace.path().join("answer.txt").exists());
+        assert_eq!(report.models.len(), 2);
+        assert_eq!(report.models[0].passed, 1);
+        assert_eq!(report.models[0].tool_calls, 1);
+        assert_eq!(report.models[0].prompt_tokens, 2000);
+        let cost = report.models[0].cost_usd.unwrap();
+        assert!((cost - 0.0045).abs() < 1e-9);
+        assert_eq!(report.models[1].passed, 0);
+        assert_ne!(
+            report.runs[1].verification.as_ref().unwrap().exit_code,
+            Some(0)
+        );
+
+        let markdown = render_markdown(&report);
+        assert!(markdown.contains("| good | 1/1 (100%)"));
+        assert!(markdown.contains("| write-answer | lazy | fail |"));
+        let json: Value = serde_json::from_str(&serde_json::to_string(&report).unwrap()).unwrap();
+        assert_eq!(json["runs"][0]["passed"], Value::Bool(true));
+    }
+}
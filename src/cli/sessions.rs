@@ -0,0 +1,39 @@
+use anyhow::{Result, anyhow};
+use std::io::Write;
+use std::path::Path;
+use vtcode_core::utils::session_archive;
+use vtcode_core::utils::session_export::{self, ExportFormat};
+
+/// Render an archived session to a file, or to stdout when no output is given
+pub fn handle_sessions_export_command(
+    id: &str,
+    format: Option<ExportFormat>,
+    output: Option<&Path>,
+) -> Result<()> {
+    let listing = session_archive::find_session(id)?.ok_or_else(|| {
+        anyhow!(
+            "No archived session matches '{}'. Run /sessions to list IDs.",
+            id
+        )
+    })?;
+    let format = format
+        .or_else(|| {
+            output
+                .and_then(|path| path.extension())
+                .and_then(|ext| ext.to_str())
+                .and_then(ExportFormat::parse)
+        })
+        .unwrap_or(ExportFormat::Markdown);
+
+    match output {
+        Some(path) => {
+            session_export::write_session(&listing.snapshot, format, path)?;
+            eprintln!("Exported {} to {}", listing.identifier(), path.display());
+        }
+        None => {
+            let rendered = session_export::render_session(&listing.snapshot, format)?;
+            std::io::stdout().write_all(rendered.as_bytes())?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,628 @@
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use vtcode_core::config::VTCodeConfig;
+use vtcode_core::config::models::ModelId;
+use vtcode_core::config::types::AgentConfig as CoreAgentConfig;
+use vtcode_core::core::agent::runner::{AgentRunner, Task};
+use vtcode_core::core::agent::types::AgentType;
+
+use super::models_compare::copy_workspace;
+
+const DEFAULT_TEST_COMMAND: &str = "python -m pytest -q";
+const TEST_TIMEOUT: Duration = Duration::from_secs(900);
+const WORKTREE_DIR: &str = "repo";
+const GIT_IDENTITY: [&str; 4] = [
+    "-c",
+    "user.name=vtcode",
+    "-c",
+    "user.email=vtcode@localhost",
+];
+const AGENT_INSTRUCTIONS: &str = "Edit the repository in the current workspace so the issue above is resolved. \
+Keep the change minimal and do not modify existing tests; hidden tests will grade the result.";
+
+/// A single SWE-bench instance read from a JSONL dataset.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct SweBenchInstance {
+    pub(crate) instance_id: String,
+    /// Local snapshot of the repository; relative paths resolve against the dataset file
+    #[serde(default, alias = "repo_dir", alias = "snapshot_path")]
+    pub(crate) repo_path: Option<PathBuf>,
+    /// Upstream `owner/name`, used to locate `owner__name` next to the dataset
+    #[serde(default)]
+    pub(crate) repo: Option<String>,
+    #[serde(default)]
+    pub(crate) base_commit: Option<String>,
+    pub(crate) problem_statement: String,
+    #[serde(default)]
+    pub(crate) hints_text: Option<String>,
+    /// Patch adding the grading tests, applied after the agent finishes
+    #[serde(default)]
+    pub(crate) test_patch: Option<String>,
+    #[serde(default, rename = "FAIL_TO_PASS", deserialize_with = "test_list")]
+    pub(crate) fail_to_pass: Vec<String>,
+    #[serde(default, rename = "PASS_TO_PASS", deserialize_with = "test_list")]
+    pub(crate) pass_to_pass: Vec<String>,
+    /// Command prefix that receives a single test identifier
+    #[serde(default, alias = "test_command")]
+    pub(crate) test_cmd: Option<String>,
+}
+
+/// Upstream SWE-bench stores test lists as JSON-encoded strings; accept both forms.
+fn test_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawTests {
+        List(Vec<String>),
+        Encoded(String),
+    }
+
+    match RawTests::deserialize(deserializer)? {
+        RawTests::List(tests) => Ok(tests),
+        RawTests::Encoded(text) if text.trim().is_empty() => Ok(Vec::new()),
+        RawTests::Encoded(text) => serde_json::from_str(&text).map_err(serde::de::Error::custom),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SweBenchReport {
+    model: String,
+    provider: String,
+    dataset: String,
+    instance_count: usize,
+    resolved: usize,
+    resolved_rate: f64,
+    empty_patches: usize,
+    errors: usize,
+    instances: Vec<InstanceReport>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct InstanceReport {
+    pub(crate) instance_id: String,
+    pub(crate) resolved: bool,
+    pub(crate) duration_ms: u128,
+    pub(crate) patch: String,
+    pub(crate) fail_to_pass: TestGroupReport,
+    pub(crate) pass_to_pass: TestGroupReport,
+    pub(crate) agent_warnings: Vec<String>,
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct TestGroupReport {
+    pub(crate) passed: Vec<String>,
+    pub(crate) failed: Vec<String>,
+}
+
+impl TestGroupReport {
+    fn all_passed(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Evaluate the agent on a SWE-bench JSONL dataset and write per-instance results.
+pub(crate) async fn run_swe_bench(
+    config: &CoreAgentConfig,
+    vt_cfg: &VTCodeConfig,
+    dataset: &Path,
+    max_tasks: Option<usize>,
+    output: Option<&Path>,
+) -> Result<()> {
+    let mut instances = load_instances(dataset)?;
+    if let Some(limit) = max_tasks {
+        if limit == 0 {
+            bail!("--max-tasks must be greater than zero when provided.");
+        }
+        instances.truncate(limit);
+    }
+
+    let model_id = ModelId::from_str(&config.model).with_context(|| {
+        format!(
+            "Model '{}' is not recognized for benchmark execution. Update vtcode.toml to a supported identifier.",
+            config.model
+        )
+    })?;
+    let dataset_dir = dataset.parent().unwrap_or_else(|| Path::new("."));
+
+    let mut reports = Vec::with_capacity(instances.len());
+    for instance in &instances {
+        eprintln!("[swe-bench] {}", instance.instance_id);
+        let started = Instant::now();
+        let mut report = match run_instance(config, vt_cfg, model_id, instance, dataset_dir).await {
+            Ok(report) => report,
+            Err(err) => InstanceReport {
+                instance_id: instance.instance_id.clone(),
+                error: Some(format!("{:#}", err)),
+                ..InstanceReport::default()
+            },
+        };
+        report.duration_ms = started.elapsed().as_millis();
+        eprintln!(
+            "[swe-bench] {}: {}",
+            instance.instance_id,
+            if report.resolved {
+                "resolved"
+            } else {
+                "unresolved"
+            }
+        );
+        reports.push(report);
+    }
+
+    let resolved = reports.iter().filter(|report| report.resolved).count();
+    let report = SweBenchReport {
+        model: config.model.clone(),
+        provider: config.provider.clone(),
+        dataset: dataset.display().to_string(),
+        instance_count: reports.len(),
+        resolved,
+        resolved_rate: if reports.is_empty() {
+            0.0
+        } else {
+            resolved as f64 / reports.len() as f64
+        },
+        empty_patches: reports
+            .iter()
+            .filter(|report| report.error.is_none() && report.patch.trim().is_empty())
+            .count(),
+        errors: reports
+            .iter()
+            .filter(|report| report.error.is_some())
+            .count(),
+        instances: reports,
+    };
+
+    let serialized = serde_json::to_string_pretty(&report)
+        .context("Failed to serialize SWE-bench report to JSON")?;
+    if let Some(path) = output {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).with_context(|| {
+                format!(
+                    "Failed to create benchmark report directory {}",
+                    parent.display()
+                )
+            })?;
+        }
+        fs::write(path, serialized.as_bytes())
+            .with_context(|| format!("Failed to write benchmark report to {}", path.display()))?;
+    }
+    println!("{}", serialized);
+
+    Ok(())
+}
+
+async fn run_instance(
+    config: &CoreAgentConfig,
+    vt_cfg: &VTCodeConfig,
+    model_id: ModelId,
+    instance: &SweBenchInstance,
+    dataset_dir: &Path,
+) -> Result<InstanceReport> {
+    let repo = resolve_repo_path(instance, dataset_dir)?;
+    let worktree = ScratchWorktree::create(&repo, instance.base_commit.as_deref())?;
+
+    let mut runner = AgentRunner::new(
+        AgentType::Single,
+        model_id,
+        config.api_key.clone(),
+        worktree.path(),
+        format!("swe-bench-{}", instance.instance_id),
+        Some(config.reasoning_effort),
+    )?;
+    runner
+        .apply_workspace_configuration(vt_cfg)
+        .await
+        .context("Failed to apply workspace configuration to benchmark runner")?;
+    runner.enable_full_auto(&vt_cfg.automation.full_auto.allowed_tools);
+
+    let task = instance_task(instance);
+    let agent_warnings = match runner.execute_task(&task, &[]).await {
+        Ok(result) => result.warnings,
+        Err(err) => vec![format!("Agent run failed: {:#}", err)],
+    };
+
+    let mut report = grade_instance(&worktree, instance).await?;
+    report.agent_warnings = agent_warnings;
+    Ok(report)
+}
+
+fn instance_task(instance: &SweBenchInstance) -> Task {
+    let mut task = Task::new(
+        instance.instance_id.clone(),
+        format!("Resolve {}", instance.instance_id),
+        instance.problem_statement.trim().to_string(),
+    );
+    let mut instructions = AGENT_INSTRUCTIONS.to_string();
+    if let Some(hints) = instance
+        .hints_text
+        .as_deref()
+        .map(str::trim)
+        .filter(|hints| !hints.is_empty())
+    {
+        instructions.push_str("\n\nHints:\n");
+        instructions.push_str(hints);
+    }
+    task.instructions = Some(instructions);
+    task
+}
+
+/// Extract the agent's diff, apply the grading tests and run both test groups.
+pub(crate) async fn grade_instance(
+    worktree: &ScratchWorktree,
+    instance: &SweBenchInstance,
+) -> Result<InstanceReport> {
+    let mut report = InstanceReport {
+        instance_id: instance.instance_id.clone(),
+        patch: worktree.diff()?,
+        ..InstanceReport::default()
+    };
+
+    if let Some(test_patch) = instance
+        .test_patch
+        .as_deref()
+        .filter(|patch| !patch.trim().is_empty())
+        && let Err(err) = worktree.apply_patch(test_patch)
+    {
+        report.error = Some(format!("Test patch did not apply: {:#}", err));
+        return Ok(report);
+    }
+
+    let test_cmd = instance.test_cmd.as_deref().unwrap_or(DEFAULT_TEST_COMMAND);
+    report.fail_to_pass = run_tests(&worktree.path(), test_cmd, &instance.fail_to_pass).await;
+    report.pass_to_pass = run_tests(&worktree.path(), test_cmd, &instance.pass_to_pass).await;
+    report.resolved = !instance.fail_to_pass.is_empty()
+        && report.fail_to_pass.all_passed()
+        && report.pass_to_pass.all_passed();
+    Ok(report)
+}
+
+async fn run_tests(workdir: &Path, test_cmd: &str, tests: &[String]) -> TestGroupReport {
+    let mut group = TestGroupReport::default();
+    for test in tests {
+        let command = format!("{} {}", test_cmd, shell_quote(test));
+        let mut process = tokio::process::Command::new("sh");
+        process
+            .arg("-c")
+            .arg(&command)
+            .current_dir(workdir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+        let passed = matches!(
+            tokio::time::timeout(TEST_TIMEOUT, process.status()).await,
+            Ok(Ok(status)) if status.success()
+        );
+        if passed {
+            group.passed.push(test.clone());
+        } else {
+            group.failed.push(test.clone());
+        }
+    }
+    group
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+pub(crate) fn load_instances(dataset: &Path) -> Result<Vec<SweBenchInstance>> {
+    let contents = fs::read_to_string(dataset)
+        .with_context(|| format!("Failed to read SWE-bench dataset {}", dataset.display()))?;
+    let mut instances = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let instance: SweBenchInstance = serde_json::from_str(trimmed).with_context(|| {
+            format!(
+                "Failed to parse SWE-bench instance on line {} of {}",
+                index + 1,
+                dataset.display()
+            )
+        })?;
+        instances.push(instance);
+    }
+    if instances.is_empty() {
+        bail!("SWE-bench dataset {} has no instances.", dataset.display());
+    }
+    Ok(instances)
+}
+
+fn resolve_repo_path(instance: &SweBenchInstance, dataset_dir: &Path) -> Result<PathBuf> {
+    let candidate = match (&instance.repo_path, &instance.repo) {
+        (Some(path), _) => dataset_dir.join(path),
+        (None, Some(repo)) => dataset_dir.join(repo.replace('/', "__")),
+        (None, None) => bail!(
+            "Instance '{}' needs `repo_path` or `repo` to locate its repository snapshot",
+            instance.instance_id
+        ),
+    };
+    if !candidate.is_dir() {
+        bail!(
+            "Repository snapshot {} for instance '{}' does not exist",
+            candidate.display(),
+            instance.instance_id
+        );
+    }
+    Ok(candidate)
+}
+
+/// Disposable checkout of an instance repository.
+///
+/// Git snapshots get a detached `git worktree`; plain directories are copied and
+/// committed into a fresh repository so the agent's changes can be diffed either way.
+pub(crate) struct ScratchWorktree {
+    root: TempDir,
+    source_repo: Option<PathBuf>,
+    base: String,
+}
+
+impl ScratchWorktree {
+    pub(crate) fn create(repo: &Path, base_commit: Option<&str>) -> Result<Self> {
+        let root = tempfile::Builder::new()
+            .prefix("vtcode-swe-bench-")
+            .tempdir()
+            .context("Failed to create scratch worktree directory")?;
+        let path = root.path().join(WORKTREE_DIR);
+
+        if repo.join(".git").exists() {
+            let base = base_commit.unwrap_or("HEAD");
+            git(
+                repo,
+                &["worktree", "add", "--detach", &path.to_string_lossy(), base],
+            )
+            .with_context(|| format!("Failed to check out {} from {}", base, repo.display()))?;
+            let mut worktree = Self {
+                root,
+                source_repo: Some(repo.to_path_buf()),
+                base: String::new(),
+            };
+            worktree.base = git(&worktree.path(), &["rev-parse", "HEAD"])?
+                .trim()
+                .to_string();
+            return Ok(worktree);
+        }
+
+        fs::create_dir_all(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        copy_workspace(repo, &path)?;
+        git(&path, &["init", "--quiet"])?;
+        git(&path, &["add", "-A"])?;
+        let mut commit = GIT_IDENTITY.to_vec();
+        commit.extend(["commit", "--quiet", "--allow-empty", "-m", "snapshot"]);
+        git(&path, &commit)?;
+        let base = git(&path, &["rev-parse", "HEAD"])?;
+        Ok(Self {
+            root,
+            source_repo: None,
+            base: base.trim().to_string(),
+        })
+    }
+
+    pub(crate) fn path(&self) -> PathBuf {
+        self.root.path().join(WORKTREE_DIR)
+    }
+
+    /// Everything the agent changed relative to the base commit, including new files.
+    pub(crate) fn diff(&self) -> Result<String> {
+        let path = self.path();
+        git(&path, &["add", "-A"])?;
+        git(&path, &["diff", "--cached", "--binary", &self.base])
+    }
+
+    /// Apply the grading tests on top of the base versions of the files they touch.
+    ///
+    /// Like the upstream harness, agent edits to those files are discarded first:
+    /// files present at the base commit are checked out again and files the patch
+    /// creates are removed, so the patch always applies cleanly.
+    fn apply_patch(&self, patch: &str) -> Result<()> {
+        let path = self.path();
+        for file in patch_paths(patch) {
+            let at_base = format!("{}:{}", self.base, file);
+            if git(&path, &["cat-file", "-e", &at_base]).is_ok() {
+                git(&path, &["checkout", &self.base, "--", &file])?;
+            } else {
+                let target = path.join(&file);
+                if target.is_file() {
+                    fs::remove_file(&target)
+                        .with_context(|| format!("Failed to remove {}", target.display()))?;
+                }
+            }
+        }
+
+        let patch_file = self.root.path().join("test.patch");
+        fs::write(&patch_file, patch).context("Failed to write test patch")?;
+        git(
+            &self.path(),
+            &[
+                "apply",
+                "--whitespace=nowarn",
+                &patch_file.to_string_lossy(),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl Drop for ScratchWorktree {
+    fn drop(&mut self) {
+        if let Some(repo) = &self.source_repo {
+            let path = self.path();
+            let _ = git(
+                repo,
+                &["worktree", "remove", "--force", &path.to_string_lossy()],
+            );
+            let _ = git(repo, &["worktree", "prune"]);
+        }
+    }
+}
+
+/// Paths named by the `---`/`+++` headers of a unified diff, without `/dev/null`.
+fn patch_paths(patch: &str) -> BTreeSet<String> {
+    patch
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("--- a/")
+                .or_else(|| line.strip_prefix("+++ b/"))
+        })
+        .map(|path| {
+            path.split('\t')
+                .next()
+                .unwrap_or(path)
+                .trim_end()
+                .to_string()
+        })
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run git; SWE-bench evaluation requires git on PATH")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(repo_path: &Path) -> SweBenchInstance {
+        let line = serde_json::json!({
+            "instance_id": "demo__calc-1",
+            "repo_path": repo_path,
+            "problem_statement": "add() returns the wrong result",
+            "test_patch": "diff --git a/tests/test_sum.sh b/tests/test_sum.sh\nnew file mode 100644\n--- /dev/null\n+++ b/tests/test_sum.sh\n@@ -0,0 +1 @@\n+grep -q 'a + b' calc.txt\n",
+            "FAIL_TO_PASS": "[\"tests/test_sum.sh\"]",
+            "PASS_TO_PASS": ["tests/test_exists.sh"],
+            "test_cmd": "sh"
+        });
+        serde_json::from_value(line).unwrap()
+    }
+
+    #[tokio::test]
+    async fn grades_agent_patch_in_scratch_worktree() {
+        let snapshot = tempfile::tempdir().unwrap();
+        fs::write(snapshot.path().join("calc.txt"), "a - b\n").unwrap();
+        fs::create_dir_all(snapshot.path().join("tests")).unwrap();
+        fs::write(
+            snapshot.path().join("tests").join("test_exists.sh"),
+            "test -f calc.txt\n",
+        )
+        .unwrap();
+        let instance = instance(snapshot.path());
+
+        let unresolved = ScratchWorktree::create(snapshot.path(), None).unwrap();
+        let report = grade_instance(&unresolved, &instance).await.unwrap();
+        assert!(!report.resolved);
+        assert!(report.patch.is_empty());
+        assert_eq!(report.fail_to_pass.failed, vec!["tests/test_sum.sh"]);
+        assert_eq!(report.pass_to_pass.passed, vec!["tests/test_exists.sh"]);
+
+        let resolved = ScratchWorktree::create(snapshot.path(), None).unwrap();
+        fs::write(resolved.path().join("calc.txt"), "a + b\n").unwrap();
+        let report = grade_instance(&resolved, &instance).await.unwrap();
+        assert!(report.resolved, "{:?}", report);
+        assert!(report.patch.contains("+a + b"));
+        assert!(!report.patch.contains("test_sum.sh"));
+
+        assert_eq!(
+            fs::read_to_string(snapshot.path().join("calc.txt")).unwrap(),
+            "a - b\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_patch_replaces_agent_edits_to_graded_files() {
+        let snapshot = tempfile::tempdir().unwrap();
+        fs::write(snapshot.path().join("calc.txt"), "a + b\n").unwrap();
+        fs::create_dir_all(snapshot.path().join("tests")).unwrap();
+        fs::write(
+            snapshot.path().join("tests").join("test_exists.sh"),
+            "test -f calc.txt\n",
+        )
+        .unwrap();
+        let mut instance = instance(snapshot.path());
+        instance.test_patch = Some(format!(
+            "{}diff --git a/tests/test_exists.sh b/tests/test_exists.sh\n--- a/tests/test_exists.sh\n+++ b/tests/test_exists.sh\n@@ -1 +1 @@\n-test -f calc.txt\n+test -s calc.txt\n",
+            instance.test_patch.as_deref().unwrap()
+        ));
+
+        let worktree = ScratchWorktree::create(snapshot.path(), None).unwrap();
+        let tests_dir = worktree.path().join("tests");
+        fs::write(tests_dir.join("test_sum.sh"), "true\n").unwrap();
+        fs::write(tests_dir.join("test_exists.sh"), "false\n").unwrap();
+        let report = grade_instance(&worktree, &instance).await.unwrap();
+
+        assert!(report.resolved, "{:?}", report);
+        assert_eq!(
+            fs::read_to_string(tests_dir.join("test_exists.sh")).unwrap(),
+            "test -s calc.txt\n"
+        );
+        assert_eq!(
+            fs::read_to_string(tests_dir.join("test_sum.sh")).unwrap(),
+            "grep -q 'a + b' calc.txt\n"
+        );
+    }
+
+    #[test]
+    fn git_snapshots_use_detached_worktrees() {
+        let snapshot = tempfile::tempdir().unwrap();
+        fs::write(snapshot.path().join("calc.txt"), "a - b\n").unwrap();
+        git(snapshot.path(), &["init", "--quiet"]).unwrap();
+        git(snapshot.path(), &["add", "-A"]).unwrap();
+        let mut commit = GIT_IDENTITY.to_vec();
+        commit.extend(["commit", "--quiet", "-m", "base"]);
+        git(snapshot.path(), &commit).unwrap();
+
+        let worktree = ScratchWorktree::create(snapshot.path(), Some("HEAD")).unwrap();
+        fs::write(worktree.path().join("new.txt"), "added\n").unwrap();
+        assert!(worktree.diff().unwrap().contains("+added"));
+        drop(worktree);
+
+        let listed = git(snapshot.path(), &["worktree", "list"]).unwrap();
+        assert_eq!(listed.lines().count(), 1);
+        assert!(!snapshot.path().join("new.txt").exists());
+    }
+
+    #[test]
+    fn resolves_repo_snapshots_next_to_dataset() {
+        let dataset_dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dataset_dir.path().join("astropy__astropy")).unwrap();
+        let instance: SweBenchInstance = serde_json::from_value(serde_json::json!({
+            "instance_id": "astropy__astropy-1",
+            "repo": "astropy/astropy",
+            "problem_statement": "bug",
+        }))
+        .unwrap();
+        assert_eq!(
+            resolve_repo_path(&instance, dataset_dir.path()).unwrap(),
+            dataset_dir.path().join("astropy__astropy")
+        );
+        assert!(instance.fail_to_pass.is_empty());
+    }
+}
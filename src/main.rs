@@ -8,13 +8,14 @@ use colorchoice::ColorChoice as GlobalColorChoice;
 use std::path::PathBuf;
 use std::str::FromStr;
 use tracing_subscriber;
-use vtcode_core::cli::args::{Cli, Commands};
+use vtcode_core::cli::args::{Cli, Commands, ConfigCommands, ModelCommands, SessionCommands};
 use vtcode_core::config::api_keys::{ApiKeySources, get_api_key, load_dotenv};
 use vtcode_core::config::constants::defaults;
 use vtcode_core::config::loader::ConfigManager;
 use vtcode_core::config::models::Provider;
 use vtcode_core::config::types::{AgentConfig as CoreAgentConfig, ModelSelectionSource};
 use vtcode_core::ui::theme::{self as ui_theme, DEFAULT_THEME_ID};
+use vtcode_core::utils::dot_config::get_dot_manager;
 use vtcode_core::{initialize_dot_folder, load_user_config, update_theme_preference};
 
 mod acp;
@@ -59,6 +60,17 @@ async fn main() -> Result<()> {
     }
 
     cli::set_workspace_env(&workspace);
+    if let Some(profile) = args.profile.as_deref() {
+        cli::set_profile_env(profile);
+    }
+
+    // Exporting an archived session only reads local files, so skip provider setup
+    if let Some(Commands::Sessions {
+        command: SessionCommands::Export { id, format, output },
+    }) = &args.command
+    {
+        return cli::handle_sessions_export_command(id, *format, output.as_deref());
+    }
 
     // Load configuration (vtcode.toml or defaults) from resolved workspace
     let config_manager = ConfigManager::load_from_workspace(&workspace).with_context(|| {
@@ -86,6 +98,12 @@ async fn main() -> Result<()> {
         );
     }
 
+    if args.plan && automation_prompt.is_some() {
+        bail!(
+            "--plan needs an interactive session to approve the plan; it cannot run a headless prompt."
+        );
+    }
+
     if full_auto_requested {
         let automation_cfg = &cfg.automation.full_auto;
         if !automation_cfg.enabled {
@@ -131,6 +149,16 @@ async fn main() -> Result<()> {
     };
 
     initialize_dot_folder().ok();
+    let themes_dir = get_dot_manager()
+        .lock()
+        .map(|manager| manager.themes_dir())
+        .ok();
+    for err in themes_dir
+        .map(|dir| ui_theme::load_custom_themes(&dir))
+        .unwrap_or_default()
+    {
+        eprintln!("Warning: skipped custom theme: {:#}", err);
+    }
     let user_theme_pref = load_user_config().ok().and_then(|dot| {
         let trimmed = dot.preferences.theme.trim();
         if trimmed.is_empty() {
@@ -216,18 +244,52 @@ async fn main() -> Result<()> {
             vtcode_core::cli::tool_policy_commands::handle_tool_policy_command(command.clone())
                 .await?;
         }
+        Some(Commands::Models {
+            command:
+                ModelCommands::Compare {
+                    models,
+                    task_file,
+                    task,
+                    output,
+                    max_tasks,
+                    json,
+                },
+        }) => {
+            let options = cli::CompareCommandOptions {
+                models: models.clone(),
+                task_file: task_file.clone(),
+                inline_task: task.clone(),
+                output: output.clone(),
+                max_tasks: *max_tasks,
+                json: *json,
+            };
+            cli::handle_models_compare_command(&core_cfg, cfg, options, full_auto_requested)
+                .await?;
+        }
         Some(Commands::Models { command }) => {
             vtcode_core::cli::models_commands::handle_models_command(&args, command).await?;
         }
         Some(Commands::Chat) => {
-            cli::handle_chat_command(&core_cfg, skip_confirmations, full_auto_requested).await?;
+            cli::handle_chat_command(
+                &core_cfg,
+                skip_confirmations,
+                full_auto_requested,
+                args.plan,
+            )
+            .await?;
         }
-        Some(Commands::Ask { prompt }) => {
-            cli::handle_ask_single_command(&core_cfg, prompt).await?;
+        Some(Commands::Ask { prompt, schema }) => {
+            cli::handle_ask_single_command(&core_cfg, prompt, schema.as_deref()).await?;
         }
         Some(Commands::ChatVerbose) => {
             // Reuse chat path; verbose behavior is handled in the module if applicable
-            cli::handle_chat_command(&core_cfg, skip_confirmations, full_auto_requested).await?;
+            cli::handle_chat_command(
+                &core_cfg,
+                skip_confirmations,
+                full_auto_requested,
+                args.plan,
+            )
+            .await?;
         }
         Some(Commands::Analyze) => {
             cli::handle_analyze_command(&core_cfg).await?;
@@ -256,7 +318,17 @@ async fn main() -> Result<()> {
         Some(Commands::Init) => {
             cli::handle_init_command(&workspace, false, false).await?;
         }
-        Some(Commands::Config { output, global }) => {
+        Some(Commands::Config {
+            command: Some(ConfigCommands::Show { origin }),
+            ..
+        }) => {
+            cli::handle_config_show_command(&workspace, *origin)?;
+        }
+        Some(Commands::Config {
+            output,
+            global,
+            command: None,
+        }) => {
             cli::handle_config_command(output.as_deref(), *global).await?;
         }
         Some(Commands::InitProject {
@@ -271,12 +343,14 @@ async fn main() -> Result<()> {
             task,
             output,
             max_tasks,
+            swe_bench,
         }) => {
             let options = cli::BenchmarkCommandOptions {
                 task_file: task_file.clone(),
                 inline_task: task.clone(),
                 output: output.clone(),
                 max_tasks: *max_tasks,
+                swe_bench: swe_bench.clone(),
             };
             cli::handle_benchmark_command(&core_cfg, cfg, options, full_auto_requested).await?;
         }
@@ -285,7 +359,13 @@ async fn main() -> Result<()> {
         }
         _ => {
             // Default to chat
-            cli::handle_chat_command(&core_cfg, skip_confirmations, full_auto_requested).await?;
+            cli::handle_chat_command(
+                &core_cfg,
+                skip_confirmations,
+                full_auto_requested,
+                args.plan,
+            )
+            .await?;
         }
     }
 
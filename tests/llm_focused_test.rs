@@ -121,6 +121,7 @@ fn test_anthropic_tool_message_handling() {
         content: "Tool result".to_string(),
         tool_calls: None,
         tool_call_id: None,
+        attachments: Vec::new(),
     };
 
     let request = LLMRequest {
@@ -135,6 +136,7 @@ fn test_anthropic_tool_message_handling() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
 
     // Validate request shape instead of internal conversion
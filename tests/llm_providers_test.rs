@@ -269,6 +269,7 @@ fn test_request_validation() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
     assert!(gemini.validate_request(&valid_gemini_request).is_ok());
 
@@ -284,6 +285,7 @@ fn test_request_validation() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
     assert!(openai.validate_request(&valid_openai_request).is_ok());
 
@@ -299,6 +301,7 @@ fn test_request_validation() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
     assert!(anthropic.validate_request(&valid_anthropic_request).is_ok());
 
@@ -314,6 +317,7 @@ fn test_request_validation() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
     assert!(
         anthropic
@@ -333,6 +337,7 @@ fn test_request_validation() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
     assert!(
         openrouter
@@ -352,6 +357,7 @@ fn test_request_validation() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
     assert!(xai.validate_request(&valid_xai_request).is_ok());
 
@@ -368,6 +374,7 @@ fn test_request_validation() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
     assert!(gemini.validate_request(&invalid_request).is_err());
     assert!(openai.validate_request(&invalid_request).is_err());
@@ -385,6 +392,7 @@ fn test_anthropic_tool_message_handling() {
         content: "Tool result content".to_string(),
         tool_calls: None,
         tool_call_id: Some("tool_123".to_string()),
+        attachments: Vec::new(),
     };
 
     let request = LLMRequest {
@@ -399,6 +407,7 @@ fn test_anthropic_tool_message_handling() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
 
     // Use the public validator as a proxy for ensuring request shape is acceptable
@@ -36,6 +36,7 @@ fn test_openai_tool_call_format() {
             json!({"location": "New York"}).to_string(),
         )]),
         tool_call_id: None,
+        attachments: Vec::new(),
     };
 
     // Test tool response message
@@ -44,6 +45,7 @@ fn test_openai_tool_call_format() {
         content: "Sunny, 72°F".to_string(),
         tool_calls: None,
         tool_call_id: Some("call_123".to_string()),
+        attachments: Vec::new(),
     };
 
     let request = LLMRequest {
@@ -62,6 +64,7 @@ fn test_openai_tool_call_format() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
 
     // Only validate shape via provider API; internal conversion details are private
@@ -95,6 +98,7 @@ fn test_anthropic_tool_call_format() {
             json!({"location": "New York"}).to_string(),
         )]),
         tool_call_id: None,
+        attachments: Vec::new(),
     };
 
     // Test tool response message
@@ -103,6 +107,7 @@ fn test_anthropic_tool_call_format() {
         content: "Sunny, 72°F".to_string(),
         tool_calls: None,
         tool_call_id: Some("toolu_123".to_string()),
+        attachments: Vec::new(),
     };
 
     let request = LLMRequest {
@@ -121,6 +126,7 @@ fn test_anthropic_tool_call_format() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
 
     // Only validate shape via provider API; internal conversion details are private
@@ -154,6 +160,7 @@ fn test_gemini_tool_call_format() {
             json!({"location": "New York"}).to_string(),
         )]),
         tool_call_id: None,
+        attachments: Vec::new(),
     };
 
     // Test tool response message
@@ -162,6 +169,7 @@ fn test_gemini_tool_call_format() {
         content: "Sunny, 72°F".to_string(),
         tool_calls: None,
         tool_call_id: Some("func_123".to_string()),
+        attachments: Vec::new(),
     };
 
     let request = LLMRequest {
@@ -180,6 +188,7 @@ fn test_gemini_tool_call_format() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
 
     assert!(provider.validate_request(&request).is_ok());
@@ -211,6 +220,7 @@ fn test_all_providers_tool_validation() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
 
     let openai_request = LLMRequest {
@@ -225,6 +235,7 @@ fn test_all_providers_tool_validation() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
 
     let anthropic_request = LLMRequest {
@@ -239,6 +250,7 @@ fn test_all_providers_tool_validation() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
 
     let openrouter_request = LLMRequest {
@@ -253,6 +265,7 @@ fn test_all_providers_tool_validation() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
 
     assert!(gemini.validate_request(&gemini_request).is_ok());
@@ -286,6 +299,7 @@ fn test_openrouter_tool_call_format() {
             json!({"location": "Paris"}).to_string(),
         )]),
         tool_call_id: None,
+        attachments: Vec::new(),
     };
 
     let tool_msg = Message {
@@ -293,6 +307,7 @@ fn test_openrouter_tool_call_format() {
         content: "Cloudy, 68°F".to_string(),
         tool_calls: None,
         tool_call_id: Some("call_456".to_string()),
+        attachments: Vec::new(),
     };
 
     let request = LLMRequest {
@@ -311,6 +326,7 @@ fn test_openrouter_tool_call_format() {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
 
     assert!(provider.validate_request(&request).is_ok());
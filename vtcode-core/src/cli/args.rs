@@ -1,6 +1,7 @@
 //! CLI argument parsing and configuration
 
 use crate::config::models::ModelId;
+use crate::utils::session_export::ExportFormat;
 use clap::{ColorChoice, Parser, Subcommand, ValueEnum, ValueHint};
 use colorchoice_clap::Color as ColorSelection;
 use std::path::PathBuf;
@@ -169,6 +170,12 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub config: Option<PathBuf>,
 
+    /// **Configuration profile** to overlay from `[profiles.<name>]`
+    ///
+    /// Also selectable with the VTCODE_PROFILE environment variable
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
     /// Log level (error, warn, info, debug, trace)
     ///
     /// Default: info
@@ -207,6 +214,13 @@ pub struct Cli {
     )]
     pub full_auto: Option<String>,
 
+    /// **Start in plan mode**
+    ///
+    /// The agent explores with read-only tools and proposes a plan; edits
+    /// are allowed only after you approve it. Toggle later with Shift+Tab or /plan.
+    #[arg(long, global = true)]
+    pub plan: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -240,7 +254,14 @@ pub enum Commands {
     ///   • Simple queries
     ///
     /// Example: vtcode ask "Explain Rust ownership"
-    Ask { prompt: String },
+    ///
+    /// Structured: vtcode ask --schema review.json "Review src/main.rs"
+    Ask {
+        prompt: String,
+        /// JSON Schema file the reply must conform to; prints validated JSON only
+        #[arg(long, value_name = "FILE")]
+        schema: Option<PathBuf>,
+    },
 
     /// **Verbose interactive chat** with enhanced transparency
     ///
@@ -299,6 +320,7 @@ pub enum Commands {
     ///   • Optimization insights
     ///
     /// Usage: vtcode benchmark --task-file swe_task.json --output reports/result.json
+    ///        vtcode benchmark --swe-bench instances.jsonl --output reports/swe.json
     Benchmark {
         /// Path to a JSON benchmark specification. Falls back to STDIN when omitted.
         #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
@@ -312,6 +334,9 @@ pub enum Commands {
         /// Limit the number of tasks executed from the specification.
         #[arg(long, value_name = "COUNT")]
         max_tasks: Option<usize>,
+        /// SWE-bench JSONL instances to resolve in scratch worktrees and grade with their tests.
+        #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath, conflicts_with_all = ["task_file", "task"])]
+        swe_bench: Option<PathBuf>,
     },
 
     /// **Create complete Rust project with advanced features**
@@ -397,6 +422,16 @@ pub enum Commands {
         max: usize,
     },
 
+    /// **Work with archived chat sessions**
+    ///
+    /// Examples:
+    ///   vtcode sessions export session-myrepo-20251018T101530Z_123456-04242
+    ///   vtcode sessions export <id> --format html --output review.html
+    Sessions {
+        #[command(subcommand)]
+        command: SessionCommands,
+    },
+
     /// **Initialize project** with enhanced dot-folder structure
     ///
     /// Features:
@@ -447,7 +482,11 @@ pub enum Commands {
     ///   vtcode config
     ///   vtcode config --output ./custom-config.toml
     ///   vtcode config --global
+    ///   vtcode config show --origin
     Config {
+        #[command(subcommand)]
+        command: Option<ConfigCommands>,
+
         /// Output file path - where to save the configuration file
         #[arg(long)]
         output: Option<std::path::PathBuf>,
@@ -506,6 +545,36 @@ pub enum AgentClientProtocolTarget {
     Zed,
 }
 
+/// Configuration inspection commands
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Show the effective configuration after merging system, user, project,
+    /// workspace, profile and environment layers
+    Show {
+        /// Annotate each key with the layer that set it
+        #[arg(long)]
+        origin: bool,
+    },
+}
+
+/// Archived session commands
+#[derive(Subcommand, Debug)]
+pub enum SessionCommands {
+    /// Export a session as Markdown, HTML or JSON with tool calls, diffs and usage
+    Export {
+        /// Session ID as listed by `/sessions`, or the path to a session file
+        id: String,
+
+        /// Output format; inferred from the output extension, otherwise Markdown
+        #[arg(short, long, value_enum)]
+        format: Option<ExportFormat>,
+
+        /// File to write; prints to stdout when omitted
+        #[arg(short, long, value_hint = ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+}
+
 /// Model management commands with concise, actionable help
 #[derive(Subcommand, Debug)]
 pub enum ModelCommands {
@@ -550,8 +619,37 @@ pub enum ModelCommands {
         provider: String,
     },
 
-    /// Compare model performance across providers (coming soon)
-    Compare,
+    /// Run benchmark tasks against several models and compare the results
+    ///
+    /// Each task runs in an isolated copy of the workspace; tasks may set a
+    /// `verify` command whose exit status decides pass/fail.
+    ///
+    /// Usage: vtcode --full-auto models compare --model gpt-5,anthropic:claude-sonnet-4-5 --task-file tasks.json
+    Compare {
+        /// Models to compare as `provider:model` or bare model ids (repeat or comma separate)
+        #[arg(
+            long = "model",
+            value_name = "MODEL",
+            required = true,
+            value_delimiter = ','
+        )]
+        models: Vec<String>,
+        /// Path to a JSON benchmark specification. Falls back to STDIN when omitted.
+        #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+        task_file: Option<PathBuf>,
+        /// Inline JSON specification for quick experiments.
+        #[arg(long, value_name = "JSON")]
+        task: Option<String>,
+        /// Write the report to this path (JSON when the extension is `.json`, Markdown otherwise)
+        #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+        output: Option<PathBuf>,
+        /// Limit the number of tasks executed from the specification.
+        #[arg(long, value_name = "COUNT")]
+        max_tasks: Option<usize>,
+        /// Print the report as JSON instead of Markdown
+        #[arg(long)]
+        json: bool,
+    },
 
     /// Show detailed model information and specifications
     Info {
@@ -655,11 +753,13 @@ impl Default for Cli {
             max_tool_calls: 10,
             verbose: false,
             config: None,
+            profile: None,
             log_level: "info".to_string(),
             no_color: false,
             theme: None,
             skip_confirmations: false,
             full_auto: None,
+            plan: false,
             debug: false,
             command: Some(Commands::Chat),
         }
@@ -28,7 +28,9 @@ pub async fn handle_models_command(cli: &Cli, command: &ModelCommands) -> Result
             .await
         }
         ModelCommands::Test { provider } => handle_test_provider(cli, provider).await,
-        ModelCommands::Compare => handle_compare_models(cli).await,
+        ModelCommands::Compare { .. } => Err(anyhow!(
+            "`models compare` needs a workspace session; run it through the vtcode binary"
+        )),
         ModelCommands::Info { model } => handle_model_info(cli, model).await,
     }
 }
@@ -38,7 +40,7 @@ async fn handle_list_models(_cli: &Cli) -> Result<()> {
     println!("{}", "Available Providers & Models".bold().underline());
     println!();
 
-    let factory = get_factory().lock().unwrap();
+    let factory = get_factory().lock();
     let config = load_user_config().unwrap_or_default();
     let providers = factory.list_providers();
 
@@ -132,7 +134,7 @@ fn is_provider_configured(config: &DotConfig, provider: &str) -> bool {
 
 /// Set default provider
 async fn handle_set_provider(_cli: &Cli, provider: &str) -> Result<()> {
-    let factory = get_factory().lock().unwrap();
+    let factory = get_factory().lock();
     let available = factory.list_providers();
 
     if !available.contains(&provider.to_string()) {
@@ -261,6 +263,7 @@ async fn handle_test_provider(_cli: &Cli, provider: &str) -> Result<()> {
             content: "Respond with 'OK' if you receive this message.".to_string(),
             tool_calls: None,
             tool_call_id: None,
+            attachments: Vec::new(),
         }],
         system_prompt: None,
         tools: None,
@@ -272,6 +275,7 @@ async fn handle_test_provider(_cli: &Cli, provider: &str) -> Result<()> {
         parallel_tool_calls: None,
         parallel_tool_config: None,
         reasoning_effort: None,
+        response_format: None,
     };
 
     match provider_instance.generate(test_request).await {
@@ -320,21 +324,6 @@ fn get_provider_credentials(
     }
 }
 
-/// Compare model performance (placeholder)
-async fn handle_compare_models(_cli: &Cli) -> Result<()> {
-    println!("{}", "✦ Model Performance Comparison".bold().underline());
-    println!();
-    println!("{} Coming soon! Will compare:", "✦".yellow());
-    println!("• Response times • Token usage • Cost • Quality");
-    println!();
-    println!(
-        "{} Use 'vtcode models list' for available models",
-        "・".blue()
-    );
-
-    Ok(())
-}
-
 /// Show model information
 async fn handle_model_info(_cli: &Cli, model: &str) -> Result<()> {
     println!("{} Model Info: {}", "・".blue(), model.bold().underline());
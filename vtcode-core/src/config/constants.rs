@@ -282,6 +282,7 @@ pub mod defaults {
     pub const DEFAULT_API_KEY_ENV: &str = "GEMINI_API_KEY";
     pub const DEFAULT_THEME: &str = "ciapre-dark";
     pub const DEFAULT_MAX_TOOL_LOOPS: usize = 100;
+    pub const DEFAULT_WEB_SEARCH_MAX_RESULTS: usize = 8;
     pub const ANTHROPIC_DEFAULT_MAX_TOKENS: u32 = 4_096;
     pub const DEFAULT_PTY_STDOUT_TAIL_LINES: usize = 20;
     pub const DEFAULT_TOOL_OUTPUT_MODE: &str = ui::TOOL_OUTPUT_MODE_COMPACT;
@@ -300,6 +301,10 @@ pub mod ui {
     pub const SLASH_PALETTE_CONTENT_PADDING: u16 = 6;
     pub const SLASH_PALETTE_HINT_PRIMARY: &str = "Type to filter slash commands.";
     pub const SLASH_PALETTE_HINT_SECONDARY: &str = "Press Enter to apply • Esc to dismiss.";
+    pub const MENTION_SUGGESTION_LIMIT: usize = 8;
+    pub const MENTION_BLOCK_TITLE: &str = "Mentions";
+    pub const MENTION_PALETTE_HINT_PRIMARY: &str = "Type to filter workspace files and symbols.";
+    pub const MENTION_PALETTE_HINT_SECONDARY: &str = "Tab or Enter to insert • Esc to dismiss.";
     pub const MODAL_MIN_WIDTH: u16 = 36;
     pub const MODAL_MIN_HEIGHT: u16 = 9;
     pub const MODAL_LIST_MIN_HEIGHT: u16 = 12;
@@ -311,6 +316,15 @@ pub mod ui {
     pub const MODAL_CONTENT_VERTICAL_PADDING: u16 = 6;
     pub const INLINE_HEADER_HEIGHT: u16 = 4;
     pub const INLINE_INPUT_HEIGHT: u16 = 3;
+    pub const INLINE_INPUT_MAX_LINES: u16 = 8;
+    pub const PROMPT_HISTORY_LIMIT: usize = 1_000;
+    pub const THEME_RELOAD_INTERVAL_MS: u64 = 1_000;
+    pub const QUEUE_PREVIEW_LIMIT: usize = 3;
+    pub const QUEUE_HINT: &str = "Queued for the next step";
+    pub const QUEUE_EDIT_HINT: &str = "to edit the last one";
+    pub const QUEUE_STEER_HINT: &str = "to interrupt and send now";
+    pub const BUSY_QUEUE_HINT: &str = "queues a message";
+    pub const BUSY_STEER_HINT: &str = "interrupts and sends";
     pub const INLINE_NAVIGATION_PERCENT: u16 = 32;
     pub const INLINE_NAVIGATION_MIN_WIDTH: u16 = 24;
     pub const INLINE_CONTENT_MIN_WIDTH: u16 = 48;
@@ -322,6 +336,11 @@ pub mod ui {
     pub const INLINE_AGENT_MESSAGE_LEFT_PADDING: &str = "  ";
     pub const INLINE_AGENT_QUOTE_PREFIX: &str = "";
     pub const INLINE_USER_MESSAGE_DIVIDER_SYMBOL: &str = "─";
+    /// Tool output blocks longer than this fold once the next block starts.
+    pub const INLINE_BLOCK_AUTO_COLLAPSE_LINES: usize = 30;
+    pub const INLINE_BLOCK_FOLD_MARKER: &str = "▸";
+    pub const INLINE_BLOCK_EXPAND_HINT: &str = "to expand";
+    pub const INLINE_BLOCK_FULL_OUTPUT_HINT: &str = "for full output";
     pub const HEADER_VERSION_PROMPT: &str = "> ";
     pub const HEADER_VERSION_PREFIX: &str = "VT Code";
     pub const HEADER_VERSION_LEFT_DELIMITER: &str = "(";
@@ -330,6 +349,7 @@ pub mod ui {
     pub const HEADER_MODE_ALTERNATE: &str = "Alternate session";
     pub const HEADER_MODE_AUTO: &str = "Auto session";
     pub const HEADER_MODE_FULL_AUTO_SUFFIX: &str = " (full auto)";
+    pub const HEADER_MODE_PLAN_SUFFIX: &str = " (plan mode)";
     pub const HEADER_MODE_PRIMARY_SEPARATOR: &str = " | ";
     pub const HEADER_MODE_SECONDARY_SEPARATOR: &str = " | ";
     pub const HEADER_PROVIDER_PREFIX: &str = "Provider: ";
@@ -339,6 +359,7 @@ pub mod ui {
     pub const HEADER_TOOLS_PREFIX: &str = "Tools: ";
     pub const HEADER_LANGUAGES_PREFIX: &str = "Languages: ";
     pub const HEADER_MCP_PREFIX: &str = "MCP: ";
+    pub const HEADER_COST_PREFIX: &str = "Cost: ";
     pub const HEADER_UNKNOWN_PLACEHOLDER: &str = "unavailable";
     pub const HEADER_STATUS_LABEL: &str = "Status";
     pub const HEADER_STATUS_ACTIVE: &str = "Active";
@@ -347,8 +368,7 @@ pub mod ui {
     pub const HEADER_INPUT_LABEL: &str = "Input";
     pub const HEADER_INPUT_ENABLED: &str = "Enabled";
     pub const HEADER_INPUT_DISABLED: &str = "Disabled";
-    pub const HEADER_SHORTCUT_HINT: &str =
-        "Shortcuts: Ctrl+Enter to submit • Esc to cancel • Ctrl+C to interrupt";
+    pub const HEADER_SHORTCUT_HINT: &str = "Shortcuts: Ctrl+Enter to submit • Shift+Enter for a new line • Ctrl+R to search history • Esc to cancel • Ctrl+C to interrupt";
     pub const HEADER_META_SEPARATOR: &str = "   ";
     pub const WELCOME_SHORTCUT_SECTION_TITLE: &str = "Keyboard Shortcuts";
     pub const WELCOME_SHORTCUT_HINT_PREFIX: &str = "Shortcuts:";
@@ -364,12 +384,11 @@ pub mod ui {
     pub const NAVIGATION_EMPTY_LABEL: &str = "Waiting for activity";
     pub const NAVIGATION_INDEX_PREFIX: &str = "#";
     pub const NAVIGATION_LABEL_AGENT: &str = "Agent";
-    pub const NAVIGATION_LABEL_ERROR: &str = "Error";
-    pub const NAVIGATION_LABEL_INFO: &str = "Info";
-    pub const NAVIGATION_LABEL_POLICY: &str = "Policy";
-    pub const NAVIGATION_LABEL_TOOL: &str = "Tool";
     pub const NAVIGATION_LABEL_USER: &str = "User";
-    pub const NAVIGATION_LABEL_PTY: &str = "PTY";
+    pub const NAVIGATION_LABEL_TOOL_CALL: &str = "Tool";
+    pub const NAVIGATION_LABEL_TOOL_RESULT: &str = "Output";
+    pub const NAVIGATION_LABEL_DIFF: &str = "Diff";
+    pub const NAVIGATION_LABEL_REASONING: &str = "Thinking";
     pub const SUGGESTION_BLOCK_TITLE: &str = "Slash Commands";
 }
 
@@ -422,7 +441,10 @@ pub mod tools {
     pub const APPLY_PATCH: &str = "apply_patch";
     pub const SRGN: &str = "srgn";
     pub const CURL: &str = "curl";
+    pub const WEB_SEARCH: &str = "web_search";
+    pub const WEB_FETCH: &str = "web_fetch";
     pub const UPDATE_PLAN: &str = "update_plan";
+    pub const REMEMBER: &str = "remember";
 
     // Explorer-specific tools
     pub const FILE_METADATA: &str = "file_metadata";
@@ -465,6 +487,12 @@ pub mod context {
 
     /// Maximum number of retry attempts when the provider signals context overflow
     pub const CONTEXT_ERROR_RETRY_LIMIT: usize = 2;
+
+    /// History size, as a percentage of the maximum token budget, that triggers compaction
+    pub const DEFAULT_COMPACTION_TRIGGER_PERCENT: u8 = 75;
+
+    /// Token allowance for the model-written compaction summary
+    pub const DEFAULT_COMPACTION_SUMMARY_TOKENS: u32 = 1_500;
 }
 
 /// Chunking constants for large file handling
@@ -154,6 +154,39 @@ fn default_max_recent_errors() -> usize {
     3
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ContextCompactionConfig {
+    /// Summarize older turns with the active model instead of dropping them
+    #[serde(default = "default_compaction_enabled")]
+    pub enabled: bool,
+    /// Percentage of `max_context_tokens` that triggers automatic compaction
+    #[serde(default = "default_compaction_trigger_percent")]
+    pub trigger_percent: u8,
+    /// Maximum tokens the model may spend on the summary
+    #[serde(default = "default_compaction_max_summary_tokens")]
+    pub max_summary_tokens: u32,
+}
+
+impl Default for ContextCompactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_compaction_enabled(),
+            trigger_percent: default_compaction_trigger_percent(),
+            max_summary_tokens: default_compaction_max_summary_tokens(),
+        }
+    }
+}
+
+fn default_compaction_enabled() -> bool {
+    true
+}
+fn default_compaction_trigger_percent() -> u8 {
+    context_defaults::DEFAULT_COMPACTION_TRIGGER_PERCENT
+}
+fn default_compaction_max_summary_tokens() -> u32 {
+    context_defaults::DEFAULT_COMPACTION_SUMMARY_TOKENS
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ContextFeaturesConfig {
     #[serde(default)]
@@ -162,6 +195,8 @@ pub struct ContextFeaturesConfig {
     pub token_budget: TokenBudgetConfig,
     #[serde(default)]
     pub curation: ContextCurationConfig,
+    #[serde(default)]
+    pub compaction: ContextCompactionConfig,
     #[serde(default = "default_max_context_tokens")]
     pub max_context_tokens: usize,
     #[serde(default = "default_trim_to_percent")]
@@ -176,6 +211,7 @@ impl Default for ContextFeaturesConfig {
             ledger: LedgerConfig::default(),
             token_budget: TokenBudgetConfig::default(),
             curation: ContextCurationConfig::default(),
+            compaction: ContextCompactionConfig::default(),
             max_context_tokens: default_max_context_tokens(),
             trim_to_percent: default_trim_to_percent(),
             preserve_recent_turns: default_preserve_recent_turns(),
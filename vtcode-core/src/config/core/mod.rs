@@ -14,4 +14,4 @@ pub use prompt_cache::{
     PromptCachingConfig, ProviderPromptCachingConfig, XAIPromptCacheSettings,
 };
 pub use security::SecurityConfig;
-pub use tools::{ToolPolicy, ToolsConfig};
+pub use tools::{ToolPolicy, ToolsConfig, WebSearchBackendKind, WebSearchConfig};
@@ -17,6 +17,11 @@ pub struct SecurityConfig {
     /// when no write tool was executed. Defaults to false for safety.
     #[serde(default)]
     pub auto_apply_detected_patches: bool,
+
+    /// Show edits from `edit_file`, `write_file` and `apply_patch` hunk by
+    /// hunk for approval before they are written.
+    #[serde(default = "default_true")]
+    pub review_file_changes: bool,
 }
 
 impl Default for SecurityConfig {
@@ -25,6 +30,7 @@ impl Default for SecurityConfig {
             human_in_the_loop: default_true(),
             require_write_tool_for_claims: default_true(),
             auto_apply_detected_patches: false,
+            review_file_changes: default_true(),
         }
     }
 }
@@ -22,6 +22,10 @@ pub struct ToolsConfig {
     ///
     #[serde(default = "default_max_tool_loops")]
     pub max_tool_loops: usize,
+
+    /// Search backend used by the `web_search` tool
+    #[serde(default)]
+    pub web_search: WebSearchConfig,
 }
 
 impl Default for ToolsConfig {
@@ -30,6 +34,7 @@ impl Default for ToolsConfig {
         policies.insert(tools::GREP_SEARCH.to_string(), ToolPolicy::Allow);
         policies.insert(tools::LIST_FILES.to_string(), ToolPolicy::Allow);
         policies.insert(tools::UPDATE_PLAN.to_string(), ToolPolicy::Allow);
+        policies.insert(tools::REMEMBER.to_string(), ToolPolicy::Prompt);
         policies.insert(tools::RUN_TERMINAL_CMD.to_string(), ToolPolicy::Allow);
         policies.insert(tools::READ_FILE.to_string(), ToolPolicy::Allow);
         policies.insert(tools::WRITE_FILE.to_string(), ToolPolicy::Prompt);
@@ -38,12 +43,85 @@ impl Default for ToolsConfig {
         policies.insert(tools::SIMPLE_SEARCH.to_string(), ToolPolicy::Allow);
         policies.insert(tools::BASH.to_string(), ToolPolicy::Allow);
         policies.insert(tools::CURL.to_string(), ToolPolicy::Prompt);
+        policies.insert(tools::WEB_SEARCH.to_string(), ToolPolicy::Prompt);
+        policies.insert(tools::WEB_FETCH.to_string(), ToolPolicy::Prompt);
         policies.insert(tools::APPLY_PATCH.to_string(), ToolPolicy::Prompt);
         policies.insert(tools::SRGN.to_string(), ToolPolicy::Prompt);
         Self {
             default_policy: default_tool_policy(),
             policies,
             max_tool_loops: default_max_tool_loops(),
+            web_search: WebSearchConfig::default(),
+        }
+    }
+}
+
+/// Backend kinds supported by the `web_search` tool
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebSearchBackendKind {
+    /// Self-hosted or public SearxNG instance (`/search?format=json`)
+    #[default]
+    Searxng,
+    /// Brave Search API
+    Brave,
+    /// Any endpoint returning JSON results, mapped with the field settings below
+    Json,
+}
+
+/// Web search configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebSearchConfig {
+    /// Which backend to query
+    #[serde(default)]
+    pub backend: WebSearchBackendKind,
+
+    /// Base URL of the backend. Required for `searxng` and `json`; `brave`
+    /// defaults to the public API.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// Environment variable holding the API key, if the backend needs one
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+
+    /// Maximum results returned per search
+    #[serde(default = "default_web_search_max_results")]
+    pub max_results: usize,
+
+    /// Query string parameter name for the `json` backend
+    #[serde(default = "default_query_param")]
+    pub query_param: String,
+
+    /// JSON pointer to the results array for the `json` backend
+    #[serde(default = "default_results_pointer")]
+    pub results_pointer: String,
+
+    /// Result field holding the title for the `json` backend
+    #[serde(default = "default_title_field")]
+    pub title_field: String,
+
+    /// Result field holding the URL for the `json` backend
+    #[serde(default = "default_url_field")]
+    pub url_field: String,
+
+    /// Result field holding the snippet for the `json` backend
+    #[serde(default = "default_snippet_field")]
+    pub snippet_field: String,
+}
+
+impl Default for WebSearchConfig {
+    fn default() -> Self {
+        Self {
+            backend: WebSearchBackendKind::default(),
+            endpoint: None,
+            api_key_env: None,
+            max_results: default_web_search_max_results(),
+            query_param: default_query_param(),
+            results_pointer: default_results_pointer(),
+            title_field: default_title_field(),
+            url_field: default_url_field(),
+            snippet_field: default_snippet_field(),
         }
     }
 }
@@ -67,3 +145,27 @@ fn default_tool_policy() -> ToolPolicy {
 fn default_max_tool_loops() -> usize {
     defaults::DEFAULT_MAX_TOOL_LOOPS
 }
+
+fn default_web_search_max_results() -> usize {
+    defaults::DEFAULT_WEB_SEARCH_MAX_RESULTS
+}
+
+fn default_query_param() -> String {
+    "q".to_string()
+}
+
+fn default_results_pointer() -> String {
+    "/results".to_string()
+}
+
+fn default_title_field() -> String {
+    "title".to_string()
+}
+
+fn default_url_field() -> String {
+    "url".to_string()
+}
+
+fn default_snippet_field() -> String {
+    "snippet".to_string()
+}
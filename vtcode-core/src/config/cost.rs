@@ -0,0 +1,48 @@
+use crate::config::models::ModelPricing;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Spend tracking and budget limits
+///
+/// ```toml
+/// [cost]
+/// max_cost_usd = 5.0
+/// max_daily_cost_usd = 20.0
+///
+/// [cost.prices."my-finetune"]
+/// input = 1.0
+/// output = 4.0
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CostConfig {
+    /// Convert token usage into spend and show it in the header and `/status`
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Pause the agent once a session has spent this many USD
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+
+    /// Pause the agent once all sessions together have spent this many USD today
+    #[serde(default)]
+    pub max_daily_cost_usd: Option<f64>,
+
+    /// Per-model price overrides in USD per million tokens, keyed by model id
+    #[serde(default)]
+    pub prices: BTreeMap<String, ModelPricing>,
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            max_cost_usd: None,
+            max_daily_cost_usd: None,
+            prices: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
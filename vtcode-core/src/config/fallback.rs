@@ -0,0 +1,110 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Separator accepted between entries when the chain is written as a single string
+const CHAIN_SEPARATOR: &str = "->";
+
+/// Provider failover configuration
+///
+/// ```toml
+/// [fallback]
+/// enabled = true
+/// chain = "anthropic:claude-sonnet-4-5 -> openrouter:anthropic/claude-sonnet-4.5 -> gemini-2.5-pro"
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FallbackConfig {
+    /// Switch to the next provider in the chain on rate limits and network failures
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Ordered `provider:model` entries (or bare model ids) tried after the active model.
+    /// Accepts a list or a single string separated by `->`.
+    #[serde(default, deserialize_with = "deserialize_chain")]
+    pub chain: Vec<String>,
+
+    /// Consecutive failures before a provider's circuit opens
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+
+    /// Seconds an open circuit waits before the provider is tried again
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+impl Default for FallbackConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            chain: Vec::new(),
+            failure_threshold: default_failure_threshold(),
+            cooldown_secs: default_cooldown_secs(),
+        }
+    }
+}
+
+impl FallbackConfig {
+    /// Whether any fallback targets are configured and enabled
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.chain.is_empty()
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_failure_threshold() -> u32 {
+    2
+}
+
+fn default_cooldown_secs() -> u64 {
+    120
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChainValue {
+    Arrow(String),
+    List(Vec<String>),
+}
+
+fn deserialize_chain<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let entries = match ChainValue::deserialize(deserializer)? {
+        ChainValue::Arrow(value) => value.split(CHAIN_SEPARATOR).map(str::to_string).collect(),
+        ChainValue::List(values) => values,
+    };
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| entry.trim().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chain_accepts_arrow_string_and_list() {
+        let arrow: FallbackConfig = toml::from_str(
+            r#"chain = "anthropic:claude-sonnet-4-5 -> openrouter:anthropic/claude-sonnet-4.5 ->gemini-2.5-pro""#,
+        )
+        .unwrap();
+        assert_eq!(
+            arrow.chain,
+            vec![
+                "anthropic:claude-sonnet-4-5",
+                "openrouter:anthropic/claude-sonnet-4.5",
+                "gemini-2.5-pro"
+            ]
+        );
+
+        let list: FallbackConfig = toml::from_str(r#"chain = ["gemini-2.5-pro", " "]"#).unwrap();
+        assert_eq!(list.chain, vec!["gemini-2.5-pro"]);
+        assert!(list.is_active());
+        assert_eq!(list.failure_threshold, 2);
+    }
+}
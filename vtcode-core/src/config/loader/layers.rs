@@ -0,0 +1,496 @@
+//! Layered configuration sources
+//!
+//! Configuration is assembled from several TOML layers, lowest precedence
+//! first: system, user, project, workspace (`.vtcode/vtcode.toml`, then
+//! `vtcode.toml`). Tables merge key by key; scalars and arrays from a higher
+//! layer replace lower ones. A `[profiles.<name>]` table, when selected, is
+//! overlaid next, and `VTCODE_<SECTION>__<KEY>` environment variables win
+//! over everything. The layer that set each key is recorded so
+//! `vtcode config show --origin` can explain the effective value.
+
+use anyhow::{Context, Result, anyhow, bail};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Environment variable selecting the active profile
+pub const PROFILE_ENV_VAR: &str = "VTCODE_PROFILE";
+/// Environment variable overriding the system configuration path
+pub const SYSTEM_CONFIG_ENV_VAR: &str = "VTCODE_SYSTEM_CONFIG";
+/// Prefix of environment variables that override configuration keys
+const ENV_OVERRIDE_PREFIX: &str = "VTCODE_";
+/// Separator between path segments in environment override names
+const ENV_PATH_SEPARATOR: &str = "__";
+/// Table holding named profiles
+const PROFILES_KEY: &str = "profiles";
+#[cfg(unix)]
+const SYSTEM_CONFIG_PATH: &str = "/etc/vtcode/vtcode.toml";
+
+/// Kind of source a configuration layer came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigLayerKind {
+    System,
+    User,
+    Project,
+    Workspace,
+    Profile,
+    Environment,
+}
+
+impl ConfigLayerKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::User => "user",
+            Self::Project => "project",
+            Self::Workspace => "workspace",
+            Self::Profile => "profile",
+            Self::Environment => "env",
+        }
+    }
+}
+
+/// Where a configuration value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigOrigin {
+    pub kind: ConfigLayerKind,
+    /// File path, profile name or environment variable
+    pub source: String,
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.kind.as_str(), self.source)
+    }
+}
+
+/// Configuration file participating in the merge
+#[derive(Debug, Clone)]
+pub struct ConfigFileLayer {
+    pub kind: ConfigLayerKind,
+    pub path: PathBuf,
+}
+
+/// Result of merging every layer
+#[derive(Debug, Clone, Default)]
+pub struct MergedConfig {
+    /// Effective TOML table, without the `profiles` table
+    pub table: Table,
+    /// Dotted key path to the layer that set it
+    pub origins: BTreeMap<String, ConfigOrigin>,
+    /// Files that were found and merged, lowest precedence first
+    pub files: Vec<ConfigFileLayer>,
+}
+
+/// Candidate configuration files for a workspace, lowest precedence first.
+/// Only files that exist are returned.
+pub fn discover_config_files(
+    workspace: &Path,
+    home_dir: Option<&Path>,
+    project_config_dir: Option<&Path>,
+) -> Vec<ConfigFileLayer> {
+    let mut candidates = Vec::new();
+
+    if let Some(system) = system_config_path() {
+        candidates.push((ConfigLayerKind::System, system));
+    }
+    if let Some(home) = home_dir {
+        candidates.push((
+            ConfigLayerKind::User,
+            home.join(".vtcode").join("vtcode.toml"),
+        ));
+    }
+    if let Some(project_dir) = project_config_dir {
+        candidates.push((ConfigLayerKind::Project, project_dir.join("vtcode.toml")));
+    }
+    candidates.push((
+        ConfigLayerKind::Workspace,
+        workspace.join(".vtcode").join("vtcode.toml"),
+    ));
+    candidates.push((ConfigLayerKind::Workspace, workspace.join("vtcode.toml")));
+
+    let mut layers: Vec<ConfigFileLayer> = Vec::new();
+    for (kind, path) in candidates {
+        // A workspace inside the home directory would otherwise load the user file twice
+        if path.is_file() && !layers.iter().any(|layer| same_file(&layer.path, &path)) {
+            layers.push(ConfigFileLayer { kind, path });
+        }
+    }
+    layers
+}
+
+fn system_config_path() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var(SYSTEM_CONFIG_ENV_VAR) {
+        return (!path.trim().is_empty()).then(|| PathBuf::from(path));
+    }
+    #[cfg(unix)]
+    {
+        Some(PathBuf::from(SYSTEM_CONFIG_PATH))
+    }
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Read and merge configuration files, then apply the selected profile and
+/// environment overrides.
+pub fn merge_config_layers(
+    files: Vec<ConfigFileLayer>,
+    profile: Option<&str>,
+    env: impl IntoIterator<Item = (String, String)>,
+) -> Result<MergedConfig> {
+    let mut merged = MergedConfig::default();
+
+    for layer in &files {
+        let content = std::fs::read_to_string(&layer.path)
+            .with_context(|| format!("Failed to read config file: {}", layer.path.display()))?;
+        let table: Table = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {}", layer.path.display()))?;
+        let origin = ConfigOrigin {
+            kind: layer.kind,
+            source: layer.path.display().to_string(),
+        };
+        merge_table(&mut merged.table, table, &origin, "", &mut merged.origins);
+    }
+    merged.files = files;
+
+    let profiles = merged.table.remove(PROFILES_KEY);
+    clear_origins(&mut merged.origins, PROFILES_KEY);
+    if let Some(name) = profile.map(str::trim).filter(|name| !name.is_empty()) {
+        let overlay = profiles
+            .as_ref()
+            .and_then(Value::as_table)
+            .and_then(|profiles| profiles.get(name))
+            .and_then(Value::as_table)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown configuration profile '{}'", name))?;
+        let origin = ConfigOrigin {
+            kind: ConfigLayerKind::Profile,
+            source: name.to_string(),
+        };
+        merge_table(&mut merged.table, overlay, &origin, "", &mut merged.origins);
+    }
+
+    let mut overrides: Vec<(String, String)> = env
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(ENV_OVERRIDE_PREFIX))
+        .collect();
+    overrides.sort();
+    for (name, raw) in overrides {
+        let Some(path) = env_override_path(&name) else {
+            continue;
+        };
+        let origin = ConfigOrigin {
+            kind: ConfigLayerKind::Environment,
+            source: name.clone(),
+        };
+        let mut overlay = Table::new();
+        let Some((last, parents)) = path.split_last() else {
+            bail!("Environment override {} does not name a setting", name);
+        };
+        let mut cursor = &mut overlay;
+        for segment in parents {
+            let Some(table) = cursor
+                .entry(segment.clone())
+                .or_insert_with(|| Value::Table(Table::new()))
+                .as_table_mut()
+            else {
+                bail!(
+                    "Environment override {} cannot set '{}' inside a non-table value",
+                    name,
+                    segment
+                );
+            };
+            cursor = table;
+        }
+        cursor.insert(last.clone(), parse_env_value(&raw));
+        merge_table(&mut merged.table, overlay, &origin, "", &mut merged.origins);
+    }
+
+    Ok(merged)
+}
+
+/// `VTCODE_AGENT__DEFAULT_MODEL` → `["agent", "default_model"]`. Variables
+/// without the `__` separator (e.g. `VTCODE_PROFILE`) are not overrides.
+fn env_override_path(name: &str) -> Option<Vec<String>> {
+    let rest = name.strip_prefix(ENV_OVERRIDE_PREFIX)?;
+    let segments: Vec<String> = rest
+        .split(ENV_PATH_SEPARATOR)
+        .map(|segment| segment.to_ascii_lowercase())
+        .collect();
+    (segments.len() >= 2 && segments.iter().all(|segment| !segment.is_empty())).then_some(segments)
+}
+
+/// Interpret an override as a TOML literal (bool, number, array, inline
+/// table), falling back to a plain string.
+fn parse_env_value(raw: &str) -> Value {
+    let parsed = format!("value = {}", raw)
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"));
+    match parsed {
+        Some(Value::Datetime(_)) | None => Value::String(raw.to_string()),
+        Some(value) => value,
+    }
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+fn clear_origins(origins: &mut BTreeMap<String, ConfigOrigin>, path: &str) {
+    let nested = format!("{}.", path);
+    origins.retain(|key, _| key != path && !key.starts_with(&nested));
+}
+
+/// Deep-merge `overlay` into `base`, recording the origin of every leaf set
+fn merge_table(
+    base: &mut Table,
+    overlay: Table,
+    origin: &ConfigOrigin,
+    prefix: &str,
+    origins: &mut BTreeMap<String, ConfigOrigin>,
+) {
+    for (key, value) in overlay {
+        let path = join_key(prefix, &key);
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(existing)), Value::Table(incoming)) => {
+                merge_table(existing, incoming, origin, &path, origins);
+            }
+            (_, value) => {
+                clear_origins(origins, &path);
+                record_origins(&value, origin, &path, origins);
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn record_origins(
+    value: &Value,
+    origin: &ConfigOrigin,
+    path: &str,
+    origins: &mut BTreeMap<String, ConfigOrigin>,
+) {
+    match value {
+        Value::Table(table) if !table.is_empty() => {
+            for (key, nested) in table {
+                record_origins(nested, origin, &join_key(path, key), origins);
+            }
+        }
+        _ => {
+            origins.insert(path.to_string(), origin.clone());
+        }
+    }
+}
+
+/// Apply the differences between `old` and `new` to `target`, so saving a
+/// modified configuration only writes the keys that changed into the file
+/// rather than copying values inherited from other layers.
+pub fn apply_changes(target: &mut Table, old: &Table, new: &Table) {
+    for (key, new_value) in new {
+        match (old.get(key), new_value) {
+            (Some(Value::Table(old_table)), Value::Table(new_table)) => {
+                let mut nested = match target.remove(key) {
+                    Some(Value::Table(existing)) => existing,
+                    _ => Table::new(),
+                };
+                apply_changes(&mut nested, old_table, new_table);
+                if !nested.is_empty() {
+                    target.insert(key.clone(), Value::Table(nested));
+                }
+            }
+            (Some(old_value), new_value) if old_value == new_value => {}
+            (_, new_value) => {
+                target.insert(key.clone(), new_value.clone());
+            }
+        }
+    }
+
+    for key in old.keys() {
+        if !new.contains_key(key) {
+            target.remove(key);
+        }
+    }
+}
+
+/// Flatten a table into dotted key paths and their values, for display
+pub fn flatten_table(table: &Table) -> Vec<(String, Value)> {
+    fn walk(table: &Table, prefix: &str, out: &mut Vec<(String, Value)>) {
+        for (key, value) in table {
+            let path = join_key(prefix, key);
+            match value {
+                Value::Table(nested) if !nested.is_empty() => walk(nested, &path, out),
+                _ => out.push((path, value.clone())),
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(table, "", &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, relative: &str, content: &str) -> PathBuf {
+        let path = dir.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn layers_merge_deeply_with_profile_and_env_overrides() {
+        let temp = tempfile::tempdir().unwrap();
+        let user = write(
+            temp.path(),
+            "home/.vtcode/vtcode.toml",
+            r#"
+[agent]
+theme = "ciapre-dark"
+custom_api_keys = { openai = "sk-user" }
+
+[profiles.fast.agent]
+default_model = "gemini-2.5-flash"
+"#,
+        );
+        let workspace = write(
+            temp.path(),
+            "repo/vtcode.toml",
+            r#"
+[agent]
+default_model = "gemini-2.5-pro"
+
+[tools]
+default_policy = "prompt"
+"#,
+        );
+        let files = vec![
+            ConfigFileLayer {
+                kind: ConfigLayerKind::User,
+                path: user.clone(),
+            },
+            ConfigFileLayer {
+                kind: ConfigLayerKind::Workspace,
+                path: workspace.clone(),
+            },
+        ];
+        let env = vec![
+            (
+                "VTCODE_AGENT__MAX_CONVERSATION_TURNS".to_string(),
+                "42".to_string(),
+            ),
+            ("VTCODE_PROFILE".to_string(), "fast".to_string()),
+            ("HOME".to_string(), "/tmp".to_string()),
+        ];
+
+        let merged = merge_config_layers(files, Some("fast"), env).unwrap();
+        let agent = merged.table["agent"].as_table().unwrap();
+        assert_eq!(agent["theme"].as_str(), Some("ciapre-dark"));
+        assert_eq!(agent["default_model"].as_str(), Some("gemini-2.5-flash"));
+        assert_eq!(agent["max_conversation_turns"].as_integer(), Some(42));
+        assert_eq!(
+            merged.table["tools"]["default_policy"].as_str(),
+            Some("prompt")
+        );
+        assert!(!merged.table.contains_key("profiles"));
+
+        assert_eq!(merged.origins["agent.theme"].kind, ConfigLayerKind::User);
+        assert_eq!(
+            merged.origins["agent.custom_api_keys.openai"].kind,
+            ConfigLayerKind::User
+        );
+        assert_eq!(
+            merged.origins["agent.default_model"],
+            ConfigOrigin {
+                kind: ConfigLayerKind::Profile,
+                source: "fast".to_string()
+            }
+        );
+        assert_eq!(
+            merged.origins["agent.max_conversation_turns"].source,
+            "VTCODE_AGENT__MAX_CONVERSATION_TURNS"
+        );
+        assert_eq!(
+            merged.origins["tools.default_policy"].source,
+            workspace.display().to_string()
+        );
+        assert!(!merged.origins.keys().any(|key| key.starts_with("profiles")));
+
+        let err = merge_config_layers(Vec::new(), Some("missing"), Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("missing"));
+    }
+
+    #[test]
+    fn env_values_parse_as_toml_literals_or_strings() {
+        assert_eq!(parse_env_value("true"), Value::Boolean(true));
+        assert_eq!(
+            parse_env_value(r#"["a", "b"]"#),
+            Value::Array(vec!["a".into(), "b".into()])
+        );
+        assert_eq!(
+            parse_env_value("gemini-2.5-pro"),
+            Value::String("gemini-2.5-pro".to_string())
+        );
+        assert_eq!(
+            parse_env_value("2025-01-01"),
+            Value::String("2025-01-01".to_string())
+        );
+        assert_eq!(env_override_path("VTCODE_PROFILE"), None);
+        assert_eq!(
+            env_override_path("VTCODE_UI__INLINE_VIEWPORT_ROWS"),
+            Some(vec!["ui".to_string(), "inline_viewport_rows".to_string()])
+        );
+    }
+
+    #[test]
+    fn apply_changes_writes_only_modified_keys() {
+        let old: Table = toml::from_str(
+            r#"
+[agent]
+theme = "ciapre-dark"
+default_model = "gemini-2.5-pro"
+custom_api_keys = { openai = "sk-user" }
+"#,
+        )
+        .unwrap();
+        let mut new = old.clone();
+        new["agent"]["default_model"] = Value::String("gpt-5".to_string());
+        new["agent"]["custom_api_keys"]
+            .as_table_mut()
+            .unwrap()
+            .remove("openai");
+
+        let mut target: Table = toml::from_str(
+            r#"
+[agent]
+default_model = "gemini-2.5-pro"
+
+[profiles.fast.agent]
+default_model = "gemini-2.5-flash"
+"#,
+        )
+        .unwrap();
+        apply_changes(&mut target, &old, &new);
+
+        assert_eq!(target["agent"]["default_model"].as_str(), Some("gpt-5"));
+        assert!(target["agent"].get("theme").is_none());
+        assert!(target.contains_key("profiles"));
+    }
+}
@@ -3,16 +3,28 @@ use crate::config::context::ContextFeaturesConfig;
 use crate::config::core::{
     AgentConfig, AutomationConfig, CommandsConfig, PromptCachingConfig, SecurityConfig, ToolsConfig,
 };
+use crate::config::cost::CostConfig;
+use crate::config::fallback::FallbackConfig;
 use crate::config::mcp::McpClientConfig;
+use crate::config::memory::MemoryConfig;
 use crate::config::router::RouterConfig;
 use crate::config::telemetry::TelemetryConfig;
 use crate::config::{PtyConfig, UiConfig};
 use crate::project::SimpleProjectManager;
 use anyhow::{Context, Result};
+use layers::{apply_changes, discover_config_files, merge_config_layers};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+mod layers;
+
+pub use layers::{
+    ConfigFileLayer, ConfigLayerKind, ConfigOrigin, PROFILE_ENV_VAR, SYSTEM_CONFIG_ENV_VAR,
+    flatten_table,
+};
+
 /// Syntax highlighting configuration
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SyntaxHighlightingConfig {
@@ -120,6 +132,18 @@ pub struct VTCodeConfig {
     #[serde(default)]
     pub router: RouterConfig,
 
+    /// Provider failover chain used on rate limits and outages
+    #[serde(default)]
+    pub fallback: FallbackConfig,
+
+    /// Spend tracking and session/daily cost limits
+    #[serde(default)]
+    pub cost: CostConfig,
+
+    /// Remembered project and user facts
+    #[serde(default)]
+    pub memory: MemoryConfig,
+
     /// Telemetry configuration (logging, trajectory)
     #[serde(default)]
     pub telemetry: TelemetryConfig,
@@ -156,6 +180,9 @@ impl Default for VTCodeConfig {
             pty: PtyConfig::default(),
             context: ContextFeaturesConfig::default(),
             router: RouterConfig::default(),
+            fallback: FallbackConfig::default(),
+            cost: CostConfig::default(),
+            memory: MemoryConfig::default(),
             telemetry: TelemetryConfig::default(),
             syntax_highlighting: SyntaxHighlightingConfig::default(),
             automation: AutomationConfig::default(),
@@ -279,6 +306,9 @@ pub struct ConfigManager {
     config_path: Option<PathBuf>,
     project_manager: Option<SimpleProjectManager>,
     project_name: Option<String>,
+    origins: BTreeMap<String, ConfigOrigin>,
+    layers: Vec<ConfigFileLayer>,
+    profile: Option<String>,
 }
 
 impl ConfigManager {
@@ -303,8 +333,18 @@ impl ConfigManager {
         dirs::home_dir()
     }
 
-    /// Load configuration from a specific workspace
+    /// Load configuration from a specific workspace, merging every layer and
+    /// applying the profile named by `VTCODE_PROFILE`, if any
     pub fn load_from_workspace(workspace: impl AsRef<Path>) -> Result<Self> {
+        let profile = std::env::var(PROFILE_ENV_VAR).ok();
+        Self::load_from_workspace_with_profile(workspace, profile.as_deref())
+    }
+
+    /// Load configuration from a specific workspace with an explicit profile
+    pub fn load_from_workspace_with_profile(
+        workspace: impl AsRef<Path>,
+        profile: Option<&str>,
+    ) -> Result<Self> {
         let workspace = workspace.as_ref();
 
         // Initialize project manager
@@ -312,65 +352,52 @@ impl ConfigManager {
         let project_name = project_manager
             .as_ref()
             .and_then(|pm| pm.identify_current_project().ok());
+        let project_config_dir = match (&project_manager, &project_name) {
+            (Some(pm), Some(pname)) => Some(pm.config_dir(pname)),
+            _ => None,
+        };
 
-        // Try vtcode.toml in workspace root first
-        let config_path = workspace.join("vtcode.toml");
-        if config_path.exists() {
-            let config = Self::load_from_file(&config_path)?;
-            return Ok(Self {
-                config: config.config,
-                config_path: config.config_path,
-                project_manager,
-                project_name,
-            });
-        }
-
-        // Try .vtcode/vtcode.toml in workspace
-        let fallback_path = workspace.join(".vtcode").join("vtcode.toml");
-        if fallback_path.exists() {
-            let config = Self::load_from_file(&fallback_path)?;
-            return Ok(Self {
-                config: config.config,
-                config_path: config.config_path,
-                project_manager,
-                project_name,
-            });
-        }
-
-        // Try ~/.vtcode/vtcode.toml in user home directory
-        if let Some(home_dir) = Self::get_home_dir() {
-            let home_config_path = home_dir.join(".vtcode").join("vtcode.toml");
-            if home_config_path.exists() {
-                let config = Self::load_from_file(&home_config_path)?;
-                return Ok(Self {
-                    config: config.config,
-                    config_path: config.config_path,
-                    project_manager,
-                    project_name,
-                });
-            }
-        }
+        let files = discover_config_files(
+            workspace,
+            Self::get_home_dir().as_deref(),
+            project_config_dir.as_deref(),
+        );
+        let merged = merge_config_layers(files, profile, std::env::vars())?;
+        let config: VTCodeConfig =
+            toml::Value::Table(merged.table)
+                .try_into()
+                .with_context(|| {
+                    let sources: Vec<String> = merged
+                        .files
+                        .iter()
+                        .map(|layer| layer.path.display().to_string())
+                        .collect();
+                    format!(
+                        "Failed to parse merged configuration from {}",
+                        if sources.is_empty() {
+                            "environment overrides".to_string()
+                        } else {
+                            sources.join(", ")
+                        }
+                    )
+                })?;
+
+        // Saves go to the most specific file, which used to be the only one loaded
+        let config_path = merged
+            .files
+            .iter()
+            .rev()
+            .find(|layer| layer.kind != ConfigLayerKind::System)
+            .map(|layer| layer.path.clone());
 
-        // Try project-specific configuration
-        if let (Some(pm), Some(pname)) = (&project_manager, &project_name) {
-            let project_config_path = pm.config_dir(pname).join("vtcode.toml");
-            if project_config_path.exists() {
-                let config = Self::load_from_file(&project_config_path)?;
-                return Ok(Self {
-                    config: config.config,
-                    config_path: config.config_path,
-                    project_manager: Some(pm.clone()),
-                    project_name: Some(pname.clone()),
-                });
-            }
-        }
-
-        // Use default configuration if no file found
         Ok(Self {
-            config: VTCodeConfig::default(),
-            config_path: None,
+            config,
+            config_path,
             project_manager,
             project_name,
+            origins: merged.origins,
+            layers: merged.files,
+            profile: profile.map(str::to_string),
         })
     }
 
@@ -394,6 +421,9 @@ impl ConfigManager {
             config_path: Some(path.to_path_buf()),
             project_manager,
             project_name: None,
+            origins: BTreeMap::new(),
+            layers: Vec::new(),
+            profile: None,
         })
     }
 
@@ -422,6 +452,21 @@ impl ConfigManager {
         self.project_name.as_deref()
     }
 
+    /// Layer that set each dotted configuration key; unset keys use defaults
+    pub fn origins(&self) -> &BTreeMap<String, ConfigOrigin> {
+        &self.origins
+    }
+
+    /// Configuration files merged into this configuration, lowest precedence first
+    pub fn layers(&self) -> &[ConfigFileLayer] {
+        &self.layers
+    }
+
+    /// Active profile, if one was selected
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
     /// Persist configuration to a specific path
     pub fn save_config_to_path(path: impl AsRef<Path>, config: &VTCodeConfig) -> Result<()> {
         let path = path.as_ref();
@@ -432,19 +477,38 @@ impl ConfigManager {
         Ok(())
     }
 
-    /// Persist configuration to the manager's associated path or workspace
+    /// Persist configuration to the manager's associated path or workspace.
+    ///
+    /// Only keys that differ from the loaded configuration are written, so
+    /// values inherited from other layers are not copied into the file.
     pub fn save_config(&self, config: &VTCodeConfig) -> Result<()> {
-        if let Some(path) = &self.config_path {
-            return Self::save_config_to_path(path, config);
-        }
+        let path = if let Some(path) = &self.config_path {
+            path.clone()
+        } else if let Some(manager) = &self.project_manager {
+            manager.workspace_root().join("vtcode.toml")
+        } else {
+            std::env::current_dir()
+                .context("Failed to resolve current directory")?
+                .join("vtcode.toml")
+        };
 
-        if let Some(manager) = &self.project_manager {
-            let path = manager.workspace_root().join("vtcode.toml");
-            return Self::save_config_to_path(path, config);
-        }
+        let mut target: toml::Table = if path.exists() {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+            toml::from_str(&content)
+                .with_context(|| format!("Failed to parse config file: {}", path.display()))?
+        } else {
+            toml::Table::new()
+        };
+        let previous =
+            toml::Table::try_from(&self.config).context("Failed to serialize configuration")?;
+        let updated = toml::Table::try_from(config).context("Failed to serialize configuration")?;
+        apply_changes(&mut target, &previous, &updated);
 
-        let cwd = std::env::current_dir().context("Failed to resolve current directory")?;
-        let path = cwd.join("vtcode.toml");
-        Self::save_config_to_path(path, config)
+        let content =
+            toml::to_string_pretty(&target).context("Failed to serialize configuration")?;
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write config file: {}", path.display()))?;
+        Ok(())
     }
 }
@@ -25,6 +25,14 @@ pub struct McpClientConfig {
     #[serde(default)]
     pub allowlist: McpAllowListConfig,
 
+    /// Interactive elicitation requests raised by providers
+    #[serde(default)]
+    pub elicitation: McpElicitationConfig,
+
+    /// Sampling (`sampling/createMessage`) requests raised by providers
+    #[serde(default)]
+    pub sampling: McpSamplingConfig,
+
     /// Maximum number of concurrent MCP connections
     #[serde(default = "default_max_concurrent_connections")]
     pub max_concurrent_connections: usize,
@@ -46,6 +54,8 @@ impl Default for McpClientConfig {
             providers: Vec::new(),
             server: McpServerConfig::default(),
             allowlist: McpAllowListConfig::default(),
+            elicitation: McpElicitationConfig::default(),
+            sampling: McpSamplingConfig::default(),
             max_concurrent_connections: default_max_concurrent_connections(),
             request_timeout_seconds: default_request_timeout_seconds(),
             retry_attempts: default_retry_attempts(),
@@ -104,6 +114,102 @@ impl Default for McpUiMode {
     }
 }
 
+/// Configuration for provider-initiated elicitation requests
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpElicitationConfig {
+    /// Route elicitation requests to the user instead of declining them
+    #[serde(default = "default_elicitation_enabled")]
+    pub enabled: bool,
+
+    /// Seconds to wait for the user before cancelling the request; also bounds
+    /// sampling approval prompts
+    #[serde(default = "default_elicitation_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+impl Default for McpElicitationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_elicitation_enabled(),
+            timeout_seconds: default_elicitation_timeout_seconds(),
+        }
+    }
+}
+
+/// Policy applied to sampling requests from a provider
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpSamplingPolicy {
+    /// Forward the request to the active model without asking
+    Allow,
+    /// Ask the user before forwarding each request
+    #[default]
+    Prompt,
+    /// Reject every request
+    Deny,
+}
+
+/// Configuration for provider-initiated sampling requests
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpSamplingConfig {
+    /// Advertise the sampling capability to providers
+    #[serde(default = "default_sampling_enabled")]
+    pub enabled: bool,
+
+    /// Policy applied when no provider-specific rule exists
+    #[serde(default)]
+    pub default_policy: McpSamplingPolicy,
+
+    /// Upper bound on completion tokens for a single sampling request
+    #[serde(default = "default_sampling_max_tokens")]
+    pub max_tokens: u32,
+
+    /// Provider-specific overrides keyed by provider name
+    #[serde(default)]
+    pub providers: BTreeMap<String, McpSamplingRules>,
+}
+
+impl Default for McpSamplingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_sampling_enabled(),
+            default_policy: McpSamplingPolicy::default(),
+            max_tokens: default_sampling_max_tokens(),
+            providers: BTreeMap::new(),
+        }
+    }
+}
+
+impl McpSamplingConfig {
+    /// Resolve the sampling policy for the given provider
+    pub fn policy_for(&self, provider: &str) -> McpSamplingPolicy {
+        self.providers
+            .get(provider)
+            .and_then(|rules| rules.policy)
+            .unwrap_or(self.default_policy)
+    }
+
+    /// Resolve the completion token cap for the given provider
+    pub fn max_tokens_for(&self, provider: &str) -> u32 {
+        self.providers
+            .get(provider)
+            .and_then(|rules| rules.max_tokens)
+            .unwrap_or(self.max_tokens)
+    }
+}
+
+/// Sampling overrides for a single provider
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct McpSamplingRules {
+    /// Policy override for this provider
+    #[serde(default)]
+    pub policy: Option<McpSamplingPolicy>,
+
+    /// Completion token cap override for this provider
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
 /// Configuration for a single MCP provider
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct McpProviderConfig {
@@ -498,6 +604,22 @@ fn default_retry_attempts() -> u32 {
     3
 }
 
+fn default_elicitation_enabled() -> bool {
+    true
+}
+
+fn default_elicitation_timeout_seconds() -> u64 {
+    300
+}
+
+fn default_sampling_enabled() -> bool {
+    false
+}
+
+fn default_sampling_max_tokens() -> u32 {
+    1024
+}
+
 fn default_provider_enabled() -> bool {
     true
 }
@@ -557,6 +679,36 @@ mod tests {
         assert!(!config.server.enabled);
         assert!(!config.allowlist.enforce);
         assert!(config.allowlist.default.tools.is_none());
+        assert!(config.elicitation.enabled);
+        assert_eq!(config.elicitation.timeout_seconds, 300);
+        assert!(!config.sampling.enabled);
+        assert_eq!(config.sampling.default_policy, McpSamplingPolicy::Prompt);
+        assert_eq!(config.sampling.max_tokens, 1024);
+    }
+
+    #[test]
+    fn test_sampling_provider_overrides() {
+        let mut config = McpSamplingConfig::default();
+        config.providers.insert(
+            "search".to_string(),
+            McpSamplingRules {
+                policy: Some(McpSamplingPolicy::Allow),
+                max_tokens: Some(256),
+            },
+        );
+        config.providers.insert(
+            "files".to_string(),
+            McpSamplingRules {
+                policy: Some(McpSamplingPolicy::Deny),
+                max_tokens: None,
+            },
+        );
+
+        assert_eq!(config.policy_for("search"), McpSamplingPolicy::Allow);
+        assert_eq!(config.max_tokens_for("search"), 256);
+        assert_eq!(config.policy_for("files"), McpSamplingPolicy::Deny);
+        assert_eq!(config.max_tokens_for("files"), 1024);
+        assert_eq!(config.policy_for("other"), McpSamplingPolicy::Prompt);
     }
 
     #[test]
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// Persistent memory files injected into the system prompt
+///
+/// ```toml
+/// [memory]
+/// enabled = true
+/// max_tokens = 1500
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MemoryConfig {
+    /// Include remembered facts in the system prompt
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Token budget for remembered facts in the system prompt
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: usize,
+
+    /// Also include `~/.vtcode/MEMORY.md` alongside the project memory
+    #[serde(default = "default_include_user_memory")]
+    pub include_user_memory: bool,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            max_tokens: default_max_tokens(),
+            include_user_memory: default_include_user_memory(),
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_max_tokens() -> usize {
+    1_500
+}
+
+fn default_include_user_memory() -> bool {
+    true
+}
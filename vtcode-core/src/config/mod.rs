@@ -176,10 +176,14 @@ pub mod api_keys;
 pub mod constants;
 pub mod context;
 pub mod core;
+pub mod cost;
 pub mod defaults;
+pub mod fallback;
 pub mod loader;
 pub mod mcp;
+pub mod memory;
 pub mod models;
+pub mod notifications;
 pub mod router;
 pub mod telemetry;
 pub mod types;
@@ -189,22 +193,28 @@ pub use acp::{
     AgentClientProtocolConfig, AgentClientProtocolTransport, AgentClientProtocolZedConfig,
     AgentClientProtocolZedToolsConfig,
 };
-pub use context::{ContextFeaturesConfig, LedgerConfig};
+pub use context::{ContextCompactionConfig, ContextFeaturesConfig, LedgerConfig};
 pub use core::{
     AgentConfig, AutomationConfig, CommandsConfig, FullAutoConfig, SecurityConfig, ToolPolicy,
-    ToolsConfig,
+    ToolsConfig, WebSearchBackendKind, WebSearchConfig,
 };
+pub use cost::CostConfig;
 pub use defaults::{ContextStoreDefaults, PerformanceDefaults, ScenarioDefaults};
+pub use fallback::FallbackConfig;
 pub use loader::{ConfigManager, VTCodeConfig};
 pub use mcp::{
-    McpAllowListConfig, McpAllowListRules, McpClientConfig, McpHttpServerConfig, McpProviderConfig,
+    McpAllowListConfig, McpAllowListRules, McpClientConfig, McpElicitationConfig,
+    McpHttpServerConfig, McpProviderConfig, McpSamplingConfig, McpSamplingPolicy, McpSamplingRules,
     McpStdioServerConfig, McpTransportConfig, McpUiConfig, McpUiMode,
 };
+pub use memory::MemoryConfig;
+pub use notifications::{NotificationBackend, NotificationsConfig};
 pub use router::{ComplexityModelMap, ResourceBudget, RouterConfig};
 pub use telemetry::TelemetryConfig;
 pub use types::ReasoningEffortLevel;
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -227,6 +237,32 @@ pub struct UiConfig {
     pub inline_viewport_rows: u16,
     #[serde(default = "default_show_timeline_pane")]
     pub show_timeline_pane: bool,
+    /// Key binding overrides keyed by action name, e.g. `submit = "ctrl+enter"`
+    #[serde(default)]
+    pub keybindings: BTreeMap<String, KeyBindingSpec>,
+    /// Modal vi-style editing for the prompt input
+    #[serde(default)]
+    pub vi_mode: bool,
+    /// Terminal and desktop notifications
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+}
+
+/// One key (`"ctrl+s"`) or several keys bound to the same action.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum KeyBindingSpec {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl KeyBindingSpec {
+    pub fn keys(&self) -> &[String] {
+        match self {
+            Self::Single(key) => std::slice::from_ref(key),
+            Self::Multiple(keys) => keys,
+        }
+    }
 }
 
 impl Default for UiConfig {
@@ -235,6 +271,9 @@ impl Default for UiConfig {
             tool_output_mode: default_tool_output_mode(),
             inline_viewport_rows: default_inline_viewport_rows(),
             show_timeline_pane: default_show_timeline_pane(),
+            keybindings: BTreeMap::new(),
+            vi_mode: false,
+            notifications: NotificationsConfig::default(),
         }
     }
 }
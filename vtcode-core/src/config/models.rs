@@ -548,6 +548,100 @@ impl ModelId {
             ModelId::OpenRouterAnthropicClaudeSonnet45 => "2025-09-29",
         }
     }
+
+    /// List prices for this model in USD per million tokens (see `docs/models.json`)
+    pub fn pricing(&self) -> ModelPricing {
+        match self {
+            // Gemini models
+            ModelId::Gemini25FlashPreview => ModelPricing::new(0.15, 0.6, Some(0.0375), None),
+            ModelId::Gemini25Flash => ModelPricing::new(0.3, 2.5, Some(0.075), Some(0.383)),
+            ModelId::Gemini25FlashLite => ModelPricing::new(0.1, 0.4, Some(0.025), None),
+            ModelId::Gemini25Pro => ModelPricing::new(1.25, 10.0, Some(0.31), None),
+            // OpenAI models
+            ModelId::GPT5 | ModelId::GPT5Codex => ModelPricing::new(1.25, 10.0, Some(0.125), None),
+            ModelId::GPT5Mini => ModelPricing::new(0.25, 2.0, Some(0.03), None),
+            ModelId::GPT5Nano => ModelPricing::new(0.05, 0.4, Some(0.01), None),
+            ModelId::CodexMiniLatest => ModelPricing::new(1.5, 6.0, Some(0.375), None),
+            // Anthropic models
+            ModelId::ClaudeOpus41 => ModelPricing::new(15.0, 75.0, Some(1.5), Some(18.75)),
+            ModelId::ClaudeSonnet45 | ModelId::ClaudeSonnet4 => {
+                ModelPricing::new(3.0, 15.0, Some(0.3), Some(3.75))
+            }
+            // DeepSeek models
+            ModelId::DeepSeekChat | ModelId::DeepSeekReasoner => {
+                ModelPricing::new(0.57, 1.68, Some(0.07), None)
+            }
+            // xAI models
+            ModelId::XaiGrok2Latest
+            | ModelId::XaiGrok2
+            | ModelId::XaiGrok2Mini
+            | ModelId::XaiGrok2Reasoning
+            | ModelId::XaiGrok2Vision => ModelPricing::new(2.0, 10.0, Some(2.0), None),
+            // OpenRouter models
+            ModelId::OpenRouterGrokCodeFast1 => ModelPricing::new(0.2, 1.5, Some(0.02), None),
+            ModelId::OpenRouterQwen3Coder => ModelPricing::new(0.3, 1.2, None, None),
+            ModelId::OpenRouterDeepSeekChatV31 => ModelPricing::new(0.2, 0.8, None, None),
+            ModelId::OpenRouterOpenAIGPT5 => ModelPricing::new(1.25, 10.0, Some(0.125), None),
+            ModelId::OpenRouterAnthropicClaudeSonnet45
+            | ModelId::OpenRouterAnthropicClaudeSonnet4 => {
+                ModelPricing::new(3.0, 15.0, Some(0.3), Some(3.75))
+            }
+        }
+    }
+}
+
+/// Token prices for a model in USD per million tokens
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    /// Uncached prompt tokens
+    pub input: f64,
+    /// Completion tokens, including reasoning output
+    pub output: f64,
+    /// Prompt tokens served from the provider cache; billed as input when absent
+    #[serde(default)]
+    pub cache_read: Option<f64>,
+    /// Prompt tokens written to the provider cache; billed as input when absent
+    #[serde(default)]
+    pub cache_write: Option<f64>,
+}
+
+impl ModelPricing {
+    pub const fn new(
+        input: f64,
+        output: f64,
+        cache_read: Option<f64>,
+        cache_write: Option<f64>,
+    ) -> Self {
+        Self {
+            input,
+            output,
+            cache_read,
+            cache_write,
+        }
+    }
+
+    /// Look up list prices for a model identifier, accepting `provider/model` slugs
+    pub fn for_model(model: &str) -> Option<Self> {
+        let model = model.trim();
+        ModelId::from_str(model)
+            .ok()
+            .or_else(|| {
+                model
+                    .split_once('/')
+                    .and_then(|(_, name)| ModelId::from_str(name).ok())
+            })
+            .map(|id| id.pricing())
+    }
+
+    /// Effective rate for cache reads
+    pub fn cache_read_rate(&self) -> f64 {
+        self.cache_read.unwrap_or(self.input)
+    }
+
+    /// Effective rate for cache writes
+    pub fn cache_write_rate(&self) -> f64 {
+        self.cache_write.unwrap_or(self.input)
+    }
 }
 
 impl fmt::Display for ModelId {
@@ -1078,4 +1172,18 @@ mod tests {
         assert!(fallbacks.contains(&ModelId::XaiGrok2Latest));
         assert!(fallbacks.contains(&ModelId::OpenRouterGrokCodeFast1));
     }
+
+    #[test]
+    fn test_model_pricing_lookup() {
+        let sonnet = ModelPricing::for_model(models::CLAUDE_SONNET_4_5).unwrap();
+        assert_eq!(sonnet.cache_write, Some(3.75));
+        assert_eq!(
+            ModelPricing::for_model("openai/gpt-5-mini"),
+            Some(ModelId::GPT5Mini.pricing())
+        );
+        assert!(ModelPricing::for_model("unknown-model").is_none());
+
+        let qwen = ModelId::OpenRouterQwen3Coder.pricing();
+        assert_eq!(qwen.cache_read_rate(), qwen.input);
+    }
 }
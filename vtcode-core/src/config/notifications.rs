@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+/// Alerts for events that need the user while they are looking elsewhere
+///
+/// ```toml
+/// [ui.notifications]
+/// enabled = true
+/// backend = "command"
+/// command = "terminal-notifier -title \"$VTCODE_NOTIFICATION_TITLE\" -message \"$VTCODE_NOTIFICATION_BODY\""
+/// min_duration_secs = 30
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NotificationsConfig {
+    /// Send notifications at all
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// How notifications are delivered
+    #[serde(default)]
+    pub backend: NotificationBackend,
+
+    /// Shell command run by the `command` backend. The title, body and event name
+    /// are passed in `VTCODE_NOTIFICATION_TITLE`, `VTCODE_NOTIFICATION_BODY` and
+    /// `VTCODE_NOTIFICATION_EVENT`.
+    #[serde(default)]
+    pub command: Option<String>,
+
+    /// Only report finished or failed turns that ran at least this long
+    #[serde(default = "default_min_duration_secs")]
+    pub min_duration_secs: u64,
+
+    /// Notify when a turn completes
+    #[serde(default = "default_true")]
+    pub turn_complete: bool,
+
+    /// Notify when a tool approval, change or plan review, or MCP request waits for you
+    #[serde(default = "default_true")]
+    pub approval: bool,
+
+    /// Notify when a turn stops on an error
+    #[serde(default = "default_true")]
+    pub errors: bool,
+}
+
+/// Delivery mechanism for notifications
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationBackend {
+    /// Ring the terminal bell
+    #[default]
+    Bell,
+    /// OSC 9 desktop notification (iTerm2, WezTerm, Windows Terminal, ...)
+    Osc9,
+    /// OSC 777 desktop notification (rxvt-unicode, foot, Ghostty, ...)
+    Osc777,
+    /// `notify-send` from libnotify
+    NotifySend,
+    /// The configured `command`
+    Command,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: NotificationBackend::default(),
+            command: None,
+            min_duration_secs: default_min_duration_secs(),
+            turn_complete: true,
+            approval: true,
+            errors: true,
+        }
+    }
+}
+
+fn default_min_duration_secs() -> u64 {
+    10
+}
+
+fn default_true() -> bool {
+    true
+}
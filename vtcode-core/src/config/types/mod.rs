@@ -198,6 +198,13 @@ pub enum CapabilityLevel {
     CodeSearch,
 }
 
+impl CapabilityLevel {
+    /// Whether tools at this level only observe the workspace
+    pub fn is_read_only(self) -> bool {
+        !matches!(self, CapabilityLevel::Bash | CapabilityLevel::Editing)
+    }
+}
+
 /// Session information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionInfo {
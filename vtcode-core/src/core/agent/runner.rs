@@ -330,6 +330,7 @@ impl AgentRunner {
                             content: content_text,
                             tool_calls: None,
                             tool_call_id: None,
+                            attachments: Vec::new(),
                         }
                     })
                     .collect(),
@@ -344,14 +345,12 @@ impl AgentRunner {
                 parallel_tool_config: Some(
                     crate::llm::provider::ParallelToolConfig::anthropic_optimized(),
                 ),
-                reasoning_effort: if self
-                    .provider_client
-                    .supports_reasoning_effort(&self.model)
-                {
+                reasoning_effort: if self.provider_client.supports_reasoning_effort(&self.model) {
                     self.reasoning_effort
                 } else {
                     None
                 },
+                response_format: None,
             };
 
             // Use provider-specific client for OpenAI/Anthropic (and generic support for others)
@@ -106,6 +106,7 @@ impl ContextCompressor {
                 content: format!("Previous conversation summary: {}", summary),
                 tool_calls: None,
                 tool_call_id: None,
+                attachments: Vec::new(),
             });
         }
 
@@ -239,12 +240,14 @@ impl ContextCompressor {
                     content: system_prompt,
                     tool_calls: None,
                     tool_call_id: None,
+                    attachments: Vec::new(),
                 },
                 Message {
                     role: MessageRole::User,
                     content: user_prompt,
                     tool_calls: None,
                     tool_call_id: None,
+                    attachments: Vec::new(),
                 },
             ],
             system_prompt: None,
@@ -257,6 +260,7 @@ impl ContextCompressor {
             parallel_tool_calls: None,
             parallel_tool_config: None,
             reasoning_effort: None,
+            response_format: None,
         };
 
         let response = self
@@ -345,12 +349,14 @@ mod tests {
                 content: "Hello world".to_string(),
                 tool_calls: None,
                 tool_call_id: None,
+                attachments: Vec::new(),
             },
             Message {
                 role: MessageRole::Assistant,
                 content: "Hi there! How can I help you?".to_string(),
                 tool_calls: None,
                 tool_call_id: None,
+                attachments: Vec::new(),
             },
         ];
 
@@ -374,6 +380,7 @@ mod tests {
             content: "x".repeat(400), // ~100 tokens
             tool_calls: None,
             tool_call_id: None,
+            attachments: Vec::new(),
         }];
 
         assert!(compressor.needs_compression(&messages));
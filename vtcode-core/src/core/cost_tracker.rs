@@ -0,0 +1,458 @@
+//! Spend accounting for LLM usage
+//!
+//! Every response's usage block is converted into USD using the price table in
+//! [`ModelPricing`] (optionally overridden from `[cost.prices]`). Spend is
+//! accumulated per turn and per session, and a small ledger under
+//! `~/.vtcode/cost_ledger.json` carries the daily total across sessions so the
+//! configured session and daily limits can pause autonomous runs. Ledger
+//! updates hold an exclusive lock on a sidecar file and replace the ledger
+//! atomically, so concurrent sessions never lose each other's spend.
+
+use crate::config::cost::CostConfig;
+use crate::config::models::ModelPricing;
+use crate::llm::provider::Usage;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::warn;
+
+/// File name of the cross-session daily spend ledger inside `~/.vtcode`.
+const LEDGER_FILE_NAME: &str = "cost_ledger.json";
+/// Suffix of the lock file guarding ledger updates.
+const LEDGER_LOCK_SUFFIX: &str = "lock";
+/// Number of days kept in the ledger.
+const LEDGER_RETENTION_DAYS: usize = 31;
+const TOKENS_PER_PRICE_UNIT: f64 = 1_000_000.0;
+
+/// Token totals accumulated over a session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenTotals {
+    pub prompt: u64,
+    pub completion: u64,
+    pub cache_read: u64,
+    pub cache_write: u64,
+}
+
+/// Spend accumulated at each scope, in USD.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CostSnapshot {
+    pub turn_usd: f64,
+    pub session_usd: f64,
+    pub daily_usd: f64,
+    pub tokens: TokenTotals,
+}
+
+/// A spend limit that has been reached.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BudgetLimit {
+    Session { limit_usd: f64, spent_usd: f64 },
+    Daily { limit_usd: f64, spent_usd: f64 },
+}
+
+impl fmt::Display for BudgetLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetLimit::Session {
+                limit_usd,
+                spent_usd,
+            } => write!(
+                f,
+                "session cost limit of {} reached ({} spent)",
+                format_usd(*limit_usd),
+                format_usd(*spent_usd)
+            ),
+            BudgetLimit::Daily {
+                limit_usd,
+                spent_usd,
+            } => write!(
+                f,
+                "daily cost limit of {} reached ({} spent today)",
+                format_usd(*limit_usd),
+                format_usd(*spent_usd)
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DailyLedger {
+    #[serde(default)]
+    days: BTreeMap<String, f64>,
+}
+
+/// Tracker shared by every part of a session that calls a model, so that
+/// summaries, prompt refinement and MCP sampling count toward the same limits.
+pub type SharedCostTracker = Arc<parking_lot::Mutex<CostTracker>>;
+
+/// Accumulates spend for a session and enforces the configured limits.
+pub struct CostTracker {
+    enabled: bool,
+    prices: BTreeMap<String, ModelPricing>,
+    session_limit_usd: Option<f64>,
+    daily_limit_usd: Option<f64>,
+    ledger_path: Option<PathBuf>,
+    day: String,
+    turn_usd: f64,
+    session_usd: f64,
+    daily_usd: f64,
+    tokens: TokenTotals,
+    unpriced_models: BTreeSet<String>,
+}
+
+impl CostTracker {
+    /// Create a tracker, seeding today's spend from the ledger at `ledger_path`.
+    pub fn new(config: &CostConfig, ledger_path: Option<PathBuf>) -> Self {
+        let day = today();
+        let daily_usd = ledger_path
+            .as_deref()
+            .map(|path| read_ledger(path).days.get(&day).copied().unwrap_or(0.0))
+            .unwrap_or(0.0);
+        Self {
+            enabled: config.enabled,
+            prices: config.prices.clone(),
+            session_limit_usd: config.max_cost_usd,
+            daily_limit_usd: config.max_daily_cost_usd,
+            ledger_path,
+            day,
+            turn_usd: 0.0,
+            session_usd: 0.0,
+            daily_usd,
+            tokens: TokenTotals::default(),
+            unpriced_models: BTreeSet::new(),
+        }
+    }
+
+    /// Wrap the tracker for sharing between the turn loop and background model calls.
+    pub fn into_shared(self) -> SharedCostTracker {
+        Arc::new(parking_lot::Mutex::new(self))
+    }
+
+    /// Location of the shared daily ledger.
+    pub fn default_ledger_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".vtcode").join(LEDGER_FILE_NAME))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Prices for `model`, preferring configured overrides.
+    pub fn pricing_for(&self, model: &str) -> Option<ModelPricing> {
+        self.prices
+            .get(model)
+            .copied()
+            .or_else(|| ModelPricing::for_model(model))
+    }
+
+    /// Reset the per-turn accumulator.
+    pub fn start_turn(&mut self) {
+        self.turn_usd = 0.0;
+    }
+
+    /// Record the usage of one response and return its cost, or `None` when
+    /// tracking is disabled or the model has no known price.
+    pub fn record(&mut self, provider: &str, model: &str, usage: &Usage) -> Option<f64> {
+        if !self.enabled {
+            return None;
+        }
+
+        let cache_reported_separately = provider.eq_ignore_ascii_case("anthropic");
+        let cache_read = usage
+            .cache_read_tokens
+            .or(usage.cached_prompt_tokens)
+            .unwrap_or(0) as u64;
+        let cache_write = if cache_reported_separately {
+            usage.cache_creation_tokens.unwrap_or(0) as u64
+        } else {
+            0
+        };
+        self.tokens.prompt += usage.prompt_tokens as u64;
+        self.tokens.completion += usage.completion_tokens as u64;
+        self.tokens.cache_read += cache_read;
+        self.tokens.cache_write += cache_write;
+
+        let Some(pricing) = self.pricing_for(model) else {
+            if self.unpriced_models.insert(model.to_string()) {
+                warn!(model, "no price known for model; its usage is not counted");
+            }
+            return None;
+        };
+
+        let cost = usage_cost(&pricing, usage, cache_reported_separately);
+        self.turn_usd += cost;
+        self.session_usd += cost;
+        self.add_daily(cost);
+        Some(cost)
+    }
+
+    pub fn snapshot(&self) -> CostSnapshot {
+        CostSnapshot {
+            turn_usd: self.turn_usd,
+            session_usd: self.session_usd,
+            daily_usd: self.current_daily_usd(),
+            tokens: self.tokens,
+        }
+    }
+
+    /// Models whose usage could not be priced this session.
+    pub fn unpriced_models(&self) -> impl Iterator<Item = &str> {
+        self.unpriced_models.iter().map(String::as_str)
+    }
+
+    pub fn session_limit_usd(&self) -> Option<f64> {
+        self.session_limit_usd
+    }
+
+    pub fn daily_limit_usd(&self) -> Option<f64> {
+        self.daily_limit_usd
+    }
+
+    /// Replace the session limit, e.g. after the user raised it with `/budget`.
+    pub fn set_session_limit_usd(&mut self, limit: Option<f64>) {
+        self.session_limit_usd = limit;
+    }
+
+    /// The first limit that has been reached, if any.
+    pub fn budget_exceeded(&self) -> Option<BudgetLimit> {
+        if !self.enabled {
+            return None;
+        }
+        if let Some(limit_usd) = self.session_limit_usd
+            && self.session_usd >= limit_usd
+        {
+            return Some(BudgetLimit::Session {
+                limit_usd,
+                spent_usd: self.session_usd,
+            });
+        }
+        if let Some(limit_usd) = self.daily_limit_usd {
+            let spent_usd = self.current_daily_usd();
+            if spent_usd >= limit_usd {
+                return Some(BudgetLimit::Daily {
+                    limit_usd,
+                    spent_usd,
+                });
+            }
+        }
+        None
+    }
+
+    /// Compact spend summary for the inline header.
+    pub fn header_label(&self) -> String {
+        let snapshot = self.snapshot();
+        let mut label = format_usd(snapshot.session_usd);
+        if let Some(limit) = self.session_limit_usd {
+            label.push_str(&format!(" / {}", format_usd(limit)));
+        }
+        label.push_str(&format!(" · today {}", format_usd(snapshot.daily_usd)));
+        label
+    }
+
+    fn current_daily_usd(&self) -> f64 {
+        if self.day == today() {
+            self.daily_usd
+        } else {
+            0.0
+        }
+    }
+
+    fn add_daily(&mut self, cost: f64) {
+        let day = today();
+        if day != self.day {
+            self.day = day;
+            self.daily_usd = 0.0;
+        }
+
+        let Some(path) = self.ledger_path.as_deref() else {
+            self.daily_usd += cost;
+            return;
+        };
+
+        // Re-read the ledger under the lock so concurrent sessions add to the same total.
+        let day = self.day.clone();
+        let result = update_ledger(path, |ledger| {
+            let total = ledger.days.entry(day).or_insert(0.0);
+            *total += cost;
+            let total = *total;
+            while ledger.days.len() > LEDGER_RETENTION_DAYS {
+                let Some(oldest) = ledger.days.keys().next().cloned() else {
+                    break;
+                };
+                ledger.days.remove(&oldest);
+            }
+            total
+        });
+        match result {
+            Ok(total) => self.daily_usd = total,
+            Err(err) => {
+                self.daily_usd += cost;
+                warn!(path = %path.display(), error = %err, "failed to update cost ledger");
+            }
+        }
+    }
+}
+
+/// Cost of a single response in USD.
+///
+/// Anthropic reports cache reads and writes separately from `input_tokens`;
+/// the other providers include cached tokens in the prompt count.
+pub fn usage_cost(pricing: &ModelPricing, usage: &Usage, cache_reported_separately: bool) -> f64 {
+    let prompt = usage.prompt_tokens as f64;
+    let completion = usage.completion_tokens as f64;
+    let cache_read = usage
+        .cache_read_tokens
+        .or(usage.cached_prompt_tokens)
+        .unwrap_or(0) as f64;
+
+    let (uncached, cache_write) = if cache_reported_separately {
+        (prompt, usage.cache_creation_tokens.unwrap_or(0) as f64)
+    } else {
+        ((prompt - cache_read).max(0.0), 0.0)
+    };
+
+    (uncached * pricing.input
+        + cache_read * pricing.cache_read_rate()
+        + cache_write * pricing.cache_write_rate()
+        + completion * pricing.output)
+        / TOKENS_PER_PRICE_UNIT
+}
+
+/// Format a USD amount with precision suited to its size.
+pub fn format_usd(value: f64) -> String {
+    if value >= 1.0 {
+        format!("${:.2}", value)
+    } else {
+        format!("${:.4}", value)
+    }
+}
+
+fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn read_ledger(path: &Path) -> DailyLedger {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Apply `update` to the ledger while holding an exclusive lock on its sidecar
+/// lock file, then atomically replace the ledger with the result.
+fn update_ledger<T>(path: &Path, update: impl FnOnce(&mut DailyLedger) -> T) -> std::io::Result<T> {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(parent)?;
+    let lock = File::create(path.with_extension(LEDGER_LOCK_SUFFIX))?;
+    lock.lock()?;
+
+    let mut ledger = read_ledger(path);
+    let value = update(&mut ledger);
+    let mut temp = tempfile::NamedTempFile::new_in(parent)?;
+    serde_json::to_writer_pretty(&mut temp, &ledger)?;
+    temp.flush()?;
+    temp.persist(path).map_err(|err| err.error)?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn usage(prompt: u32, completion: u32, cache_read: Option<u32>) -> Usage {
+        Usage {
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            total_tokens: prompt + completion,
+            cached_prompt_tokens: cache_read,
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
+        }
+    }
+
+    #[test]
+    fn cached_tokens_use_cache_rates() {
+        let pricing = ModelPricing::new(3.0, 15.0, Some(0.3), Some(3.75));
+
+        // OpenAI-style: 1M prompt tokens of which half were cached.
+        let inclusive = usage_cost(&pricing, &usage(1_000_000, 0, Some(500_000)), false);
+        assert!((inclusive - (1.5 + 0.15)).abs() < 1e-9);
+
+        // Anthropic-style: cache tokens are reported on top of input tokens.
+        let mut separate = usage(1_000_000, 100_000, None);
+        separate.cache_read_tokens = Some(1_000_000);
+        separate.cache_creation_tokens = Some(1_000_000);
+        let cost = usage_cost(&pricing, &separate, true);
+        assert!((cost - (3.0 + 0.3 + 3.75 + 1.5)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn session_limit_pauses_and_ledger_is_shared() {
+        let dir = TempDir::new().unwrap();
+        let ledger = dir.path().join(LEDGER_FILE_NAME);
+        let config = CostConfig {
+            max_cost_usd: Some(0.01),
+            ..CostConfig::default()
+        };
+
+        let mut first = CostTracker::new(&config, Some(ledger.clone()));
+        first.start_turn();
+        assert!(first.budget_exceeded().is_none());
+        let cost = first
+            .record("openai", "gpt-5", &usage(10_000, 1_000, None))
+            .unwrap();
+        assert!((cost - 0.0225).abs() < 1e-9);
+        assert!(matches!(
+            first.budget_exceeded(),
+            Some(BudgetLimit::Session { .. })
+        ));
+        first.set_session_limit_usd(Some(1.0));
+        assert!(first.budget_exceeded().is_none());
+
+        let second = CostTracker::new(&config, Some(ledger));
+        assert!((second.snapshot().daily_usd - cost).abs() < 1e-9);
+        assert_eq!(second.snapshot().session_usd, 0.0);
+
+        let mut unpriced = CostTracker::new(&CostConfig::default(), None);
+        assert!(
+            unpriced
+                .record("custom", "my-model", &usage(10, 10, None))
+                .is_none()
+        );
+        assert_eq!(
+            unpriced.unpriced_models().collect::<Vec<_>>(),
+            vec!["my-model"]
+        );
+    }
+
+    #[test]
+    fn concurrent_sessions_do_not_lose_spend() {
+        let dir = TempDir::new().unwrap();
+        let ledger = dir.path().join(LEDGER_FILE_NAME);
+        let config = CostConfig::default();
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let ledger = ledger.clone();
+                let config = config.clone();
+                std::thread::spawn(move || {
+                    let mut tracker = CostTracker::new(&config, Some(ledger));
+                    for _ in 0..10 {
+                        tracker.record("openai", "gpt-5", &usage(10_000, 1_000, None));
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        let total = CostTracker::new(&config, Some(ledger)).snapshot().daily_usd;
+        assert!((total - 40.0 * 0.0225).abs() < 1e-9);
+    }
+}
@@ -54,6 +54,7 @@ pub mod agent;
 pub mod context_compression;
 pub mod context_curator;
 pub mod conversation_summarizer;
+pub mod cost_tracker;
 pub mod decision_tracker;
 pub mod error_recovery;
 pub mod orchestrator_retry;
@@ -63,4 +64,5 @@ pub mod prompt_caching;
 pub mod router;
 pub mod timeout_detector;
 pub mod token_budget;
+pub mod tokenizer;
 pub mod trajectory;
@@ -419,12 +419,14 @@ impl PromptOptimizer {
                     content: system_prompt,
                     tool_calls: None,
                     tool_call_id: None,
+                    attachments: Vec::new(),
                 },
                 Message {
                     role: MessageRole::User,
                     content: user_prompt,
                     tool_calls: None,
                     tool_call_id: None,
+                    attachments: Vec::new(),
                 },
             ],
             system_prompt: None,
@@ -437,6 +439,7 @@ impl PromptOptimizer {
             parallel_tool_calls: None,
             parallel_tool_config: None,
             reasoning_effort: None,
+            response_format: None,
         };
 
         let response = self
@@ -139,7 +139,7 @@ impl Router {
                     .ok()
                     .map(|model| model.provider().to_string())
                     .or_else(|| {
-                        let factory = get_factory().lock().unwrap();
+                        let factory = get_factory().lock();
                         factory.provider_from_model(core.model.as_str())
                     })
                     .unwrap_or_else(|| "gemini".to_string())
@@ -173,6 +173,7 @@ impl Router {
                     parallel_tool_calls: None,
                     parallel_tool_config: None,
                     reasoning_effort,
+                    response_format: None,
                 };
                 if let Ok(resp) = provider.generate(req).await {
                     if let Some(text) = resp.content {
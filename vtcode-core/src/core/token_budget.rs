@@ -5,12 +5,13 @@
 //! principles, it helps prevent context rot by tracking token usage and
 //! triggering compaction when thresholds are exceeded.
 
-use anyhow::{Context, Result, anyhow};
+use super::tokenizer::{ProviderTokenizer, Tokenizer};
+use crate::config::models::{ModelId, Provider};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tiktoken_rs::get_bpe_from_model;
 use tokio::sync::RwLock;
 use tracing::debug;
 
@@ -118,40 +119,55 @@ pub struct TokenBudgetManager {
     config: Arc<RwLock<TokenBudgetConfig>>,
     stats: Arc<RwLock<TokenUsageStats>>,
     component_tokens: Arc<RwLock<HashMap<String, usize>>>,
-    tokenizer_cache: Arc<RwLock<Option<tiktoken_rs::CoreBPE>>>,
+    tokenizer: parking_lot::RwLock<Arc<ProviderTokenizer>>,
 }
 
 impl TokenBudgetManager {
-    /// Create a new token budget manager
+    /// Create a new token budget manager, inferring the provider from the
+    /// configured model name
     pub fn new(config: TokenBudgetConfig) -> Self {
+        let provider = config
+            .model
+            .parse::<ModelId>()
+            .map(|model| model.provider())
+            .unwrap_or(Provider::OpenAI);
+        let tokenizer = ProviderTokenizer::shared(provider, &config.model);
+        Self::with_tokenizer(config, tokenizer)
+    }
+
+    /// Create a token budget manager that shares an existing provider tokenizer
+    pub fn with_tokenizer(config: TokenBudgetConfig, tokenizer: Arc<ProviderTokenizer>) -> Self {
+        debug!(
+            "Token budget using tokenizer {} for provider {}",
+            tokenizer.name(),
+            tokenizer.provider()
+        );
         Self {
             config: Arc::new(RwLock::new(config)),
             stats: Arc::new(RwLock::new(TokenUsageStats::new())),
             component_tokens: Arc::new(RwLock::new(HashMap::new())),
-            tokenizer_cache: Arc::new(RwLock::new(None)),
+            tokenizer: parking_lot::RwLock::new(tokenizer),
         }
     }
 
-    /// Initialize or update tokenizer for the current model
-    async fn ensure_tokenizer(&self) -> Result<()> {
-        let mut cache = self.tokenizer_cache.write().await;
-        if cache.is_none() {
-            let config = self.config.read().await;
-            let bpe = get_bpe_from_model(&config.model)
-                .with_context(|| format!("Failed to get tokenizer for model: {}", config.model))?;
-            *cache = Some(bpe);
-        }
-        Ok(())
+    /// Tokenizer used for counting, shared with context trimming
+    pub fn tokenizer(&self) -> Arc<ProviderTokenizer> {
+        Arc::clone(&self.tokenizer.read())
+    }
+
+    /// Replace the tokenizer after the active provider or model changes
+    pub fn set_tokenizer(&self, tokenizer: Arc<ProviderTokenizer>) {
+        debug!(
+            "Token budget switching to tokenizer {} for provider {}",
+            tokenizer.name(),
+            tokenizer.provider()
+        );
+        *self.tokenizer.write() = tokenizer;
     }
 
     /// Count tokens in text
     pub async fn count_tokens(&self, text: &str) -> Result<usize> {
-        self.ensure_tokenizer().await?;
-        let cache = self.tokenizer_cache.read().await;
-        let bpe = cache
-            .as_ref()
-            .ok_or_else(|| anyhow!("Tokenizer not initialized"))?;
-        Ok(bpe.encode_with_special_tokens(text).len())
+        Ok(self.tokenizer.read().count(text))
     }
 
     /// Count tokens with component tracking
@@ -340,6 +356,22 @@ mod tests {
         assert!(count > 0);
     }
 
+    #[tokio::test]
+    async fn test_set_tokenizer_switches_provider() {
+        let manager = TokenBudgetManager::with_tokenizer(
+            TokenBudgetConfig::default(),
+            ProviderTokenizer::shared(Provider::OpenAI, "gpt-5"),
+        );
+        assert_eq!(manager.tokenizer().provider(), Provider::OpenAI);
+
+        manager.set_tokenizer(ProviderTokenizer::shared(
+            Provider::Anthropic,
+            "claude-sonnet-4-20250514",
+        ));
+        assert_eq!(manager.tokenizer().provider(), Provider::Anthropic);
+        assert!(manager.count_tokens("Hello, world!").await.unwrap() > 0);
+    }
+
     #[tokio::test]
     async fn test_component_tracking() {
         let mut config = TokenBudgetConfig::default();
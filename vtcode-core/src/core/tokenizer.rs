@@ -0,0 +1,296 @@
+//! Provider-aware token counting
+//!
+//! Token budgets and context trimming need token counts that match what the
+//! provider will actually bill. OpenAI models expose their BPE vocabularies via
+//! `tiktoken`, but Gemini, Claude, DeepSeek and Grok do not ship a local
+//! tokenizer. For those providers we fall back to calibrated character
+//! estimators and continuously correct them using the `prompt_tokens` value
+//! returned in each response's usage block.
+
+use crate::config::models::{ModelId, Provider};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tiktoken_rs::CoreBPE;
+
+/// Lower and upper bounds for the usage-derived correction factor.
+const MIN_CORRECTION: f64 = 0.5;
+const MAX_CORRECTION: f64 = 2.0;
+/// Weight applied to each new usage observation when updating the correction.
+const CORRECTION_SMOOTHING: f64 = 0.3;
+/// Observations below this size are too noisy to calibrate against.
+const MIN_CALIBRATION_TOKENS: usize = 64;
+
+/// Counts tokens for a specific model family.
+pub trait Tokenizer: Send + Sync {
+    /// Human readable identifier used in logs and reports.
+    fn name(&self) -> &str;
+
+    /// Count the tokens in `text`.
+    fn count(&self, text: &str) -> usize;
+}
+
+/// Exact byte-pair encoding tokenizer for OpenAI-compatible models.
+pub struct BpeTokenizer {
+    name: String,
+    bpe: CoreBPE,
+}
+
+impl BpeTokenizer {
+    /// Resolve the BPE vocabulary for `model`, returning `None` when the model
+    /// does not use an OpenAI vocabulary.
+    pub fn for_model(model: &str) -> Option<Self> {
+        let model = model.strip_prefix("openai/").unwrap_or(model);
+        if let Ok(bpe) = tiktoken_rs::get_bpe_from_model(model) {
+            return Some(Self {
+                name: format!("bpe:{}", model),
+                bpe,
+            });
+        }
+
+        // Newer OpenAI model names are not always known to tiktoken yet; they
+        // all share the o200k vocabulary.
+        let is_openai_family = ["gpt-", "o1", "o3", "o4", "chatgpt-"]
+            .iter()
+            .any(|prefix| model.starts_with(prefix));
+        if is_openai_family && let Ok(bpe) = tiktoken_rs::o200k_base() {
+            return Some(Self {
+                name: "bpe:o200k_base".to_string(),
+                bpe,
+            });
+        }
+
+        None
+    }
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn count(&self, text: &str) -> usize {
+        self.bpe.encode_with_special_tokens(text).len()
+    }
+}
+
+/// Character-based estimator calibrated for a provider's vocabulary.
+///
+/// ASCII text is divided by the provider's average characters-per-token,
+/// while non-ASCII characters (CJK, emoji, accented text) are counted
+/// individually since they rarely merge into multi-character tokens.
+pub struct EstimatingTokenizer {
+    name: &'static str,
+    chars_per_token: f64,
+}
+
+impl EstimatingTokenizer {
+    pub const fn new(name: &'static str, chars_per_token: f64) -> Self {
+        Self {
+            name,
+            chars_per_token,
+        }
+    }
+
+    /// Calibrated estimator for a provider without a local tokenizer.
+    pub fn for_provider(provider: Provider) -> Self {
+        match provider {
+            Provider::Anthropic => Self::new("estimate:anthropic", 3.5),
+            Provider::Gemini => Self::new("estimate:gemini", 4.0),
+            Provider::DeepSeek => Self::new("estimate:deepseek", 3.3),
+            Provider::XAI => Self::new("estimate:xai", 3.8),
+            Provider::OpenAI | Provider::OpenRouter => Self::new("estimate:generic", 4.0),
+        }
+    }
+}
+
+impl Tokenizer for EstimatingTokenizer {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn count(&self, text: &str) -> usize {
+        if text.is_empty() {
+            return 0;
+        }
+
+        let mut ascii = 0usize;
+        let mut other = 0usize;
+        for ch in text.chars() {
+            if ch.is_ascii() {
+                ascii += 1;
+            } else {
+                other += 1;
+            }
+        }
+
+        let estimate = ascii as f64 / self.chars_per_token + other as f64;
+        (estimate.ceil() as usize).max(1)
+    }
+}
+
+/// Tokenizer selected for the active provider, with a correction factor learned
+/// from the usage numbers the provider reports.
+pub struct ProviderTokenizer {
+    provider: Provider,
+    inner: Box<dyn Tokenizer>,
+    exact: bool,
+    correction_bits: AtomicU64,
+}
+
+impl ProviderTokenizer {
+    /// Select the most accurate tokenizer available for `model` served by
+    /// `provider`.
+    pub fn for_model(provider: Provider, model: &str) -> Self {
+        let family = model_family(provider, model);
+        let bpe = matches!(family, Provider::OpenAI)
+            .then(|| BpeTokenizer::for_model(model))
+            .flatten();
+
+        let (inner, exact): (Box<dyn Tokenizer>, bool) = match bpe {
+            Some(bpe) => (Box::new(bpe), true),
+            None => (Box::new(EstimatingTokenizer::for_provider(family)), false),
+        };
+
+        Self {
+            provider,
+            inner,
+            exact,
+            correction_bits: AtomicU64::new(1.0f64.to_bits()),
+        }
+    }
+
+    /// Convenience constructor returning a shareable handle.
+    pub fn shared(provider: Provider, model: &str) -> Arc<Self> {
+        Arc::new(Self::for_model(provider, model))
+    }
+
+    pub fn provider(&self) -> Provider {
+        self.provider
+    }
+
+    /// Whether the underlying tokenizer is an exact vocabulary match.
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    /// Current multiplicative correction applied to raw counts.
+    pub fn correction(&self) -> f64 {
+        f64::from_bits(self.correction_bits.load(Ordering::Relaxed))
+    }
+
+    /// Feed back the provider-reported prompt token count for a request whose
+    /// prompt this tokenizer estimated at `estimated_tokens` (after correction).
+    pub fn record_usage(&self, estimated_tokens: usize, actual_prompt_tokens: usize) {
+        if estimated_tokens < MIN_CALIBRATION_TOKENS || actual_prompt_tokens == 0 {
+            return;
+        }
+
+        let current = self.correction();
+        let raw_estimate = estimated_tokens as f64 / current;
+        let observed =
+            (actual_prompt_tokens as f64 / raw_estimate).clamp(MIN_CORRECTION, MAX_CORRECTION);
+        let updated = (current * (1.0 - CORRECTION_SMOOTHING) + observed * CORRECTION_SMOOTHING)
+            .clamp(MIN_CORRECTION, MAX_CORRECTION);
+        self.correction_bits
+            .store(updated.to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl Tokenizer for ProviderTokenizer {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn count(&self, text: &str) -> usize {
+        let raw = self.inner.count(text);
+        if raw == 0 {
+            return 0;
+        }
+        ((raw as f64) * self.correction()).ceil() as usize
+    }
+}
+
+/// Determine which vendor's vocabulary a model uses. OpenRouter model ids are
+/// prefixed with the upstream vendor (e.g. `anthropic/claude-sonnet-4`).
+fn model_family(provider: Provider, model: &str) -> Provider {
+    if let Ok(id) = model.parse::<ModelId>() {
+        let family = id.provider();
+        if family != Provider::OpenRouter {
+            return family;
+        }
+    }
+
+    if let Some((vendor, _)) = model.split_once('/') {
+        return match vendor {
+            "openai" => Provider::OpenAI,
+            "anthropic" => Provider::Anthropic,
+            "google" => Provider::Gemini,
+            "deepseek" => Provider::DeepSeek,
+            "x-ai" | "xai" => Provider::XAI,
+            _ => provider,
+        };
+    }
+
+    provider
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn openai_models_use_exact_bpe() {
+        let tokenizer = ProviderTokenizer::for_model(Provider::OpenAI, "gpt-4o-mini");
+        assert!(tokenizer.is_exact());
+        assert!(tokenizer.count("Hello, world!") > 0);
+
+        let routed = ProviderTokenizer::for_model(Provider::OpenRouter, "openai/gpt-4o");
+        assert!(routed.is_exact());
+    }
+
+    #[test]
+    fn non_openai_models_use_calibrated_estimators() {
+        let claude = ProviderTokenizer::for_model(Provider::Anthropic, "claude-sonnet-4-5");
+        assert!(!claude.is_exact());
+        assert_eq!(claude.name(), "estimate:anthropic");
+
+        let routed = ProviderTokenizer::for_model(Provider::OpenRouter, "x-ai/grok-4");
+        assert_eq!(routed.name(), "estimate:xai");
+
+        let gemini = ProviderTokenizer::for_model(Provider::Gemini, "gemini-2.5-flash");
+        assert_eq!(gemini.count(&"a".repeat(400)), 100);
+    }
+
+    #[test]
+    fn estimator_counts_non_ascii_characters_individually() {
+        let tokenizer = EstimatingTokenizer::new("test", 4.0);
+        assert_eq!(tokenizer.count(""), 0);
+        assert_eq!(tokenizer.count("日本語"), 3);
+    }
+
+    #[test]
+    fn usage_feedback_corrects_estimates() {
+        let tokenizer = ProviderTokenizer::for_model(Provider::Gemini, "gemini-2.5-flash");
+        let text = "a".repeat(4_000);
+        let initial = tokenizer.count(&text);
+        assert_eq!(initial, 1_000);
+
+        for _ in 0..20 {
+            let estimate = tokenizer.count(&text);
+            tokenizer.record_usage(estimate, 1_300);
+        }
+
+        let corrected = tokenizer.count(&text);
+        assert!((1_250..=1_310).contains(&corrected), "got {}", corrected);
+    }
+
+    #[test]
+    fn usage_feedback_ignores_tiny_and_outlier_samples() {
+        let tokenizer = ProviderTokenizer::for_model(Provider::Anthropic, "claude-sonnet-4-5");
+        tokenizer.record_usage(10, 1_000);
+        assert_eq!(tokenizer.correction(), 1.0);
+
+        tokenizer.record_usage(1_000, 100_000);
+        assert!(tokenizer.correction() <= MAX_CORRECTION);
+    }
+}
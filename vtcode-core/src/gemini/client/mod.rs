@@ -6,7 +6,7 @@ pub use retry::RetryConfig;
 
 use crate::gemini::models::{GenerateContentRequest, GenerateContentResponse};
 use crate::gemini::streaming::{
-    StreamingError, StreamingMetrics, StreamingProcessor, StreamingResponse,
+    StreamChunk, StreamingError, StreamingMetrics, StreamingProcessor, StreamingResponse,
 };
 use anyhow::{Context, Result};
 use reqwest::Client as ReqwestClient;
@@ -181,7 +181,14 @@ impl Client {
 
         // Process the streaming response
         let mut processor = StreamingProcessor::new();
-        let result = processor.process_stream(response, on_chunk).await;
+        // Callers of this client only receive response text.
+        let mut on_chunk = on_chunk;
+        let result = processor
+            .process_stream(response, |chunk| match chunk {
+                StreamChunk::Text(text) => on_chunk(text),
+                StreamChunk::Thought(_) => Ok(()),
+            })
+            .await;
 
         self.metrics.total_requests += 1;
         self.metrics.total_response_time += start_time.elapsed();
@@ -16,6 +16,6 @@ pub use models::{
     Tool, ToolConfig,
 };
 pub use streaming::{
-    StreamingCandidate, StreamingConfig, StreamingError, StreamingMetrics, StreamingProcessor,
-    StreamingResponse,
+    StreamChunk, StreamingCandidate, StreamingConfig, StreamingError, StreamingMetrics,
+    StreamingProcessor, StreamingResponse,
 };
@@ -56,6 +56,12 @@ impl SystemInstruction {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Part {
+    /// Thought summary, sent only when `includeThoughts` is requested. Listed before
+    /// `Text` so the untagged representation keeps the `thought` flag.
+    Thought {
+        text: String,
+        thought: bool,
+    },
     Text {
         text: String,
     },
@@ -67,6 +73,18 @@ pub enum Part {
     FunctionResponse {
         function_response: crate::gemini::function_calling::FunctionResponse,
     },
+    #[serde(rename_all = "camelCase")]
+    InlineData {
+        inline_data: InlineData,
+    },
+}
+
+/// Base64 encoded media sent inline with a request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineData {
+    pub mime_type: String,
+    pub data: String,
 }
 
 impl Part {
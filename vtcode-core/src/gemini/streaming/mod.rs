@@ -20,6 +20,15 @@ pub struct StreamingMetrics {
     pub retry_count: usize,
 }
 
+/// A piece of streamed model output handed to the chunk callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamChunk<'a> {
+    /// Response text
+    Text(&'a str),
+    /// Thought summary text
+    Thought(&'a str),
+}
+
 /// Streaming response structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -5,7 +5,7 @@
 
 use crate::gemini::models::{Content, Part};
 use crate::gemini::streaming::{
-    StreamingCandidate, StreamingError, StreamingMetrics, StreamingResponse,
+    StreamChunk, StreamingCandidate, StreamingError, StreamingMetrics, StreamingResponse,
 };
 use futures::stream::StreamExt;
 use reqwest::Response;
@@ -79,7 +79,7 @@ impl StreamingProcessor {
         mut on_chunk: F,
     ) -> Result<StreamingResponse, StreamingError>
     where
-        F: FnMut(&str) -> Result<(), StreamingError>,
+        F: FnMut(StreamChunk<'_>) -> Result<(), StreamingError>,
     {
         self.metrics.request_start_time = Some(Instant::now());
         self.metrics.total_requests += 1;
@@ -198,7 +198,7 @@ impl StreamingProcessor {
         on_chunk: &mut F,
     ) -> Result<bool, StreamingError>
     where
-        F: FnMut(&str) -> Result<(), StreamingError>,
+        F: FnMut(StreamChunk<'_>) -> Result<(), StreamingError>,
     {
         let mut _has_valid_content = false;
         let mut processed_chars = 0;
@@ -233,7 +233,7 @@ impl StreamingProcessor {
         on_chunk: &mut F,
     ) -> Result<bool, StreamingError>
     where
-        F: FnMut(&str) -> Result<(), StreamingError>,
+        F: FnMut(StreamChunk<'_>) -> Result<(), StreamingError>,
     {
         let mut _has_valid_content = false;
 
@@ -273,7 +273,7 @@ impl StreamingProcessor {
         on_chunk: &mut F,
     ) -> Result<bool, StreamingError>
     where
-        F: FnMut(&str) -> Result<(), StreamingError>,
+        F: FnMut(StreamChunk<'_>) -> Result<(), StreamingError>,
     {
         let mut _has_valid_content = false;
         let line = raw_line.trim_end_matches('\r');
@@ -358,7 +358,7 @@ impl StreamingProcessor {
         on_chunk: &mut F,
     ) -> Result<bool, StreamingError>
     where
-        F: FnMut(&str) -> Result<(), StreamingError>,
+        F: FnMut(StreamChunk<'_>) -> Result<(), StreamingError>,
     {
         if self.current_event_data.trim().is_empty() {
             self.current_event_data.clear();
@@ -375,7 +375,7 @@ impl StreamingProcessor {
         on_chunk: &mut F,
     ) -> Result<bool, StreamingError>
     where
-        F: FnMut(&str) -> Result<(), StreamingError>,
+        F: FnMut(StreamChunk<'_>) -> Result<(), StreamingError>,
     {
         let trimmed = self.current_event_data.trim();
         if trimmed.is_empty() {
@@ -407,7 +407,7 @@ impl StreamingProcessor {
         on_chunk: &mut F,
     ) -> Result<bool, StreamingError>
     where
-        F: FnMut(&str) -> Result<(), StreamingError>,
+        F: FnMut(StreamChunk<'_>) -> Result<(), StreamingError>,
     {
         let trimmed = event_data.trim();
 
@@ -467,16 +467,25 @@ impl StreamingProcessor {
         on_chunk: &mut F,
     ) -> Result<bool, StreamingError>
     where
-        F: FnMut(&str) -> Result<(), StreamingError>,
+        F: FnMut(StreamChunk<'_>) -> Result<(), StreamingError>,
     {
         let mut _has_valid_content = false;
 
         // Process each part of the content
         for part in &candidate.content.parts {
             match part {
-                Part::Text { text } => {
+                Part::Thought {
+                    text,
+                    thought: true,
+                } => {
                     if !text.trim().is_empty() {
-                        on_chunk(text)?;
+                        on_chunk(StreamChunk::Thought(text))?;
+                        _has_valid_content = true;
+                    }
+                }
+                Part::Text { text } | Part::Thought { text, .. } => {
+                    if !text.trim().is_empty() {
+                        on_chunk(StreamChunk::Text(text))?;
                         _has_valid_content = true;
                     }
                 }
@@ -484,7 +493,7 @@ impl StreamingProcessor {
                     // Function calls are handled separately in the tool execution flow
                     _has_valid_content = true;
                 }
-                Part::FunctionResponse { .. } => {
+                Part::FunctionResponse { .. } | Part::InlineData { .. } => {
                     _has_valid_content = true;
                 }
             }
@@ -500,7 +509,7 @@ impl StreamingProcessor {
         on_chunk: &mut F,
     ) -> Result<bool, StreamingError>
     where
-        F: FnMut(&str) -> Result<(), StreamingError>,
+        F: FnMut(StreamChunk<'_>) -> Result<(), StreamingError>,
     {
         match value {
             Value::Array(items) => {
@@ -556,7 +565,7 @@ impl StreamingProcessor {
                                 if let Some(text) = Self::extract_text_from_value(&candidate_value)
                                 {
                                     if !text.trim().is_empty() {
-                                        on_chunk(&text)?;
+                                        on_chunk(StreamChunk::Text(&text))?;
                                         self.append_text_candidate(accumulated_response, &text);
                                         has_valid = true;
                                     }
@@ -573,7 +582,7 @@ impl StreamingProcessor {
 
                 if let Some(text_value) = map.get("text").and_then(Value::as_str) {
                     if !text_value.trim().is_empty() {
-                        on_chunk(text_value)?;
+                        on_chunk(StreamChunk::Text(text_value))?;
                         self.append_text_candidate(accumulated_response, text_value);
                         has_valid = true;
                     }
@@ -585,7 +594,7 @@ impl StreamingProcessor {
                 if text.trim().is_empty() {
                     Ok(false)
                 } else {
-                    on_chunk(&text)?;
+                    on_chunk(StreamChunk::Text(&text))?;
                     self.append_text_candidate(accumulated_response, &text);
                     Ok(true)
                 }
@@ -631,7 +640,17 @@ impl StreamingProcessor {
 
         for part in source_parts {
             match (target.last_mut(), &part) {
-                (Some(Part::Text { text: existing }), Part::Text { text: new_text }) => {
+                (Some(Part::Text { text: existing }), Part::Text { text: new_text })
+                | (
+                    Some(Part::Thought {
+                        text: existing,
+                        thought: true,
+                    }),
+                    Part::Thought {
+                        text: new_text,
+                        thought: true,
+                    },
+                ) => {
                     existing.push_str(new_text);
                 }
                 _ => target.push(part),
@@ -753,8 +772,10 @@ mod tests {
         );
 
         {
-            let mut on_chunk = |chunk: &str| {
-                received_chunks.push(chunk.to_string());
+            let mut on_chunk = |chunk: StreamChunk<'_>| {
+                if let StreamChunk::Text(text) = chunk {
+                    received_chunks.push(text.to_string());
+                }
                 Ok(())
             };
             let has_valid = processor
@@ -771,4 +792,49 @@ mod tests {
         };
         assert_eq!(combined, "Hello world");
     }
+
+    #[test]
+    fn reports_thought_parts_separately_from_text() {
+        let mut processor = StreamingProcessor::new();
+        let mut accumulated = StreamingResponse {
+            candidates: Vec::new(),
+            usage_metadata: None,
+        };
+        let mut received: Vec<(bool, String)> = Vec::new();
+        let mut buffer = String::from(
+            "data: {\"candidates\":[{\"index\":0,\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\"Checking\",\"thought\":true}]}}]}\n",
+        );
+        buffer.push_str(
+            "data: {\"candidates\":[{\"index\":0,\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\" files\",\"thought\":true},{\"text\":\"Done\"}]}}]}\n",
+        );
+
+        {
+            let mut on_chunk = |chunk: StreamChunk<'_>| {
+                match chunk {
+                    StreamChunk::Thought(text) => received.push((true, text.to_string())),
+                    StreamChunk::Text(text) => received.push((false, text.to_string())),
+                }
+                Ok(())
+            };
+            processor
+                .process_buffer(&mut buffer, &mut accumulated, &mut on_chunk)
+                .expect("processing should succeed");
+        }
+
+        assert_eq!(
+            received,
+            vec![
+                (true, "Checking".to_string()),
+                (true, " files".to_string()),
+                (false, "Done".to_string()),
+            ]
+        );
+        let parts = &accumulated.candidates[0].content.parts;
+        assert_eq!(parts.len(), 2);
+        assert!(matches!(
+            &parts[0],
+            Part::Thought { text, thought: true } if text == "Checking files"
+        ));
+        assert!(matches!(&parts[1], Part::Text { text } if text == "Done"));
+    }
 }
@@ -133,9 +133,11 @@ pub mod gemini;
 pub mod llm;
 pub mod markdown_storage;
 pub mod mcp_client;
+pub mod mcp_interaction;
 pub mod models;
 pub mod project;
 pub mod project_doc;
+pub mod project_memory;
 pub mod prompts;
 pub mod safety;
 pub mod simple_indexer;
@@ -192,7 +192,8 @@ impl Default for LLMFactory {
 }
 
 /// Global factory instance
-use std::sync::{LazyLock, Mutex};
+use parking_lot::Mutex;
+use std::sync::LazyLock;
 
 static FACTORY: LazyLock<Mutex<LLMFactory>> = LazyLock::new(|| Mutex::new(LLMFactory::new()));
 
@@ -207,7 +208,7 @@ pub fn create_provider_for_model(
     api_key: String,
     prompt_cache: Option<PromptCachingConfig>,
 ) -> Result<Box<dyn LLMProvider>, LLMError> {
-    let factory = get_factory().lock().unwrap();
+    let factory = get_factory().lock();
     let provider_name = factory.provider_from_model(model).ok_or_else(|| {
         LLMError::InvalidRequest(format!("Cannot determine provider for model: {}", model))
     })?;
@@ -230,7 +231,7 @@ pub fn create_provider_with_config(
     model: Option<String>,
     prompt_cache: Option<PromptCachingConfig>,
 ) -> Result<Box<dyn LLMProvider>, LLMError> {
-    let factory = get_factory().lock().unwrap();
+    let factory = get_factory().lock();
     let config = ProviderConfig {
         api_key,
         base_url,
@@ -0,0 +1,691 @@
+//! Provider failover
+//!
+//! [`FallbackProvider`] wraps the session's provider and walks an ordered
+//! chain of alternatives when a request fails with a rate limit or network
+//! error. Each provider has its own circuit breaker so a provider that keeps
+//! failing is skipped until its cooldown elapses, after which it is probed
+//! again. History is normalised before every dispatch so tool call IDs and
+//! inline reasoning produced by one provider are accepted by the next.
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use super::factory::{create_provider_with_config, get_factory};
+use super::provider::{LLMError, LLMProvider, LLMRequest, LLMResponse, LLMStream, Message};
+use crate::config::FallbackConfig;
+use crate::config::api_keys::{ApiKeySources, get_api_key};
+use crate::config::core::PromptCachingConfig;
+
+/// Longest tool call ID accepted by every supported provider (OpenAI caps at 40)
+const MAX_TOOL_CALL_ID_LEN: usize = 40;
+/// Hex digits of the hash suffix used when shortening long tool call IDs
+const TOOL_CALL_ID_HASH_LEN: usize = 12;
+
+type ProviderFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, LLMError>> + Send + 'a>>;
+
+/// Provider in the failover chain with the model it should serve
+pub struct FallbackTarget {
+    pub provider: Box<dyn LLMProvider>,
+    pub model: String,
+}
+
+impl FallbackTarget {
+    pub fn new(provider: Box<dyn LLMProvider>, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+        }
+    }
+}
+
+/// Provider currently serving requests in place of the preferred one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallbackNotice {
+    pub provider: String,
+    pub model: String,
+    /// Error that caused the switch away from the preferred provider
+    pub reason: String,
+}
+
+/// Failover state shared with the UI so it can surface the active provider
+#[derive(Debug, Default)]
+pub struct FallbackStatus {
+    active: Mutex<Option<FallbackNotice>>,
+}
+
+impl FallbackStatus {
+    /// The fallback serving requests, or `None` while the preferred provider is healthy
+    pub fn active(&self) -> Option<FallbackNotice> {
+        self.active.lock().clone()
+    }
+
+    fn set(&self, notice: Option<FallbackNotice>) {
+        *self.active.lock() = notice;
+    }
+}
+
+/// Consecutive-failure circuit breaker
+#[derive(Debug)]
+struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            consecutive_failures: 0,
+            opened_at: None,
+        }
+    }
+
+    /// Closed circuits always allow requests; open ones allow a probe once cooled down
+    fn allows(&self, now: Instant) -> bool {
+        match self.opened_at {
+            None => true,
+            Some(opened_at) => now.duration_since(opened_at) >= self.cooldown,
+        }
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+    }
+
+    fn record_failure(&mut self, now: Instant) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.consecutive_failures >= self.failure_threshold {
+            self.opened_at = Some(now);
+        }
+    }
+}
+
+struct ChainMember {
+    target: FallbackTarget,
+    breaker: Mutex<CircuitBreaker>,
+}
+
+/// [`LLMProvider`] that fails over along a chain of providers
+pub struct FallbackProvider {
+    /// Preferred provider first, then fallbacks in configured order
+    chain: Vec<ChainMember>,
+    status: Arc<FallbackStatus>,
+}
+
+impl FallbackProvider {
+    /// Wrap `primary` (serving `primary_model`) with the given fallback targets
+    pub fn new(
+        primary: Box<dyn LLMProvider>,
+        primary_model: impl Into<String>,
+        fallbacks: Vec<FallbackTarget>,
+        config: &FallbackConfig,
+    ) -> Self {
+        let cooldown = Duration::from_secs(config.cooldown_secs);
+        let chain = std::iter::once(FallbackTarget::new(primary, primary_model))
+            .chain(fallbacks)
+            .map(|target| ChainMember {
+                target,
+                breaker: Mutex::new(CircuitBreaker::new(config.failure_threshold, cooldown)),
+            })
+            .collect();
+
+        Self {
+            chain,
+            status: Arc::new(FallbackStatus::default()),
+        }
+    }
+
+    /// Shared status handle for surfacing the active provider
+    pub fn status(&self) -> Arc<FallbackStatus> {
+        Arc::clone(&self.status)
+    }
+
+    fn primary(&self) -> &dyn LLMProvider {
+        self.chain[0].target.provider.as_ref()
+    }
+
+    /// Chain indices to try in order, skipping open circuits. When every
+    /// circuit is open the whole chain is tried rather than failing outright.
+    fn candidates(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let allowed: Vec<usize> = (0..self.chain.len())
+            .filter(|&index| self.chain[index].breaker.lock().allows(now))
+            .collect();
+        if allowed.is_empty() {
+            (0..self.chain.len()).collect()
+        } else {
+            allowed
+        }
+    }
+
+    /// Request adapted for the chain member at `index`. The preferred
+    /// provider keeps the caller's model so routing decisions still apply.
+    fn request_for(&self, index: usize, request: &LLMRequest) -> LLMRequest {
+        let target = &self.chain[index].target;
+        let mut adapted = request.clone();
+        if index > 0 {
+            adapted.model = target.model.clone();
+        }
+        if !target.provider.supports_reasoning_effort(&adapted.model) {
+            adapted.reasoning_effort = None;
+        }
+        // The preferred provider produced this history, so only fallbacks need it normalised
+        if index > 0 {
+            adapted.messages = translate_history(&adapted.messages);
+        }
+        adapted
+    }
+
+    fn record_success(&self, index: usize, reason: Option<&LLMError>) {
+        self.chain[index].breaker.lock().record_success();
+        if index == 0 {
+            self.status.set(None);
+            return;
+        }
+
+        let target = &self.chain[index].target;
+        let previous = self.status.active();
+        let reason = reason
+            .map(ToString::to_string)
+            .or_else(|| previous.map(|notice| notice.reason))
+            .unwrap_or_default();
+        self.status.set(Some(FallbackNotice {
+            provider: target.provider.name().to_string(),
+            model: target.model.clone(),
+            reason,
+        }));
+    }
+
+    fn record_failure(&self, index: usize, error: &LLMError) {
+        let member = &self.chain[index];
+        member.breaker.lock().record_failure(Instant::now());
+        warn!(
+            provider = member.target.provider.name(),
+            model = member.target.model.as_str(),
+            error = %error,
+            "Provider failed; trying next provider in fallback chain"
+        );
+    }
+
+    async fn dispatch<T, F>(&self, request: LLMRequest, call: F) -> Result<T, LLMError>
+    where
+        F: for<'a> Fn(&'a dyn LLMProvider, LLMRequest) -> ProviderFuture<'a, T>,
+    {
+        let mut first_error: Option<LLMError> = None;
+        let mut last_error: Option<LLMError> = None;
+
+        for index in self.candidates() {
+            let adapted = self.request_for(index, &request);
+            match call(self.chain[index].target.provider.as_ref(), adapted).await {
+                Ok(value) => {
+                    self.record_success(index, first_error.as_ref());
+                    return Ok(value);
+                }
+                Err(error) if is_failover_error(&error) => {
+                    self.record_failure(index, &error);
+                    if first_error.is_none() {
+                        first_error = Some(clone_error(&error));
+                    }
+                    last_error = Some(error);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            LLMError::Provider("No providers available in fallback chain".to_string())
+        }))
+    }
+}
+
+#[async_trait]
+impl LLMProvider for FallbackProvider {
+    fn name(&self) -> &str {
+        self.primary().name()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.primary().supports_streaming()
+    }
+
+    fn supports_reasoning(&self, model: &str) -> bool {
+        self.primary().supports_reasoning(model)
+    }
+
+    fn supports_reasoning_effort(&self, model: &str) -> bool {
+        self.primary().supports_reasoning_effort(model)
+    }
+
+    fn supports_tools(&self, model: &str) -> bool {
+        self.primary().supports_tools(model)
+    }
+
+    async fn generate(&self, request: LLMRequest) -> Result<LLMResponse, LLMError> {
+        self.dispatch(request, |provider, request| provider.generate(request))
+            .await
+    }
+
+    async fn stream(&self, request: LLMRequest) -> Result<LLMStream, LLMError> {
+        // Only failures before the first event can fail over; once tokens are
+        // rendered the stream cannot be replayed on another provider.
+        self.dispatch(request, |provider, request| provider.stream(request))
+            .await
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.chain
+            .iter()
+            .flat_map(|member| member.target.provider.supported_models())
+            .filter(|model| seen.insert(model.clone()))
+            .collect()
+    }
+
+    fn validate_request(&self, request: &LLMRequest) -> Result<(), LLMError> {
+        self.primary().validate_request(request)
+    }
+}
+
+/// Rate limits and transport failures are worth retrying elsewhere; request
+/// and authentication errors would fail the same way on every provider.
+pub fn is_failover_error(error: &LLMError) -> bool {
+    matches!(error, LLMError::RateLimit | LLMError::Network(_))
+}
+
+fn clone_error(error: &LLMError) -> LLMError {
+    match error {
+        LLMError::Authentication(msg) => LLMError::Authentication(msg.clone()),
+        LLMError::RateLimit => LLMError::RateLimit,
+        LLMError::InvalidRequest(msg) => LLMError::InvalidRequest(msg.clone()),
+        LLMError::Network(msg) => LLMError::Network(msg.clone()),
+        LLMError::Provider(msg) => LLMError::Provider(msg.clone()),
+    }
+}
+
+/// Normalise history produced by any provider so every provider accepts it
+pub fn translate_history(messages: &[Message]) -> Vec<Message> {
+    messages
+        .iter()
+        .map(|message| {
+            let mut message = message.clone();
+            if let Some(tool_calls) = message.tool_calls.as_mut() {
+                for call in tool_calls.iter_mut() {
+                    call.id = normalize_tool_call_id(&call.id);
+                }
+                message.content = strip_inline_reasoning(&message.content);
+            } else if message.role == super::provider::MessageRole::Assistant {
+                message.content = strip_inline_reasoning(&message.content);
+            }
+            if let Some(id) = message.tool_call_id.as_mut() {
+                *id = normalize_tool_call_id(id);
+            }
+            message
+        })
+        .collect()
+}
+
+/// Map a tool call ID onto `[A-Za-z0-9_-]{1,40}`. IDs that already conform
+/// are unchanged; others keep a sanitised prefix plus a hash of the original
+/// so IDs differing only in rejected characters stay distinct.
+pub fn normalize_tool_call_id(id: &str) -> String {
+    let valid_char = |ch: char| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-';
+    if !id.is_empty() && id.len() <= MAX_TOOL_CALL_ID_LEN && id.chars().all(valid_char) {
+        return id.to_string();
+    }
+
+    let prefix_len = MAX_TOOL_CALL_ID_LEN - TOOL_CALL_ID_HASH_LEN - 1;
+    let prefix: String = id
+        .chars()
+        .map(|ch| if valid_char(ch) { ch } else { '_' })
+        .take(prefix_len)
+        .collect();
+    let prefix = if prefix.is_empty() {
+        "call".to_string()
+    } else {
+        prefix
+    };
+    format!("{}_{:012x}", prefix, fnv1a(id) & 0xffff_ffff_ffff)
+}
+
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Remove `<think>…</think>` blocks that some models inline in assistant
+/// text; other providers would treat them as prior output.
+fn strip_inline_reasoning(content: &str) -> String {
+    const OPEN: &str = "<think>";
+    const CLOSE: &str = "</think>";
+
+    if !content.contains(OPEN) {
+        return content.to_string();
+    }
+
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find(OPEN) {
+        result.push_str(&rest[..start]);
+        match rest[start..].find(CLOSE) {
+            Some(end) => rest = &rest[start + end + CLOSE.len()..],
+            None => {
+                rest = "";
+                break;
+            }
+        }
+    }
+    result.push_str(rest);
+    result.trim().to_string()
+}
+
+/// Split a chain entry into provider and model. Entries are `provider:model`
+/// or a bare model ID whose provider is inferred. Model IDs may themselves
+/// contain `:` (e.g. `qwen/qwen3-coder:free`), so the prefix only counts as a
+/// provider when it names a registered one.
+pub fn parse_chain_entry(entry: &str) -> Result<(String, String), LLMError> {
+    let factory = get_factory().lock();
+    if let Some((prefix, model)) = entry.split_once(':') {
+        let provider = prefix.trim().to_lowercase();
+        if factory.list_providers().contains(&provider) && !model.trim().is_empty() {
+            return Ok((provider, model.trim().to_string()));
+        }
+    }
+
+    let model = entry.trim();
+    let provider = factory.provider_from_model(model).ok_or_else(|| {
+        LLMError::InvalidRequest(format!(
+            "Cannot determine provider for fallback entry '{}'; use provider:model",
+            entry
+        ))
+    })?;
+    Ok((provider, model.to_string()))
+}
+
+/// Instantiate the configured fallback targets. The entry for the active
+/// provider and model is skipped since it is already first in the chain;
+/// entries that cannot be resolved or lack an API key are skipped and
+/// reported as warnings.
+pub fn create_fallback_targets(
+    config: &FallbackConfig,
+    active_provider: &str,
+    active_model: &str,
+    custom_api_keys: &BTreeMap<String, String>,
+    prompt_cache: Option<PromptCachingConfig>,
+) -> (Vec<FallbackTarget>, Vec<String>) {
+    let mut targets = Vec::new();
+    let mut warnings = Vec::new();
+
+    for entry in &config.chain {
+        let (provider_name, model) = match parse_chain_entry(entry) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                warnings.push(err.to_string());
+                continue;
+            }
+        };
+        if provider_name.eq_ignore_ascii_case(active_provider) && model == active_model {
+            continue;
+        }
+
+        let api_key = match custom_api_keys.get(&provider_name) {
+            Some(key) if !key.trim().is_empty() => key.clone(),
+            _ => match get_api_key(&provider_name, &ApiKeySources::for_provider(&provider_name)) {
+                Ok(key) => key,
+                Err(err) => {
+                    warnings.push(format!("Skipping fallback '{}': {}", entry, err));
+                    continue;
+                }
+            },
+        };
+
+        match create_provider_with_config(
+            &provider_name,
+            Some(api_key),
+            None,
+            Some(model.clone()),
+            prompt_cache.clone(),
+        ) {
+            Ok(provider) => targets.push(FallbackTarget::new(provider, model)),
+            Err(err) => warnings.push(format!("Skipping fallback '{}': {}", entry, err)),
+        }
+    }
+
+    (targets, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::provider::{FinishReason, ToolCall};
+
+    struct ScriptedProvider {
+        name: &'static str,
+        outcomes: Mutex<Vec<Result<&'static str, LLMError>>>,
+        requests: Arc<Mutex<Vec<LLMRequest>>>,
+    }
+
+    impl ScriptedProvider {
+        fn boxed(
+            name: &'static str,
+            outcomes: Vec<Result<&'static str, LLMError>>,
+        ) -> (Box<dyn LLMProvider>, Arc<Mutex<Vec<LLMRequest>>>) {
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let provider = Self {
+                name,
+                outcomes: Mutex::new(outcomes.into_iter().rev().collect()),
+                requests: Arc::clone(&requests),
+            };
+            (Box::new(provider), requests)
+        }
+    }
+
+    #[async_trait]
+    impl LLMProvider for ScriptedProvider {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn generate(&self, request: LLMRequest) -> Result<LLMResponse, LLMError> {
+            self.requests.lock().push(request);
+            let outcome = self.outcomes.lock().pop().unwrap_or(Ok("ok"));
+            outcome.map(|text| LLMResponse {
+                content: Some(text.to_string()),
+                tool_calls: None,
+                usage: None,
+                finish_reason: FinishReason::Stop,
+                reasoning: None,
+            })
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            vec![self.name.to_string()]
+        }
+
+        fn validate_request(&self, _request: &LLMRequest) -> Result<(), LLMError> {
+            Ok(())
+        }
+    }
+
+    fn request() -> LLMRequest {
+        LLMRequest {
+            messages: vec![Message::user("hello".to_string())],
+            system_prompt: None,
+            tools: None,
+            model: "primary-model".to_string(),
+            max_tokens: None,
+            temperature: None,
+            stream: false,
+            tool_choice: None,
+            parallel_tool_calls: None,
+            parallel_tool_config: None,
+            reasoning_effort: None,
+            response_format: None,
+        }
+    }
+
+    fn config(failure_threshold: u32) -> FallbackConfig {
+        FallbackConfig {
+            chain: vec!["unused".to_string()],
+            failure_threshold,
+            cooldown_secs: 3600,
+            ..FallbackConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn fails_over_on_rate_limit_and_reports_status() {
+        let (primary, primary_requests) =
+            ScriptedProvider::boxed("anthropic", vec![Err(LLMError::RateLimit)]);
+        let (backup, backup_requests) =
+            ScriptedProvider::boxed("openrouter", vec![Ok("from backup")]);
+        let provider = FallbackProvider::new(
+            primary,
+            "primary-model",
+            vec![FallbackTarget::new(backup, "anthropic/claude-sonnet-4.5")],
+            &config(2),
+        );
+
+        let response = provider.generate(request()).await.unwrap();
+        assert_eq!(response.content.as_deref(), Some("from backup"));
+        assert_eq!(primary_requests.lock()[0].model, "primary-model");
+        assert_eq!(
+            backup_requests.lock()[0].model,
+            "anthropic/claude-sonnet-4.5"
+        );
+
+        let notice = provider.status().active().expect("fallback active");
+        assert_eq!(notice.provider, "openrouter");
+        assert_eq!(notice.reason, "Rate limit exceeded");
+
+        // Primary recovers: status clears
+        provider.generate(request()).await.unwrap();
+        assert!(provider.status().active().is_none());
+    }
+
+    #[tokio::test]
+    async fn open_circuit_skips_primary_and_other_errors_do_not_fail_over() {
+        let (primary, primary_requests) = ScriptedProvider::boxed(
+            "anthropic",
+            vec![
+                Err(LLMError::Network("connection reset".to_string())),
+                Err(LLMError::InvalidRequest("bad".to_string())),
+            ],
+        );
+        let (backup, backup_requests) = ScriptedProvider::boxed("gemini", vec![]);
+        let provider = FallbackProvider::new(
+            primary,
+            "primary-model",
+            vec![FallbackTarget::new(backup, "gemini-2.5-pro")],
+            &config(1),
+        );
+
+        provider.generate(request()).await.unwrap();
+        provider.generate(request()).await.unwrap();
+        assert_eq!(primary_requests.lock().len(), 1);
+        assert_eq!(backup_requests.lock().len(), 2);
+
+        let provider = FallbackProvider::new(
+            ScriptedProvider::boxed(
+                "anthropic",
+                vec![Err(LLMError::InvalidRequest("bad".to_string()))],
+            )
+            .0,
+            "primary-model",
+            vec![FallbackTarget::new(
+                ScriptedProvider::boxed("gemini", vec![]).0,
+                "gemini-2.5-pro",
+            )],
+            &config(1),
+        );
+        assert!(matches!(
+            provider.generate(request()).await,
+            Err(LLMError::InvalidRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn only_fallbacks_receive_translated_history() {
+        let (primary, primary_requests) =
+            ScriptedProvider::boxed("anthropic", vec![Err(LLMError::RateLimit)]);
+        let (backup, backup_requests) = ScriptedProvider::boxed("gemini", vec![]);
+        let (duplicate, _) = ScriptedProvider::boxed("anthropic", vec![]);
+        let provider = FallbackProvider::new(
+            primary,
+            "primary-model",
+            vec![
+                FallbackTarget::new(backup, "gemini-2.5-pro"),
+                FallbackTarget::new(duplicate, "claude-sonnet-4-5"),
+            ],
+            &config(1),
+        );
+        let mut request = request();
+        request
+            .messages
+            .push(Message::assistant("<think>plan</think>Done".to_string()));
+
+        provider.generate(request).await.unwrap();
+        assert_eq!(
+            primary_requests.lock()[0].messages[1].content,
+            "<think>plan</think>Done"
+        );
+        assert_eq!(backup_requests.lock()[0].messages[1].content, "Done");
+        assert_eq!(provider.supported_models(), vec!["anthropic", "gemini"]);
+    }
+
+    #[test]
+    fn history_translation_normalizes_ids_and_strips_reasoning() {
+        let long_id = "toolu_01".to_string() + &"x".repeat(60);
+        let history = vec![
+            Message::assistant_with_tools(
+                "<think>plan the call</think>Checking files".to_string(),
+                vec![ToolCall::function(
+                    long_id.clone(),
+                    "list_files".to_string(),
+                    "{}".to_string(),
+                )],
+            ),
+            Message::tool_response(long_id.clone(), "[]".to_string()),
+            Message::assistant("<think>unterminated".to_string()),
+        ];
+
+        let translated = translate_history(&history);
+        let call_id = &translated[0].tool_calls.as_ref().unwrap()[0].id;
+        assert!(call_id.len() <= MAX_TOOL_CALL_ID_LEN);
+        assert_eq!(translated[1].tool_call_id.as_ref(), Some(call_id));
+        assert_eq!(translated[0].content, "Checking files");
+        assert_eq!(translated[2].content, "");
+
+        assert_eq!(normalize_tool_call_id("call_abc-123"), "call_abc-123");
+        assert_ne!(
+            normalize_tool_call_id("call.a"),
+            normalize_tool_call_id("call:a")
+        );
+    }
+
+    #[test]
+    fn chain_entries_resolve_provider_prefix_or_model() {
+        assert_eq!(
+            parse_chain_entry("openrouter:qwen/qwen3-coder:free").unwrap(),
+            (
+                "openrouter".to_string(),
+                "qwen/qwen3-coder:free".to_string()
+            )
+        );
+        assert_eq!(
+            parse_chain_entry("gemini-2.5-pro").unwrap(),
+            ("gemini".to_string(), "gemini-2.5-pro".to_string())
+        );
+        assert!(parse_chain_entry("mystery").is_err());
+    }
+}
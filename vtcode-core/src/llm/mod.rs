@@ -164,9 +164,11 @@
 pub mod client;
 pub mod error_display;
 pub mod factory;
+pub mod fallback;
 pub mod provider;
 pub mod providers;
 pub mod rig_adapter;
+pub mod structured;
 pub mod types;
 
 #[cfg(test)]
@@ -46,10 +46,14 @@
 //! tool_response.validate_for_provider("openai").unwrap();
 //! ```
 
+use anyhow::Context;
 use async_stream::try_stream;
 use async_trait::async_trait;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use std::path::Path;
 use std::pin::Pin;
 
 use crate::config::types::ReasoningEffortLevel;
@@ -78,6 +82,10 @@ pub struct LLMRequest {
     /// Reasoning effort level for models that support it (low, medium, high)
     /// Applies to: Claude, GPT-5, Gemini, Qwen3, DeepSeek with reasoning capability
     pub reasoning_effort: Option<ReasoningEffortLevel>,
+
+    /// Constrain the final answer to JSON matching a schema
+    /// Mapped to each provider's native structured output mechanism
+    pub response_format: Option<JsonSchema>,
 }
 
 /// Tool choice configuration that works across different providers
@@ -202,6 +210,125 @@ impl ToolChoice {
     }
 }
 
+/// Provider-agnostic structured output specification
+/// OpenAI and OpenRouter use `response_format`, Gemini uses `responseSchema`,
+/// Anthropic forces a synthetic tool whose input is the structured answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonSchema {
+    /// Identifier sent to providers that require a schema name
+    pub name: String,
+    /// JSON Schema describing the expected response
+    pub schema: Value,
+    /// Request strict schema adherence where the provider supports it
+    pub strict: bool,
+}
+
+impl JsonSchema {
+    /// Create a schema, normalizing the name to the `[a-zA-Z0-9_-]{1,64}`
+    /// form accepted by every provider
+    pub fn new(name: impl AsRef<str>, schema: Value) -> Self {
+        let mut normalized: String = name
+            .as_ref()
+            .chars()
+            .map(|ch| {
+                if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
+                    ch
+                } else {
+                    '_'
+                }
+            })
+            .take(64)
+            .collect();
+        if normalized.is_empty() {
+            normalized.push_str("structured_response");
+        }
+        Self {
+            name: normalized,
+            schema,
+            strict: false,
+        }
+    }
+
+    /// Request strict schema adherence
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Instruction appended to the system prompt for providers that only
+    /// support free-form JSON output
+    pub fn instruction(&self) -> String {
+        format!(
+            "Respond only with a JSON value that conforms to this JSON Schema, without prose or code fences:\n{}",
+            serde_json::to_string_pretty(&self.schema).unwrap_or_else(|_| self.schema.to_string())
+        )
+    }
+
+    /// Convert to provider-specific format
+    pub fn to_provider_format(&self, provider: &str) -> Value {
+        match provider {
+            "anthropic" => json!({
+                "name": self.name,
+                "description": "Return the final answer as structured data matching this schema.",
+                "input_schema": self.schema,
+            }),
+            "gemini" => gemini_compatible_schema(&self.schema),
+            "deepseek" => json!({"type": "json_object"}),
+            // Responses API nests the format under `text.format`
+            "openai-responses" => json!({
+                "type": "json_schema",
+                "name": self.name,
+                "schema": self.schema,
+                "strict": self.strict,
+            }),
+            // OpenAI chat completions format, also used by OpenRouter and xAI
+            _ => json!({
+                "type": "json_schema",
+                "json_schema": {
+                    "name": self.name,
+                    "schema": self.schema,
+                    "strict": self.strict,
+                }
+            }),
+        }
+    }
+}
+
+/// Gemini accepts an OpenAPI subset and rejects JSON Schema meta keywords
+fn gemini_compatible_schema(schema: &Value) -> Value {
+    const UNSUPPORTED: &[&str] = &[
+        "$schema",
+        "$id",
+        "additionalProperties",
+        "$defs",
+        "definitions",
+    ];
+    match schema {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(key, _)| !UNSUPPORTED.contains(&key.as_str()))
+                .map(|(key, value)| {
+                    // Property names are user data, only their schemas need cleaning
+                    let cleaned = match (key.as_str(), value) {
+                        ("properties", Value::Object(properties)) => Value::Object(
+                            properties
+                                .iter()
+                                .map(|(name, property)| {
+                                    (name.clone(), gemini_compatible_schema(property))
+                                })
+                                .collect(),
+                        ),
+                        _ => gemini_compatible_schema(value),
+                    };
+                    (key.clone(), cleaned)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(gemini_compatible_schema).collect()),
+        other => other.clone(),
+    }
+}
+
 /// Configuration for parallel tool use behavior
 /// Based on Anthropic's parallel tool use guidelines
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -255,6 +382,159 @@ pub struct Message {
     pub content: String,
     pub tool_calls: Option<Vec<ToolCall>>,
     pub tool_call_id: Option<String>,
+    /// Images and documents sent alongside the text content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+}
+
+/// Kind of non-text content carried by an [`Attachment`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentKind {
+    Image,
+    Document,
+}
+
+/// Binary content attached to a message, serialized per provider as image or
+/// document parts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub kind: AttachmentKind,
+    /// IANA media type such as `image/png` or `application/pdf`
+    pub media_type: String,
+    /// Base64 encoded payload
+    pub data: String,
+    /// Original file name or URI, when known
+    pub name: Option<String>,
+}
+
+impl Attachment {
+    /// Largest file accepted as an attachment; providers reject bigger payloads
+    pub const MAX_BYTES: u64 = 20 * 1024 * 1024;
+
+    /// Create an image attachment from base64 data
+    pub fn image(media_type: impl Into<String>, data: impl Into<String>) -> Self {
+        Self {
+            kind: AttachmentKind::Image,
+            media_type: media_type.into(),
+            data: data.into(),
+            name: None,
+        }
+    }
+
+    /// Create a document attachment from base64 data
+    pub fn document(
+        media_type: impl Into<String>,
+        data: impl Into<String>,
+        name: Option<String>,
+    ) -> Self {
+        Self {
+            kind: AttachmentKind::Document,
+            media_type: media_type.into(),
+            data: data.into(),
+            name,
+        }
+    }
+
+    /// Create an attachment from a media type, choosing the kind automatically
+    pub fn from_media_type(media_type: &str, data: impl Into<String>) -> Option<Self> {
+        match Self::kind_for_media_type(media_type)? {
+            AttachmentKind::Image => Some(Self::image(media_type, data)),
+            AttachmentKind::Document => Some(Self::document(media_type, data, None)),
+        }
+    }
+
+    /// Media type for a path whose extension denotes attachable content
+    pub fn media_type_for_path(path: &Path) -> Option<&'static str> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Some("image/png"),
+            "jpg" | "jpeg" => Some("image/jpeg"),
+            "gif" => Some("image/gif"),
+            "webp" => Some("image/webp"),
+            "pdf" => Some("application/pdf"),
+            _ => None,
+        }
+    }
+
+    /// Whether the provider-facing attachment kinds support this media type
+    pub fn kind_for_media_type(media_type: &str) -> Option<AttachmentKind> {
+        match media_type {
+            "image/png" | "image/jpeg" | "image/gif" | "image/webp" => Some(AttachmentKind::Image),
+            "application/pdf" => Some(AttachmentKind::Document),
+            _ => None,
+        }
+    }
+
+    /// Load an image or PDF from disk
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let media_type = Self::media_type_for_path(path).ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} is not a supported attachment (png, jpeg, gif, webp or pdf)",
+                path.display()
+            )
+        })?;
+        let metadata = std::fs::metadata(path)
+            .with_context(|| format!("Failed to read attachment {}", path.display()))?;
+        if metadata.len() > Self::MAX_BYTES {
+            anyhow::bail!(
+                "{} is {} bytes, larger than the {} byte attachment limit",
+                path.display(),
+                metadata.len(),
+                Self::MAX_BYTES
+            );
+        }
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read attachment {}", path.display()))?;
+        let mut attachment = Self::from_media_type(media_type, BASE64_STANDARD.encode(bytes))
+            .ok_or_else(|| anyhow::anyhow!("Unsupported attachment media type {}", media_type))?;
+        attachment.name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+        Ok(attachment)
+    }
+
+    /// `data:` URL form used by OpenAI-compatible APIs
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.media_type, self.data)
+    }
+
+    /// File name reported to providers that require one for documents
+    pub fn file_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("attachment.pdf")
+    }
+
+    /// Convert to provider-specific content part format
+    pub fn to_provider_format(&self, provider: &str) -> Value {
+        match (self.kind, provider) {
+            (AttachmentKind::Image, "anthropic") => json!({
+                "type": "image",
+                "source": {"type": "base64", "media_type": self.media_type, "data": self.data}
+            }),
+            (AttachmentKind::Document, "anthropic") => json!({
+                "type": "document",
+                "source": {"type": "base64", "media_type": self.media_type, "data": self.data}
+            }),
+            (AttachmentKind::Image, "openai-responses") => json!({
+                "type": "input_image",
+                "image_url": self.data_url()
+            }),
+            (AttachmentKind::Document, "openai-responses") => json!({
+                "type": "input_file",
+                "filename": self.file_name(),
+                "file_data": self.data_url()
+            }),
+            // OpenAI chat completions format, also used by OpenRouter
+            (AttachmentKind::Image, _) => json!({
+                "type": "image_url",
+                "image_url": {"url": self.data_url()}
+            }),
+            (AttachmentKind::Document, _) => json!({
+                "type": "file",
+                "file": {"filename": self.file_name(), "file_data": self.data_url()}
+            }),
+        }
+    }
 }
 
 impl Message {
@@ -265,7 +545,37 @@ impl Message {
             content,
             tool_calls: None,
             tool_call_id: None,
+            attachments: Vec::new(),
+        }
+    }
+
+    /// Create a user message carrying images or documents
+    pub fn user_with_attachments(content: String, attachments: Vec<Attachment>) -> Self {
+        Self {
+            attachments,
+            ..Self::user(content)
+        }
+    }
+
+    /// Whether the message carries images or documents
+    pub fn has_attachments(&self) -> bool {
+        !self.attachments.is_empty()
+    }
+
+    /// Content as an array of provider-specific parts: the text (if any)
+    /// followed by each attachment. `text_type` is the provider's text part
+    /// type, e.g. `text` or `input_text`.
+    pub fn content_parts(&self, provider: &str, text_type: &str) -> Vec<Value> {
+        let mut parts = Vec::with_capacity(self.attachments.len() + 1);
+        if !self.content.is_empty() {
+            parts.push(json!({"type": text_type, "text": self.content}));
         }
+        parts.extend(
+            self.attachments
+                .iter()
+                .map(|attachment| attachment.to_provider_format(provider)),
+        );
+        parts
     }
 
     /// Create an assistant message
@@ -275,6 +585,7 @@ impl Message {
             content,
             tool_calls: None,
             tool_call_id: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -286,6 +597,7 @@ impl Message {
             content,
             tool_calls: Some(tool_calls),
             tool_call_id: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -296,6 +608,7 @@ impl Message {
             content,
             tool_calls: None,
             tool_call_id: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -314,6 +627,7 @@ impl Message {
             content,
             tool_calls: None,
             tool_call_id: Some(tool_call_id),
+            attachments: Vec::new(),
         }
     }
 
@@ -5,16 +5,20 @@ use crate::config::types::ReasoningEffortLevel;
 use crate::llm::client::LLMClient;
 use crate::llm::error_display;
 use crate::llm::provider::{
-    FinishReason, LLMError, LLMProvider, LLMRequest, LLMResponse, Message, MessageRole,
-    ParallelToolConfig, ToolCall, ToolChoice, ToolDefinition,
+    FinishReason, LLMError, LLMProvider, LLMRequest, LLMResponse, LLMStream, LLMStreamEvent,
+    Message, MessageRole, ParallelToolConfig, ToolCall, ToolChoice, ToolDefinition,
 };
 use crate::llm::rig_adapter::reasoning_parameters_for;
 use crate::llm::types as llm_types;
+use async_stream::try_stream;
 use async_trait::async_trait;
+use futures::StreamExt;
 use reqwest::Client as HttpClient;
-use serde_json::{Value, json};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
 
 use super::extract_reasoning_trace;
+use super::sse::{extract_data_payload, find_sse_boundary};
 
 pub struct AnthropicProvider {
     api_key: String,
@@ -130,6 +134,7 @@ impl AnthropicProvider {
             parallel_tool_calls: None,
             parallel_tool_config: None,
             reasoning_effort: None,
+            response_format: None,
         }
     }
 
@@ -357,6 +362,7 @@ impl AnthropicProvider {
             parallel_tool_calls,
             parallel_tool_config,
             reasoning_effort,
+            response_format: None,
         })
     }
 
@@ -550,20 +556,30 @@ impl AnthropicProvider {
                     }
                 }
                 _ => {
-                    if msg.content.is_empty() {
+                    if msg.content.is_empty() && !msg.has_attachments() {
                         continue;
                     }
 
-                    let mut block = json!({
-                        "type": "text",
-                        "text": msg.content
-                    });
+                    // Anthropic recommends placing images and documents before the text
+                    let mut blocks: Vec<Value> = msg
+                        .attachments
+                        .iter()
+                        .map(|attachment| attachment.to_provider_format("anthropic"))
+                        .collect();
+                    if !msg.content.is_empty() {
+                        blocks.push(json!({
+                            "type": "text",
+                            "text": msg.content
+                        }));
+                    }
 
                     if msg.role == MessageRole::User
                         && self.prompt_cache_settings.cache_user_messages
                         && breakpoints_remaining > 0
                     {
-                        if let Some(cache_control) = cache_control_template.as_ref() {
+                        if let (Some(cache_control), Some(block)) =
+                            (cache_control_template.as_ref(), blocks.last_mut())
+                        {
                             block["cache_control"] = cache_control.clone();
                             breakpoints_remaining -= 1;
                         }
@@ -571,7 +587,7 @@ impl AnthropicProvider {
 
                     messages.push(json!({
                         "role": msg.role.as_anthropic_str(),
-                        "content": [block]
+                        "content": blocks
                     }));
                 }
             }
@@ -602,11 +618,21 @@ impl AnthropicProvider {
             anthropic_request["temperature"] = json!(temperature);
         }
 
+        // Anthropic has no response format parameter; structured output is
+        // obtained by forcing a tool whose input schema is the requested one.
+        if let Some(format) = &request.response_format {
+            tools_json
+                .get_or_insert_with(Vec::new)
+                .push(format.to_provider_format("anthropic"));
+        }
+
         if let Some(tools) = tools_json {
             anthropic_request["tools"] = Value::Array(tools);
         }
 
-        if let Some(tool_choice) = &request.tool_choice {
+        if let Some(format) = &request.response_format {
+            anthropic_request["tool_choice"] = json!({"type": "tool", "name": format.name});
+        } else if let Some(tool_choice) = &request.tool_choice {
             anthropic_request["tool_choice"] = tool_choice.to_provider_format("anthropic");
         }
 
@@ -623,7 +649,71 @@ impl AnthropicProvider {
         Ok(anthropic_request)
     }
 
-    fn parse_anthropic_response(&self, response_json: Value) -> Result<LLMResponse, LLMError> {
+    /// Surface the forced structured output tool call as the response text
+    fn unwrap_structured_response(mut response: LLMResponse, schema_name: &str) -> LLMResponse {
+        if let Some(calls) = response.tool_calls.take() {
+            let (structured, remaining): (Vec<_>, Vec<_>) = calls
+                .into_iter()
+                .partition(|call| call.function.name == schema_name);
+            if let Some(call) = structured.into_iter().next() {
+                response.content = Some(call.function.arguments);
+                response.finish_reason = FinishReason::Stop;
+            }
+            response.tool_calls = (!remaining.is_empty()).then_some(remaining);
+        }
+        response
+    }
+
+    async fn send_messages_request(
+        &self,
+        anthropic_request: &Value,
+    ) -> Result<reqwest::Response, LLMError> {
+        let url = format!("{}/messages", self.base_url);
+
+        let mut request_builder = self
+            .http_client
+            .post(&url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", urls::ANTHROPIC_API_VERSION);
+
+        if let Some(beta_header) = self.prompt_cache_beta_header_value() {
+            request_builder = request_builder.header("anthropic-beta", beta_header);
+        }
+
+        let response = request_builder
+            .json(anthropic_request)
+            .send()
+            .await
+            .map_err(|e| {
+                let formatted_error =
+                    error_display::format_llm_error("Anthropic", &format!("Network error: {}", e));
+                LLMError::Network(formatted_error)
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+
+            // Handle specific HTTP status codes
+            if status.as_u16() == 429
+                || error_text.contains("insufficient_quota")
+                || error_text.contains("quota")
+                || error_text.contains("rate limit")
+            {
+                return Err(LLMError::RateLimit);
+            }
+
+            let formatted_error = error_display::format_llm_error(
+                "Anthropic",
+                &format!("HTTP {}: {}", status, error_text),
+            );
+            return Err(LLMError::Provider(formatted_error));
+        }
+
+        Ok(response)
+    }
+
+    fn parse_anthropic_response(response_json: Value) -> Result<LLMResponse, LLMError> {
         let content = response_json
             .get("content")
             .and_then(|c| c.as_array())
@@ -752,12 +842,160 @@ impl AnthropicProvider {
     }
 }
 
+/// Rebuilds a Messages API response from its stream events, surfacing text and
+/// thinking deltas as they arrive.
+#[derive(Default)]
+struct AnthropicStreamState {
+    message: Map<String, Value>,
+    blocks: Vec<Value>,
+    tool_inputs: HashMap<usize, String>,
+}
+
+impl AnthropicStreamState {
+    fn apply_sse_event(&mut self, event: &str) -> Result<Option<LLMStreamEvent>, LLMError> {
+        let Some(payload) = extract_data_payload(event) else {
+            return Ok(None);
+        };
+        let payload = payload.trim();
+        if payload.is_empty() {
+            return Ok(None);
+        }
+        let value: Value = serde_json::from_str(payload).map_err(|err| {
+            let formatted_error = error_display::format_llm_error(
+                "Anthropic",
+                &format!("Failed to parse stream payload: {}", err),
+            );
+            LLMError::Provider(formatted_error)
+        })?;
+        self.apply(&value)
+    }
+
+    fn apply(&mut self, event: &Value) -> Result<Option<LLMStreamEvent>, LLMError> {
+        let index = event
+            .get("index")
+            .and_then(Value::as_u64)
+            .map(|index| index as usize);
+
+        match event.get("type").and_then(Value::as_str) {
+            Some("message_start") => {
+                if let Some(Value::Object(message)) = event.get("message") {
+                    self.message = message.clone();
+                }
+            }
+            Some("content_block_start") => {
+                if let (Some(index), Some(block)) = (index, event.get("content_block")) {
+                    if self.blocks.len() <= index {
+                        self.blocks.resize(index + 1, Value::Null);
+                    }
+                    self.blocks[index] = block.clone();
+                }
+            }
+            Some("content_block_delta") => {
+                let (Some(index), Some(delta)) = (index, event.get("delta")) else {
+                    return Ok(None);
+                };
+                let Some(block) = self.blocks.get_mut(index) else {
+                    return Ok(None);
+                };
+                match delta.get("type").and_then(Value::as_str) {
+                    Some("text_delta") => {
+                        if let Some(text) = delta.get("text").and_then(Value::as_str) {
+                            append_block_text(block, "text", text);
+                            return Ok(Some(LLMStreamEvent::Token {
+                                delta: text.to_string(),
+                            }));
+                        }
+                    }
+                    Some("thinking_delta") => {
+                        if let Some(thinking) = delta.get("thinking").and_then(Value::as_str) {
+                            append_block_text(block, "thinking", thinking);
+                            return Ok(Some(LLMStreamEvent::Reasoning {
+                                delta: thinking.to_string(),
+                            }));
+                        }
+                    }
+                    Some("input_json_delta") => {
+                        if let Some(partial) = delta.get("partial_json").and_then(Value::as_str) {
+                            self.tool_inputs.entry(index).or_default().push_str(partial);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Some("content_block_stop") => {
+                let Some(index) = index else {
+                    return Ok(None);
+                };
+                if let (Some(input), Some(block)) =
+                    (self.tool_inputs.remove(&index), self.blocks.get_mut(index))
+                {
+                    block["input"] = if input.trim().is_empty() {
+                        json!({})
+                    } else {
+                        serde_json::from_str(&input).map_err(|err| {
+                            let formatted_error = error_display::format_llm_error(
+                                "Anthropic",
+                                &format!("Failed to parse streamed tool input: {}", err),
+                            );
+                            LLMError::Provider(formatted_error)
+                        })?
+                    };
+                }
+            }
+            Some("message_delta") => {
+                if let Some(stop_reason) = event
+                    .get("delta")
+                    .and_then(|delta| delta.get("stop_reason"))
+                {
+                    self.message
+                        .insert("stop_reason".to_string(), stop_reason.clone());
+                }
+                if let Some(Value::Object(usage)) = event.get("usage") {
+                    let target = self.message.entry("usage").or_insert_with(|| json!({}));
+                    for (key, value) in usage {
+                        target[key] = value.clone();
+                    }
+                }
+            }
+            Some("error") => {
+                let message = event
+                    .get("error")
+                    .and_then(|error| error.get("message"))
+                    .and_then(Value::as_str)
+                    .unwrap_or("Unknown streaming error");
+                let formatted_error = error_display::format_llm_error("Anthropic", message);
+                return Err(LLMError::Provider(formatted_error));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn into_message(mut self) -> Value {
+        let blocks = self.blocks.into_iter().filter(|block| !block.is_null());
+        self.message
+            .insert("content".to_string(), Value::Array(blocks.collect()));
+        Value::Object(self.message)
+    }
+}
+
+fn append_block_text(block: &mut Value, key: &str, text: &str) {
+    match block.get_mut(key) {
+        Some(Value::String(existing)) => existing.push_str(text),
+        _ => block[key] = Value::String(text.to_string()),
+    }
+}
+
 #[async_trait]
 impl LLMProvider for AnthropicProvider {
     fn name(&self) -> &str {
         "anthropic"
     }
 
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
     fn supports_reasoning(&self, _model: &str) -> bool {
         false
     }
@@ -775,47 +1013,7 @@ impl LLMProvider for AnthropicProvider {
 
     async fn generate(&self, request: LLMRequest) -> Result<LLMResponse, LLMError> {
         let anthropic_request = self.convert_to_anthropic_format(&request)?;
-        let url = format!("{}/messages", self.base_url);
-
-        let mut request_builder = self
-            .http_client
-            .post(&url)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", urls::ANTHROPIC_API_VERSION);
-
-        if let Some(beta_header) = self.prompt_cache_beta_header_value() {
-            request_builder = request_builder.header("anthropic-beta", beta_header);
-        }
-
-        let response = request_builder
-            .json(&anthropic_request)
-            .send()
-            .await
-            .map_err(|e| {
-                let formatted_error =
-                    error_display::format_llm_error("Anthropic", &format!("Network error: {}", e));
-                LLMError::Network(formatted_error)
-            })?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-
-            // Handle specific HTTP status codes
-            if status.as_u16() == 429
-                || error_text.contains("insufficient_quota")
-                || error_text.contains("quota")
-                || error_text.contains("rate limit")
-            {
-                return Err(LLMError::RateLimit);
-            }
-
-            let formatted_error = error_display::format_llm_error(
-                "Anthropic",
-                &format!("HTTP {}: {}", status, error_text),
-            );
-            return Err(LLMError::Provider(formatted_error));
-        }
+        let response = self.send_messages_request(&anthropic_request).await?;
 
         let anthropic_response: Value = response.json().await.map_err(|e| {
             let formatted_error = error_display::format_llm_error(
@@ -825,7 +1023,57 @@ impl LLMProvider for AnthropicProvider {
             LLMError::Provider(formatted_error)
         })?;
 
-        self.parse_anthropic_response(anthropic_response)
+        let response = Self::parse_anthropic_response(anthropic_response)?;
+        Ok(match &request.response_format {
+            Some(format) => Self::unwrap_structured_response(response, &format.name),
+            None => response,
+        })
+    }
+
+    async fn stream(&self, mut request: LLMRequest) -> Result<LLMStream, LLMError> {
+        request.stream = true;
+        let anthropic_request = self.convert_to_anthropic_format(&request)?;
+        let response = self.send_messages_request(&anthropic_request).await?;
+        let structured_output = request.response_format.map(|format| format.name);
+
+        let stream = try_stream! {
+            let mut body_stream = response.bytes_stream();
+            let mut buffer = String::new();
+            let mut state = AnthropicStreamState::default();
+
+            while let Some(chunk_result) = body_stream.next().await {
+                let chunk = chunk_result.map_err(|err| {
+                    let formatted_error = error_display::format_llm_error(
+                        "Anthropic",
+                        &format!("Streaming error: {}", err),
+                    );
+                    LLMError::Network(formatted_error)
+                })?;
+
+                buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+                while let Some((split_idx, delimiter_len)) = find_sse_boundary(&buffer) {
+                    let event = buffer[..split_idx].to_string();
+                    buffer.drain(..split_idx + delimiter_len);
+                    if let Some(delta) = state.apply_sse_event(&event)? {
+                        yield delta;
+                    }
+                }
+            }
+
+            if let Some(delta) = state.apply_sse_event(&buffer)? {
+                yield delta;
+            }
+
+            let response = Self::parse_anthropic_response(state.into_message())?;
+            let response = match &structured_output {
+                Some(name) => Self::unwrap_structured_response(response, name),
+                None => response,
+            };
+            yield LLMStreamEvent::Completed { response };
+        };
+
+        Ok(Box::pin(stream))
     }
 
     fn supported_models(&self) -> Vec<String> {
@@ -865,7 +1113,7 @@ impl LLMProvider for AnthropicProvider {
 mod tests {
     use super::*;
     use crate::config::core::PromptCachingConfig;
-    use crate::llm::provider::{Message, ToolDefinition};
+    use crate::llm::provider::{Attachment, JsonSchema, Message, ToolDefinition};
     use serde_json::{Value, json};
 
     fn base_prompt_cache_config() -> PromptCachingConfig {
@@ -903,6 +1151,7 @@ mod tests {
             parallel_tool_calls: None,
             parallel_tool_config: None,
             reasoning_effort: None,
+            response_format: None,
         }
     }
 
@@ -963,6 +1212,125 @@ mod tests {
         assert!(beta_header.contains("extended-cache-ttl-2025-04-11"));
     }
 
+    #[test]
+    fn user_attachments_precede_text_blocks() {
+        let provider = AnthropicProvider::new("key".to_string());
+        let mut request = sample_request();
+        request.messages = vec![Message::user_with_attachments(
+            "Summarize the diagram".to_string(),
+            vec![
+                Attachment::image("image/png", "aGk="),
+                Attachment::document("application/pdf", "JVBE", Some("spec.pdf".to_string())),
+            ],
+        )];
+
+        let converted = provider
+            .convert_to_anthropic_format(&request)
+            .expect("conversion should succeed");
+        let blocks = converted["messages"][0]["content"].as_array().unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0]["type"], "image");
+        assert_eq!(blocks[0]["source"]["media_type"], "image/png");
+        assert_eq!(blocks[1]["type"], "document");
+        assert_eq!(blocks[2]["type"], "text");
+    }
+
+    #[test]
+    fn response_format_forces_structured_output_tool() {
+        let provider = AnthropicProvider::new("key".to_string());
+        let mut request = sample_request();
+        request.response_format = Some(JsonSchema::new(
+            "forecast",
+            json!({"type": "object", "properties": {"summary": {"type": "string"}}}),
+        ));
+
+        let converted = provider
+            .convert_to_anthropic_format(&request)
+            .expect("conversion should succeed");
+        let tools = converted["tools"].as_array().expect("tools array");
+        assert_eq!(tools.last().unwrap()["name"], "forecast");
+        assert_eq!(
+            converted["tool_choice"],
+            json!({"type": "tool", "name": "forecast"})
+        );
+
+        let response = LLMResponse {
+            content: None,
+            tool_calls: Some(vec![ToolCall::function(
+                "toolu_1".to_string(),
+                "forecast".to_string(),
+                "{\"summary\":\"sunny\"}".to_string(),
+            )]),
+            usage: None,
+            finish_reason: FinishReason::ToolCalls,
+            reasoning: None,
+        };
+        let unwrapped = AnthropicProvider::unwrap_structured_response(response, "forecast");
+        assert_eq!(
+            unwrapped.content.as_deref(),
+            Some("{\"summary\":\"sunny\"}")
+        );
+        assert!(unwrapped.tool_calls.is_none());
+    }
+
+    #[test]
+    fn stream_events_rebuild_thinking_text_and_tool_calls() {
+        let events = [
+            r#"{"type":"message_start","message":{"id":"msg_1","role":"assistant","content":[],"usage":{"input_tokens":12,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"thinking","thinking":""}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"Check the "}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"files"}}"#,
+            r#"{"type":"content_block_stop","index":0}"#,
+            r#"{"type":"content_block_start","index":1,"content_block":{"type":"text","text":""}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"text_delta","text":"Listing"}}"#,
+            r#"{"type":"content_block_stop","index":1}"#,
+            r#"{"type":"content_block_start","index":2,"content_block":{"type":"tool_use","id":"toolu_1","name":"list_files","input":{}}}"#,
+            r#"{"type":"content_block_delta","index":2,"delta":{"type":"input_json_delta","partial_json":"{\"path\":"}}"#,
+            r#"{"type":"content_block_delta","index":2,"delta":{"type":"input_json_delta","partial_json":"\".\"}"}}"#,
+            r#"{"type":"content_block_stop","index":2}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":30}}"#,
+            r#"{"type":"message_stop"}"#,
+        ];
+
+        let mut state = AnthropicStreamState::default();
+        let mut reasoning = String::new();
+        let mut tokens = String::new();
+        for event in events {
+            let sse = format!("event: message\ndata: {}", event);
+            match state.apply_sse_event(&sse).expect("event should apply") {
+                Some(LLMStreamEvent::Reasoning { delta }) => reasoning.push_str(&delta),
+                Some(LLMStreamEvent::Token { delta }) => tokens.push_str(&delta),
+                Some(LLMStreamEvent::Completed { .. }) => panic!("unexpected completion"),
+                None => {}
+            }
+        }
+        assert_eq!(reasoning, "Check the files");
+        assert_eq!(tokens, "Listing");
+
+        let response = AnthropicProvider::parse_anthropic_response(state.into_message())
+            .expect("rebuilt message should parse");
+        assert_eq!(response.reasoning.as_deref(), Some("Check the files"));
+        assert_eq!(response.content.as_deref(), Some("Listing"));
+        assert_eq!(response.finish_reason, FinishReason::ToolCalls);
+        let calls = response.tool_calls.expect("tool call should be present");
+        assert_eq!(calls[0].function.name, "list_files");
+        assert_eq!(calls[0].function.arguments, r#"{"path":"."}"#);
+        let usage = response.usage.expect("usage should be present");
+        assert_eq!(usage.prompt_tokens, 12);
+        assert_eq!(usage.completion_tokens, 30);
+    }
+
+    #[test]
+    fn stream_error_event_fails() {
+        let mut state = AnthropicStreamState::default();
+        let result = state.apply_sse_event(
+            r#"data: {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+        );
+        assert!(
+            matches!(result, Err(LLMError::Provider(message)) if message.contains("Overloaded"))
+        );
+    }
+
     #[test]
     fn cache_control_absent_when_disabled() {
         let mut config = PromptCachingConfig::default();
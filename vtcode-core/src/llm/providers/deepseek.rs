@@ -101,6 +101,7 @@ impl DeepSeekProvider {
             parallel_tool_calls: None,
             parallel_tool_config: None,
             reasoning_effort: None,
+            response_format: None,
         }
     }
 
@@ -159,6 +160,7 @@ impl DeepSeekProvider {
                         content,
                         tool_calls,
                         tool_call_id: None,
+                        attachments: Vec::new(),
                     });
                 }
                 "tool" => {
@@ -197,6 +199,7 @@ impl DeepSeekProvider {
             parallel_tool_calls: None,
             parallel_tool_config: None,
             reasoning_effort: None,
+            response_format: None,
         })
     }
 
@@ -225,11 +228,21 @@ impl DeepSeekProvider {
             Value::Array(self.serialize_messages(request)?),
         );
 
-        if let Some(system_prompt) = &request.system_prompt {
-            payload.insert(
-                "system".to_string(),
-                Value::String(system_prompt.trim().to_string()),
-            );
+        // DeepSeek only offers JSON object mode, so the schema travels in the
+        // system prompt and is enforced by response validation.
+        let schema_instruction = request
+            .response_format
+            .as_ref()
+            .map(|format| format.instruction());
+        let system_prompt = match (request.system_prompt.as_deref(), schema_instruction) {
+            (Some(prompt), Some(instruction)) => {
+                Some(format!("{}\n\n{}", prompt.trim(), instruction))
+            }
+            (Some(prompt), None) => Some(prompt.trim().to_string()),
+            (None, instruction) => instruction,
+        };
+        if let Some(system_prompt) = system_prompt {
+            payload.insert("system".to_string(), Value::String(system_prompt));
         }
 
         if let Some(max_tokens) = request.max_tokens {
@@ -265,6 +278,13 @@ impl DeepSeekProvider {
             );
         }
 
+        if let Some(format) = &request.response_format {
+            payload.insert(
+                "response_format".to_string(),
+                format.to_provider_format(PROVIDER_KEY),
+            );
+        }
+
         if let Some(effort) = request.reasoning_effort {
             payload.insert(
                 "reasoning_effort".to_string(),
@@ -288,10 +308,23 @@ impl DeepSeekProvider {
                 "role".to_string(),
                 Value::String(message.role.as_generic_str().to_string()),
             );
-            message_map.insert(
-                "content".to_string(),
-                Value::String(message.content.clone()),
-            );
+            // DeepSeek models are text-only; note dropped attachments so the
+            // model does not assume it has seen them.
+            let content = if message.has_attachments() {
+                let names = message
+                    .attachments
+                    .iter()
+                    .map(|attachment| attachment.name.as_deref().unwrap_or("unnamed"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "{}\n\n[Attachments not supported by this model were omitted: {}]",
+                    message.content, names
+                )
+            } else {
+                message.content.clone()
+            };
+            message_map.insert("content".to_string(), Value::String(content));
 
             if let Some(tool_calls) = &message.tool_calls {
                 let serialized_calls = tool_calls
@@ -3,9 +3,9 @@ use crate::config::core::{GeminiPromptCacheMode, GeminiPromptCacheSettings, Prom
 use crate::gemini::function_calling::{
     FunctionCall as GeminiFunctionCall, FunctionCallingConfig, FunctionResponse,
 };
-use crate::gemini::models::SystemInstruction;
+use crate::gemini::models::{InlineData, SystemInstruction};
 use crate::gemini::streaming::{
-    StreamingCandidate, StreamingError, StreamingProcessor, StreamingResponse,
+    StreamChunk, StreamingCandidate, StreamingError, StreamingProcessor, StreamingResponse,
 };
 use crate::gemini::{
     Candidate, Content, FunctionDeclaration, GenerateContentRequest, GenerateContentResponse, Part,
@@ -217,20 +217,31 @@ impl LLMProvider for GeminiProvider {
             let mut processor = StreamingProcessor::new();
             let token_sender = completion_sender.clone();
             let mut aggregated_text = String::new();
-            let mut on_chunk = |chunk: &str| -> Result<(), StreamingError> {
-                if chunk.is_empty() {
+            let mut on_chunk = |chunk: StreamChunk<'_>| -> Result<(), StreamingError> {
+                let (event, delta) = match chunk {
+                    StreamChunk::Text(text) => {
+                        aggregated_text.push_str(text);
+                        let event = LLMStreamEvent::Token {
+                            delta: text.to_string(),
+                        };
+                        (event, text)
+                    }
+                    StreamChunk::Thought(text) => {
+                        let event = LLMStreamEvent::Reasoning {
+                            delta: text.to_string(),
+                        };
+                        (event, text)
+                    }
+                };
+                if delta.is_empty() {
                     return Ok(());
                 }
 
-                aggregated_text.push_str(chunk);
-
                 token_sender
-                    .send(Ok(LLMStreamEvent::Token {
-                        delta: chunk.to_string(),
-                    }))
+                    .send(Ok(event))
                     .map_err(|_| StreamingError::StreamingError {
                         message: "Streaming consumer dropped".to_string(),
-                        partial_content: Some(chunk.to_string()),
+                        partial_content: Some(delta.to_string()),
                     })?;
                 Ok(())
             };
@@ -343,6 +354,19 @@ impl GeminiProvider {
                     text: message.content.clone(),
                 });
             }
+            if message.role == MessageRole::User {
+                parts.extend(
+                    message
+                        .attachments
+                        .iter()
+                        .map(|attachment| Part::InlineData {
+                            inline_data: InlineData {
+                                mime_type: attachment.media_type.clone(),
+                                data: attachment.data.clone(),
+                            },
+                        }),
+                );
+            }
 
             if message.role == MessageRole::Assistant
                 && let Some(tool_calls) = &message.tool_calls
@@ -421,6 +445,20 @@ impl GeminiProvider {
         if let Some(temp) = request.temperature {
             generation_config.insert("temperature".to_string(), json!(temp));
         }
+        if request.reasoning_effort.is_some() {
+            // Ask for thought summaries so reasoning can be shown as it streams.
+            generation_config.insert(
+                "thinkingConfig".to_string(),
+                json!({ "includeThoughts": true }),
+            );
+        }
+        if let Some(format) = &request.response_format {
+            generation_config.insert("responseMimeType".to_string(), json!("application/json"));
+            generation_config.insert(
+                "responseSchema".to_string(),
+                format.to_provider_format("gemini"),
+            );
+        }
         let has_tools = request
             .tools
             .as_ref()
@@ -487,11 +525,18 @@ impl GeminiProvider {
         }
 
         let mut text_content = String::new();
+        let mut reasoning = String::new();
         let mut tool_calls = Vec::new();
 
         for part in candidate.content.parts {
             match part {
-                Part::Text { text } => {
+                Part::Thought {
+                    text,
+                    thought: true,
+                } => {
+                    reasoning.push_str(&text);
+                }
+                Part::Text { text } | Part::Thought { text, .. } => {
                     text_content.push_str(&text);
                 }
                 Part::FunctionCall { function_call } => {
@@ -515,8 +560,8 @@ impl GeminiProvider {
                         },
                     });
                 }
-                Part::FunctionResponse { .. } => {
-                    // Ignore echoed tool responses to avoid duplicating tool output
+                Part::FunctionResponse { .. } | Part::InlineData { .. } => {
+                    // Ignore echoed tool responses and inline media to avoid duplicating input
                 }
             }
         }
@@ -543,7 +588,10 @@ impl GeminiProvider {
             },
             usage: None,
             finish_reason,
-            reasoning: None,
+            reasoning: {
+                let trimmed = reasoning.trim();
+                (!trimmed.is_empty()).then(|| trimmed.to_string())
+            },
         })
     }
 
@@ -699,6 +747,7 @@ impl LLMClient for GeminiProvider {
                             content: content_text,
                             tool_calls: None,
                             tool_call_id: None,
+                            attachments: Vec::new(),
                         });
                     }
 
@@ -740,6 +789,7 @@ impl LLMClient for GeminiProvider {
                         parallel_tool_calls: None,
                         parallel_tool_config: None,
                         reasoning_effort: None,
+                        response_format: None,
                     };
 
                     // Use the standard LLMProvider generate method
@@ -789,6 +839,7 @@ impl LLMClient for GeminiProvider {
                             content: prompt.to_string(),
                             tool_calls: None,
                             tool_call_id: None,
+                            attachments: Vec::new(),
                         }],
                         system_prompt: None,
                         tools: None,
@@ -800,6 +851,7 @@ impl LLMClient for GeminiProvider {
                         parallel_tool_calls: None,
                         parallel_tool_config: None,
                         reasoning_effort: None,
+                        response_format: None,
                     }
                 }
             }
@@ -811,6 +863,7 @@ impl LLMClient for GeminiProvider {
                     content: prompt.to_string(),
                     tool_calls: None,
                     tool_call_id: None,
+                    attachments: Vec::new(),
                 }],
                 system_prompt: None,
                 tools: None,
@@ -822,6 +875,7 @@ impl LLMClient for GeminiProvider {
                 parallel_tool_calls: None,
                 parallel_tool_config: None,
                 reasoning_effort: None,
+                response_format: None,
             }
         };
 
@@ -855,7 +909,9 @@ impl LLMClient for GeminiProvider {
 mod tests {
     use super::*;
     use crate::config::constants::models;
-    use crate::llm::provider::{SpecificFunctionChoice, SpecificToolChoice, ToolDefinition};
+    use crate::llm::provider::{
+        Attachment, SpecificFunctionChoice, SpecificToolChoice, ToolDefinition,
+    };
 
     #[test]
     fn convert_to_gemini_request_maps_history_and_system_prompt() {
@@ -902,6 +958,7 @@ mod tests {
             parallel_tool_calls: None,
             parallel_tool_config: None,
             reasoning_effort: None,
+            response_format: None,
         };
 
         let gemini_request = provider
@@ -935,6 +992,43 @@ mod tests {
         assert_eq!(tool_part.name, "list_files");
     }
 
+    #[test]
+    fn convert_to_gemini_request_inlines_user_attachments() {
+        let provider = GeminiProvider::new("test-key".to_string());
+        let request = LLMRequest {
+            messages: vec![Message::user_with_attachments(
+                "Describe this".to_string(),
+                vec![Attachment::image("image/webp", "UklG")],
+            )],
+            system_prompt: None,
+            tools: None,
+            model: models::google::GEMINI_2_5_FLASH_PREVIEW.to_string(),
+            max_tokens: None,
+            temperature: None,
+            stream: false,
+            tool_choice: None,
+            parallel_tool_calls: None,
+            parallel_tool_config: None,
+            reasoning_effort: None,
+            response_format: None,
+        };
+
+        let gemini_request = provider
+            .convert_to_gemini_request(&request)
+            .expect("conversion should succeed");
+
+        let parts = &gemini_request.contents[0].parts;
+        assert!(parts.iter().any(|part| matches!(
+            part,
+            Part::InlineData { inline_data } if inline_data.mime_type == "image/webp"
+        )));
+        assert!(
+            parts
+                .iter()
+                .any(|part| matches!(part, Part::Text { text } if text == "Describe this"))
+        );
+    }
+
     #[test]
     fn convert_from_gemini_response_extracts_tool_calls() {
         let response = GenerateContentResponse {
@@ -973,6 +1067,29 @@ mod tests {
         assert_eq!(llm_response.finish_reason, FinishReason::ToolCalls);
     }
 
+    #[test]
+    fn convert_from_gemini_response_separates_thoughts() {
+        let response: GenerateContentResponse = serde_json::from_value(json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [
+                        { "text": "Weighing options", "thought": true },
+                        { "text": "Use a map." }
+                    ]
+                },
+                "finishReason": "STOP"
+            }]
+        }))
+        .expect("response should deserialize");
+
+        let llm_response = GeminiProvider::convert_from_gemini_response(response)
+            .expect("conversion should succeed");
+
+        assert_eq!(llm_response.content.as_deref(), Some("Use a map."));
+        assert_eq!(llm_response.reasoning.as_deref(), Some("Weighing options"));
+    }
+
     #[test]
     fn sanitize_function_parameters_removes_additional_properties() {
         let parameters = json!({
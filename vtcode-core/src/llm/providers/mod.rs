@@ -7,6 +7,7 @@ pub mod xai;
 
 mod codex_prompt;
 mod reasoning;
+mod sse;
 
 pub(crate) use codex_prompt::gpt5_codex_developer_prompt;
 pub(crate) use reasoning::extract_reasoning_trace;
@@ -135,6 +135,7 @@ impl OpenAIProvider {
             parallel_tool_calls: None,
             parallel_tool_config: None,
             reasoning_effort: None,
+            response_format: None,
         }
     }
 
@@ -205,6 +206,7 @@ impl OpenAIProvider {
                             content: text_content,
                             tool_calls: Some(calls),
                             tool_call_id: None,
+                            attachments: Vec::new(),
                         }
                     } else {
                         Message::assistant(text_content)
@@ -231,6 +233,7 @@ impl OpenAIProvider {
                         content: content_value,
                         tool_calls: None,
                         tool_call_id,
+                        attachments: Vec::new(),
                     });
                 }
                 _ => {
@@ -318,6 +321,7 @@ impl OpenAIProvider {
             parallel_tool_calls,
             parallel_tool_config: None,
             reasoning_effort,
+            response_format: None,
         })
     }
 
@@ -380,9 +384,14 @@ impl OpenAIProvider {
 
         for msg in &request.messages {
             let role = msg.role.as_openai_str();
+            let content = if msg.has_attachments() {
+                Value::Array(msg.content_parts("openai", "text"))
+            } else {
+                Value::String(msg.content.clone())
+            };
             let mut message = json!({
                 "role": role,
-                "content": msg.content
+                "content": content
             });
             let mut skip_message = false;
 
@@ -460,6 +469,10 @@ impl OpenAIProvider {
             openai_request["parallel_tool_calls"] = Value::Bool(parallel);
         }
 
+        if let Some(format) = &request.response_format {
+            openai_request["response_format"] = format.to_provider_format("openai");
+        }
+
         if let Some(effort) = request.reasoning_effort {
             if self.supports_reasoning_effort(&request.model) {
                 if let Some(payload) = reasoning_parameters_for(Provider::OpenAI, effort) {
@@ -516,6 +529,11 @@ impl OpenAIProvider {
             openai_request["parallel_tool_calls"] = Value::Bool(parallel);
         }
 
+        if let Some(format) = &request.response_format {
+            openai_request["text"] =
+                json!({ "format": format.to_provider_format("openai-responses") });
+        }
+
         if let Some(effort) = request.reasoning_effort {
             if self.supports_reasoning_effort(&request.model) {
                 if let Some(payload) = reasoning_parameters_for(Provider::OpenAI, effort) {
@@ -833,6 +851,7 @@ impl OpenAIProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::llm::provider::{Attachment, JsonSchema};
 
     fn sample_tool() -> ToolDefinition {
         ToolDefinition::function(
@@ -862,6 +881,7 @@ mod tests {
             parallel_tool_calls: None,
             parallel_tool_config: None,
             reasoning_effort: None,
+            response_format: None,
         }
     }
 
@@ -913,6 +933,51 @@ mod tests {
         assert!(!tool_object.contains_key("name"));
     }
 
+    #[test]
+    fn user_attachments_serialize_as_content_parts() {
+        let provider =
+            OpenAIProvider::with_model(String::new(), models::openai::DEFAULT_MODEL.to_string());
+        let mut request = sample_request(models::openai::DEFAULT_MODEL);
+        request.messages = vec![Message::user_with_attachments(
+            "What is wrong here?".to_string(),
+            vec![Attachment::image("image/png", "aGk=")],
+        )];
+
+        let payload = provider
+            .convert_to_openai_format(&request)
+            .expect("conversion should succeed");
+        let content = payload["messages"][0]["content"]
+            .as_array()
+            .expect("multi-part content");
+        assert_eq!(content[0]["type"], "text");
+        assert_eq!(content[1]["image_url"]["url"], "data:image/png;base64,aGk=");
+
+        let payload = provider
+            .convert_to_openai_responses_format(&request)
+            .expect("conversion should succeed");
+        let content = payload["input"][0]["content"].as_array().unwrap();
+        assert_eq!(content[1]["type"], "input_image");
+    }
+
+    #[test]
+    fn response_format_maps_to_json_schema() {
+        let provider =
+            OpenAIProvider::with_model(String::new(), models::openai::DEFAULT_MODEL.to_string());
+        let mut request = sample_request(models::openai::DEFAULT_MODEL);
+        request.response_format = Some(JsonSchema::new("review", json!({"type": "object"})));
+
+        let payload = provider
+            .convert_to_openai_format(&request)
+            .expect("conversion should succeed");
+        assert_eq!(payload["response_format"]["type"], "json_schema");
+        assert_eq!(payload["response_format"]["json_schema"]["name"], "review");
+
+        let payload = provider
+            .convert_to_openai_responses_format(&request)
+            .expect("conversion should succeed");
+        assert_eq!(payload["text"]["format"]["name"], "review");
+    }
+
     #[test]
     fn responses_payload_uses_function_wrapper() {
         let provider =
@@ -981,12 +1046,17 @@ fn build_standard_responses_input_openai(request: &LLMRequest) -> Result<Vec<Val
                 }
             }
             MessageRole::User => {
-                input.push(json!({
-                    "role": "user",
-                    "content": [{
+                let content = if msg.has_attachments() {
+                    msg.content_parts("openai-responses", "input_text")
+                } else {
+                    vec![json!({
                         "type": "input_text",
                         "text": msg.content.clone()
-                    }]
+                    })]
+                };
+                input.push(json!({
+                    "role": "user",
+                    "content": content
                 }));
             }
             MessageRole::Assistant => {
@@ -1086,12 +1156,17 @@ fn build_codex_responses_input_openai(request: &LLMRequest) -> Result<Vec<Value>
                 }
             }
             MessageRole::User => {
-                input.push(json!({
-                    "role": "user",
-                    "content": [{
+                let content = if msg.has_attachments() {
+                    msg.content_parts("openai-responses", "input_text")
+                } else {
+                    vec![json!({
                         "type": "input_text",
                         "text": msg.content.clone()
-                    }]
+                    })]
+                };
+                input.push(json!({
+                    "role": "user",
+                    "content": content
                 }));
             }
             MessageRole::Assistant => {
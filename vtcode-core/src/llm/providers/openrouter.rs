@@ -16,6 +16,7 @@ use futures::StreamExt;
 use reqwest::{Client as HttpClient, Response, StatusCode};
 use serde_json::{Map, Value, json};
 
+use super::sse::{extract_data_payload, find_sse_boundary};
 use super::{extract_reasoning_trace, gpt5_codex_developer_prompt};
 
 #[derive(Default, Clone)]
@@ -611,7 +612,10 @@ fn parse_chat_completion_chunk(
                     );
                 }
 
-                if let Some(reasoning_value) = delta.get("reasoning") {
+                if let Some(reasoning_value) = delta
+                    .get("reasoning")
+                    .or_else(|| delta.get("reasoning_content"))
+                {
                     push_reasoning_value(reasoning, reasoning_value, &mut deltas);
                 }
 
@@ -720,27 +724,6 @@ fn update_usage_from_value(source: &Value, usage: &mut Option<Usage>) {
     }
 }
 
-fn extract_data_payload(event: &str) -> Option<String> {
-    let mut data_lines: Vec<String> = Vec::new();
-
-    for raw_line in event.lines() {
-        let line = raw_line.trim_end_matches('\r');
-        if line.is_empty() || line.starts_with(':') {
-            continue;
-        }
-
-        if let Some(value) = line.strip_prefix("data:") {
-            data_lines.push(value.trim_start().to_string());
-        }
-    }
-
-    if data_lines.is_empty() {
-        None
-    } else {
-        Some(data_lines.join("\n"))
-    }
-}
-
 fn parse_stream_payload(
     payload: &Value,
     aggregated_content: &mut String,
@@ -896,6 +879,7 @@ impl OpenRouterProvider {
             parallel_tool_calls: None,
             parallel_tool_config: None,
             reasoning_effort: None,
+            response_format: None,
         }
     }
 
@@ -1091,6 +1075,7 @@ impl OpenRouterProvider {
                             content: text_content,
                             tool_calls: Some(calls),
                             tool_call_id: None,
+                            attachments: Vec::new(),
                         }
                     } else {
                         Message::assistant(text_content)
@@ -1117,6 +1102,7 @@ impl OpenRouterProvider {
                         content: content_value,
                         tool_calls: None,
                         tool_call_id,
+                        attachments: Vec::new(),
                     });
                 }
                 _ => {
@@ -1204,6 +1190,7 @@ impl OpenRouterProvider {
             parallel_tool_calls,
             parallel_tool_config: None,
             reasoning_effort,
+            response_format: None,
         })
     }
 
@@ -1282,12 +1269,17 @@ impl OpenRouterProvider {
                     }
                 }
                 MessageRole::User => {
-                    input.push(json!({
-                        "role": "user",
-                        "content": [{
+                    let content = if msg.has_attachments() {
+                        msg.content_parts("openai-responses", "input_text")
+                    } else {
+                        vec![json!({
                             "type": "input_text",
                             "text": msg.content.clone()
-                        }]
+                        })]
+                    };
+                    input.push(json!({
+                        "role": "user",
+                        "content": content
                     }));
                 }
                 MessageRole::Assistant => {
@@ -1377,12 +1369,17 @@ impl OpenRouterProvider {
                     }
                 }
                 MessageRole::User => {
-                    input.push(json!({
-                        "role": "user",
-                        "content": [{
+                    let content = if msg.has_attachments() {
+                        msg.content_parts("openai-responses", "input_text")
+                    } else {
+                        vec![json!({
                             "type": "input_text",
                             "text": msg.content.clone()
-                        }]
+                        })]
+                    };
+                    input.push(json!({
+                        "role": "user",
+                        "content": content
                     }));
                 }
                 MessageRole::Assistant => {
@@ -1523,6 +1520,11 @@ impl OpenRouterProvider {
             provider_request["parallel_tool_calls"] = Value::Bool(parallel);
         }
 
+        if let Some(format) = &request.response_format {
+            provider_request["text"] =
+                json!({ "format": format.to_provider_format("openai-responses") });
+        }
+
         if let Some(effort) = request.reasoning_effort {
             if self.supports_reasoning_effort(resolved_model) {
                 if let Some(payload) = reasoning_parameters_for(Provider::OpenRouter, effort) {
@@ -1553,9 +1555,14 @@ impl OpenRouterProvider {
 
         for msg in &request.messages {
             let role = msg.role.as_openai_str();
+            let content = if msg.has_attachments() {
+                Value::Array(msg.content_parts("openrouter", "text"))
+            } else {
+                Value::String(msg.content.clone())
+            };
             let mut message = json!({
                 "role": role,
-                "content": msg.content
+                "content": content
             });
 
             if msg.role == MessageRole::Assistant {
@@ -1635,6 +1642,10 @@ impl OpenRouterProvider {
             provider_request["parallel_tool_calls"] = Value::Bool(parallel);
         }
 
+        if let Some(format) = &request.response_format {
+            provider_request["response_format"] = format.to_provider_format("openrouter");
+        }
+
         if let Some(effort) = request.reasoning_effort {
             if self.supports_reasoning_effort(resolved_model) {
                 if let Some(payload) = reasoning_parameters_for(Provider::OpenRouter, effort) {
@@ -1889,24 +1900,6 @@ impl LLMProvider for OpenRouterProvider {
     async fn stream(&self, request: LLMRequest) -> Result<LLMStream, LLMError> {
         let response = self.send_with_tool_fallback(&request, Some(true)).await?;
 
-        fn find_sse_boundary(buffer: &str) -> Option<(usize, usize)> {
-            let newline_boundary = buffer.find("\n\n").map(|idx| (idx, 2));
-            let carriage_boundary = buffer.find("\r\n\r\n").map(|idx| (idx, 4));
-
-            match (newline_boundary, carriage_boundary) {
-                (Some((n_idx, n_len)), Some((c_idx, c_len))) => {
-                    if n_idx <= c_idx {
-                        Some((n_idx, n_len))
-                    } else {
-                        Some((c_idx, c_len))
-                    }
-                }
-                (Some(boundary), None) => Some(boundary),
-                (None, Some(boundary)) => Some(boundary),
-                (None, None) => None,
-            }
-        }
-
         let stream = try_stream! {
             let mut body_stream = response.bytes_stream();
             let mut buffer = String::new();
@@ -2188,6 +2181,40 @@ mod tests {
         assert_eq!(aggregated, "Stream");
     }
 
+    #[test]
+    fn test_parse_stream_payload_reasoning_content_delta() {
+        let payload = json!({
+            "choices": [{
+                "delta": {
+                    "reasoning_content": "Checking the tests"
+                }
+            }]
+        });
+
+        let mut aggregated = String::new();
+        let mut builders = Vec::new();
+        let mut reasoning = ReasoningBuffer::default();
+        let mut usage = None;
+        let mut finish_reason = FinishReason::Stop;
+
+        let delta = parse_stream_payload(
+            &payload,
+            &mut aggregated,
+            &mut builders,
+            &mut reasoning,
+            &mut usage,
+            &mut finish_reason,
+        );
+
+        let fragments = delta.expect("delta should exist").into_fragments();
+        assert_eq!(
+            fragments,
+            vec![StreamFragment::Reasoning("Checking the tests".to_string())]
+        );
+        assert!(aggregated.is_empty());
+        assert_eq!(reasoning.finalize().as_deref(), Some("Checking the tests"));
+    }
+
     #[test]
     fn test_extract_data_payload_joins_multiline_events() {
         let event = ": keep-alive\n".to_string() + "data: {\"a\":1}\n" + "data: {\"b\":2}\n";
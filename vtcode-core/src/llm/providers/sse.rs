@@ -0,0 +1,42 @@
+//! Helpers for reading server-sent event streams returned by provider APIs.
+
+/// Position and length of the first blank-line delimiter ending an event.
+pub(crate) fn find_sse_boundary(buffer: &str) -> Option<(usize, usize)> {
+    let newline_boundary = buffer.find("\n\n").map(|idx| (idx, 2));
+    let carriage_boundary = buffer.find("\r\n\r\n").map(|idx| (idx, 4));
+
+    match (newline_boundary, carriage_boundary) {
+        (Some((n_idx, n_len)), Some((c_idx, c_len))) => {
+            if n_idx <= c_idx {
+                Some((n_idx, n_len))
+            } else {
+                Some((c_idx, c_len))
+            }
+        }
+        (Some(boundary), None) => Some(boundary),
+        (None, Some(boundary)) => Some(boundary),
+        (None, None) => None,
+    }
+}
+
+/// Joined `data:` lines of a single event, if it has any.
+pub(crate) fn extract_data_payload(event: &str) -> Option<String> {
+    let mut data_lines: Vec<String> = Vec::new();
+
+    for raw_line in event.lines() {
+        let line = raw_line.trim_end_matches('\r');
+        if line.is_empty() || line.starts_with(':') {
+            continue;
+        }
+
+        if let Some(value) = line.strip_prefix("data:") {
+            data_lines.push(value.trim_start().to_string());
+        }
+    }
+
+    if data_lines.is_empty() {
+        None
+    } else {
+        Some(data_lines.join("\n"))
+    }
+}
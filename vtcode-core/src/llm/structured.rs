@@ -0,0 +1,471 @@
+//! Structured output support
+//!
+//! Providers honour `LLMRequest::response_format` with varying strictness
+//! (DeepSeek only offers JSON mode, Gemini accepts a schema subset), so the
+//! returned text is always parsed and validated locally. A response that fails
+//! validation gets a single repair attempt with the validation errors fed back
+//! to the model.
+
+use serde_json::Value;
+
+use super::provider::{JsonSchema, LLMError, LLMProvider, LLMRequest, LLMResponse, Message};
+
+/// Validated structured response
+#[derive(Debug, Clone)]
+pub struct StructuredResponse {
+    /// Parsed JSON value that conforms to the requested schema
+    pub value: Value,
+    /// Raw provider response that produced the value
+    pub response: LLMResponse,
+    /// Whether a repair retry was needed
+    pub repaired: bool,
+}
+
+/// Generate a response constrained to `request.response_format`, validating
+/// the result and retrying once with repair instructions on failure.
+pub async fn generate_structured(
+    provider: &dyn LLMProvider,
+    mut request: LLMRequest,
+) -> Result<StructuredResponse, LLMError> {
+    let schema = request.response_format.clone().ok_or_else(|| {
+        LLMError::InvalidRequest("Structured generation requires a response_format".to_string())
+    })?;
+    request.stream = false;
+
+    let response = provider.generate(request.clone()).await?;
+    let errors = match parse_and_validate(&response, &schema) {
+        Ok(value) => {
+            return Ok(StructuredResponse {
+                value,
+                response,
+                repaired: false,
+            });
+        }
+        Err(errors) => errors,
+    };
+
+    let previous = response.content.clone().unwrap_or_default();
+    request.messages.push(Message::assistant(previous));
+    request.messages.push(Message::user(repair_prompt(&errors)));
+
+    let response = provider.generate(request).await?;
+    match parse_and_validate(&response, &schema) {
+        Ok(value) => Ok(StructuredResponse {
+            value,
+            response,
+            repaired: true,
+        }),
+        Err(errors) => Err(LLMError::Provider(format!(
+            "Response did not match schema '{}' after a repair attempt: {}",
+            schema.name,
+            errors.join("; ")
+        ))),
+    }
+}
+
+fn repair_prompt(errors: &[String]) -> String {
+    let mut prompt =
+        String::from("Your previous response did not match the required JSON Schema:\n");
+    for error in errors {
+        prompt.push_str("- ");
+        prompt.push_str(error);
+        prompt.push('\n');
+    }
+    prompt.push_str("Respond again with only the corrected JSON value.");
+    prompt
+}
+
+fn parse_and_validate(response: &LLMResponse, schema: &JsonSchema) -> Result<Value, Vec<String>> {
+    let text = response
+        .content
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .ok_or_else(|| vec!["response was empty".to_string()])?;
+    let value = parse_json_payload(text)
+        .map_err(|err| vec![format!("response is not valid JSON: {}", err)])?;
+    let errors = validate(&value, &schema.schema);
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parse JSON, tolerating a surrounding Markdown code fence.
+pub fn parse_json_payload(text: &str) -> Result<Value, serde_json::Error> {
+    let trimmed = text.trim();
+    let unfenced = trimmed
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map(|body| {
+            let body = body.strip_prefix("json").unwrap_or(body);
+            body.trim()
+        })
+        .unwrap_or(trimmed);
+    serde_json::from_str(unfenced)
+}
+
+/// Validate `value` against a JSON Schema, returning one message per violation.
+///
+/// Supports the keywords commonly used for structured output: `type`, `enum`,
+/// `const`, `properties`, `required`, `additionalProperties`, `items`,
+/// `minItems`/`maxItems`, `minLength`/`maxLength`, `minimum`/`maximum`,
+/// `anyOf`/`oneOf`/`allOf` and local `$ref`s into `$defs`/`definitions`.
+pub fn validate(value: &Value, schema: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(value, schema, schema, "$", &mut errors);
+    errors
+}
+
+fn validate_at(value: &Value, schema: &Value, root: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        if schema == &Value::Bool(false) {
+            errors.push(format!("{}: no value is allowed here", path));
+        }
+        return;
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        match resolve_ref(root, reference) {
+            Some(target) => validate_at(value, target, root, path, errors),
+            None => errors.push(format!("{}: unresolvable $ref '{}'", path, reference)),
+        }
+        return;
+    }
+
+    if let Some(expected) = schema.get("type")
+        && !matches_type(value, expected)
+    {
+        errors.push(format!(
+            "{}: expected type {}, found {}",
+            path,
+            expected,
+            type_name(value)
+        ));
+        return;
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array)
+        && !options.contains(value)
+    {
+        errors.push(format!(
+            "{}: value {} is not one of {}",
+            path,
+            value,
+            Value::Array(options.clone())
+        ));
+    }
+
+    if let Some(expected) = schema.get("const")
+        && expected != value
+    {
+        errors.push(format!("{}: expected constant {}", path, expected));
+    }
+
+    if let Some(subschemas) = schema.get("allOf").and_then(Value::as_array) {
+        for subschema in subschemas {
+            validate_at(value, subschema, root, path, errors);
+        }
+    }
+
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(subschemas) = schema.get(keyword).and_then(Value::as_array) {
+            let matching = subschemas
+                .iter()
+                .filter(|subschema| {
+                    let mut scratch = Vec::new();
+                    validate_at(value, subschema, root, path, &mut scratch);
+                    scratch.is_empty()
+                })
+                .count();
+            let satisfied = if keyword == "oneOf" {
+                matching == 1
+            } else {
+                matching > 0
+            };
+            if !satisfied {
+                errors.push(format!("{}: value does not satisfy {}", path, keyword));
+            }
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(key) {
+                        errors.push(format!("{}: missing required property '{}'", path, key));
+                    }
+                }
+            }
+            for (key, item) in map {
+                let child_path = format!("{}.{}", path, key);
+                match properties.and_then(|props| props.get(key)) {
+                    Some(property_schema) => {
+                        validate_at(item, property_schema, root, &child_path, errors)
+                    }
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("{}: unexpected property '{}'", path, key))
+                        }
+                        Some(additional @ Value::Object(_)) => {
+                            validate_at(item, additional, root, &child_path, errors)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64)
+                && (items.len() as u64) < min
+            {
+                errors.push(format!("{}: expected at least {} items", path, min));
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64)
+                && (items.len() as u64) > max
+            {
+                errors.push(format!("{}: expected at most {} items", path, max));
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(
+                        item,
+                        item_schema,
+                        root,
+                        &format!("{}[{}]", path, index),
+                        errors,
+                    );
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64)
+                && length < min
+            {
+                errors.push(format!("{}: string shorter than {} characters", path, min));
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64)
+                && length > max
+            {
+                errors.push(format!("{}: string longer than {} characters", path, max));
+            }
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64)
+                && number < min
+            {
+                errors.push(format!("{}: {} is below the minimum {}", path, number, min));
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64)
+                && number > max
+            {
+                errors.push(format!("{}: {} is above the maximum {}", path, number, max));
+            }
+        }
+        Value::Bool(_) | Value::Null => {}
+    }
+}
+
+fn resolve_ref<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    root.pointer(pointer)
+}
+
+fn matches_type(value: &Value, expected: &Value) -> bool {
+    match expected {
+        Value::String(name) => matches_type_name(value, name),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|name| matches_type_name(value, name)),
+        _ => true,
+    }
+}
+
+fn matches_type_name(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+        Value::Number(_) => "number",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::provider::FinishReason;
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    struct ScriptedProvider {
+        replies: Mutex<Vec<String>>,
+        requests: Mutex<Vec<LLMRequest>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(replies: &[&str]) -> Self {
+            Self {
+                replies: Mutex::new(replies.iter().rev().map(|s| s.to_string()).collect()),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl LLMProvider for ScriptedProvider {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        async fn generate(&self, request: LLMRequest) -> Result<LLMResponse, LLMError> {
+            self.requests.lock().unwrap().push(request);
+            let content = self.replies.lock().unwrap().pop();
+            Ok(LLMResponse {
+                content,
+                tool_calls: None,
+                usage: None,
+                finish_reason: FinishReason::Stop,
+                reasoning: None,
+            })
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            vec!["scripted".to_string()]
+        }
+
+        fn validate_request(&self, _request: &LLMRequest) -> Result<(), LLMError> {
+            Ok(())
+        }
+    }
+
+    fn review_schema() -> JsonSchema {
+        JsonSchema::new(
+            "code review",
+            json!({
+                "type": "object",
+                "required": ["verdict", "issues"],
+                "additionalProperties": false,
+                "properties": {
+                    "verdict": {"enum": ["approve", "request_changes"]},
+                    "issues": {"type": "array", "items": {"$ref": "#/$defs/issue"}}
+                },
+                "$defs": {
+                    "issue": {
+                        "type": "object",
+                        "required": ["line"],
+                        "properties": {"line": {"type": "integer", "minimum": 1}}
+                    }
+                }
+            }),
+        )
+    }
+
+    fn request(schema: JsonSchema) -> LLMRequest {
+        LLMRequest {
+            messages: vec![Message::user("review this".to_string())],
+            system_prompt: None,
+            tools: None,
+            model: "scripted".to_string(),
+            max_tokens: None,
+            temperature: None,
+            stream: true,
+            tool_choice: None,
+            parallel_tool_calls: None,
+            parallel_tool_config: None,
+            reasoning_effort: None,
+            response_format: Some(schema),
+        }
+    }
+
+    #[test]
+    fn validator_reports_each_violation_with_path() {
+        let schema = review_schema().schema;
+        let errors = validate(
+            &json!({"verdict": "maybe", "issues": [{"line": 0}], "extra": true}),
+            &schema,
+        );
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.starts_with("$.verdict")));
+        assert!(errors.iter().any(|e| e.starts_with("$.issues[0].line")));
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("unexpected property 'extra'"))
+        );
+
+        assert!(validate(&json!({"verdict": "approve", "issues": []}), &schema).is_empty());
+    }
+
+    #[test]
+    fn schema_names_are_normalized_for_providers() {
+        assert_eq!(review_schema().name, "code_review");
+        let gemini = review_schema().to_provider_format("gemini");
+        assert!(gemini.get("additionalProperties").is_none());
+        assert!(gemini["properties"].get("verdict").is_some());
+    }
+
+    #[tokio::test]
+    async fn accepts_fenced_json_without_retry() {
+        let provider =
+            ScriptedProvider::new(&["```json\n{\"verdict\": \"approve\", \"issues\": []}\n```"]);
+        let result = generate_structured(&provider, request(review_schema()))
+            .await
+            .unwrap();
+        assert!(!result.repaired);
+        assert_eq!(result.value["verdict"], "approve");
+        assert!(!provider.requests.lock().unwrap()[0].stream);
+    }
+
+    #[tokio::test]
+    async fn repairs_invalid_response_once() {
+        let provider = ScriptedProvider::new(&[
+            "{\"verdict\": \"approve\"}",
+            "{\"verdict\": \"approve\", \"issues\": [{\"line\": 3}]}",
+        ]);
+        let result = generate_structured(&provider, request(review_schema()))
+            .await
+            .unwrap();
+        assert!(result.repaired);
+
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let repair = &requests[1].messages.last().unwrap().content;
+        assert!(repair.contains("missing required property 'issues'"));
+    }
+
+    #[tokio::test]
+    async fn fails_after_single_repair_attempt() {
+        let provider = ScriptedProvider::new(&["not json", "still not json", "{}"]);
+        let error = generate_structured(&provider, request(review_schema()))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LLMError::Provider(_)));
+        assert_eq!(provider.requests.lock().unwrap().len(), 2);
+    }
+}
@@ -4,7 +4,12 @@
 //! managing MCP provider connections and tool execution.
 
 use crate::config::mcp::{
-    McpAllowListConfig, McpClientConfig, McpProviderConfig, McpTransportConfig,
+    McpAllowListConfig, McpClientConfig, McpElicitationConfig, McpProviderConfig,
+    McpSamplingConfig, McpSamplingPolicy, McpTransportConfig,
+};
+use crate::mcp_interaction::{
+    ElicitationForm, McpInteraction, McpProgress, McpSamplingBackend, SamplingSummary,
+    build_sampling_request, elicitation_cancelled, elicitation_declined, sampling_result,
 };
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -15,9 +20,11 @@ use rmcp::{
     handler::client::ClientHandler,
     model::{
         CallToolRequestParam, CallToolResult, CancelledNotificationParam, ClientCapabilities,
-        ClientInfo, CreateElicitationRequestParam, CreateElicitationResult, ElicitationAction,
-        Implementation, ListToolsResult, LoggingLevel, LoggingMessageNotificationParam,
-        ProgressNotificationParam, ResourceUpdatedNotificationParam, RootsCapabilities,
+        ClientInfo, CreateElicitationRequestParam, CreateElicitationResult,
+        CreateMessageRequestMethod, CreateMessageRequestParam, CreateMessageResult,
+        ElicitationCapability, Implementation, ListToolsResult, LoggingLevel,
+        LoggingMessageNotificationParam, ProgressNotificationParam,
+        ResourceUpdatedNotificationParam, RootsCapabilities,
     },
     service::{NotificationContext, RequestContext, RoleClient},
     transport::TokioChildProcess,
@@ -29,22 +36,60 @@ use std::process::Stdio;
 use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
-use tokio::sync::Mutex;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::{Mutex, oneshot};
 use tracing::{Level, debug, error, info, warn};
 
+/// State shared between the client and every provider connection so that
+/// provider-initiated requests can reach the host UI and the active model.
+#[derive(Default)]
+struct McpClientHost {
+    elicitation: McpElicitationConfig,
+    sampling: McpSamplingConfig,
+    interactions: RwLock<Option<UnboundedSender<McpInteraction>>>,
+    sampling_backend: RwLock<Option<McpSamplingBackend>>,
+}
+
+impl McpClientHost {
+    fn new(config: &McpClientConfig) -> Self {
+        Self {
+            elicitation: config.elicitation.clone(),
+            sampling: config.sampling.clone(),
+            ..Self::default()
+        }
+    }
+
+    fn interaction_sender(&self) -> Option<UnboundedSender<McpInteraction>> {
+        self.interactions
+            .read()
+            .as_ref()
+            .filter(|sender| !sender.is_closed())
+            .cloned()
+    }
+
+    fn sampling_backend(&self) -> Option<McpSamplingBackend> {
+        self.sampling_backend.read().clone()
+    }
+}
+
 #[derive(Clone)]
 struct LoggingClientHandler {
     provider_name: String,
     info: ClientInfo,
+    host: Arc<McpClientHost>,
 }
 
 impl LoggingClientHandler {
-    fn new(provider_name: &str) -> Self {
+    fn new(provider_name: &str, host: Arc<McpClientHost>) -> Self {
         let mut info = ClientInfo::default();
         info.capabilities = ClientCapabilities {
             roots: Some(RootsCapabilities {
                 list_changed: Some(true),
             }),
+            elicitation: host.elicitation.enabled.then_some(ElicitationCapability {
+                schema_validation: Some(true),
+            }),
+            sampling: host.sampling.enabled.then(Map::new),
             ..ClientCapabilities::default()
         };
         info.client_info = Implementation {
@@ -58,9 +103,174 @@ impl LoggingClientHandler {
         Self {
             provider_name: provider_name.to_string(),
             info,
+            host,
         }
     }
 
+    async fn elicit(
+        &self,
+        request: CreateElicitationRequestParam,
+    ) -> Result<CreateElicitationResult, rmcp::ErrorData> {
+        let CreateElicitationRequestParam {
+            message,
+            requested_schema,
+        } = request;
+
+        let sender = match self.host.interaction_sender() {
+            Some(sender) if self.host.elicitation.enabled => sender,
+            _ => {
+                info!(
+                    provider = self.provider_name.as_str(),
+                    message = message.as_str(),
+                    "MCP provider requested elicitation; declining"
+                );
+                return Ok(elicitation_declined());
+            }
+        };
+
+        let form = ElicitationForm::from_schema(&requested_schema).map_err(|err| {
+            warn!(
+                provider = self.provider_name.as_str(),
+                error = %err,
+                "MCP provider sent an unsupported elicitation schema"
+            );
+            rmcp::ErrorData::invalid_params(err.to_string(), None)
+        })?;
+
+        let (responder, receiver) = oneshot::channel();
+        let interaction = McpInteraction::Elicitation {
+            provider: self.provider_name.clone(),
+            message,
+            form,
+            responder,
+        };
+        if sender.send(interaction).is_err() {
+            return Ok(elicitation_declined());
+        }
+
+        let timeout = std::time::Duration::from_secs(self.host.elicitation.timeout_seconds);
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(result)) => {
+                info!(
+                    provider = self.provider_name.as_str(),
+                    action = ?result.action,
+                    "MCP elicitation answered"
+                );
+                Ok(result)
+            }
+            Ok(Err(_)) => Ok(elicitation_cancelled()),
+            Err(_) => {
+                warn!(
+                    provider = self.provider_name.as_str(),
+                    "MCP elicitation timed out waiting for the user"
+                );
+                Ok(elicitation_cancelled())
+            }
+        }
+    }
+
+    async fn sample(
+        &self,
+        params: CreateMessageRequestParam,
+    ) -> Result<CreateMessageResult, rmcp::ErrorData> {
+        let sampling = &self.host.sampling;
+        if !sampling.enabled {
+            return Err(rmcp::ErrorData::method_not_found::<
+                CreateMessageRequestMethod,
+            >());
+        }
+
+        let policy = sampling.policy_for(&self.provider_name);
+        if policy == McpSamplingPolicy::Deny {
+            return Err(rmcp::ErrorData::invalid_request(
+                format!(
+                    "Sampling is not permitted for provider '{}'",
+                    self.provider_name
+                ),
+                None,
+            ));
+        }
+
+        let backend = self.host.sampling_backend().ok_or_else(|| {
+            rmcp::ErrorData::internal_error("No model is available for sampling", None)
+        })?;
+        if let Some(limit) = backend
+            .cost_tracker
+            .as_ref()
+            .and_then(|tracker| tracker.lock().budget_exceeded())
+        {
+            return Err(rmcp::ErrorData::invalid_request(
+                format!("Sampling is paused: {}", limit),
+                None,
+            ));
+        }
+        let request = build_sampling_request(
+            &params,
+            &backend.model,
+            sampling.max_tokens_for(&self.provider_name),
+        )
+        .map_err(|err| rmcp::ErrorData::invalid_params(err.to_string(), None))?;
+
+        if policy == McpSamplingPolicy::Prompt {
+            let approved = match self.host.interaction_sender() {
+                Some(sender) => {
+                    let (responder, receiver) = oneshot::channel();
+                    let interaction = McpInteraction::SamplingApproval {
+                        provider: self.provider_name.clone(),
+                        summary: SamplingSummary::from_request(&request),
+                        responder,
+                    };
+                    if sender.send(interaction).is_err() {
+                        false
+                    } else {
+                        let timeout =
+                            std::time::Duration::from_secs(self.host.elicitation.timeout_seconds);
+                        match tokio::time::timeout(timeout, receiver).await {
+                            Ok(result) => result.unwrap_or(false),
+                            Err(_) => {
+                                warn!(
+                                    provider = self.provider_name.as_str(),
+                                    "MCP sampling approval timed out waiting for the user"
+                                );
+                                false
+                            }
+                        }
+                    }
+                }
+                None => false,
+            };
+            if !approved {
+                info!(
+                    provider = self.provider_name.as_str(),
+                    "MCP sampling request rejected"
+                );
+                return Err(rmcp::ErrorData::invalid_request(
+                    "User rejected the sampling request",
+                    None,
+                ));
+            }
+        }
+
+        info!(
+            provider = self.provider_name.as_str(),
+            model = backend.model.as_str(),
+            max_tokens = ?request.max_tokens,
+            "Forwarding MCP sampling request"
+        );
+        let response = backend
+            .provider
+            .generate(request)
+            .await
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))?;
+        if let (Some(tracker), Some(usage)) = (&backend.cost_tracker, response.usage.as_ref()) {
+            tracker
+                .lock()
+                .record(backend.provider.name(), &backend.model, usage);
+        }
+
+        Ok(sampling_result(&backend.model, response))
+    }
+
     fn handle_logging(&self, params: LoggingMessageNotificationParam) {
         let LoggingMessageNotificationParam {
             level,
@@ -118,16 +328,16 @@ impl ClientHandler for LoggingClientHandler {
         _context: RequestContext<RoleClient>,
     ) -> impl std::future::Future<Output = Result<CreateElicitationResult, rmcp::ErrorData>> + Send + '_
     {
-        let CreateElicitationRequestParam { message, .. } = request;
-        info!(
-            provider = self.provider_name.as_str(),
-            message = message.as_str(),
-            "MCP provider requested elicitation; declining"
-        );
-        future::ready(Ok(CreateElicitationResult {
-            action: ElicitationAction::Decline,
-            content: None,
-        }))
+        self.elicit(request)
+    }
+
+    fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> impl std::future::Future<Output = Result<CreateMessageResult, rmcp::ErrorData>> + Send + '_
+    {
+        self.sample(params)
     }
 
     fn on_cancelled(
@@ -157,6 +367,14 @@ impl ClientHandler for LoggingClientHandler {
             message = ?params.message,
             "MCP provider progress update"
         );
+        if let Some(sender) = self.host.interaction_sender() {
+            let _ = sender.send(McpInteraction::Progress(McpProgress {
+                provider: self.provider_name.clone(),
+                progress: params.progress,
+                total: params.total,
+                message: params.message,
+            }));
+        }
         future::ready(())
     }
 
@@ -227,18 +445,21 @@ pub struct McpClient {
     active_connections: Arc<Mutex<HashMap<String, Arc<RunningMcpService>>>>,
     allowlist: Arc<RwLock<McpAllowListConfig>>,
     tool_provider_index: Arc<RwLock<HashMap<String, String>>>,
+    host: Arc<McpClientHost>,
 }
 
 impl McpClient {
     /// Create a new MCP client with the given configuration
     pub fn new(config: McpClientConfig) -> Self {
         let allowlist = Arc::new(RwLock::new(config.allowlist.clone()));
+        let host = Arc::new(McpClientHost::new(&config));
         Self {
             config,
             providers: HashMap::new(),
             active_connections: Arc::new(Mutex::new(HashMap::new())),
             allowlist,
             tool_provider_index: Arc::new(RwLock::new(HashMap::new())),
+            host,
         }
     }
 
@@ -270,6 +491,16 @@ impl McpClient {
         }
     }
 
+    /// Route elicitation and sampling approval requests to the given channel
+    pub fn set_interaction_sender(&self, sender: UnboundedSender<McpInteraction>) {
+        *self.host.interactions.write() = Some(sender);
+    }
+
+    /// Use the given model to answer `sampling/createMessage` requests
+    pub fn set_sampling_backend(&self, backend: McpSamplingBackend) {
+        *self.host.sampling_backend.write() = Some(backend);
+    }
+
     /// Replace the in-memory MCP allow list with the provided configuration
     pub fn update_allowlist(&self, allowlist: McpAllowListConfig) {
         *self.allowlist.write() = allowlist;
@@ -378,7 +609,8 @@ impl McpClient {
                 info!("Initializing MCP provider '{}'", provider_config.name);
 
                 match McpProvider::new(provider_config.clone()).await {
-                    Ok(provider) => {
+                    Ok(mut provider) => {
+                        provider.host = Arc::clone(&self.host);
                         let provider = Arc::new(provider);
                         self.providers
                             .insert(provider_config.name.clone(), provider);
@@ -1312,6 +1544,7 @@ pub struct McpToolInfo {
 pub struct McpProvider {
     config: McpProviderConfig,
     tools_cache: Arc<Mutex<Option<ListToolsResult>>>,
+    host: Arc<McpClientHost>,
 }
 
 impl McpProvider {
@@ -1320,6 +1553,7 @@ impl McpProvider {
         Ok(Self {
             config,
             tools_cache: Arc::new(Mutex::new(None)),
+            host: Arc::new(McpClientHost::default()),
         })
     }
 
@@ -1682,7 +1916,7 @@ impl McpProvider {
                 }
 
                 // Add timeout and better error handling for the MCP service
-                let handler = LoggingClientHandler::new(provider_name);
+                let handler = LoggingClientHandler::new(provider_name, Arc::clone(&self.host));
 
                 match tokio::time::timeout(
                     tokio::time::Duration::from_secs(30),
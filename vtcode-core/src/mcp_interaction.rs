@@ -0,0 +1,563 @@
+//! Interactive requests raised by MCP providers
+//!
+//! MCP servers can ask the client for user input (`elicitation/create`) or for
+//! a model completion (`sampling/createMessage`) while a tool call is running,
+//! and report how far the call has got (`notifications/progress`).
+//! This module describes those requests in a UI-agnostic form so the host
+//! (inline TUI, ACP bridge, ...) can answer or display them.
+
+use anyhow::{Result, anyhow};
+use rmcp::model::{
+    Content, CreateMessageRequestParam, CreateMessageResult, ElicitationAction, Role,
+    SamplingMessage,
+};
+use serde_json::{Map, Number, Value};
+use std::sync::Arc;
+use tokio::sync::oneshot;
+
+use crate::core::cost_tracker::SharedCostTracker;
+use crate::llm::provider::{FinishReason, LLMProvider, LLMRequest, LLMResponse, Message};
+
+pub use rmcp::model::CreateElicitationResult;
+
+/// A request from an MCP provider that needs an answer from the host UI
+pub enum McpInteraction {
+    /// The provider asks the user to fill in a form
+    Elicitation {
+        provider: String,
+        message: String,
+        form: ElicitationForm,
+        responder: oneshot::Sender<CreateElicitationResult>,
+    },
+    /// The provider wants to run a completion against the active model
+    SamplingApproval {
+        provider: String,
+        summary: SamplingSummary,
+        responder: oneshot::Sender<bool>,
+    },
+    /// The provider reports progress on a running tool call; no answer is expected
+    Progress(McpProgress),
+}
+
+/// Progress notification for a running tool call
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpProgress {
+    pub provider: String,
+    pub progress: f64,
+    pub total: Option<f64>,
+    pub message: Option<String>,
+}
+
+impl McpProgress {
+    /// Short status such as `3/10 Indexing files` or `42`
+    pub fn label(&self) -> String {
+        let amount = |value: f64| {
+            if value.fract() == 0.0 {
+                format!("{}", value as i64)
+            } else {
+                format!("{value:.1}")
+            }
+        };
+        let mut label = match self.total {
+            Some(total) if total > 0.0 => format!("{}/{}", amount(self.progress), amount(total)),
+            _ => amount(self.progress),
+        };
+        if let Some(message) = self.message.as_deref().map(str::trim)
+            && !message.is_empty()
+        {
+            label.push(' ');
+            label.push_str(message);
+        }
+        label
+    }
+}
+
+/// Short description of a sampling request shown to the user before approval
+#[derive(Debug, Clone)]
+pub struct SamplingSummary {
+    pub model: String,
+    pub max_tokens: u32,
+    pub message_count: usize,
+    pub system_prompt: Option<String>,
+    pub last_message: Option<String>,
+}
+
+/// Model used to answer sampling requests from MCP providers
+#[derive(Clone)]
+pub struct McpSamplingBackend {
+    pub provider: Arc<dyn LLMProvider>,
+    pub model: String,
+    /// Session spend tracker; sampling is refused once a limit is reached
+    pub cost_tracker: Option<SharedCostTracker>,
+}
+
+/// Primitive field kinds supported by the MCP elicitation schema subset
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElicitationFieldKind {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    /// String constrained to a fixed set of values, with optional display labels
+    Enum {
+        values: Vec<String>,
+        labels: Vec<String>,
+    },
+}
+
+/// A single input rendered from a `properties` entry of the requested schema
+#[derive(Debug, Clone)]
+pub struct ElicitationField {
+    pub name: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub kind: ElicitationFieldKind,
+    pub required: bool,
+    pub default: Option<Value>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+}
+
+impl ElicitationField {
+    /// Label shown to the user, falling back to the property name
+    pub fn label(&self) -> &str {
+        self.title.as_deref().unwrap_or(self.name.as_str())
+    }
+
+    /// Short type hint such as `string`, `integer 1..10` or `one of a, b`
+    pub fn type_hint(&self) -> String {
+        let base = match &self.kind {
+            ElicitationFieldKind::String => "string".to_string(),
+            ElicitationFieldKind::Number => "number".to_string(),
+            ElicitationFieldKind::Integer => "integer".to_string(),
+            ElicitationFieldKind::Boolean => "yes/no".to_string(),
+            ElicitationFieldKind::Enum { values, .. } => format!("one of {}", values.join(", ")),
+        };
+        match (self.minimum, self.maximum) {
+            (Some(min), Some(max)) => format!("{base} {min}..{max}"),
+            (Some(min), None) => format!("{base} >= {min}"),
+            (None, Some(max)) => format!("{base} <= {max}"),
+            (None, None) => base,
+        }
+    }
+
+    /// Convert raw user input into a JSON value that satisfies the field constraints.
+    ///
+    /// Returns `Ok(None)` when the input is empty and the field is optional.
+    pub fn parse_input(&self, input: &str) -> Result<Option<Value>> {
+        let trimmed = input.trim();
+        if trimmed.is_empty() {
+            if let Some(default) = &self.default {
+                return Ok(Some(default.clone()));
+            }
+            if self.required {
+                return Err(anyhow!("'{}' is required", self.label()));
+            }
+            return Ok(None);
+        }
+
+        let value = match &self.kind {
+            ElicitationFieldKind::String => {
+                let length = trimmed.chars().count();
+                if let Some(min) = self.min_length
+                    && length < min
+                {
+                    return Err(anyhow!(
+                        "'{}' must be at least {} characters",
+                        self.label(),
+                        min
+                    ));
+                }
+                if let Some(max) = self.max_length
+                    && length > max
+                {
+                    return Err(anyhow!(
+                        "'{}' must be at most {} characters",
+                        self.label(),
+                        max
+                    ));
+                }
+                Value::String(trimmed.to_string())
+            }
+            ElicitationFieldKind::Number => {
+                let number: f64 = trimmed
+                    .parse()
+                    .map_err(|_| anyhow!("'{}' must be a number", self.label()))?;
+                self.check_range(number)?;
+                Number::from_f64(number)
+                    .map(Value::Number)
+                    .ok_or_else(|| anyhow!("'{}' must be a finite number", self.label()))?
+            }
+            ElicitationFieldKind::Integer => {
+                let number: i64 = trimmed
+                    .parse()
+                    .map_err(|_| anyhow!("'{}' must be an integer", self.label()))?;
+                self.check_range(number as f64)?;
+                Value::Number(number.into())
+            }
+            ElicitationFieldKind::Boolean => match trimmed.to_lowercase().as_str() {
+                "y" | "yes" | "true" | "1" => Value::Bool(true),
+                "n" | "no" | "false" | "0" => Value::Bool(false),
+                _ => return Err(anyhow!("'{}' must be yes or no", self.label())),
+            },
+            ElicitationFieldKind::Enum { values, labels } => {
+                let matched = values
+                    .iter()
+                    .zip(labels.iter())
+                    .find(|(value, label)| {
+                        value.eq_ignore_ascii_case(trimmed) || label.eq_ignore_ascii_case(trimmed)
+                    })
+                    .map(|(value, _)| value.clone())
+                    .ok_or_else(|| {
+                        anyhow!("'{}' must be one of: {}", self.label(), values.join(", "))
+                    })?;
+                Value::String(matched)
+            }
+        };
+
+        Ok(Some(value))
+    }
+
+    fn check_range(&self, number: f64) -> Result<()> {
+        if let Some(min) = self.minimum
+            && number < min
+        {
+            return Err(anyhow!("'{}' must be >= {}", self.label(), min));
+        }
+        if let Some(max) = self.maximum
+            && number > max
+        {
+            return Err(anyhow!("'{}' must be <= {}", self.label(), max));
+        }
+        Ok(())
+    }
+}
+
+/// Form derived from the `requestedSchema` of an elicitation request
+#[derive(Debug, Clone, Default)]
+pub struct ElicitationForm {
+    pub fields: Vec<ElicitationField>,
+}
+
+impl ElicitationForm {
+    /// Build a form from a flat JSON object schema.
+    ///
+    /// Nested objects and arrays are outside the MCP elicitation subset and are rejected.
+    pub fn from_schema(schema: &Map<String, Value>) -> Result<Self> {
+        if let Some(kind) = schema.get("type").and_then(Value::as_str)
+            && kind != "object"
+        {
+            return Err(anyhow!(
+                "elicitation schema must be an object, got '{kind}'"
+            ));
+        }
+
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut fields = Vec::new();
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, property) in properties {
+                let property = property
+                    .as_object()
+                    .ok_or_else(|| anyhow!("property '{name}' must be an object schema"))?;
+                fields.push(Self::parse_field(
+                    name,
+                    property,
+                    required.contains(&name.as_str()),
+                )?);
+            }
+        }
+
+        Ok(Self { fields })
+    }
+
+    fn parse_field(
+        name: &str,
+        property: &Map<String, Value>,
+        required: bool,
+    ) -> Result<ElicitationField> {
+        let string_list = |key: &str| -> Option<Vec<String>> {
+            property.get(key).and_then(Value::as_array).map(|items| {
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_owned)
+                    .collect()
+            })
+        };
+
+        let kind = if let Some(values) = string_list("enum") {
+            let labels = string_list("enumNames")
+                .filter(|labels| labels.len() == values.len())
+                .unwrap_or_else(|| values.clone());
+            ElicitationFieldKind::Enum { values, labels }
+        } else {
+            match property.get("type").and_then(Value::as_str) {
+                Some("string") | None => ElicitationFieldKind::String,
+                Some("number") => ElicitationFieldKind::Number,
+                Some("integer") => ElicitationFieldKind::Integer,
+                Some("boolean") => ElicitationFieldKind::Boolean,
+                Some(other) => {
+                    return Err(anyhow!("property '{name}' has unsupported type '{other}'"));
+                }
+            }
+        };
+
+        let text = |key: &str| property.get(key).and_then(Value::as_str).map(str::to_owned);
+        let count = |key: &str| {
+            property
+                .get(key)
+                .and_then(Value::as_u64)
+                .map(|value| value as usize)
+        };
+
+        Ok(ElicitationField {
+            name: name.to_string(),
+            title: text("title"),
+            description: text("description"),
+            kind,
+            required,
+            default: property.get("default").cloned(),
+            min_length: count("minLength"),
+            max_length: count("maxLength"),
+            minimum: property.get("minimum").and_then(Value::as_f64),
+            maximum: property.get("maximum").and_then(Value::as_f64),
+        })
+    }
+
+    /// Check that every required field is present in the collected content
+    pub fn validate(&self, content: &Map<String, Value>) -> Result<()> {
+        for field in &self.fields {
+            if field.required && !content.contains_key(&field.name) {
+                return Err(anyhow!("'{}' is required", field.label()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Result sent back when the user declines an elicitation request
+pub fn elicitation_declined() -> CreateElicitationResult {
+    CreateElicitationResult {
+        action: ElicitationAction::Decline,
+        content: None,
+    }
+}
+
+/// Result sent back when the user cancels an elicitation request
+pub fn elicitation_cancelled() -> CreateElicitationResult {
+    CreateElicitationResult {
+        action: ElicitationAction::Cancel,
+        content: None,
+    }
+}
+
+/// Result sent back when the user accepts an elicitation request
+pub fn elicitation_accepted(content: Map<String, Value>) -> CreateElicitationResult {
+    CreateElicitationResult {
+        action: ElicitationAction::Accept,
+        content: Some(Value::Object(content)),
+    }
+}
+
+impl SamplingSummary {
+    /// Summarize a sampling request for display in an approval prompt
+    pub fn from_request(request: &LLMRequest) -> Self {
+        Self {
+            model: request.model.clone(),
+            max_tokens: request.max_tokens.unwrap_or_default(),
+            message_count: request.messages.len(),
+            system_prompt: request.system_prompt.clone(),
+            last_message: request
+                .messages
+                .last()
+                .map(|message| message.content.clone()),
+        }
+    }
+}
+
+/// Translate a `sampling/createMessage` request into an [`LLMRequest`] for the given model.
+///
+/// The requested `max_tokens` is clamped to `max_tokens_cap`. Only text content is supported.
+pub fn build_sampling_request(
+    params: &CreateMessageRequestParam,
+    model: &str,
+    max_tokens_cap: u32,
+) -> Result<LLMRequest> {
+    let mut messages = Vec::with_capacity(params.messages.len());
+    for message in &params.messages {
+        let text = message
+            .content
+            .as_text()
+            .map(|content| content.text.clone())
+            .ok_or_else(|| anyhow!("only text content is supported in sampling requests"))?;
+        messages.push(match message.role {
+            Role::User => Message::user(text),
+            Role::Assistant => Message::assistant(text),
+        });
+    }
+
+    if messages.is_empty() {
+        return Err(anyhow!("sampling request contains no messages"));
+    }
+
+    Ok(LLMRequest {
+        messages,
+        system_prompt: params.system_prompt.clone(),
+        tools: None,
+        model: model.to_string(),
+        max_tokens: Some(params.max_tokens.min(max_tokens_cap)),
+        temperature: params.temperature,
+        stream: false,
+        tool_choice: None,
+        parallel_tool_calls: None,
+        parallel_tool_config: None,
+        reasoning_effort: None,
+        response_format: None,
+    })
+}
+
+/// Convert a provider response into the `sampling/createMessage` result
+pub fn sampling_result(model: &str, response: LLMResponse) -> CreateMessageResult {
+    let stop_reason = match response.finish_reason {
+        FinishReason::Length => CreateMessageResult::STOP_REASON_END_MAX_TOKEN.to_string(),
+        FinishReason::Error(reason) => reason,
+        _ => CreateMessageResult::STOP_REASON_END_TURN.to_string(),
+    };
+    CreateMessageResult {
+        model: model.to_string(),
+        stop_reason: Some(stop_reason),
+        message: SamplingMessage {
+            role: Role::Assistant,
+            content: Content::text(response.content.unwrap_or_default()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn form(schema: Value) -> ElicitationForm {
+        let schema = schema.as_object().cloned().unwrap_or_default();
+        ElicitationForm::from_schema(&schema).unwrap()
+    }
+
+    #[test]
+    fn parses_primitive_fields() {
+        let form = form(json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "title": "Name", "minLength": 2},
+                "age": {"type": "integer", "minimum": 0, "maximum": 150},
+                "subscribe": {"type": "boolean", "default": false},
+                "color": {"type": "string", "enum": ["red", "green"], "enumNames": ["Red", "Green"]}
+            },
+            "required": ["name", "age"]
+        }));
+
+        assert_eq!(form.fields.len(), 4);
+        let name = form.fields.iter().find(|f| f.name == "name").unwrap();
+        assert!(name.required);
+        assert_eq!(name.label(), "Name");
+        let color = form.fields.iter().find(|f| f.name == "color").unwrap();
+        assert!(matches!(color.kind, ElicitationFieldKind::Enum { .. }));
+    }
+
+    #[test]
+    fn builds_sampling_request_with_token_cap() {
+        let params: CreateMessageRequestParam = serde_json::from_value(json!({
+            "messages": [
+                {"role": "user", "content": {"type": "text", "text": "Summarize the diff"}}
+            ],
+            "systemPrompt": "Be brief",
+            "maxTokens": 4096
+        }))
+        .unwrap();
+
+        let request = build_sampling_request(&params, "gpt-5", 512).unwrap();
+        assert_eq!(request.model, "gpt-5");
+        assert_eq!(request.max_tokens, Some(512));
+        assert_eq!(request.messages.len(), 1);
+        assert_eq!(request.system_prompt.as_deref(), Some("Be brief"));
+
+        let summary = SamplingSummary::from_request(&request);
+        assert_eq!(summary.last_message.as_deref(), Some("Summarize the diff"));
+    }
+
+    #[test]
+    fn formats_progress_labels() {
+        let mut progress = McpProgress {
+            provider: "indexer".to_string(),
+            progress: 3.0,
+            total: Some(10.0),
+            message: Some("Indexing files".to_string()),
+        };
+        assert_eq!(progress.label(), "3/10 Indexing files");
+
+        progress.total = None;
+        progress.progress = 0.5;
+        progress.message = None;
+        assert_eq!(progress.label(), "0.5");
+    }
+
+    #[test]
+    fn rejects_nested_schemas() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"tags": {"type": "array"}}
+        });
+        let schema = schema.as_object().cloned().unwrap_or_default();
+        assert!(ElicitationForm::from_schema(&schema).is_err());
+    }
+
+    #[test]
+    fn coerces_and_validates_input() {
+        let form = form(json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "minLength": 2},
+                "age": {"type": "integer", "minimum": 0, "maximum": 150},
+                "subscribe": {"type": "boolean", "default": false},
+                "color": {"type": "string", "enum": ["red", "green"], "enumNames": ["Red", "Green"]}
+            },
+            "required": ["name"]
+        }));
+        let field = |name: &str| form.fields.iter().find(|f| f.name == name).unwrap();
+
+        assert!(field("name").parse_input("").is_err());
+        assert!(field("name").parse_input("a").is_err());
+        assert_eq!(
+            field("name").parse_input("Ada").unwrap(),
+            Some(json!("Ada"))
+        );
+        assert_eq!(field("age").parse_input("42").unwrap(), Some(json!(42)));
+        assert!(field("age").parse_input("200").is_err());
+        assert_eq!(field("age").parse_input("").unwrap(), None);
+        assert_eq!(
+            field("subscribe").parse_input("").unwrap(),
+            Some(json!(false))
+        );
+        assert_eq!(
+            field("subscribe").parse_input("yes").unwrap(),
+            Some(json!(true))
+        );
+        assert_eq!(
+            field("color").parse_input("Green").unwrap(),
+            Some(json!("green"))
+        );
+        assert!(field("color").parse_input("blue").is_err());
+
+        let mut content = Map::new();
+        assert!(form.validate(&content).is_err());
+        content.insert("name".to_string(), json!("Ada"));
+        assert!(form.validate(&content).is_ok());
+    }
+}
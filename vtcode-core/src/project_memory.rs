@@ -0,0 +1,626 @@
+//! Persistent memory files that carry learned facts between sessions.
+//!
+//! Facts live as Markdown bullet lists in `<workspace>/.vtcode/MEMORY.md` (project scope,
+//! meant to be committed) and `~/.vtcode/MEMORY.md` (user scope). A line of the form
+//! `@import path/to/notes.md` pulls another Markdown file into the memory when it is loaded.
+//! Imports must stay inside the workspace (project memory) or `~/.vtcode` (user memory).
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tracing::warn;
+
+use crate::config::MemoryConfig;
+use crate::core::tokenizer::{ProviderTokenizer, Tokenizer};
+use crate::project_doc::ProjectDocBundle;
+
+const MEMORY_DIR: &str = ".vtcode";
+const MEMORY_FILENAME: &str = "MEMORY.md";
+const IMPORT_DIRECTIVE: &str = "@import";
+const ENTRY_PREFIX: &str = "- ";
+const MAX_IMPORT_DEPTH: usize = 4;
+const MAX_ENTRY_CHARS: usize = 500;
+const PROMPT_HEADING: &str = "## PROJECT MEMORY";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryScope {
+    Project,
+    User,
+}
+
+impl MemoryScope {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MemoryScope::Project => "project",
+            MemoryScope::User => "user",
+        }
+    }
+
+    fn file_header(&self) -> &'static str {
+        match self {
+            MemoryScope::Project => {
+                "# Project memory\n\nFacts vtcode remembers about this project. Edit freely; `@import path.md` includes another file.\n\n"
+            }
+            MemoryScope::User => {
+                "# User memory\n\nFacts vtcode remembers about you across projects. Edit freely; `@import path.md` includes another file.\n\n"
+            }
+        }
+    }
+}
+
+impl fmt::Display for MemoryScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for MemoryScope {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "project" | "workspace" => Ok(MemoryScope::Project),
+            "user" | "global" => Ok(MemoryScope::User),
+            other => Err(anyhow!(
+                "Unknown memory scope '{}'. Use 'project' or 'user'.",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RememberAction {
+    Add,
+    List,
+    Update,
+    Remove,
+}
+
+#[derive(Debug, Deserialize)]
+struct RememberArgs {
+    #[serde(default = "default_action")]
+    action: RememberAction,
+    #[serde(default = "default_scope")]
+    scope: MemoryScope,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    index: Option<usize>,
+}
+
+fn default_action() -> RememberAction {
+    RememberAction::Add
+}
+
+fn default_scope() -> MemoryScope {
+    MemoryScope::Project
+}
+
+/// Reads and edits the project and user memory files.
+#[derive(Debug, Clone)]
+pub struct MemoryStore {
+    project_path: PathBuf,
+    user_path: Option<PathBuf>,
+}
+
+impl MemoryStore {
+    /// Memory files for `workspace` and the current user.
+    pub fn for_workspace(workspace: &Path) -> Self {
+        Self::new(
+            workspace.join(MEMORY_DIR).join(MEMORY_FILENAME),
+            dirs::home_dir().map(|home| home.join(MEMORY_DIR).join(MEMORY_FILENAME)),
+        )
+    }
+
+    pub fn new(project_path: PathBuf, user_path: Option<PathBuf>) -> Self {
+        Self {
+            project_path,
+            user_path,
+        }
+    }
+
+    pub fn path(&self, scope: MemoryScope) -> Option<&Path> {
+        match scope {
+            MemoryScope::Project => Some(&self.project_path),
+            MemoryScope::User => self.user_path.as_deref(),
+        }
+    }
+
+    fn require_path(&self, scope: MemoryScope) -> Result<&Path> {
+        self.path(scope)
+            .ok_or_else(|| anyhow!("No home directory available for user memory"))
+    }
+
+    /// Entries written directly in the scope's memory file (imports excluded).
+    pub fn entries(&self, scope: MemoryScope) -> Result<Vec<String>> {
+        let Some(path) = self.path(scope) else {
+            return Ok(Vec::new());
+        };
+        Ok(read_lines(path)?
+            .iter()
+            .filter_map(|line| line.strip_prefix(ENTRY_PREFIX))
+            .map(|entry| entry.trim().to_string())
+            .collect())
+    }
+
+    /// Append an entry and return its 1-based index. Duplicates are not added twice.
+    pub fn add(&self, scope: MemoryScope, text: &str) -> Result<usize> {
+        let entry = normalize_entry(text)?;
+        let entries = self.entries(scope)?;
+        if let Some(position) = entries.iter().position(|existing| *existing == entry) {
+            return Ok(position + 1);
+        }
+
+        let path = self.require_path(scope)?;
+        let mut lines = read_lines(path)?;
+        if lines.is_empty() {
+            lines = scope.file_header().lines().map(str::to_string).collect();
+        }
+        lines.push(format!("{}{}", ENTRY_PREFIX, entry));
+        write_lines(path, &lines)?;
+        Ok(entries.len() + 1)
+    }
+
+    /// Replace the entry at the 1-based `index`, returning the previous text.
+    pub fn update(&self, scope: MemoryScope, index: usize, text: &str) -> Result<String> {
+        let entry = normalize_entry(text)?;
+        self.modify_entry(scope, index, Some(entry))
+    }
+
+    /// Delete the entry at the 1-based `index`, returning its text.
+    pub fn remove(&self, scope: MemoryScope, index: usize) -> Result<String> {
+        self.modify_entry(scope, index, None)
+    }
+
+    fn modify_entry(
+        &self,
+        scope: MemoryScope,
+        index: usize,
+        replacement: Option<String>,
+    ) -> Result<String> {
+        let path = self.require_path(scope)?;
+        let mut lines = read_lines(path)?;
+        let line_index = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.starts_with(ENTRY_PREFIX))
+            .map(|(line_index, _)| line_index)
+            .nth(index.wrapping_sub(1))
+            .ok_or_else(|| anyhow!("No {} memory entry #{}", scope, index))?;
+
+        let previous = lines[line_index][ENTRY_PREFIX.len()..].trim().to_string();
+        match replacement {
+            Some(entry) => lines[line_index] = format!("{}{}", ENTRY_PREFIX, entry),
+            None => {
+                lines.remove(line_index);
+            }
+        }
+        write_lines(path, &lines)?;
+        Ok(previous)
+    }
+
+    /// Load project and (optionally) user memory with `@import`s expanded.
+    ///
+    /// Files that cannot be read are logged and skipped so one bad file does not
+    /// drop the rest of the memory.
+    pub fn load_bundle(&self, include_user: bool) -> Result<Option<ProjectDocBundle>> {
+        // Project memory lives in `<workspace>/.vtcode`, user memory in `~/.vtcode`.
+        let project_root = self
+            .project_path
+            .parent()
+            .and_then(Path::parent)
+            .unwrap_or_else(|| Path::new("."));
+        let mut files = vec![(self.project_path.as_path(), project_root)];
+        if include_user
+            && let Some(user) = self.user_path.as_deref()
+            && let Some(user_root) = user.parent()
+        {
+            files.push((user, user_root));
+        }
+
+        let mut visited = HashSet::new();
+        let mut sources = Vec::new();
+        let mut parts = Vec::new();
+        for (path, root) in files {
+            let Ok(root) = root.canonicalize() else {
+                continue;
+            };
+            let mut contents = String::new();
+            let mut expansion = ImportExpansion {
+                root: &root,
+                visited: &mut visited,
+                sources: &mut sources,
+            };
+            if let Err(err) = expansion.expand_file(path, 0, &mut contents) {
+                warn!("Skipping memory file {}: {err:#}", path.display());
+            }
+            if !contents.trim().is_empty() {
+                parts.push(contents);
+            }
+        }
+
+        if parts.is_empty() {
+            return Ok(None);
+        }
+        let contents = parts.join("\n\n");
+        Ok(Some(ProjectDocBundle {
+            bytes_read: contents.len(),
+            contents,
+            sources,
+            truncated: false,
+        }))
+    }
+
+    /// System prompt section listing remembered facts within the configured token budget,
+    /// counted with the active provider's `tokenizer`.
+    pub fn prompt_section(
+        &self,
+        config: &MemoryConfig,
+        tokenizer: &ProviderTokenizer,
+    ) -> Option<String> {
+        if !config.enabled || config.max_tokens == 0 {
+            return None;
+        }
+        let bundle = match self.load_bundle(config.include_user_memory) {
+            Ok(Some(bundle)) => bundle,
+            Ok(None) => return None,
+            Err(err) => {
+                warn!("failed to load memory files: {err:#}");
+                return None;
+            }
+        };
+
+        let highlights = bundle.highlights(usize::MAX);
+        if highlights.is_empty() {
+            return None;
+        }
+
+        let mut section = format!(
+            "{}\nFacts remembered from earlier sessions. Use the `remember` tool to add, correct or remove entries.\n",
+            PROMPT_HEADING
+        );
+        let mut used_tokens = 0usize;
+        let mut included = 0usize;
+        for highlight in &highlights {
+            let tokens = tokenizer.count(&format!("- {}\n", highlight));
+            if used_tokens + tokens > config.max_tokens {
+                break;
+            }
+            used_tokens += tokens;
+            included += 1;
+            section.push_str(&format!("- {}\n", highlight));
+        }
+
+        if included == 0 {
+            return None;
+        }
+        if included < highlights.len() {
+            section.push_str(&format!(
+                "({} more entries omitted to stay within the memory budget)\n",
+                highlights.len() - included
+            ));
+        }
+        Some(section)
+    }
+
+    /// Execute the `remember` tool.
+    pub fn execute_tool(&self, args: Value) -> Result<Value> {
+        let args: RememberArgs = serde_json::from_value(args)
+            .context("remember expects {action, scope, text, index}")?;
+        let scope = args.scope;
+        let path = self.require_path(scope)?.display().to_string();
+
+        let message = match args.action {
+            RememberAction::List => None,
+            RememberAction::Add => {
+                let text = args
+                    .text
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("remember add requires 'text'"))?;
+                let index = self.add(scope, text)?;
+                Some(format!("Remembered as {} memory entry #{}", scope, index))
+            }
+            RememberAction::Update => {
+                let index = args
+                    .index
+                    .ok_or_else(|| anyhow!("remember update requires 'index'"))?;
+                let text = args
+                    .text
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("remember update requires 'text'"))?;
+                self.update(scope, index, text)?;
+                Some(format!("Updated {} memory entry #{}", scope, index))
+            }
+            RememberAction::Remove => {
+                let index = args
+                    .index
+                    .ok_or_else(|| anyhow!("remember remove requires 'index'"))?;
+                let removed = self.remove(scope, index)?;
+                Some(format!("Removed {} memory entry: {}", scope, removed))
+            }
+        };
+
+        let entries: Vec<Value> = self
+            .entries(scope)?
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| json!({ "index": index + 1, "text": text }))
+            .collect();
+        Ok(json!({
+            "success": true,
+            "scope": scope,
+            "path": path,
+            "message": message,
+            "entries": entries,
+        }))
+    }
+}
+
+fn normalize_entry(text: &str) -> Result<String> {
+    let entry = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if entry.is_empty() {
+        bail!("Memory entries cannot be empty");
+    }
+    if entry.chars().count() > MAX_ENTRY_CHARS {
+        bail!(
+            "Memory entries are limited to {} characters; keep them to one fact",
+            MAX_ENTRY_CHARS
+        );
+    }
+    Ok(entry)
+}
+
+fn read_lines(path: &Path) -> Result<Vec<String>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents.lines().map(str::to_string).collect()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+fn write_lines(path: &Path, lines: &[String]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Walks a memory file and its `@import`s without leaving `root`.
+struct ImportExpansion<'a> {
+    root: &'a Path,
+    visited: &'a mut HashSet<PathBuf>,
+    sources: &'a mut Vec<PathBuf>,
+}
+
+impl ImportExpansion<'_> {
+    fn expand_file(&mut self, path: &Path, depth: usize, out: &mut String) -> Result<()> {
+        let canonical = match path.canonicalize() {
+            Ok(canonical) => canonical,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to resolve {}", path.display()));
+            }
+        };
+        if !canonical.starts_with(self.root) {
+            bail!("{} is outside {}", canonical.display(), self.root.display());
+        }
+        if !self.visited.insert(canonical.clone()) {
+            return Ok(());
+        }
+
+        let contents = fs::read_to_string(&canonical)
+            .with_context(|| format!("Failed to read memory file {}", canonical.display()))?;
+        self.sources.push(canonical.clone());
+        let base_dir = canonical.parent().unwrap_or_else(|| Path::new("."));
+
+        for line in contents.lines() {
+            let Some(target) = line.trim().strip_prefix(IMPORT_DIRECTIVE) else {
+                out.push_str(line);
+                out.push('\n');
+                continue;
+            };
+            let target = target.trim();
+            if target.is_empty() {
+                continue;
+            }
+            if depth >= MAX_IMPORT_DEPTH {
+                warn!(
+                    "Skipping memory import '{}' in {}: nesting deeper than {}",
+                    target,
+                    canonical.display(),
+                    MAX_IMPORT_DEPTH
+                );
+                continue;
+            }
+            let resolved = resolve_import(base_dir, target);
+            if !resolved.exists() {
+                warn!(
+                    "Memory import '{}' in {} does not exist",
+                    target,
+                    canonical.display()
+                );
+                continue;
+            }
+            if let Err(err) = self.expand_file(&resolved, depth + 1, out) {
+                warn!(
+                    "Skipping memory import '{}' in {}: {err:#}",
+                    target,
+                    canonical.display()
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn resolve_import(base_dir: &Path, target: &str) -> PathBuf {
+    if let Some(rest) = target.strip_prefix("~/")
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(rest);
+    }
+    base_dir.join(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn tokenizer() -> ProviderTokenizer {
+        ProviderTokenizer::for_model(crate::config::models::Provider::Anthropic, "")
+    }
+
+    fn store(tmp: &TempDir) -> MemoryStore {
+        MemoryStore::new(
+            tmp.path().join("project").join(MEMORY_FILENAME),
+            Some(tmp.path().join("home").join(MEMORY_FILENAME)),
+        )
+    }
+
+    #[test]
+    fn add_update_and_remove_entries() {
+        let tmp = TempDir::new().unwrap();
+        let store = store(&tmp);
+
+        assert_eq!(
+            store
+                .add(MemoryScope::Project, "Run `make gen` before cargo build")
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            store
+                .add(
+                    MemoryScope::Project,
+                    "Integration tests need\n  DOCKER_HOST set"
+                )
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            store
+                .add(MemoryScope::Project, "Run `make gen` before cargo build")
+                .unwrap(),
+            1
+        );
+
+        store
+            .update(
+                MemoryScope::Project,
+                2,
+                "Integration tests need Docker running",
+            )
+            .unwrap();
+        let removed = store.remove(MemoryScope::Project, 1).unwrap();
+        assert_eq!(removed, "Run `make gen` before cargo build");
+        assert_eq!(
+            store.entries(MemoryScope::Project).unwrap(),
+            vec!["Integration tests need Docker running"]
+        );
+        assert!(store.remove(MemoryScope::Project, 5).is_err());
+        assert!(store.remove(MemoryScope::Project, 0).is_err());
+
+        let contents = fs::read_to_string(store.path(MemoryScope::Project).unwrap()).unwrap();
+        assert!(contents.starts_with("# Project memory"));
+    }
+
+    #[test]
+    fn prompt_section_expands_imports_within_budget() {
+        let tmp = TempDir::new().unwrap();
+        let store = store(&tmp);
+        store
+            .add(MemoryScope::Project, "Use nightly rustfmt")
+            .unwrap();
+        let project_dir = tmp.path().join("project");
+        fs::write(
+            project_dir.join("build.md"),
+            "- Set RUSTFLAGS=-Dwarnings in CI\n",
+        )
+        .unwrap();
+        let project_file = project_dir.join(MEMORY_FILENAME);
+        let mut contents = fs::read_to_string(&project_file).unwrap();
+        contents.push_str("@import build.md\n@import MEMORY.md\n");
+        fs::write(&project_file, contents).unwrap();
+        store
+            .add(MemoryScope::User, "Prefers concise answers")
+            .unwrap();
+
+        let section = store
+            .prompt_section(&MemoryConfig::default(), &tokenizer())
+            .unwrap();
+        assert!(section.contains("- Use nightly rustfmt"));
+        assert!(section.contains("- Set RUSTFLAGS=-Dwarnings in CI"));
+        assert!(section.contains("- Prefers concise answers"));
+        assert_eq!(store.entries(MemoryScope::Project).unwrap().len(), 1);
+
+        let tight = MemoryConfig {
+            max_tokens: 10,
+            include_user_memory: false,
+            ..MemoryConfig::default()
+        };
+        let section = store.prompt_section(&tight, &tokenizer()).unwrap();
+        assert!(section.contains("- Use nightly rustfmt"));
+        assert!(section.contains("1 more entries omitted"));
+        assert!(!section.contains("Prefers concise answers"));
+    }
+
+    #[test]
+    fn imports_outside_memory_roots_are_skipped() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path().join("workspace");
+        let memory_dir = workspace.join(MEMORY_DIR);
+        fs::create_dir_all(&memory_dir).unwrap();
+        fs::write(tmp.path().join("secret.md"), "- outside secret\n").unwrap();
+        fs::write(workspace.join("notes.md"), "- inside note\n").unwrap();
+        fs::create_dir(workspace.join("folder.md")).unwrap();
+        fs::write(workspace.join("binary.md"), [0xff, 0xfe, 0x00]).unwrap();
+        let secret = tmp.path().join("secret.md").canonicalize().unwrap();
+        fs::write(
+            memory_dir.join(MEMORY_FILENAME),
+            format!(
+                "- kept entry\n@import ../../secret.md\n@import {}\n@import ../folder.md\n@import ../binary.md\n@import ../notes.md\n",
+                secret.display()
+            ),
+        )
+        .unwrap();
+        let store = MemoryStore::new(memory_dir.join(MEMORY_FILENAME), None);
+
+        let bundle = store.load_bundle(false).unwrap().unwrap();
+        assert!(bundle.contents.contains("- kept entry"));
+        assert!(bundle.contents.contains("- inside note"));
+        assert!(!bundle.contents.contains("outside secret"));
+    }
+
+    #[test]
+    fn remember_tool_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let store = store(&tmp);
+        let added = store
+            .execute_tool(json!({ "text": "cargo test needs --features full" }))
+            .unwrap();
+        assert_eq!(added["entries"][0]["index"], 1);
+
+        let removed = store
+            .execute_tool(json!({ "action": "remove", "index": 1 }))
+            .unwrap();
+        assert_eq!(removed["entries"].as_array().unwrap().len(), 0);
+        assert!(
+            store
+                .execute_tool(json!({ "action": "update", "text": "x" }))
+                .is_err()
+        );
+    }
+}
@@ -1,8 +1,11 @@
 //! System instructions and prompt management
 
 use crate::config::constants::project_doc as project_doc_constants;
+use crate::config::models::Provider;
+use crate::core::tokenizer::ProviderTokenizer;
 use crate::gemini::Content;
 use crate::project_doc::{ProjectDocBundle, read_project_doc};
+use crate::project_memory::MemoryStore;
 use std::fs;
 use std::path::Path;
 use tracing::warn;
@@ -279,6 +282,24 @@ pub fn generate_system_instruction_with_config(
         instruction.push_str("\n\nThese guidelines take precedence over general instructions.");
     }
 
+    let memory_config = vtcode_config
+        .map(|cfg| cfg.memory.clone())
+        .unwrap_or_default();
+    let tokenizer = vtcode_config
+        .map(|cfg| {
+            ProviderTokenizer::for_model(
+                cfg.agent.provider.parse::<Provider>().unwrap_or_default(),
+                &cfg.agent.default_model,
+            )
+        })
+        .unwrap_or_else(|| ProviderTokenizer::for_model(Provider::default(), ""));
+    if let Some(section) =
+        MemoryStore::for_workspace(project_root).prompt_section(&memory_config, &tokenizer)
+    {
+        instruction.push_str("\n\n");
+        instruction.push_str(&section);
+    }
+
     Content::system_text(instruction)
 }
 
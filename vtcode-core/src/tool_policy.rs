@@ -34,6 +34,22 @@ const AUTO_ALLOW_TOOLS: &[&str] = &[
 ];
 const DEFAULT_CURL_MAX_RESPONSE_BYTES: usize = 64 * 1024;
 
+fn default_denied_url_hosts() -> Vec<String> {
+    [
+        "localhost",
+        "127.0.0.1",
+        "0.0.0.0",
+        "::1",
+        ".localhost",
+        ".local",
+        ".internal",
+        ".lan",
+    ]
+    .iter()
+    .map(|host| host.to_string())
+    .collect()
+}
+
 /// Tool execution policy
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -445,16 +461,7 @@ impl ToolPolicyManager {
             entry.allowed_url_schemes = Some(vec!["https".to_string()]);
         }
         if entry.denied_url_hosts.is_none() {
-            entry.denied_url_hosts = Some(vec![
-                "localhost".to_string(),
-                "127.0.0.1".to_string(),
-                "0.0.0.0".to_string(),
-                "::1".to_string(),
-                ".localhost".to_string(),
-                ".local".to_string(),
-                ".internal".to_string(),
-                ".lan".to_string(),
-            ]);
+            entry.denied_url_hosts = Some(default_denied_url_hosts());
         }
     }
 
@@ -1065,6 +1072,35 @@ pub struct ToolConstraints {
     /// Denied URL hosts or suffixes for network tools
     #[serde(default)]
     pub denied_url_hosts: Option<Vec<String>>,
+    /// When set, network tools only reach these hosts or suffixes
+    #[serde(default)]
+    pub allowed_url_hosts: Option<Vec<String>>,
+}
+
+impl ToolConstraints {
+    /// Whether `host` passes the allow and deny lists. Entries starting with
+    /// '.' match any subdomain; other entries match the host and its subdomains.
+    pub fn permits_host(&self, host: &str) -> bool {
+        let lowered = host.to_lowercase();
+        let matches = |pattern: &String| {
+            let normalized = pattern.to_lowercase();
+            if normalized.starts_with('.') {
+                lowered.ends_with(&normalized)
+            } else {
+                lowered == normalized || lowered.ends_with(&format!(".{}", normalized))
+            }
+        };
+
+        if let Some(denied) = self.denied_url_hosts.as_ref()
+            && denied.iter().any(matches)
+        {
+            return false;
+        }
+        match self.allowed_url_hosts.as_ref() {
+            Some(allowed) if !allowed.is_empty() => allowed.iter().any(matches),
+            _ => true,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1091,6 +1127,16 @@ mod tests {
         assert_eq!(config.policies, deserialized.policies);
     }
 
+    #[test]
+    fn test_remember_requires_approval_by_default() {
+        // Memory is injected into every future system prompt, so writes must be visible
+        assert!(!AUTO_ALLOW_TOOLS.contains(&tools::REMEMBER));
+        assert_eq!(
+            ToolsConfig::default().policies.get(tools::REMEMBER),
+            Some(&ConfigToolPolicy::Prompt)
+        );
+    }
+
     #[test]
     fn test_policy_updates() {
         let dir = tempdir().unwrap();
@@ -273,7 +273,7 @@ impl Patch {
     }
 
     /// Apply hunks to content
-    fn apply_hunks_to_content(content: &str, hunks: &[PatchHunk]) -> Result<String> {
+    pub(crate) fn apply_hunks_to_content(content: &str, hunks: &[PatchHunk]) -> Result<String> {
         let original_lines: Vec<&str> = content.lines().collect();
         let ends_with_newline = content.ends_with('\n');
         let mut lines: Vec<String> = original_lines.into_iter().map(|s| s.to_string()).collect();
@@ -7,17 +7,35 @@ use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
 use serde_json::{Value, json};
 use std::{path::PathBuf, process::Stdio, time::Duration};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::{process::Command, time::timeout};
 
+/// A line printed by a running terminal command, sent before the command exits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandOutputLine {
+    Stdout(String),
+    Stderr(String),
+}
+
 /// Command execution tool using standard process handling
 #[derive(Clone)]
 pub struct CommandTool {
     workspace_root: PathBuf,
+    output_sender: Option<UnboundedSender<CommandOutputLine>>,
 }
 
 impl CommandTool {
     pub fn new(workspace_root: PathBuf) -> Self {
-        Self { workspace_root }
+        Self {
+            workspace_root,
+            output_sender: None,
+        }
+    }
+
+    /// Forward output lines to `sender` while commands run
+    pub fn set_output_sender(&mut self, sender: UnboundedSender<CommandOutputLine>) {
+        self.output_sender = Some(sender);
     }
 
     async fn execute_terminal_command(&self, input: &EnhancedTerminalInput) -> Result<Value> {
@@ -63,10 +81,25 @@ impl CommandTool {
         cmd.current_dir(work_dir);
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
+        cmd.kill_on_drop(true);
 
         let duration = Duration::from_secs(input.timeout_secs.unwrap_or(30));
         let command_str = input.command.join(" ");
-        let output = timeout(duration, cmd.output())
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("failed to run command: {}", command_str))?;
+        let stdout_pipe = child.stdout.take();
+        let stderr_pipe = child.stderr.take();
+        let sender = self.output_sender.as_ref();
+        let run = async {
+            let (status, stdout, stderr) = tokio::join!(
+                child.wait(),
+                read_output(stdout_pipe, sender, CommandOutputLine::Stdout),
+                read_output(stderr_pipe, sender, CommandOutputLine::Stderr),
+            );
+            Ok::<_, std::io::Error>((status?, stdout?, stderr?))
+        };
+        let (status, stdout, stderr) = timeout(duration, run)
             .await
             .with_context(|| {
                 format!(
@@ -76,12 +109,12 @@ impl CommandTool {
                 )
             })?
             .with_context(|| format!("failed to run command: {}", command_str))?;
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let stdout = String::from_utf8_lossy(&stdout).to_string();
+        let stderr = String::from_utf8_lossy(&stderr).to_string();
 
         Ok(json!({
-            "success": output.status.success(),
-            "exit_code": output.status.code().unwrap_or_default(),
+            "success": status.success(),
+            "exit_code": status.code().unwrap_or_default(),
             "stdout": stdout,
             "stderr": stderr,
             "mode": "terminal",
@@ -133,6 +166,32 @@ impl CommandTool {
     }
 }
 
+/// Collect everything a pipe produces, forwarding each complete line as it arrives.
+async fn read_output<R: AsyncRead + Unpin>(
+    pipe: Option<R>,
+    sender: Option<&UnboundedSender<CommandOutputLine>>,
+    wrap: fn(String) -> CommandOutputLine,
+) -> std::io::Result<Vec<u8>> {
+    let mut collected = Vec::new();
+    let Some(pipe) = pipe else {
+        return Ok(collected);
+    };
+    let mut reader = BufReader::new(pipe);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).await? == 0 {
+            break;
+        }
+        collected.extend_from_slice(&line);
+        if let Some(sender) = sender {
+            let text = String::from_utf8_lossy(&line);
+            let _ = sender.send(wrap(text.trim_end_matches(['\n', '\r']).to_string()));
+        }
+    }
+    Ok(collected)
+}
+
 #[async_trait]
 impl Tool for CommandTool {
     async fn execute(&self, args: Value) -> Result<Value> {
@@ -169,3 +228,33 @@ impl ModeTool for CommandTool {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn forwards_output_lines_while_running() {
+        let workspace = tempfile::tempdir().unwrap();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut tool = CommandTool::new(workspace.path().to_path_buf());
+        tool.set_output_sender(sender);
+
+        let result = tool
+            .execute(json!({ "command": ["echo one; echo two >&2; echo three"] }))
+            .await
+            .unwrap();
+
+        assert_eq!(result["stdout"], "one\nthree\n");
+        assert_eq!(result["stderr"], "two\n");
+        let mut lines = Vec::new();
+        while let Ok(line) = receiver.try_recv() {
+            lines.push(line);
+        }
+        assert_eq!(lines.len(), 3);
+        assert!(lines.contains(&CommandOutputLine::Stdout("one".to_string())));
+        assert!(lines.contains(&CommandOutputLine::Stderr("two".to_string())));
+        assert!(lines.contains(&CommandOutputLine::Stdout("three".to_string())));
+    }
+}
@@ -228,47 +228,7 @@ impl CurlTool {
     }
 
     fn validate_url(&self, url: &Url) -> Result<()> {
-        if url.scheme() != "https" {
-            return Err(anyhow!("Only HTTPS URLs are allowed"));
-        }
-
-        if !url.username().is_empty() || url.password().is_some() {
-            return Err(anyhow!("Credentials in URLs are not supported"));
-        }
-
-        let host = url
-            .host_str()
-            .ok_or_else(|| anyhow!("URL must include a host"))?
-            .to_lowercase();
-
-        if host.parse::<IpAddr>().is_ok() {
-            return Err(anyhow!("IP address targets are blocked for security"));
-        }
-
-        let forbidden_hosts = ["localhost", "127.0.0.1", "0.0.0.0", "::1"];
-
-        if forbidden_hosts
-            .iter()
-            .any(|blocked| host == *blocked || host.ends_with(&format!(".{}", blocked)))
-        {
-            return Err(anyhow!("Access to local or loopback hosts is blocked"));
-        }
-
-        let forbidden_suffixes = [".localhost", ".local", ".internal", ".lan"];
-        if forbidden_suffixes
-            .iter()
-            .any(|suffix| host.ends_with(suffix))
-        {
-            return Err(anyhow!("Private network hosts are not permitted"));
-        }
-
-        if let Some(port) = url.port()
-            && port != 443
-        {
-            return Err(anyhow!("Custom HTTPS ports are blocked by policy"));
-        }
-
-        Ok(())
+        validate_public_https_url(url)
     }
 
     fn validate_content_type(&self, content_type: &str) -> Result<()> {
@@ -293,6 +253,51 @@ impl CurlTool {
     }
 }
 
+/// Reject anything but HTTPS requests to public hosts on the default port.
+pub(crate) fn validate_public_https_url(url: &Url) -> Result<()> {
+    if url.scheme() != "https" {
+        return Err(anyhow!("Only HTTPS URLs are allowed"));
+    }
+
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err(anyhow!("Credentials in URLs are not supported"));
+    }
+
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow!("URL must include a host"))?
+        .to_lowercase();
+
+    if host.parse::<IpAddr>().is_ok() {
+        return Err(anyhow!("IP address targets are blocked for security"));
+    }
+
+    let forbidden_hosts = ["localhost", "127.0.0.1", "0.0.0.0", "::1"];
+
+    if forbidden_hosts
+        .iter()
+        .any(|blocked| host == *blocked || host.ends_with(&format!(".{}", blocked)))
+    {
+        return Err(anyhow!("Access to local or loopback hosts is blocked"));
+    }
+
+    let forbidden_suffixes = [".localhost", ".local", ".internal", ".lan"];
+    if forbidden_suffixes
+        .iter()
+        .any(|suffix| host.ends_with(suffix))
+    {
+        return Err(anyhow!("Private network hosts are not permitted"));
+    }
+
+    if let Some(port) = url.port()
+        && port != 443
+    {
+        return Err(anyhow!("Custom HTTPS ports are blocked by policy"));
+    }
+
+    Ok(())
+}
+
 #[async_trait]
 impl Tool for CurlTool {
     async fn execute(&self, args: Value) -> Result<Value> {
@@ -0,0 +1,257 @@
+//! Hunk-level review of file changes proposed by write tools
+//!
+//! The runloop previews what `edit_file`, `write_file` and `apply_patch` would
+//! write, splits each file change into hunks and lets the user accept, reject
+//! or rewrite every hunk before anything lands on disk.
+
+use serde_json::{Value, json};
+
+use crate::ui::diff_renderer::{DiffLine, DiffLineType, FileDiff};
+
+/// A file change a write tool would make, computed without touching disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposedChange {
+    /// Workspace-relative path the content is written to.
+    pub path: String,
+    /// Original path when the change moves a file.
+    pub source_path: Option<String>,
+    pub old_content: String,
+    pub new_content: String,
+    /// The change deletes `path` instead of writing it.
+    pub delete: bool,
+}
+
+/// A contiguous run of removed and added lines plus surrounding context for display.
+#[derive(Debug, Clone)]
+pub struct ReviewHunk {
+    /// Zero-based index of the first old line the hunk replaces.
+    pub old_start: usize,
+    pub removed: Vec<String>,
+    pub added: Vec<String>,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HunkDecision {
+    Accepted,
+    Rejected,
+    Edited(String),
+}
+
+impl HunkDecision {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HunkDecision::Accepted => "accepted",
+            HunkDecision::Rejected => "rejected",
+            HunkDecision::Edited(_) => "edited",
+        }
+    }
+}
+
+/// Group the changed lines of `diff` into hunks, keeping up to `context_lines`
+/// unchanged lines on either side of each hunk for display.
+pub fn split_hunks(diff: &FileDiff, context_lines: usize) -> Vec<ReviewHunk> {
+    let mut hunks = Vec::new();
+    let mut old_index = 0;
+    let mut position = 0;
+
+    while position < diff.lines.len() {
+        let line = &diff.lines[position];
+        if line.line_type != DiffLineType::Added && line.line_type != DiffLineType::Removed {
+            if line.line_type == DiffLineType::Context {
+                old_index += 1;
+            }
+            position += 1;
+            continue;
+        }
+
+        let start = position;
+        let old_start = old_index;
+        let mut removed = Vec::new();
+        let mut added = Vec::new();
+        while let Some(line) = diff.lines.get(position) {
+            match line.line_type {
+                DiffLineType::Removed => {
+                    removed.push(line.content.clone());
+                    old_index += 1;
+                }
+                DiffLineType::Added => added.push(line.content.clone()),
+                _ => break,
+            }
+            position += 1;
+        }
+
+        let context_start = diff.lines[..start]
+            .iter()
+            .rev()
+            .take(context_lines)
+            .take_while(|line| line.line_type == DiffLineType::Context)
+            .count();
+        let context_end = diff.lines[position..]
+            .iter()
+            .take(context_lines)
+            .take_while(|line| line.line_type == DiffLineType::Context)
+            .count();
+
+        hunks.push(ReviewHunk {
+            old_start,
+            removed,
+            added,
+            lines: diff.lines[start - context_start..position + context_end].to_vec(),
+        });
+    }
+
+    hunks
+}
+
+/// A proposed change together with the user's verdict on each of its hunks.
+#[derive(Debug, Clone)]
+pub struct ReviewedChange {
+    pub change: ProposedChange,
+    pub hunks: Vec<ReviewHunk>,
+    pub decisions: Vec<HunkDecision>,
+}
+
+impl ReviewedChange {
+    pub fn all_accepted(&self) -> bool {
+        self.decisions
+            .iter()
+            .all(|decision| *decision == HunkDecision::Accepted)
+    }
+
+    pub fn all_rejected(&self) -> bool {
+        !self.decisions.is_empty()
+            && self
+                .decisions
+                .iter()
+                .all(|decision| *decision == HunkDecision::Rejected)
+    }
+
+    /// Rebuild the file from the old content, taking each hunk as decided.
+    pub fn final_content(&self) -> String {
+        if self.all_accepted() {
+            return self.change.new_content.clone();
+        }
+        if self.all_rejected() {
+            return self.change.old_content.clone();
+        }
+
+        let old_lines: Vec<&str> = self.change.old_content.lines().collect();
+        let mut lines: Vec<String> = Vec::new();
+        let mut cursor = 0;
+        for (hunk, decision) in self.hunks.iter().zip(&self.decisions) {
+            let start = hunk.old_start.min(old_lines.len());
+            lines.extend(
+                old_lines[cursor.min(start)..start]
+                    .iter()
+                    .map(|s| s.to_string()),
+            );
+            match decision {
+                HunkDecision::Accepted => lines.extend(hunk.added.iter().cloned()),
+                HunkDecision::Rejected => lines.extend(hunk.removed.iter().cloned()),
+                HunkDecision::Edited(text) => lines.extend(text.lines().map(str::to_string)),
+            }
+            cursor = start + hunk.removed.len();
+        }
+        if cursor < old_lines.len() {
+            lines.extend(old_lines[cursor..].iter().map(|s| s.to_string()));
+        }
+
+        let trailing_newline = if self.change.new_content.is_empty() {
+            self.change.old_content.ends_with('\n')
+        } else {
+            self.change.new_content.ends_with('\n')
+        };
+        let mut content = lines.join("\n");
+        if trailing_newline && !content.is_empty() {
+            content.push('\n');
+        }
+        content
+    }
+
+    /// Per-hunk verdicts reported back to the model.
+    pub fn summary(&self) -> Value {
+        let hunks: Vec<Value> = self
+            .hunks
+            .iter()
+            .zip(&self.decisions)
+            .enumerate()
+            .map(|(index, (hunk, decision))| {
+                let mut entry = json!({
+                    "hunk": index + 1,
+                    "old_line": hunk.old_start + 1,
+                    "status": decision.label(),
+                });
+                if let HunkDecision::Edited(text) = decision {
+                    entry["content"] = json!(text);
+                }
+                entry
+            })
+            .collect();
+        json!({
+            "path": self.change.path,
+            "hunks": hunks,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::diff_renderer::DiffRenderer;
+
+    fn reviewed(old: &str, new: &str, decide: impl Fn(usize) -> HunkDecision) -> ReviewedChange {
+        let diff = DiffRenderer::new(false, 3, false).generate_diff(old, new, "src/lib.rs");
+        let hunks = split_hunks(&diff, 3);
+        let decisions = (0..hunks.len()).map(decide).collect();
+        ReviewedChange {
+            change: ProposedChange {
+                path: "src/lib.rs".to_string(),
+                source_path: None,
+                old_content: old.to_string(),
+                new_content: new.to_string(),
+                delete: false,
+            },
+            hunks,
+            decisions,
+        }
+    }
+
+    #[test]
+    fn splits_separate_changes_into_hunks() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\n";
+        let new = "a\nB\nc\nd\ne\nf\nG\nh\n";
+        let change = reviewed(old, new, |_| HunkDecision::Accepted);
+        assert_eq!(change.hunks.len(), 2);
+        assert_eq!(change.hunks[0].old_start, 1);
+        assert_eq!(change.hunks[0].removed, vec!["b".to_string()]);
+        assert_eq!(change.hunks[1].added, vec!["G".to_string()]);
+        assert_eq!(change.final_content(), new);
+    }
+
+    #[test]
+    fn assembles_partial_and_edited_decisions() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\n";
+        let new = "a\nB\nc\nd\ne\nf\nG\nh\n";
+
+        let partial = reviewed(old, new, |index| {
+            if index == 0 {
+                HunkDecision::Rejected
+            } else {
+                HunkDecision::Accepted
+            }
+        });
+        assert_eq!(partial.final_content(), "a\nb\nc\nd\ne\nf\nG\nh\n");
+
+        let edited = reviewed(old, new, |index| {
+            if index == 0 {
+                HunkDecision::Edited("b1\nb2".to_string())
+            } else {
+                HunkDecision::Rejected
+            }
+        });
+        assert_eq!(edited.final_content(), "a\nb1\nb2\nc\nd\ne\nf\ng\nh\n");
+        assert_eq!(edited.summary()["hunks"][0]["status"], "edited");
+        assert_eq!(edited.summary()["hunks"][1]["status"], "rejected");
+    }
+}
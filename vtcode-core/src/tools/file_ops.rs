@@ -3,6 +3,7 @@
 use super::traits::{CacheableTool, FileTool, ModeTool, Tool};
 use super::types::*;
 use crate::config::constants::diff;
+use crate::llm::provider::Attachment;
 use crate::tools::grep_search::GrepSearchManager;
 use crate::utils::vtcodegitignore::should_exclude_file;
 use anyhow::{Context, Result, anyhow};
@@ -361,6 +362,10 @@ impl FileOpsTool {
             }
 
             if candidate_path.exists() && candidate_path.is_file() {
+                if input.mode.as_deref() == Some("attachment") {
+                    return self.read_file_as_attachment(candidate_path);
+                }
+
                 // Check if chunking is needed
                 let should_chunk = if let Some(max_lines) = input.max_lines {
                     // User specified max_lines threshold
@@ -451,6 +456,27 @@ impl FileOpsTool {
         ))
     }
 
+    /// Return an image or PDF as an attachment the agent forwards to the model
+    fn read_file_as_attachment(&self, path: &Path) -> Result<Value> {
+        let attachment = Attachment::from_path(path)?;
+        let size = std::fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+        Ok(json!({
+            "success": true,
+            "content": format!(
+                "{} ({}, {} bytes) attached for viewing",
+                attachment.file_name(),
+                attachment.media_type,
+                size
+            ),
+            "path": path.strip_prefix(&self.workspace_root).unwrap_or(path).to_string_lossy(),
+            "attachment": attachment,
+            "metadata": {
+                "size": size,
+                "media_type": attachment.media_type,
+            }
+        }))
+    }
+
     /// Write file with various modes and chunking support for large content
     pub async fn write_file(&self, args: Value) -> Result<Value> {
         let input: WriteInput = serde_json::from_value(args)
@@ -99,6 +99,7 @@ impl FileSearcher {
         builder.git_ignore(true);
         builder.git_global(true);
         builder.git_exclude(true);
+        builder.add_custom_ignore_filename(".vtcodegitignore");
         builder
     }
 
@@ -130,6 +130,7 @@ pub mod bash_tool;
 pub mod cache;
 pub mod command;
 pub mod curl_tool;
+pub mod edit_review;
 pub mod file_ops;
 pub mod file_search;
 pub mod grep_search;
@@ -141,11 +142,14 @@ pub mod srgn;
 pub mod traits;
 pub mod tree_sitter;
 pub mod types;
+pub mod web_fetch;
+pub mod web_search;
 
 // Re-export main types and traits for backward compatibility
 pub use ast_grep_tool::AstGrepTool;
 pub use bash_tool::BashTool;
 pub use cache::FileCache;
+pub use command::CommandOutputLine;
 pub use curl_tool::CurlTool;
 pub use grep_search::GrepSearchManager;
 pub use plan::{
@@ -42,6 +42,12 @@ pub(super) fn builtin_tool_registrations() -> Vec<ToolRegistration> {
             false,
             ToolRegistry::update_plan_executor,
         ),
+        ToolRegistration::new(
+            tools::REMEMBER,
+            CapabilityLevel::Editing,
+            false,
+            ToolRegistry::remember_executor,
+        ),
         ToolRegistration::new(
             tools::RUN_TERMINAL_CMD,
             CapabilityLevel::Bash,
@@ -54,6 +60,18 @@ pub(super) fn builtin_tool_registrations() -> Vec<ToolRegistration> {
             false,
             ToolRegistry::curl_executor,
         ),
+        ToolRegistration::new(
+            tools::WEB_SEARCH,
+            CapabilityLevel::CodeSearch,
+            false,
+            ToolRegistry::web_search_executor,
+        ),
+        ToolRegistration::new(
+            tools::WEB_FETCH,
+            CapabilityLevel::CodeSearch,
+            false,
+            ToolRegistry::web_fetch_executor,
+        ),
         ToolRegistration::new(
             tools::READ_FILE,
             CapabilityLevel::FileReading,
@@ -86,7 +104,7 @@ pub(super) fn builtin_tool_registrations() -> Vec<ToolRegistration> {
         ),
         ToolRegistration::new(
             tools::BASH,
-            CapabilityLevel::CodeSearch,
+            CapabilityLevel::Bash,
             false,
             ToolRegistry::bash_executor,
         )
@@ -100,7 +118,7 @@ pub(super) fn builtin_tool_registrations() -> Vec<ToolRegistration> {
         .with_llm_visibility(false),
         ToolRegistration::new(
             tools::SRGN,
-            CapabilityLevel::CodeSearch,
+            CapabilityLevel::Editing,
             false,
             ToolRegistry::srgn_executor,
         ),
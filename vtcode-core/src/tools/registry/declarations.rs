@@ -69,7 +69,8 @@ fn base_function_declarations() -> Vec<FunctionDeclaration> {
                     "path": {"type": "string", "description": "File path to read"},
                     "max_bytes": {"type": "integer", "description": "Maximum bytes to read (optional)", "default": null},
                     "chunk_lines": {"type": "integer", "description": "Line threshold for chunking (optional, default: 2000)", "default": 2000},
-                    "max_lines": {"type": "integer", "description": "Alternative parameter for chunk_lines (optional)", "default": null}
+                    "max_lines": {"type": "integer", "description": "Alternative parameter for chunk_lines (optional)", "default": null},
+                    "mode": {"type": "string", "enum": ["text", "attachment"], "description": "'attachment' returns images (png, jpeg, gif, webp) and PDFs so you can view them. Defaults to 'text'"}
                 },
                 "required": ["path"]
             }),
@@ -136,6 +137,33 @@ fn base_function_declarations() -> Vec<FunctionDeclaration> {
                 "required": ["url"]
             }),
         },
+        FunctionDeclaration {
+            name: tools::WEB_SEARCH.to_string(),
+            description: "Search the web through the configured backend. Returns title, url and snippet per result; hosts blocked by policy are dropped. Use to find documentation URLs instead of guessing, then fetch them with curl.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": {"type": "string", "description": "Search query"},
+                    "max_results": {"type": "integer", "description": "Maximum results to return (capped by configuration)", "default": 8}
+                },
+                "required": ["query"]
+            }),
+        },
+        FunctionDeclaration {
+            name: tools::WEB_FETCH.to_string(),
+            description: "Fetch a public HTTPS page and return its main content as Markdown (headings, code blocks and links kept; navigation and boilerplate dropped). Redirects are followed within policy. Long pages are paged by section: the outline lists each heading with its page. Pages are cached for the session. Prefer over curl for documentation.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "url": {"type": "string", "description": "HTTPS URL to fetch (public hosts only)."},
+                    "max_tokens": {"type": "integer", "description": "Approximate token budget per page of output", "default": 4000},
+                    "page": {"type": "integer", "description": "1-based page to return", "default": 1},
+                    "section": {"type": "string", "description": "Return the page containing the first heading that matches this text"},
+                    "refresh": {"type": "boolean", "description": "Bypass the session cache and fetch again", "default": false}
+                },
+                "required": ["url"]
+            }),
+        },
 
         // AST-grep search and transformation tool
         FunctionDeclaration {
@@ -258,6 +286,34 @@ fn base_function_declarations() -> Vec<FunctionDeclaration> {
                 "additionalProperties": false
             }),
         },
+        FunctionDeclaration {
+            name: tools::REMEMBER.to_string(),
+            description: "Saves, corrects or forgets durable facts in the memory files that are loaded into every future session. Use it when you learn something worth keeping about the project (build quirks, conventions, commands that must run first) or about the user's preferences. Store one short fact per entry, list entries before updating or removing them, and never store secrets. Scope 'project' writes .vtcode/MEMORY.md in the workspace; scope 'user' writes ~/.vtcode/MEMORY.md.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["add", "list", "update", "remove"],
+                        "description": "Operation to perform (default: add)."
+                    },
+                    "scope": {
+                        "type": "string",
+                        "enum": ["project", "user"],
+                        "description": "Which memory file to use (default: project)."
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "The fact to store, required for add and update."
+                    },
+                    "index": {
+                        "type": "integer",
+                        "description": "1-based entry number, required for update and remove."
+                    }
+                },
+                "additionalProperties": false
+            }),
+        },
     ]
 }
 
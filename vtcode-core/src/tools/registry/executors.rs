@@ -2,6 +2,9 @@ use anyhow::{Context, Result, anyhow};
 use futures::future::BoxFuture;
 use serde_json::{Value, json};
 
+use crate::config::constants::tools;
+use crate::project_memory::MemoryStore;
+use crate::tool_policy::ToolConstraints;
 use crate::tools::apply_patch::Patch;
 use crate::tools::traits::Tool;
 use crate::tools::{PlanUpdateResult, UpdatePlanArgs};
@@ -31,6 +34,37 @@ impl ToolRegistry {
         Box::pin(async move { tool.execute(args).await })
     }
 
+    pub(super) fn web_search_executor(&mut self, args: Value) -> BoxFuture<'_, Result<Value>> {
+        let tool = self.web_search_tool.clone();
+        let constraints = self.network_constraints(tools::WEB_SEARCH);
+        Box::pin(async move {
+            let constraints: Vec<&ToolConstraints> = constraints.iter().collect();
+            tool.search(args, &constraints).await
+        })
+    }
+
+    pub(super) fn web_fetch_executor(&mut self, args: Value) -> BoxFuture<'_, Result<Value>> {
+        let tool = self.web_fetch_tool.clone();
+        let constraints = self.network_constraints(tools::WEB_FETCH);
+        Box::pin(async move {
+            let constraints: Vec<&ToolConstraints> = constraints.iter().collect();
+            tool.fetch(args, &constraints).await
+        })
+    }
+
+    /// Host lists from curl's constraints plus those of the network tool `name`.
+    fn network_constraints(&self, name: &str) -> Vec<ToolConstraints> {
+        [tools::CURL, name]
+            .iter()
+            .filter_map(|tool| {
+                self.tool_policy
+                    .as_ref()
+                    .and_then(|policy| policy.get_constraints(tool))
+                    .cloned()
+            })
+            .collect()
+    }
+
     pub(super) fn read_file_executor(&mut self, args: Value) -> BoxFuture<'_, Result<Value>> {
         let tool = self.file_ops_tool.clone();
         Box::pin(async move { tool.read_file(args).await })
@@ -80,6 +114,11 @@ impl ToolRegistry {
         })
     }
 
+    pub(super) fn remember_executor(&mut self, args: Value) -> BoxFuture<'_, Result<Value>> {
+        let store = MemoryStore::for_workspace(&self.workspace_root);
+        Box::pin(async move { store.execute_tool(args) })
+    }
+
     pub(super) async fn execute_apply_patch(&self, args: Value) -> Result<Value> {
         let input = args
             .get("input")
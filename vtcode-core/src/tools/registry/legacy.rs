@@ -33,39 +33,9 @@ impl ToolRegistry {
             .as_str()
             .ok_or_else(|| anyhow!("Failed to read file content"))?;
 
-        let mut replacement_occurred = false;
-        let mut new_content = current_content.to_string();
+        let replaced = utils::replace_text(current_content, &input.old_str, &input.new_str);
 
-        if current_content.contains(&input.old_str) {
-            new_content = current_content.replace(&input.old_str, &input.new_str);
-            replacement_occurred = new_content != current_content;
-        }
-
-        if !replacement_occurred {
-            let normalized_content = utils::normalize_whitespace(current_content);
-            let normalized_old_str = utils::normalize_whitespace(&input.old_str);
-
-            if normalized_content.contains(&normalized_old_str) {
-                let old_lines: Vec<&str> = input.old_str.lines().collect();
-                let content_lines: Vec<&str> = current_content.lines().collect();
-
-                for i in 0..=(content_lines.len().saturating_sub(old_lines.len())) {
-                    let window = &content_lines[i..i + old_lines.len()];
-                    if utils::lines_match(window, &old_lines) {
-                        let before = content_lines[..i].join("\n");
-                        let after = content_lines[i + old_lines.len()..].join("\n");
-                        let replacement_lines: Vec<&str> = input.new_str.lines().collect();
-
-                        new_content =
-                            format!("{}\n{}\n{}", before, replacement_lines.join("\n"), after);
-                        replacement_occurred = true;
-                        break;
-                    }
-                }
-            }
-        }
-
-        if !replacement_occurred {
+        let Some(new_content) = replaced else {
             let content_preview = if current_content.len() > 500 {
                 format!(
                     "{}...{}",
@@ -81,7 +51,7 @@ impl ToolRegistry {
                 input.old_str,
                 content_preview
             ));
-        }
+        };
 
         let write_args = json!({
             "path": input.path,
@@ -10,6 +10,7 @@ mod legacy;
 mod policy;
 mod pty;
 mod registration;
+mod review;
 mod utils;
 
 pub use declarations::{
@@ -34,17 +35,21 @@ use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicUsize;
+use tokio::sync::mpsc::UnboundedSender;
 use tracing::{debug, warn};
 
 use super::bash_tool::BashTool;
-use super::command::CommandTool;
+use super::command::{CommandOutputLine, CommandTool};
 use super::curl_tool::CurlTool;
 use super::file_ops::FileOpsTool;
 use super::plan::PlanManager;
 use super::search::SearchTool;
 use super::simple_search::SimpleSearchTool;
 use super::srgn::SrgnTool;
+use super::web_fetch::WebFetchTool;
+use super::web_search::WebSearchTool;
 use crate::mcp_client::{McpClient, McpToolExecutor, McpToolInfo};
+use crate::ui::notifications::{NotificationEvent, Notifier};
 
 #[cfg(test)]
 use super::traits::Tool;
@@ -60,6 +65,8 @@ pub struct ToolRegistry {
     file_ops_tool: FileOpsTool,
     command_tool: CommandTool,
     curl_tool: CurlTool,
+    web_search_tool: WebSearchTool,
+    web_fetch_tool: WebFetchTool,
     grep_search: Arc<GrepSearchManager>,
     ast_grep_engine: Option<Arc<AstGrepEngine>>,
     tool_policy: Option<ToolPolicyManager>,
@@ -73,6 +80,9 @@ pub struct ToolRegistry {
     tool_lookup: HashMap<&'static str, usize>,
     preapproved_tools: HashSet<String>,
     full_auto_allowlist: Option<HashSet<String>>,
+    plan_mode: bool,
+    notifier: Option<Arc<Notifier>>,
+    attachments_enabled: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -112,6 +122,8 @@ impl ToolRegistry {
         let file_ops_tool = FileOpsTool::new(workspace_root.clone(), grep_search.clone());
         let command_tool = CommandTool::new(workspace_root.clone());
         let curl_tool = CurlTool::new();
+        let web_search_tool = WebSearchTool::new();
+        let web_fetch_tool = WebFetchTool::new();
         let srgn_tool = SrgnTool::new(workspace_root.clone());
         let plan_manager = PlanManager::new();
 
@@ -139,6 +151,8 @@ impl ToolRegistry {
             file_ops_tool,
             command_tool,
             curl_tool,
+            web_search_tool,
+            web_fetch_tool,
             grep_search,
             ast_grep_engine,
             tool_policy: policy_manager,
@@ -152,6 +166,9 @@ impl ToolRegistry {
             tool_lookup: HashMap::new(),
             preapproved_tools: HashSet::new(),
             full_auto_allowlist: None,
+            plan_mode: false,
+            notifier: None,
+            attachments_enabled: false,
         };
 
         register_builtin_tools(&mut registry, todo_planning_enabled);
@@ -227,6 +244,52 @@ impl ToolRegistry {
         })
     }
 
+    /// Restrict execution to read-only tools while a plan is being prepared.
+    pub fn set_plan_mode(&mut self, enabled: bool) {
+        self.plan_mode = enabled;
+    }
+
+    pub fn plan_mode(&self) -> bool {
+        self.plan_mode
+    }
+
+    /// Notify the user through `notifier` when a tool waits for approval.
+    pub fn set_notifier(&mut self, notifier: Arc<Notifier>) {
+        self.notifier = Some(notifier);
+    }
+
+    /// Keep `attachment` payloads in tool results. Only enable this when the caller moves
+    /// them into message attachments; otherwise the base64 data would be sent as tool text.
+    pub fn set_attachments_enabled(&mut self, enabled: bool) {
+        self.attachments_enabled = enabled;
+    }
+
+    /// Stream `run_terminal_cmd` output lines to `sender` while commands run.
+    pub fn set_command_output_sender(&mut self, sender: UnboundedSender<CommandOutputLine>) {
+        self.command_tool.set_output_sender(sender);
+    }
+
+    /// Whether the tool only observes the workspace. MCP tools are never considered read-only.
+    pub fn is_read_only_tool(&self, name: &str) -> bool {
+        self.tool_lookup
+            .get(name)
+            .and_then(|index| self.tool_registrations.get(*index))
+            .map(|registration| registration.capability().is_read_only())
+            .unwrap_or(false)
+    }
+
+    /// Explanation returned to the model when plan mode blocks `name`.
+    pub fn plan_mode_restriction(&self, name: &str) -> Option<String> {
+        if !self.plan_mode || self.is_read_only_tool(name) {
+            return None;
+        }
+        Some(format!(
+            "Tool '{}' is not available in plan mode. Explore with read-only tools, record the plan with {} and wait for the user to approve it before making changes.",
+            name,
+            tools::UPDATE_PLAN
+        ))
+    }
+
     pub fn has_tool(&self, name: &str) -> bool {
         self.tool_lookup.contains_key(name)
     }
@@ -257,6 +320,11 @@ impl ToolRegistry {
             policy_manager.apply_tools_config(tools_config)?;
         }
 
+        match WebSearchTool::from_config(&tools_config.web_search) {
+            Ok(tool) => self.web_search_tool = tool,
+            Err(err) => warn!(error = %err, "Invalid web_search configuration; search disabled"),
+        }
+
         Ok(())
     }
 
@@ -275,6 +343,12 @@ impl ToolRegistry {
             return Ok(error.to_json_value());
         }
 
+        if let Some(message) = self.plan_mode_restriction(name) {
+            let error =
+                ToolExecutionError::new(name.to_string(), ToolErrorType::PolicyViolation, message);
+            return Ok(error.to_json_value());
+        }
+
         let skip_policy_prompt = self.preapproved_tools.remove(name);
 
         if !skip_policy_prompt
@@ -428,7 +502,20 @@ impl ToolRegistry {
         }
 
         match result {
-            Ok(value) => Ok(normalize_tool_output(value)),
+            Ok(mut value) => {
+                if !self.attachments_enabled
+                    && let Some(obj) = value.as_object_mut()
+                    && obj.remove("attachment").is_some()
+                {
+                    let error = ToolExecutionError::new(
+                        name.to_string(),
+                        ToolErrorType::InvalidParameters,
+                        "Attachments are not supported in this session; read the file in 'text' mode instead".to_string(),
+                    );
+                    return Ok(error.to_json_value());
+                }
+                Ok(normalize_tool_output(value))
+            }
             Err(err) => {
                 let error_type = classify_error(&err);
                 let error = ToolExecutionError::with_original_error(
@@ -573,7 +660,25 @@ impl ToolRegistry {
         }
     }
 
+    /// Decide whether `name` may run. A `Prompt` outcome also notifies the user,
+    /// since the session stalls until they answer.
     pub fn evaluate_tool_policy(&mut self, name: &str) -> Result<ToolPermissionDecision> {
+        let decision = self.decide_tool_policy(name)?;
+        if decision == ToolPermissionDecision::Prompt
+            && let Some(notifier) = self.notifier.as_ref()
+        {
+            notifier.notify(&NotificationEvent::ApprovalRequested {
+                tool: name.to_string(),
+            });
+        }
+        Ok(decision)
+    }
+
+    fn decide_tool_policy(&mut self, name: &str) -> Result<ToolPermissionDecision> {
+        if self.plan_mode_restriction(name).is_some() {
+            return Ok(ToolPermissionDecision::Deny);
+        }
+
         if let Some(tool_name) = name.strip_prefix("mcp_") {
             return self.evaluate_mcp_tool_policy(name, tool_name);
         }
@@ -781,6 +886,113 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn plan_mode_blocks_mutating_tools() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut registry = ToolRegistry::new(temp_dir.path().to_path_buf());
+        registry.set_plan_mode(true);
+
+        assert!(registry.preflight_tool_permission(tools::READ_FILE)?);
+        assert!(!registry.preflight_tool_permission(tools::EDIT_FILE)?);
+        assert!(!registry.preflight_tool_permission(tools::RUN_TERMINAL_CMD)?);
+        // Memory files persist across sessions, so plan mode must not write them either
+        assert!(!registry.preflight_tool_permission(tools::REMEMBER)?);
+        // Web research only reads; host filtering still applies through the policy constraints
+        assert!(registry.plan_mode_restriction(tools::WEB_SEARCH).is_none());
+        assert!(registry.plan_mode_restriction(tools::WEB_FETCH).is_none());
+        assert!(registry.plan_mode_restriction(tools::CURL).is_some());
+
+        let response = registry
+            .execute_tool(
+                tools::WRITE_FILE,
+                json!({"path": "notes.txt", "content": "hello"}),
+            )
+            .await?;
+        assert!(response["error"].is_object());
+        assert!(!temp_dir.path().join("notes.txt").exists());
+
+        let response = registry
+            .execute_tool(
+                tools::REMEMBER,
+                json!({"action": "add", "text": "Run make first"}),
+            )
+            .await?;
+        assert!(response["error"].is_object());
+        assert!(!temp_dir.path().join(".vtcode/MEMORY.md").exists());
+
+        registry.set_plan_mode(false);
+        assert!(registry.plan_mode_restriction(tools::EDIT_FILE).is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn attachments_are_opt_in_and_stripped_unless_enabled() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut registry = ToolRegistry::new(temp_dir.path().to_path_buf());
+        registry.allow_all_tools().ok();
+        std::fs::write(temp_dir.path().join("shot.png"), b"png")?;
+
+        let response = registry
+            .execute_tool(tools::READ_FILE, json!({"path": "shot.png"}))
+            .await?;
+        assert!(response.get("attachment").is_none());
+        assert_eq!(response["content"], "png");
+
+        let attach = json!({"path": "shot.png", "mode": "attachment"});
+        let response = registry
+            .execute_tool(tools::READ_FILE, attach.clone())
+            .await?;
+        assert!(response.get("attachment").is_none());
+        assert!(response["error"].is_object());
+
+        registry.set_attachments_enabled(true);
+        let response = registry.execute_tool(tools::READ_FILE, attach).await?;
+        assert_eq!(response["attachment"]["media_type"], "image/png");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reviewed_changes_write_only_accepted_hunks() -> Result<()> {
+        use crate::tools::edit_review::{HunkDecision, ReviewedChange, split_hunks};
+        use crate::ui::diff_renderer::DiffRenderer;
+
+        let temp_dir = TempDir::new()?;
+        let mut registry = ToolRegistry::new(temp_dir.path().to_path_buf());
+        std::fs::write(temp_dir.path().join("notes.txt"), "one\ntwo\nthree\n")?;
+
+        let changes = registry
+            .preview_file_changes(
+                tools::EDIT_FILE,
+                &json!({"path": "notes.txt", "old_str": "two", "new_str": "2"}),
+            )
+            .await?;
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].new_content, "one\n2\nthree\n");
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("notes.txt"))?,
+            "one\ntwo\nthree\n"
+        );
+
+        let change = changes.into_iter().next().unwrap();
+        let diff = DiffRenderer::new(false, 3, false).generate_diff(
+            &change.old_content,
+            &change.new_content,
+            &change.path,
+        );
+        let reviewed = ReviewedChange {
+            hunks: split_hunks(&diff, 3),
+            decisions: vec![HunkDecision::Edited("deux".to_string())],
+            change,
+        };
+        let response = registry.apply_reviewed_changes(&[reviewed]).await?;
+        assert_eq!(response["files"][0]["hunks"][0]["status"], "edited");
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("notes.txt"))?,
+            "one\ndeux\nthree\n"
+        );
+        Ok(())
+    }
+
     #[test]
     fn normalizes_mcp_tool_identifiers() {
         assert_eq!(
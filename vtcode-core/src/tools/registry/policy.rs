@@ -31,11 +31,11 @@ impl ToolRegistry {
                 .as_object_mut()
                 .ok_or_else(|| anyhow!("Error: tool arguments must be an object"))?;
 
-            if let Some(fmt) = constraints.default_response_format {
+            if let Some(fmt) = constraints.default_response_format.as_ref() {
                 obj.entry("response_format").or_insert(json!(fmt));
             }
 
-            if let Some(allowed) = constraints.allowed_modes
+            if let Some(allowed) = constraints.allowed_modes.as_ref()
                 && let Some(mode) = obj.get("mode").and_then(|v| v.as_str())
                 && !allowed.iter().any(|m| m == mode)
             {
@@ -130,30 +130,37 @@ impl ToolRegistry {
                         }
                     }
 
-                    if let Some(denied_hosts) = constraints.denied_url_hosts.as_ref()
-                        && let Some(host_str) = parsed.host_str()
+                    if let Some(host_str) = parsed.host_str()
+                        && !constraints.permits_host(host_str)
                     {
-                        let lowered = host_str.to_lowercase();
-                        let blocked = denied_hosts.iter().any(|pattern| {
-                            let normalized = pattern.to_lowercase();
-                            if normalized.starts_with('.') {
-                                lowered.ends_with(&normalized)
-                            } else {
-                                lowered == normalized
-                                    || lowered.ends_with(&format!(".{}", normalized))
-                            }
-                        });
-                        if blocked {
-                            return Err(anyhow!(format!(
-                                "URL host '{}' is blocked by policy",
-                                host_str
-                            )));
-                        }
+                        return Err(anyhow!(format!(
+                            "URL host '{}' is blocked by policy",
+                            host_str
+                        )));
                     }
                 }
                 _ => {}
             }
         }
+
+        // Structural rewrites are limited to previews until the plan is approved.
+        if self.plan_mode
+            && name == tools::AST_GREP_SEARCH
+            && let Some(obj) = args.as_object_mut()
+        {
+            let operation = obj
+                .get("operation")
+                .and_then(|v| v.as_str())
+                .unwrap_or("search");
+            if operation == "refactor" {
+                return Err(anyhow!(
+                    "ast_grep refactor is not available in plan mode; use 'search' or a 'transform' preview"
+                ));
+            }
+            obj.insert("preview_only".to_string(), json!(true));
+            obj.insert("update_all".to_string(), json!(false));
+            obj.remove("rewrite");
+        }
         Ok(args)
     }
 
@@ -0,0 +1,194 @@
+use anyhow::{Context, Result, anyhow};
+use serde_json::{Value, json};
+use std::path::Path;
+
+use crate::config::constants::tools;
+use crate::tools::apply_patch::{Patch, PatchOperation};
+use crate::tools::edit_review::{ProposedChange, ReviewedChange};
+use crate::tools::types::{EditInput, WriteInput};
+
+use super::ToolRegistry;
+use super::utils;
+
+impl ToolRegistry {
+    /// Whether `name` writes files in a way that can be previewed and reviewed hunk by hunk.
+    pub fn supports_change_review(name: &str) -> bool {
+        matches!(
+            name,
+            tools::EDIT_FILE | tools::WRITE_FILE | tools::APPLY_PATCH
+        )
+    }
+
+    /// Compute the file changes a write tool would make without touching disk.
+    pub async fn preview_file_changes(
+        &self,
+        name: &str,
+        args: &Value,
+    ) -> Result<Vec<ProposedChange>> {
+        match name {
+            tools::EDIT_FILE => {
+                let input: EditInput =
+                    serde_json::from_value(args.clone()).context("invalid edit_file args")?;
+                let old_content = read_existing(&self.workspace_root.join(&input.path))
+                    .await?
+                    .ok_or_else(|| anyhow!("File not found: {}", input.path))?;
+                let new_content = utils::replace_text(&old_content, &input.old_str, &input.new_str)
+                    .ok_or_else(|| anyhow!("Could not find text to replace in {}", input.path))?;
+                Ok(vec![ProposedChange {
+                    path: input.path,
+                    source_path: None,
+                    old_content,
+                    new_content,
+                    delete: false,
+                }])
+            }
+            tools::WRITE_FILE => {
+                let input: WriteInput =
+                    serde_json::from_value(args.clone()).context("invalid write_file args")?;
+                let existing = read_existing(&self.workspace_root.join(&input.path)).await?;
+                let new_content = match (input.mode.as_str(), existing.as_deref()) {
+                    ("skip_if_exists", Some(_)) => return Ok(Vec::new()),
+                    ("append", Some(old)) => format!("{}{}", old, input.content),
+                    ("overwrite" | "append" | "skip_if_exists", _) => input.content,
+                    (mode, _) => return Err(anyhow!("Unsupported write mode '{}'", mode)),
+                };
+                Ok(vec![ProposedChange {
+                    path: input.path,
+                    source_path: None,
+                    old_content: existing.unwrap_or_default(),
+                    new_content,
+                    delete: false,
+                }])
+            }
+            tools::APPLY_PATCH => {
+                let input = args
+                    .get("input")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow!("Missing 'input' string with patch content"))?;
+                let patch = Patch::parse(input)?;
+                let mut changes = Vec::new();
+                for operation in patch.operations {
+                    match operation {
+                        PatchOperation::AddFile { path, content } => {
+                            let existing = read_existing(&self.workspace_root.join(&path)).await?;
+                            changes.push(ProposedChange {
+                                path,
+                                source_path: None,
+                                old_content: existing.unwrap_or_default(),
+                                new_content: content,
+                                delete: false,
+                            });
+                        }
+                        PatchOperation::DeleteFile { path } => {
+                            if let Some(old_content) =
+                                read_existing(&self.workspace_root.join(&path)).await?
+                            {
+                                changes.push(ProposedChange {
+                                    path,
+                                    source_path: None,
+                                    old_content,
+                                    new_content: String::new(),
+                                    delete: true,
+                                });
+                            }
+                        }
+                        PatchOperation::UpdateFile {
+                            path,
+                            new_path,
+                            hunks,
+                        } => {
+                            let old_content = read_existing(&self.workspace_root.join(&path))
+                                .await?
+                                .ok_or_else(|| anyhow!("File not found: {}", path))?;
+                            let new_content = Patch::apply_hunks_to_content(&old_content, &hunks)?;
+                            let (path, source_path) = match new_path {
+                                Some(target) => (target, Some(path)),
+                                None => (path, None),
+                            };
+                            changes.push(ProposedChange {
+                                path,
+                                source_path,
+                                old_content,
+                                new_content,
+                                delete: false,
+                            });
+                        }
+                    }
+                }
+                Ok(changes)
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Write the reviewed changes, keeping only the hunks the user accepted or edited.
+    pub async fn apply_reviewed_changes(&mut self, reviewed: &[ReviewedChange]) -> Result<Value> {
+        let mut applied = Vec::new();
+        for item in reviewed {
+            if item.all_rejected() {
+                continue;
+            }
+
+            let change = &item.change;
+            let target = self.workspace_root.join(&change.path);
+            let content = item.final_content();
+            if change.delete && content.is_empty() {
+                tokio::fs::remove_file(&target)
+                    .await
+                    .with_context(|| format!("failed to delete file: {}", target.display()))?;
+                applied.push(format!("Deleted file: {}", change.path));
+                continue;
+            }
+
+            self.file_ops_tool
+                .write_file(json!({
+                    "path": change.path,
+                    "content": content,
+                    "mode": "overwrite",
+                }))
+                .await?;
+            if let Some(source) = change.source_path.as_deref() {
+                let source_path = self.workspace_root.join(source);
+                if source_path.exists() {
+                    tokio::fs::remove_file(&source_path)
+                        .await
+                        .with_context(|| {
+                            format!("failed to remove old file: {}", source_path.display())
+                        })?;
+                }
+                applied.push(format!("Updated file: {} -> {}", source, change.path));
+            } else {
+                applied.push(format!("Updated file: {}", change.path));
+            }
+        }
+
+        let files: Vec<Value> = reviewed.iter().map(ReviewedChange::summary).collect();
+        if applied.is_empty() {
+            return Ok(json!({
+                "success": false,
+                "reviewed": true,
+                "rejected": true,
+                "files": files,
+                "message": "The user rejected every proposed hunk; nothing was written.",
+            }));
+        }
+
+        Ok(json!({
+            "success": true,
+            "reviewed": true,
+            "applied": applied,
+            "files": files,
+            "message": "Only accepted and edited hunks were written. Re-read the files before editing them again.",
+        }))
+    }
+}
+
+async fn read_existing(path: &Path) -> Result<Option<String>> {
+    if !tokio::fs::try_exists(path).await? {
+        return Ok(None);
+    }
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("failed to read file: {}", path.display()))?;
+    Ok(Some(content))
+}
@@ -44,6 +44,42 @@ pub(super) fn lines_match(content_lines: &[&str], expected_lines: &[&str]) -> bo
         .all(|(content_line, expected_line)| content_line.trim() == expected_line.trim())
 }
 
+/// Replace `old_str` with `new_str`, falling back to a whitespace-tolerant
+/// line match. Returns `None` when nothing matched.
+pub(super) fn replace_text(current: &str, old_str: &str, new_str: &str) -> Option<String> {
+    if current.contains(old_str) {
+        let replaced = current.replace(old_str, new_str);
+        if replaced != current {
+            return Some(replaced);
+        }
+    }
+
+    let normalized_content = normalize_whitespace(current);
+    let normalized_old_str = normalize_whitespace(old_str);
+    if !normalized_content.contains(&normalized_old_str) {
+        return None;
+    }
+
+    let old_lines: Vec<&str> = old_str.lines().collect();
+    let content_lines: Vec<&str> = current.lines().collect();
+    for i in 0..=(content_lines.len().saturating_sub(old_lines.len())) {
+        let window = &content_lines[i..i + old_lines.len()];
+        if lines_match(window, &old_lines) {
+            let before = content_lines[..i].join("\n");
+            let after = content_lines[i + old_lines.len()..].join("\n");
+            let replacement_lines: Vec<&str> = new_str.lines().collect();
+            return Some(format!(
+                "{}\n{}\n{}",
+                before,
+                replacement_lines.join("\n"),
+                after
+            ));
+        }
+    }
+
+    None
+}
+
 pub(super) fn astgrep_to_concise(v: Value) -> Value {
     let mut out = Vec::new();
     match v {
@@ -71,6 +71,9 @@ pub struct Input {
     pub chunk_lines: Option<usize>,
     #[serde(default)]
     pub max_lines: Option<usize>,
+    /// `text` (default) or `attachment` to return images and PDFs as media
+    #[serde(default)]
+    pub mode: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,496 @@
+//! Readability-style main content extraction and HTML to Markdown conversion
+
+use reqwest::Url;
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// Elements that never carry article content.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "nav", "footer", "aside",
+    "form", "button", "input", "select", "textarea", "dialog", "img", "picture", "video", "audio",
+];
+
+/// Landmark roles used for site chrome rather than content.
+const SKIPPED_ROLES: &[&str] = &[
+    "navigation",
+    "banner",
+    "contentinfo",
+    "complementary",
+    "search",
+];
+
+/// Class or id fragments that mark boilerplate blocks.
+const BOILERPLATE_MARKERS: &[&str] = &[
+    "sidebar",
+    "navbar",
+    "breadcrumb",
+    "cookie",
+    "advert",
+    "social",
+    "share-",
+    "footer",
+    "toc-",
+];
+
+/// Containers tried, in order, before falling back to density scoring.
+const CONTENT_SELECTORS: &[&str] = &["article", "main", "[role=main]"];
+
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "blockquote",
+    "body",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "html",
+    "li",
+    "main",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "ul",
+];
+
+pub(super) struct ExtractedPage {
+    pub title: Option<String>,
+    pub markdown: String,
+}
+
+/// Extract the main content of `html` as Markdown, resolving links against `base`.
+pub(super) fn extract_markdown(html: &str, base: Option<&Url>) -> ExtractedPage {
+    let document = Html::parse_document(html);
+    let title = Selector::parse("title")
+        .ok()
+        .and_then(|selector| document.select(&selector).next())
+        .map(|element| collapse_whitespace(&element.text().collect::<String>()))
+        .filter(|title| !title.is_empty());
+
+    let root = main_content(&document).unwrap_or_else(|| document.root_element());
+    let writer = MarkdownWriter { base };
+    ExtractedPage {
+        title,
+        markdown: tidy(&writer.blocks(root)),
+    }
+}
+
+fn main_content(document: &Html) -> Option<ElementRef<'_>> {
+    for query in CONTENT_SELECTORS {
+        let Ok(selector) = Selector::parse(query) else {
+            continue;
+        };
+        let best = document
+            .select(&selector)
+            .filter(|element| !is_skipped(element))
+            .max_by_key(|element| text_length(*element));
+        if let Some(element) = best
+            && text_length(element) > 0
+        {
+            return Some(element);
+        }
+    }
+
+    // No semantic container: take the block that directly holds the most prose.
+    let selector = Selector::parse("div, section").ok()?;
+    let body_score = Selector::parse("p, pre")
+        .ok()
+        .map(|paragraphs| document.select(&paragraphs).map(text_length).sum::<usize>())
+        .unwrap_or(0);
+    let (best, score) = document
+        .select(&selector)
+        .filter(|element| !is_skipped(element))
+        .map(|element| (element, direct_prose_length(element)))
+        .max_by_key(|(_, score)| *score)?;
+    (score * 2 >= body_score && score > 0).then_some(best)
+}
+
+fn direct_prose_length(element: ElementRef<'_>) -> usize {
+    element
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|child| {
+            matches!(
+                child.value().name(),
+                "p" | "pre" | "ul" | "ol" | "blockquote" | "table" | "h2" | "h3"
+            )
+        })
+        .map(text_length)
+        .sum()
+}
+
+fn text_length(element: ElementRef<'_>) -> usize {
+    element.text().map(|text| text.trim().len()).sum()
+}
+
+fn is_skipped(element: &ElementRef<'_>) -> bool {
+    let value = element.value();
+    if SKIPPED_ELEMENTS.contains(&value.name()) {
+        return true;
+    }
+    if value.attr("hidden").is_some() || value.attr("aria-hidden") == Some("true") {
+        return true;
+    }
+    if let Some(role) = value.attr("role")
+        && SKIPPED_ROLES.contains(&role)
+    {
+        return true;
+    }
+    // Page headers are chrome unless they hold the article title.
+    if value.name() == "header"
+        && Selector::parse("h1")
+            .map(|h1| element.select(&h1).next().is_none())
+            .unwrap_or(true)
+    {
+        return true;
+    }
+    value
+        .classes()
+        .chain(value.id())
+        .map(|token| token.to_lowercase())
+        .any(|token| {
+            BOILERPLATE_MARKERS
+                .iter()
+                .any(|marker| token.contains(marker))
+        })
+}
+
+fn is_block(element: &ElementRef<'_>) -> bool {
+    BLOCK_ELEMENTS.contains(&element.value().name())
+}
+
+struct MarkdownWriter<'a> {
+    base: Option<&'a Url>,
+}
+
+impl MarkdownWriter<'_> {
+    /// Render the children of `element` as blank-line separated blocks.
+    fn blocks(&self, element: ElementRef<'_>) -> String {
+        let mut blocks: Vec<String> = Vec::new();
+        let mut inline = String::new();
+
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => inline.push_str(&text.replace('\n', " ")),
+                Node::Element(_) => {
+                    let Some(child) = ElementRef::wrap(child) else {
+                        continue;
+                    };
+                    if is_skipped(&child) {
+                        continue;
+                    }
+                    if is_block(&child) {
+                        push_paragraph(&mut blocks, &mut inline);
+                        let rendered = self.block(child);
+                        if !rendered.trim().is_empty() {
+                            blocks.push(rendered);
+                        }
+                    } else {
+                        inline.push_str(&self.inline_element(child));
+                    }
+                }
+                _ => {}
+            }
+        }
+        push_paragraph(&mut blocks, &mut inline);
+        blocks.join("\n\n")
+    }
+
+    fn block(&self, element: ElementRef<'_>) -> String {
+        let name = element.value().name();
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                let text = normalize_inline(&self.inline(element)).replace('\n', " ");
+                if text.is_empty() {
+                    String::new()
+                } else {
+                    format!("{} {}", "#".repeat(level), text)
+                }
+            }
+            "p" | "dt" | "summary" | "figcaption" => normalize_inline(&self.inline(element)),
+            "pre" => code_block(element),
+            "ul" | "ol" => self.list(element, name == "ol"),
+            "blockquote" => self
+                .blocks(element)
+                .lines()
+                .map(|line| {
+                    if line.is_empty() {
+                        ">".to_string()
+                    } else {
+                        format!("> {}", line)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            "table" => self.table(element),
+            "hr" => "---".to_string(),
+            _ => self.blocks(element),
+        }
+    }
+
+    fn inline(&self, element: ElementRef<'_>) -> String {
+        let mut out = String::new();
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => out.push_str(&text.replace('\n', " ")),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child)
+                        && !is_skipped(&child)
+                    {
+                        out.push_str(&self.inline_element(child));
+                    }
+                }
+                _ => {}
+            }
+        }
+        out
+    }
+
+    fn inline_element(&self, element: ElementRef<'_>) -> String {
+        match element.value().name() {
+            "br" => "\n".to_string(),
+            "a" => {
+                let text = normalize_inline(&self.inline(element)).replace('\n', " ");
+                let href = element
+                    .value()
+                    .attr("href")
+                    .and_then(|href| self.resolve_link(href));
+                match href {
+                    Some(href) if !text.is_empty() => format!("[{}]({})", text, href),
+                    _ => text,
+                }
+            }
+            "strong" | "b" => wrap_inline(&self.inline(element), "**"),
+            "em" | "i" => wrap_inline(&self.inline(element), "*"),
+            "code" | "kbd" | "samp" => {
+                let code = collapse_whitespace(&element.text().collect::<String>());
+                if code.is_empty() {
+                    String::new()
+                } else if code.contains('`') {
+                    format!("`` {} ``", code)
+                } else {
+                    format!("`{}`", code)
+                }
+            }
+            _ if is_block(&element) => format!(" {} ", self.inline(element)),
+            _ => self.inline(element),
+        }
+    }
+
+    fn list(&self, element: ElementRef<'_>, ordered: bool) -> String {
+        let mut items = Vec::new();
+        let mut number = element
+            .value()
+            .attr("start")
+            .and_then(|start| start.parse::<usize>().ok())
+            .unwrap_or(1);
+        for item in element.children().filter_map(ElementRef::wrap) {
+            if item.value().name() != "li" || is_skipped(&item) {
+                continue;
+            }
+            let marker = if ordered {
+                let marker = format!("{}. ", number);
+                number += 1;
+                marker
+            } else {
+                "- ".to_string()
+            };
+            let body = self.blocks(item);
+            let indent = " ".repeat(marker.len());
+            let mut lines = body.lines();
+            let mut rendered = format!("{}{}", marker, lines.next().unwrap_or_default());
+            for line in lines {
+                rendered.push('\n');
+                if !line.is_empty() {
+                    rendered.push_str(&indent);
+                    rendered.push_str(line);
+                }
+            }
+            items.push(rendered);
+        }
+        items.join("\n")
+    }
+
+    fn table(&self, element: ElementRef<'_>) -> String {
+        let Ok(row_selector) = Selector::parse("tr") else {
+            return String::new();
+        };
+        let rows: Vec<Vec<String>> = element
+            .select(&row_selector)
+            .map(|row| {
+                row.children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|cell| matches!(cell.value().name(), "th" | "td"))
+                    .map(|cell| {
+                        normalize_inline(&self.inline(cell))
+                            .replace('\n', " ")
+                            .replace('|', "\\|")
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cells| !cells.is_empty())
+            .collect();
+        let Some(columns) = rows.iter().map(Vec::len).max() else {
+            return String::new();
+        };
+
+        let render_row = |cells: &[String]| {
+            let mut padded = cells.to_vec();
+            padded.resize(columns, String::new());
+            format!("| {} |", padded.join(" | "))
+        };
+        let mut lines = vec![
+            render_row(&rows[0]),
+            format!("|{}", " --- |".repeat(columns)),
+        ];
+        lines.extend(rows[1..].iter().map(|row| render_row(row)));
+        lines.join("\n")
+    }
+
+    fn resolve_link(&self, href: &str) -> Option<String> {
+        let href = href.trim();
+        if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+            return None;
+        }
+        match self.base {
+            Some(base) => base.join(href).ok().map(|url| url.to_string()),
+            None => Url::parse(href).ok().map(|url| url.to_string()),
+        }
+    }
+}
+
+fn code_block(element: ElementRef<'_>) -> String {
+    let language = std::iter::once(element)
+        .chain(element.children().filter_map(ElementRef::wrap))
+        .flat_map(|node| node.value().classes())
+        .find_map(|class| {
+            class
+                .strip_prefix("language-")
+                .or_else(|| class.strip_prefix("lang-"))
+        })
+        .unwrap_or("");
+    let code = element.text().collect::<String>();
+    let code = code.trim_matches('\n').trim_end();
+    let fence = if code.contains("```") { "~~~~" } else { "```" };
+    format!("{fence}{language}\n{code}\n{fence}")
+}
+
+fn push_paragraph(blocks: &mut Vec<String>, inline: &mut String) {
+    let paragraph = normalize_inline(inline);
+    if !paragraph.is_empty() {
+        blocks.push(paragraph);
+    }
+    inline.clear();
+}
+
+fn wrap_inline(text: &str, marker: &str) -> String {
+    let trimmed = collapse_whitespace(text);
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("{marker}{trimmed}{marker}")
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Collapse whitespace in inline content while keeping explicit `<br>` breaks.
+fn normalize_inline(text: &str) -> String {
+    text.split('\n')
+        .map(collapse_whitespace)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Squash runs of blank lines outside fenced code blocks.
+fn tidy(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut blank_run = 0;
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        if line.starts_with("```") || line.starts_with("~~~~") {
+            in_fence = !in_fence;
+        }
+        let line = if in_fence { line } else { line.trim_end() };
+        if line.is_empty() && !in_fence {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_article_with_code_and_links() {
+        let html = r#"<html><head><title> Guide | Docs </title></head><body>
+            <nav><a href="/">Home</a><a href="/blog">Blog</a></nav>
+            <article>
+              <h1>Getting started</h1>
+              <p>Install the <strong>crate</strong> with <code>cargo add</code>, then read
+                 the <a href="/api/index.html">API docs</a>.</p>
+              <pre><code class="language-rust">fn main() {
+    println!("hi");
+}</code></pre>
+              <h2>Options</h2>
+              <ul><li>Fast</li><li>Small<ul><li>Nested</li></ul></li></ul>
+              <table><tr><th>Name</th><th>Default</th></tr><tr><td>jobs</td><td>4</td></tr></table>
+            </article>
+            <footer>Copyright</footer>
+        </body></html>"#;
+        let base = Url::parse("https://docs.example.com/guide/").unwrap();
+        let page = extract_markdown(html, Some(&base));
+
+        assert_eq!(page.title.as_deref(), Some("Guide | Docs"));
+        assert_eq!(
+            page.markdown,
+            "# Getting started\n\n\
+             Install the **crate** with `cargo add`, then read the [API docs](https://docs.example.com/api/index.html).\n\n\
+             ```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n\n\
+             ## Options\n\n\
+             - Fast\n- Small\n\n  - Nested\n\n\
+             | Name | Default |\n| --- | --- |\n| jobs | 4 |"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_densest_block_without_landmarks() {
+        let html = r#"<body>
+            <div class="sidebar"><p>Related posts everywhere</p></div>
+            <div id="wrapper"><div class="post">
+              <p>The first paragraph carries the actual article content for readers.</p>
+              <p>The second paragraph continues with more detail.</p>
+            </div></div>
+        </body>"#;
+        let page = extract_markdown(html, None);
+        assert!(page.markdown.starts_with("The first paragraph"));
+        assert!(!page.markdown.contains("Related posts"));
+    }
+}
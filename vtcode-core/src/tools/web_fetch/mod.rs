@@ -0,0 +1,571 @@
+//! Fetch web pages and return their main content as paged Markdown
+
+mod markdown;
+
+use super::curl_tool::validate_public_https_url;
+use super::traits::Tool;
+use crate::config::constants::tools;
+use crate::tool_policy::ToolConstraints;
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use futures::StreamExt;
+use parking_lot::RwLock;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 15;
+const MAX_REDIRECTS: usize = 5;
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+const DEFAULT_MAX_TOKENS: usize = 4_000;
+const MIN_MAX_TOKENS: usize = 200;
+const MAX_MAX_TOKENS: usize = 20_000;
+const CHARS_PER_TOKEN: usize = 4;
+const MAX_CACHED_PAGES: usize = 32;
+
+#[derive(Debug, Deserialize)]
+struct WebFetchArgs {
+    url: String,
+    #[serde(default)]
+    max_tokens: Option<usize>,
+    #[serde(default)]
+    page: Option<usize>,
+    #[serde(default)]
+    section: Option<String>,
+    #[serde(default)]
+    refresh: bool,
+}
+
+#[derive(Debug, Clone)]
+struct CachedPage {
+    final_url: String,
+    title: Option<String>,
+    content_type: String,
+    markdown: String,
+    fetched_at: Instant,
+}
+
+/// A heading-delimited slice of the page
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Section {
+    heading: Option<String>,
+    text: String,
+}
+
+/// Fetches pages over HTTPS, extracts readable content and caches it for the session
+#[derive(Clone)]
+pub struct WebFetchTool {
+    client: Client,
+    cache: Arc<RwLock<HashMap<String, CachedPage>>>,
+}
+
+impl WebFetchTool {
+    pub fn new() -> Self {
+        // Redirects are followed by hand so every hop passes the URL policy.
+        let client = Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+            .user_agent("vtcode-web-fetch/0.1")
+            .build()
+            .unwrap_or_else(|error| {
+                warn!(
+                    ?error,
+                    "Failed to build dedicated web fetch client; falling back to default"
+                );
+                Client::new()
+            });
+        Self {
+            client,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Fetch `url` (or reuse the cached copy) and return one page of Markdown.
+    pub async fn fetch(&self, args: Value, constraints: &[&ToolConstraints]) -> Result<Value> {
+        let args: WebFetchArgs = serde_json::from_value(args)
+            .context("Invalid arguments for web_fetch. Provide an object with at least a 'url'.")?;
+        let url = Url::parse(args.url.trim()).context("Invalid URL provided to web_fetch")?;
+        validate_url(&url, constraints)?;
+
+        let key = url.to_string();
+        let cached = if args.refresh {
+            None
+        } else {
+            self.cache.read().get(&key).cloned()
+        };
+        let from_cache = cached.is_some();
+        let page = match cached {
+            Some(page) => page,
+            None => {
+                let page = self.download(url, constraints).await?;
+                self.store(key, page.clone());
+                page
+            }
+        };
+
+        let max_tokens = args
+            .max_tokens
+            .unwrap_or(DEFAULT_MAX_TOKENS)
+            .clamp(MIN_MAX_TOKENS, MAX_MAX_TOKENS);
+        let budget = max_tokens * CHARS_PER_TOKEN;
+        let sections = split_oversized(split_sections(&page.markdown), budget);
+        let pages = paginate(&sections, budget);
+        let total_pages = pages.len().max(1);
+
+        let requested = match args.section.as_deref() {
+            Some(query) => find_section_page(&sections, &pages, query).ok_or_else(|| {
+                anyhow!(
+                    "No section heading matches '{}'. Use the outline to pick one.",
+                    query
+                )
+            })?,
+            None => args.page.unwrap_or(1).max(1),
+        };
+        if requested > total_pages {
+            return Err(anyhow!(
+                "Page {} is out of range; the document has {} page(s) at max_tokens={}",
+                requested,
+                total_pages,
+                max_tokens
+            ));
+        }
+
+        let content = pages
+            .get(requested - 1)
+            .map(|indices| {
+                indices
+                    .iter()
+                    .map(|index| sections[*index].text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            })
+            .unwrap_or_default();
+        let outline: Vec<Value> = sections
+            .iter()
+            .enumerate()
+            .filter_map(|(index, section)| {
+                let heading = section.heading.as_ref()?;
+                let page = pages.iter().position(|indices| indices.contains(&index))? + 1;
+                Some(json!({"heading": heading, "page": page}))
+            })
+            .collect();
+
+        Ok(json!({
+            "success": true,
+            "url": args.url,
+            "final_url": page.final_url,
+            "title": page.title,
+            "content_type": page.content_type,
+            "page": requested,
+            "total_pages": total_pages,
+            "outline": outline,
+            "content": content,
+            "estimated_tokens": content.len() / CHARS_PER_TOKEN,
+            "cached": from_cache,
+        }))
+    }
+
+    async fn download(&self, url: Url, constraints: &[&ToolConstraints]) -> Result<CachedPage> {
+        let mut current = url;
+        let mut hops = 0;
+        let response = loop {
+            let response = self
+                .client
+                .get(current.clone())
+                .header(
+                    reqwest::header::ACCEPT,
+                    "text/html, application/xhtml+xml, text/markdown, text/plain;q=0.9, */*;q=0.1",
+                )
+                .send()
+                .await
+                .with_context(|| format!("Failed to fetch {}", current))?;
+
+            if !response.status().is_redirection() {
+                break response;
+            }
+            hops += 1;
+            if hops > MAX_REDIRECTS {
+                return Err(anyhow!("Too many redirects while fetching {}", current));
+            }
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| anyhow!("Redirect from {} has no Location header", current))?;
+            let next = current
+                .join(location)
+                .with_context(|| format!("Invalid redirect target '{}'", location))?;
+            validate_url(&next, constraints)
+                .with_context(|| format!("Redirect to {} is blocked", next))?;
+            current = next;
+        };
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("Request returned non-success status: {}", status));
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("")
+            .to_lowercase();
+        let is_html = content_type.is_empty() || content_type.contains("html");
+        if !is_html && !is_textual(&content_type) {
+            return Err(anyhow!(
+                "Content type '{}' cannot be converted to text",
+                content_type
+            ));
+        }
+
+        let mut body = Vec::new();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let bytes = chunk.with_context(|| format!("Failed to read body from {}", current))?;
+            let remaining = MAX_BODY_BYTES.saturating_sub(body.len());
+            body.extend_from_slice(&bytes[..bytes.len().min(remaining)]);
+            if body.len() >= MAX_BODY_BYTES {
+                break;
+            }
+        }
+        let text = String::from_utf8_lossy(&body).to_string();
+
+        let (title, markdown) = if is_html {
+            let extracted = markdown::extract_markdown(&text, Some(&current));
+            (extracted.title, extracted.markdown)
+        } else {
+            (None, text.trim().to_string())
+        };
+
+        Ok(CachedPage {
+            final_url: current.to_string(),
+            title,
+            content_type,
+            markdown,
+            fetched_at: Instant::now(),
+        })
+    }
+
+    fn store(&self, key: String, page: CachedPage) {
+        let mut cache = self.cache.write();
+        if cache.len() >= MAX_CACHED_PAGES
+            && !cache.contains_key(&key)
+            && let Some(oldest) = cache
+                .iter()
+                .min_by_key(|(_, page)| page.fetched_at)
+                .map(|(key, _)| key.clone())
+        {
+            cache.remove(&oldest);
+        }
+        cache.insert(key, page);
+    }
+}
+
+impl Default for WebFetchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for WebFetchTool {
+    async fn execute(&self, args: Value) -> Result<Value> {
+        self.fetch(args, &[]).await
+    }
+
+    fn name(&self) -> &'static str {
+        tools::WEB_FETCH
+    }
+
+    fn description(&self) -> &'static str {
+        "Fetches a web page and returns its main content as paged Markdown."
+    }
+}
+
+fn validate_url(url: &Url, constraints: &[&ToolConstraints]) -> Result<()> {
+    validate_public_https_url(url)?;
+    if let Some(host) = url.host_str()
+        && !constraints
+            .iter()
+            .all(|constraint| constraint.permits_host(host))
+    {
+        return Err(anyhow!("URL host '{}' is blocked by policy", host));
+    }
+    Ok(())
+}
+
+fn is_textual(content_type: &str) -> bool {
+    content_type.starts_with("text/")
+        || content_type.contains("json")
+        || content_type.contains("xml")
+        || content_type.contains("markdown")
+        || content_type.contains("yaml")
+        || content_type.contains("toml")
+}
+
+/// Split Markdown into sections at headings, ignoring `#` lines inside code fences.
+fn split_sections(markdown: &str) -> Vec<Section> {
+    let mut sections = Vec::new();
+    let mut current = Section {
+        heading: None,
+        text: String::new(),
+    };
+    let mut fence: Option<(char, usize)> = None;
+
+    for line in markdown.lines() {
+        if let Some((marker, len)) = fence_marker(line) {
+            match fence {
+                None => fence = Some((marker, len)),
+                // A fence only closes with the same character, at least as long
+                Some((open, open_len)) if open == marker && len >= open_len => fence = None,
+                Some(_) => {}
+            }
+        }
+        let heading = fence
+            .is_none()
+            .then(|| line.trim_start_matches('#'))
+            .filter(|rest| rest.len() < line.len() && rest.starts_with(' '))
+            .map(|rest| rest.trim().to_string());
+        if let Some(heading) = heading {
+            if !current.text.trim().is_empty() {
+                sections.push(current);
+            }
+            current = Section {
+                heading: Some(heading),
+                text: String::new(),
+            };
+        }
+        current.text.push_str(line);
+        current.text.push('\n');
+    }
+    if !current.text.trim().is_empty() {
+        sections.push(current);
+    }
+
+    for section in &mut sections {
+        section.text = section.text.trim().to_string();
+    }
+    sections
+}
+
+/// Fence character and run length when `line` is a CommonMark code fence
+/// (three or more backticks or tildes after at most three spaces).
+fn fence_marker(line: &str) -> Option<(char, usize)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let marker = trimmed
+        .chars()
+        .next()
+        .filter(|ch| matches!(ch, '`' | '~'))?;
+    let len = trimmed.chars().take_while(|&ch| ch == marker).count();
+    (len >= 3).then_some((marker, len))
+}
+
+/// Pack whole sections into pages of at most `budget` characters.
+fn paginate(sections: &[Section], budget: usize) -> Vec<Vec<usize>> {
+    let mut pages: Vec<Vec<usize>> = Vec::new();
+    let mut current: Vec<usize> = Vec::new();
+    let mut used = 0;
+
+    for (index, section) in sections.iter().enumerate() {
+        let size = section.text.len() + 2;
+        if used + size > budget && !current.is_empty() {
+            pages.push(std::mem::take(&mut current));
+            used = 0;
+        }
+        current.push(index);
+        used += size;
+    }
+    if !current.is_empty() {
+        pages.push(current);
+    }
+    pages
+}
+
+/// Break sections larger than `budget` into paragraph-sized pieces so paging never truncates.
+fn split_oversized(sections: Vec<Section>, budget: usize) -> Vec<Section> {
+    let mut result = Vec::new();
+    for section in sections {
+        if section.text.len() <= budget {
+            result.push(section);
+            continue;
+        }
+        let mut chunk = String::new();
+        let mut first = true;
+        for paragraph in section.text.split("\n\n") {
+            if !chunk.is_empty() && chunk.len() + paragraph.len() + 2 > budget {
+                result.push(Section {
+                    heading: if first { section.heading.clone() } else { None },
+                    text: std::mem::take(&mut chunk),
+                });
+                first = false;
+            }
+            for piece in split_at_chars(paragraph, budget) {
+                if !chunk.is_empty() {
+                    chunk.push_str("\n\n");
+                }
+                chunk.push_str(piece);
+                if chunk.len() >= budget {
+                    result.push(Section {
+                        heading: if first { section.heading.clone() } else { None },
+                        text: std::mem::take(&mut chunk),
+                    });
+                    first = false;
+                }
+            }
+        }
+        if !chunk.is_empty() {
+            result.push(Section {
+                heading: if first { section.heading.clone() } else { None },
+                text: chunk,
+            });
+        }
+    }
+    result
+}
+
+fn split_at_chars(text: &str, budget: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut rest = text;
+    while rest.len() > budget {
+        let mut cut = budget;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let (head, tail) = rest.split_at(cut);
+        pieces.push(head);
+        rest = tail;
+    }
+    pieces.push(rest);
+    pieces
+}
+
+fn find_section_page(sections: &[Section], pages: &[Vec<usize>], query: &str) -> Option<usize> {
+    let query = query.trim().to_lowercase();
+    let index = sections.iter().position(|section| {
+        section
+            .heading
+            .as_ref()
+            .is_some_and(|heading| heading.to_lowercase().contains(&query))
+    })?;
+    pages
+        .iter()
+        .position(|indices| indices.contains(&index))
+        .map(|page| page + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded_tool(url: &str, markdown: &str) -> WebFetchTool {
+        let tool = WebFetchTool::new();
+        tool.store(
+            url.to_string(),
+            CachedPage {
+                final_url: url.to_string(),
+                title: Some("Docs".to_string()),
+                content_type: "text/html".to_string(),
+                markdown: markdown.to_string(),
+                fetched_at: Instant::now(),
+            },
+        );
+        tool
+    }
+
+    #[test]
+    fn sections_ignore_headings_inside_code() {
+        let sections =
+            split_sections("Intro\n\n# Usage\n```sh\n# not a heading\n```\n## API\nCall it");
+        let headings: Vec<_> = sections.iter().map(|s| s.heading.clone()).collect();
+        assert_eq!(
+            headings,
+            vec![None, Some("Usage".to_string()), Some("API".to_string())]
+        );
+    }
+
+    #[test]
+    fn sections_recognise_tilde_and_indented_fences() {
+        let sections = split_sections(
+            "# Usage\n~~~\n# tilde\n~~~\n  ```sh\n# indented\n```\n````\n```\n# nested\n````\n# API\nCall it",
+        );
+        let headings: Vec<_> = sections.iter().map(|s| s.heading.clone()).collect();
+        assert_eq!(
+            headings,
+            vec![Some("Usage".to_string()), Some("API".to_string())]
+        );
+    }
+
+    #[test]
+    fn oversized_sections_are_split_without_losing_text() {
+        let body = "word ".repeat(300);
+        let sections = vec![Section {
+            heading: Some("Big".to_string()),
+            text: format!("# Big\n\n{}\n\n{}", body.trim(), body.trim()),
+        }];
+        let split = split_oversized(sections.clone(), 1_000);
+        assert!(split.len() > 1);
+        assert!(split.iter().all(|section| section.text.len() <= 1_000));
+        assert_eq!(split[0].heading.as_deref(), Some("Big"));
+        let rejoined: usize = split
+            .iter()
+            .map(|s| s.text.split_whitespace().count())
+            .sum();
+        assert_eq!(rejoined, sections[0].text.split_whitespace().count());
+    }
+
+    #[tokio::test]
+    async fn pages_by_section_from_the_session_cache() -> Result<()> {
+        let url = "https://docs.example.com/guide";
+        let long = "text ".repeat(235);
+        let markdown =
+            format!("# Intro\n\n{long}\n\n# Install\n\n{long}\n\n# Configure\n\nSet `jobs`.");
+        let tool = seeded_tool(url, &markdown);
+
+        let first = tool
+            .fetch(json!({"url": url, "max_tokens": 300}), &[])
+            .await?;
+        assert_eq!(first["cached"], true);
+        assert_eq!(first["page"], 1);
+        assert_eq!(first["total_pages"], 3);
+        assert!(first["content"].as_str().unwrap().starts_with("# Intro"));
+        assert_eq!(first["outline"][2]["heading"], "Configure");
+
+        let configure = tool
+            .fetch(
+                json!({"url": url, "max_tokens": 300, "section": "config"}),
+                &[],
+            )
+            .await?;
+        assert_eq!(configure["page"], 3);
+        assert_eq!(configure["content"], "# Configure\n\nSet `jobs`.");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rejects_local_and_denied_hosts() {
+        let tool = WebFetchTool::new();
+        assert!(
+            tool.fetch(json!({"url": "https://localhost/docs"}), &[])
+                .await
+                .is_err()
+        );
+
+        let constraints = ToolConstraints {
+            denied_url_hosts: Some(vec!["example.com".to_string()]),
+            ..ToolConstraints::default()
+        };
+        let tool = seeded_tool("https://docs.example.com/", "# Cached");
+        assert!(
+            tool.fetch(json!({"url": "https://docs.example.com/"}), &[&constraints])
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,525 @@
+//! Web search tool backed by pluggable search providers
+
+use super::traits::Tool;
+use crate::config::constants::tools;
+use crate::config::core::{WebSearchBackendKind, WebSearchConfig};
+use crate::tool_policy::ToolConstraints;
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+const BRAVE_API_ENDPOINT: &str = "https://api.search.brave.com/res/v1/web/search";
+const BRAVE_API_KEY_ENV: &str = "BRAVE_API_KEY";
+const MAX_SNIPPET_CHARS: usize = 400;
+
+/// A single search hit returned to the model
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+/// A search provider queried by the `web_search` tool
+#[async_trait]
+pub trait SearchBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn search(&self, client: &Client, query: &str, limit: usize)
+    -> Result<Vec<SearchResult>>;
+}
+
+/// SearxNG instance queried through its JSON output format
+pub struct SearxngBackend {
+    endpoint: Url,
+}
+
+impl SearxngBackend {
+    pub fn new(endpoint: &str) -> Result<Self> {
+        let base = if endpoint.ends_with('/') {
+            endpoint.to_string()
+        } else {
+            format!("{}/", endpoint)
+        };
+        Ok(Self {
+            endpoint: parse_endpoint(&base)?.join("search")?,
+        })
+    }
+}
+
+#[async_trait]
+impl SearchBackend for SearxngBackend {
+    fn name(&self) -> &'static str {
+        "searxng"
+    }
+
+    async fn search(
+        &self,
+        client: &Client,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let request = client
+            .get(self.endpoint.clone())
+            .query(&[("q", query), ("format", "json")]);
+        let body = send_json(request).await?;
+        Ok(collect_results(
+            body.pointer("/results"),
+            "title",
+            "url",
+            "content",
+            limit,
+        ))
+    }
+}
+
+/// Brave Search web results API
+pub struct BraveBackend {
+    endpoint: Url,
+    api_key: String,
+}
+
+impl BraveBackend {
+    pub fn new(endpoint: Option<&str>, api_key: String) -> Result<Self> {
+        Ok(Self {
+            endpoint: parse_endpoint(endpoint.unwrap_or(BRAVE_API_ENDPOINT))?,
+            api_key,
+        })
+    }
+}
+
+#[async_trait]
+impl SearchBackend for BraveBackend {
+    fn name(&self) -> &'static str {
+        "brave"
+    }
+
+    async fn search(
+        &self,
+        client: &Client,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let request = client
+            .get(self.endpoint.clone())
+            .header("X-Subscription-Token", &self.api_key)
+            .query(&[("q", query), ("count", &limit.to_string())]);
+        let body = send_json(request).await?;
+        Ok(collect_results(
+            body.pointer("/web/results"),
+            "title",
+            "url",
+            "description",
+            limit,
+        ))
+    }
+}
+
+/// Any endpoint that answers `GET ?<query_param>=...` with a JSON array of results
+pub struct JsonEndpointBackend {
+    endpoint: Url,
+    api_key: Option<String>,
+    query_param: String,
+    results_pointer: String,
+    title_field: String,
+    url_field: String,
+    snippet_field: String,
+}
+
+impl JsonEndpointBackend {
+    pub fn from_config(config: &WebSearchConfig, api_key: Option<String>) -> Result<Self> {
+        let endpoint = config
+            .endpoint
+            .as_deref()
+            .ok_or_else(|| anyhow!("web_search json backend requires an endpoint"))?;
+        Ok(Self {
+            endpoint: parse_endpoint(endpoint)?,
+            api_key,
+            query_param: config.query_param.clone(),
+            results_pointer: config.results_pointer.clone(),
+            title_field: config.title_field.clone(),
+            url_field: config.url_field.clone(),
+            snippet_field: config.snippet_field.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl SearchBackend for JsonEndpointBackend {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    async fn search(
+        &self,
+        client: &Client,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let mut request = client
+            .get(self.endpoint.clone())
+            .query(&[(self.query_param.as_str(), query)]);
+        if let Some(key) = self.api_key.as_deref() {
+            request = request.bearer_auth(key);
+        }
+        let body = send_json(request).await?;
+        let results = if self.results_pointer.is_empty() {
+            Some(&body)
+        } else {
+            body.pointer(&self.results_pointer)
+        };
+        Ok(collect_results(
+            results,
+            &self.title_field,
+            &self.url_field,
+            &self.snippet_field,
+            limit,
+        ))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WebSearchArgs {
+    query: String,
+    #[serde(default)]
+    max_results: Option<usize>,
+}
+
+/// Searches the web through the configured backend and filters results by host policy
+#[derive(Clone)]
+pub struct WebSearchTool {
+    client: Client,
+    backend: Option<Arc<dyn SearchBackend>>,
+    max_results: usize,
+}
+
+impl WebSearchTool {
+    /// Tool without a backend; searches fail until one is configured.
+    pub fn new() -> Self {
+        Self::with_backend(None, WebSearchConfig::default().max_results)
+    }
+
+    pub fn with_backend(backend: Option<Arc<dyn SearchBackend>>, max_results: usize) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+            .user_agent("vtcode-web-search/0.1")
+            .build()
+            .unwrap_or_else(|error| {
+                warn!(
+                    ?error,
+                    "Failed to build dedicated web search client; falling back to default"
+                );
+                Client::new()
+            });
+        Self {
+            client,
+            backend,
+            max_results: max_results.max(1),
+        }
+    }
+
+    pub fn from_config(config: &WebSearchConfig) -> Result<Self> {
+        let api_key = config
+            .api_key_env
+            .as_deref()
+            .and_then(|name| std::env::var(name).ok())
+            .filter(|value| !value.trim().is_empty());
+
+        let backend: Option<Arc<dyn SearchBackend>> = match config.backend {
+            WebSearchBackendKind::Searxng => match config.endpoint.as_deref() {
+                Some(endpoint) => Some(Arc::new(SearxngBackend::new(endpoint)?)),
+                None => None,
+            },
+            WebSearchBackendKind::Brave => {
+                let key = api_key.or_else(|| std::env::var(BRAVE_API_KEY_ENV).ok());
+                match key {
+                    Some(key) => Some(Arc::new(BraveBackend::new(
+                        config.endpoint.as_deref(),
+                        key,
+                    )?)),
+                    None => None,
+                }
+            }
+            WebSearchBackendKind::Json => {
+                Some(Arc::new(JsonEndpointBackend::from_config(config, api_key)?))
+            }
+        };
+
+        Ok(Self::with_backend(backend, config.max_results))
+    }
+
+    /// Run a search, dropping results whose host any of `constraints` rejects.
+    pub async fn search(&self, args: Value, constraints: &[&ToolConstraints]) -> Result<Value> {
+        let args: WebSearchArgs = serde_json::from_value(args)
+            .context("Invalid arguments for web_search. Provide an object with a 'query'.")?;
+        let query = args.query.trim();
+        if query.is_empty() {
+            return Err(anyhow!("query must not be empty"));
+        }
+
+        let backend = self.backend.as_ref().ok_or_else(|| {
+            anyhow!(
+                "web_search has no backend configured. Set [tools.web_search] backend and endpoint (or api_key_env) in vtcode.toml."
+            )
+        })?;
+
+        let limit = args
+            .max_results
+            .unwrap_or(self.max_results)
+            .clamp(1, self.max_results);
+        let found = backend
+            .search(&self.client, query, limit)
+            .await
+            .with_context(|| format!("{} search failed", backend.name()))?;
+
+        let total = found.len();
+        let results: Vec<SearchResult> = found
+            .into_iter()
+            .filter(|result| result_permitted(&result.url, constraints))
+            .take(limit)
+            .collect();
+        let filtered = total.saturating_sub(results.len());
+
+        Ok(json!({
+            "success": true,
+            "backend": backend.name(),
+            "query": query,
+            "results": results,
+            "filtered_by_policy": filtered,
+        }))
+    }
+}
+
+impl Default for WebSearchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for WebSearchTool {
+    async fn execute(&self, args: Value) -> Result<Value> {
+        self.search(args, &[]).await
+    }
+
+    fn name(&self) -> &'static str {
+        tools::WEB_SEARCH
+    }
+
+    fn description(&self) -> &'static str {
+        "Searches the web and returns titles, URLs and snippets."
+    }
+}
+
+fn parse_endpoint(endpoint: &str) -> Result<Url> {
+    let url = Url::parse(endpoint)
+        .with_context(|| format!("Invalid web_search endpoint '{}'", endpoint))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(anyhow!("web_search endpoint must use http or https"));
+    }
+    Ok(url)
+}
+
+async fn send_json(request: RequestBuilder) -> Result<Value> {
+    let response = request
+        .header(reqwest::header::ACCEPT, "application/json")
+        .send()
+        .await
+        .context("Failed to reach search backend")?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("Search backend returned status {}", status));
+    }
+    response
+        .json::<Value>()
+        .await
+        .context("Search backend returned invalid JSON")
+}
+
+fn collect_results(
+    results: Option<&Value>,
+    title_field: &str,
+    url_field: &str,
+    snippet_field: &str,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let Some(items) = results.and_then(|value| value.as_array()) else {
+        return Vec::new();
+    };
+    let field = |item: &Value, name: &str| {
+        item.get(name)
+            .and_then(|value| value.as_str())
+            .map(|text| text.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let url = field(item, url_field);
+            if url.is_empty() {
+                return None;
+            }
+            let snippet: String = field(item, snippet_field)
+                .chars()
+                .take(MAX_SNIPPET_CHARS)
+                .collect();
+            Some(SearchResult {
+                title: field(item, title_field),
+                url,
+                snippet,
+            })
+        })
+        // Over-fetch so policy filtering can still fill the requested count
+        .take(limit.saturating_mul(2))
+        .collect()
+}
+
+fn result_permitted(url: &str, constraints: &[&ToolConstraints]) -> bool {
+    let Ok(parsed) = Url::parse(url) else {
+        return false;
+    };
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return false;
+    }
+    let Some(host) = parsed.host_str() else {
+        return false;
+    };
+    constraints
+        .iter()
+        .all(|constraint| constraint.permits_host(host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// Serve `body` as JSON to a single request and report the request line.
+    fn stub_server(body: Value) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = Vec::new();
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                    break;
+                }
+                request.push(line.trim_end().to_string());
+            }
+            let payload = body.to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                payload.len(),
+                payload
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+            sender.send(request.join("\n")).unwrap();
+        });
+        (address, receiver)
+    }
+
+    #[tokio::test]
+    async fn searxng_backend_maps_results() -> Result<()> {
+        let (endpoint, requests) = stub_server(json!({
+            "results": [
+                {"title": "Tokio", "url": "https://tokio.rs/", "content": "An async runtime"},
+                {"title": "Docs", "url": "https://docs.rs/tokio", "content": "API docs"}
+            ]
+        }));
+        let tool = WebSearchTool::from_config(&WebSearchConfig {
+            endpoint: Some(endpoint),
+            ..WebSearchConfig::default()
+        })?;
+
+        let response = tool.search(json!({"query": "tokio runtime"}), &[]).await?;
+        assert_eq!(response["backend"], "searxng");
+        assert_eq!(response["results"][0]["title"], "Tokio");
+        assert_eq!(response["results"][0]["snippet"], "An async runtime");
+        assert_eq!(response["results"].as_array().unwrap().len(), 2);
+
+        let request = requests.recv()?;
+        assert!(request.starts_with("GET /search?q=tokio+runtime&format=json"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn brave_backend_sends_subscription_token() -> Result<()> {
+        let (endpoint, requests) = stub_server(json!({
+            "web": {"results": [
+                {"title": "Rust", "url": "https://www.rust-lang.org/", "description": "A language"}
+            ]}
+        }));
+        let backend = BraveBackend::new(Some(&endpoint), "secret".to_string())?;
+        let tool = WebSearchTool::with_backend(Some(Arc::new(backend)), 5);
+
+        let response = tool.search(json!({"query": "rust"}), &[]).await?;
+        assert_eq!(response["results"][0]["url"], "https://www.rust-lang.org/");
+        assert_eq!(response["results"][0]["snippet"], "A language");
+
+        let request = requests.recv()?.to_lowercase();
+        assert!(request.contains("x-subscription-token: secret"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn json_backend_applies_host_policy() -> Result<()> {
+        let (endpoint, _requests) = stub_server(json!({
+            "data": {"hits": [
+                {"name": "Internal", "link": "https://wiki.corp.internal/page", "summary": "private"},
+                {"name": "Blocked", "link": "https://ads.example.com/", "summary": "ads"},
+                {"name": "Public", "link": "https://docs.example.com/guide", "summary": "guide"}
+            ]}
+        }));
+        let tool = WebSearchTool::from_config(&WebSearchConfig {
+            backend: WebSearchBackendKind::Json,
+            endpoint: Some(endpoint),
+            results_pointer: "/data/hits".to_string(),
+            title_field: "name".to_string(),
+            url_field: "link".to_string(),
+            snippet_field: "summary".to_string(),
+            ..WebSearchConfig::default()
+        })?;
+
+        let curl = ToolConstraints {
+            denied_url_hosts: Some(vec![".internal".to_string()]),
+            ..ToolConstraints::default()
+        };
+        let search = ToolConstraints {
+            denied_url_hosts: Some(vec!["ads.example.com".to_string()]),
+            allowed_url_hosts: Some(vec!["example.com".to_string()]),
+            ..ToolConstraints::default()
+        };
+
+        let response = tool
+            .search(json!({"query": "guide"}), &[&curl, &search])
+            .await?;
+        let results = response["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["title"], "Public");
+        assert_eq!(response["filtered_by_policy"], 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fails_without_backend() {
+        let tool = WebSearchTool::new();
+        assert!(
+            tool.search(json!({"query": "anything"}), &[])
+                .await
+                .is_err()
+        );
+    }
+}
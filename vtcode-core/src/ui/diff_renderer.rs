@@ -102,6 +102,11 @@ impl DiffRenderer {
         output
     }
 
+    /// Render a subset of diff lines, one string per line, without the file summary.
+    pub fn render_lines(&self, lines: &[DiffLine]) -> Vec<String> {
+        lines.iter().map(|line| self.render_line(line)).collect()
+    }
+
     fn render_summary(&self, diff: &FileDiff) -> String {
         let bullet = self.paint(&self.palette.bullet, "•");
         let label = self.paint(&self.palette.label, "Edited");
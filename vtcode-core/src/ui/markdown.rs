@@ -729,7 +729,8 @@ fn try_highlight(
     }
 
     let syntax = select_syntax(language);
-    let theme = load_theme(&config.theme, config.cache_themes);
+    let theme_name = theme::active_syntax_theme().unwrap_or_else(|| config.theme.clone());
+    let theme = load_theme(&theme_name, config.cache_themes);
     let mut highlighter = HighlightLines::new(syntax, &theme);
     let mut rendered = Vec::new();
 
@@ -767,7 +768,15 @@ fn load_theme(theme_name: &str, cache: bool) -> Theme {
     }
 
     let defaults = ThemeSet::load_defaults();
-    if let Some(theme) = defaults.themes.get(theme_name).cloned() {
+    // Custom UI themes may point at a `.tmTheme` file instead of a bundled theme.
+    let loaded = if theme_name.ends_with(".tmTheme") {
+        ThemeSet::get_theme(theme_name)
+            .map_err(|error| warn!(%error, "theme" = theme_name, "Failed to load syntax theme"))
+            .ok()
+    } else {
+        defaults.themes.get(theme_name).cloned()
+    };
+    if let Some(theme) = loaded {
         if cache {
             let mut guard = THEME_CACHE.write();
             if guard.len() >= MAX_THEME_CACHE_SIZE {
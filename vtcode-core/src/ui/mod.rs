@@ -5,6 +5,7 @@
 
 pub mod diff_renderer;
 pub mod markdown;
+pub mod notifications;
 pub mod slash;
 pub mod styled;
 pub mod terminal;
@@ -0,0 +1,263 @@
+//! Terminal and desktop notifications for events that need the user
+//!
+//! Turns can run for minutes and tool approvals wait silently, so the session
+//! reports them through the backend selected in `[ui.notifications]`.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tracing::debug;
+
+use crate::config::{NotificationBackend, NotificationsConfig};
+
+const APP_NAME: &str = "VT Code";
+const BODY_LIMIT: usize = 200;
+
+/// Something the user may want to hear about while looking elsewhere
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotificationEvent {
+    TurnCompleted,
+    ApprovalRequested {
+        tool: String,
+    },
+    /// A review or MCP request is waiting in a modal; `message` says which.
+    InputRequested {
+        message: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl NotificationEvent {
+    /// Stable name passed to notification commands
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TurnCompleted => "turn_complete",
+            Self::ApprovalRequested { .. } => "approval",
+            Self::InputRequested { .. } => "input",
+            Self::Error { .. } => "error",
+        }
+    }
+
+    pub fn title(&self) -> String {
+        let summary = match self {
+            Self::TurnCompleted => "turn complete",
+            Self::ApprovalRequested { .. } => "approval needed",
+            Self::InputRequested { .. } => "input needed",
+            Self::Error { .. } => "turn failed",
+        };
+        format!("{APP_NAME}: {summary}")
+    }
+
+    pub fn body(&self) -> String {
+        let body = match self {
+            Self::TurnCompleted => "The agent finished and is waiting for you.".to_string(),
+            Self::ApprovalRequested { tool } => format!("'{tool}' is waiting for your approval."),
+            Self::InputRequested { message } | Self::Error { message } => message.clone(),
+        };
+        if body.chars().count() > BODY_LIMIT {
+            let mut truncated: String = body.chars().take(BODY_LIMIT - 1).collect();
+            truncated.push('…');
+            truncated
+        } else {
+            body
+        }
+    }
+}
+
+/// Delivers [`NotificationEvent`]s according to the user's configuration
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    config: NotificationsConfig,
+}
+
+impl Notifier {
+    pub fn new(config: NotificationsConfig) -> Self {
+        Self { config }
+    }
+
+    /// Send `event` if notifications for it are enabled.
+    pub fn notify(&self, event: &NotificationEvent) {
+        if self.should_send(event, None) {
+            self.deliver(event);
+        }
+    }
+
+    /// Send `event` that ended `elapsed` of work, skipping runs shorter than
+    /// `min_duration_secs`.
+    pub fn notify_after(&self, event: &NotificationEvent, elapsed: Duration) {
+        if self.should_send(event, Some(elapsed)) {
+            self.deliver(event);
+        }
+    }
+
+    fn should_send(&self, event: &NotificationEvent, elapsed: Option<Duration>) -> bool {
+        let wanted = match event {
+            NotificationEvent::TurnCompleted => self.config.turn_complete,
+            NotificationEvent::ApprovalRequested { .. }
+            | NotificationEvent::InputRequested { .. } => self.config.approval,
+            NotificationEvent::Error { .. } => self.config.errors,
+        };
+        let long_enough = elapsed
+            .is_none_or(|elapsed| elapsed >= Duration::from_secs(self.config.min_duration_secs));
+        self.config.enabled && wanted && long_enough
+    }
+
+    fn deliver(&self, event: &NotificationEvent) {
+        let title = event.title();
+        let body = event.body();
+        let result = match self.config.backend {
+            NotificationBackend::Bell | NotificationBackend::Osc9 | NotificationBackend::Osc777 => {
+                write_to_terminal(&escape_sequence(self.config.backend, &title, &body))
+            }
+            NotificationBackend::NotifySend => {
+                let mut command = Command::new("notify-send");
+                command
+                    .arg("--app-name")
+                    .arg(APP_NAME)
+                    .arg(&title)
+                    .arg(&body);
+                spawn_detached(command)
+            }
+            NotificationBackend::Command => match self.config.command.as_deref() {
+                Some(line) if !line.trim().is_empty() => {
+                    let mut command = shell_command(line);
+                    command
+                        .env("VTCODE_NOTIFICATION_TITLE", &title)
+                        .env("VTCODE_NOTIFICATION_BODY", &body)
+                        .env("VTCODE_NOTIFICATION_EVENT", event.name());
+                    spawn_detached(command)
+                }
+                _ => Err(io::Error::other(
+                    "the command backend needs `ui.notifications.command`",
+                )),
+            },
+        };
+        if let Err(error) = result {
+            debug!(%error, event = event.name(), "failed to deliver notification");
+        }
+    }
+}
+
+/// Terminal escape sequence for the bell and OSC backends.
+fn escape_sequence(backend: NotificationBackend, title: &str, body: &str) -> String {
+    match backend {
+        NotificationBackend::Osc9 => {
+            format!("\x1b]9;{}: {}\x07", sanitize(title), sanitize(body))
+        }
+        NotificationBackend::Osc777 => format!(
+            "\x1b]777;notify;{};{}\x07",
+            sanitize(title).replace(';', ","),
+            sanitize(body)
+        ),
+        _ => "\x07".to_string(),
+    }
+}
+
+/// Drop control characters that would end or corrupt an escape sequence.
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|ch| if ch.is_control() { ' ' } else { ch })
+        .collect()
+}
+
+fn write_to_terminal(sequence: &str) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()
+}
+
+fn shell_command(line: &str) -> Command {
+    if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(line);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(line);
+        command
+    }
+}
+
+/// Start `command` without tying up the terminal, reaping it in the background.
+fn spawn_detached(mut command: Command) -> io::Result<()> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notifier(enabled: bool) -> Notifier {
+        Notifier::new(NotificationsConfig {
+            enabled,
+            min_duration_secs: 10,
+            errors: false,
+            ..NotificationsConfig::default()
+        })
+    }
+
+    #[test]
+    fn respects_enabled_events_and_duration_threshold() {
+        let approval = NotificationEvent::ApprovalRequested {
+            tool: "run_terminal_cmd".to_string(),
+        };
+        let error = NotificationEvent::Error {
+            message: "Provider error".to_string(),
+        };
+
+        assert!(!notifier(false).should_send(&approval, None));
+
+        let notifier = notifier(true);
+        assert!(notifier.should_send(&approval, None));
+        assert!(notifier.should_send(
+            &NotificationEvent::InputRequested {
+                message: "The plan is waiting for review.".to_string(),
+            },
+            None
+        ));
+        assert!(!notifier.should_send(&error, Some(Duration::from_secs(60))));
+        assert!(!notifier.should_send(
+            &NotificationEvent::TurnCompleted,
+            Some(Duration::from_secs(3))
+        ));
+        assert!(notifier.should_send(
+            &NotificationEvent::TurnCompleted,
+            Some(Duration::from_secs(12))
+        ));
+    }
+
+    #[test]
+    fn builds_escape_sequences_without_control_characters() {
+        assert_eq!(
+            escape_sequence(NotificationBackend::Bell, "VT Code", "done"),
+            "\x07"
+        );
+        assert_eq!(
+            escape_sequence(NotificationBackend::Osc9, "VT Code", "line\x07break"),
+            "\x1b]9;VT Code: line break\x07"
+        );
+        assert_eq!(
+            escape_sequence(NotificationBackend::Osc777, "a;b", "c;d"),
+            "\x1b]777;notify;a,b;c;d\x07"
+        );
+    }
+
+    #[test]
+    fn truncates_long_error_bodies() {
+        let event = NotificationEvent::Error {
+            message: "x".repeat(500),
+        };
+        assert_eq!(event.body().chars().count(), BODY_LIMIT);
+        assert!(event.body().ends_with('…'));
+    }
+}
@@ -38,6 +38,34 @@ pub static SLASH_COMMANDS: Lazy<Vec<SlashCommandInfo>> = Lazy::new(|| {
             name: "sessions",
             description: "List recent archived sessions (usage: /sessions [limit])",
         },
+        SlashCommandInfo {
+            name: "status",
+            description: "Show session model, token usage and spend",
+        },
+        SlashCommandInfo {
+            name: "budget",
+            description: "Show or change the session cost limit (usage: /budget [usd|off])",
+        },
+        SlashCommandInfo {
+            name: "compact",
+            description: "Summarize older turns to free context (usage: /compact [focus])",
+        },
+        SlashCommandInfo {
+            name: "plan",
+            description: "Toggle read-only plan mode; edits wait for plan approval (usage: /plan [on|off])",
+        },
+        SlashCommandInfo {
+            name: "memory",
+            description: "List or edit remembered facts (usage: /memory [add|edit <n>|remove <n>] [--user] [text])",
+        },
+        SlashCommandInfo {
+            name: "export",
+            description: "Export the conversation with tool calls (usage: /export [md|html|json] [path])",
+        },
+        SlashCommandInfo {
+            name: "branches",
+            description: "List conversation branches or switch to one (usage: /branches [number])",
+        },
         SlashCommandInfo {
             name: "help",
             description: "Show slash command help",
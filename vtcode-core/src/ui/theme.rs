@@ -1,9 +1,13 @@
 use anstyle::{Color, Effects, RgbColor, Style};
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use catppuccin::PALETTE;
 use once_cell::sync::Lazy;
 use parking_lot::RwLock;
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use crate::config::constants::defaults;
 
@@ -32,7 +36,7 @@ impl ThemePalette {
         style
     }
 
-    fn build_styles(&self) -> ThemeStyles {
+    fn build_styles(&self, min_contrast: f64) -> ThemeStyles {
         let primary = self.primary_accent;
         let background = self.background;
         let secondary = self.secondary_accent;
@@ -42,7 +46,7 @@ impl ThemePalette {
         let text_color = ensure_contrast(
             self.foreground,
             background,
-            MIN_CONTRAST,
+            min_contrast,
             &[
                 lighten(self.foreground, 0.25),
                 lighten(secondary, 0.2),
@@ -52,21 +56,26 @@ impl ThemePalette {
         let info_color = ensure_contrast(
             secondary,
             background,
-            MIN_CONTRAST,
+            min_contrast,
             &[lighten(secondary, 0.2), text_color, fallback_light],
         );
         let tool_candidate = mix(self.alert, background, 0.35);
         let tool_color = ensure_contrast(
             tool_candidate,
             background,
-            MIN_CONTRAST,
-            &[self.alert, mix(self.alert, secondary, 0.25), fallback_light],
+            min_contrast,
+            &[
+                self.alert,
+                mix(self.alert, secondary, 0.25),
+                fallback_light,
+                text_color,
+            ],
         );
         let tool_body_candidate = mix(tool_color, text_color, 0.35);
         let tool_body_color = ensure_contrast(
             tool_body_candidate,
             background,
-            MIN_CONTRAST,
+            min_contrast,
             &[lighten(tool_color, 0.2), text_color, fallback_light],
         );
         let tool_style = Style::new().fg_color(Some(Color::Rgb(tool_color))).bold();
@@ -74,26 +83,26 @@ impl ThemePalette {
         let response_color = ensure_contrast(
             text_color,
             background,
-            MIN_CONTRAST,
+            min_contrast,
             &[lighten(text_color, 0.15), fallback_light],
         );
         let reasoning_color = ensure_contrast(
             lighten(secondary, 0.3),
             background,
-            MIN_CONTRAST,
+            min_contrast,
             &[lighten(secondary, 0.15), text_color, fallback_light],
         );
         let reasoning_style = Self::style_from(reasoning_color, false).effects(Effects::ITALIC);
         let user_color = ensure_contrast(
             lighten(primary, 0.25),
             background,
-            MIN_CONTRAST,
+            min_contrast,
             &[lighten(secondary, 0.15), info_color, text_color],
         );
         let alert_color = ensure_contrast(
             self.alert,
             background,
-            MIN_CONTRAST,
+            min_contrast,
             &[lighten(self.alert, 0.2), fallback_light, text_color],
         );
 
@@ -109,7 +118,7 @@ impl ThemePalette {
                 ensure_contrast(
                     lighten(primary, 0.35),
                     background,
-                    MIN_CONTRAST,
+                    min_contrast,
                     &[lighten(primary, 0.5), info_color, text_color],
                 ),
                 true,
@@ -118,7 +127,7 @@ impl ThemePalette {
                 ensure_contrast(
                     lighten(self.logo_accent, 0.2),
                     background,
-                    MIN_CONTRAST,
+                    min_contrast,
                     &[lighten(self.logo_accent, 0.35), info_color, fallback_light],
                 ),
                 true,
@@ -164,6 +173,36 @@ struct ActiveTheme {
     label: String,
     palette: ThemePalette,
     styles: ThemeStyles,
+    syntax_theme: Option<String>,
+    min_contrast: f64,
+}
+
+/// A theme loaded from a TOML file in the user's themes directory.
+#[derive(Clone, Debug)]
+struct CustomTheme {
+    label: String,
+    palette: ThemePalette,
+    syntax_theme: Option<String>,
+    min_contrast: f64,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+/// On-disk format of a custom theme. Colors are `#RRGGBB` hex strings.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CustomThemeFile {
+    label: Option<String>,
+    /// Syntect theme name, or a path to a `.tmTheme` file relative to the theme file.
+    syntax_theme: Option<String>,
+    /// Minimum contrast ratio against the background, 4.5 (WCAG AA) by default.
+    min_contrast: Option<f64>,
+    primary_accent: String,
+    background: String,
+    foreground: String,
+    secondary_accent: String,
+    alert: String,
+    logo_accent: Option<String>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
@@ -274,35 +313,252 @@ fn catppuccin_rgb(color: catppuccin::Color) -> RgbColor {
     RgbColor(color.rgb.r, color.rgb.g, color.rgb.b)
 }
 
+static CUSTOM_THEMES: Lazy<RwLock<HashMap<String, CustomTheme>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
 static ACTIVE: Lazy<RwLock<ActiveTheme>> = Lazy::new(|| {
     let default = REGISTRY
         .get(DEFAULT_THEME_ID)
         .expect("default theme must exist");
-    let styles = default.palette.build_styles();
+    let styles = default.palette.build_styles(MIN_CONTRAST);
     RwLock::new(ActiveTheme {
         id: default.id.to_string(),
         label: default.label.to_string(),
         palette: default.palette.clone(),
         styles,
+        syntax_theme: None,
+        min_contrast: MIN_CONTRAST,
     })
 });
 
 /// Set the active theme by identifier.
 pub fn set_active_theme(theme_id: &str) -> Result<()> {
     let id_lc = theme_id.trim().to_lowercase();
-    let theme = REGISTRY
-        .get(id_lc.as_str())
-        .ok_or_else(|| anyhow!("Unknown theme '{theme_id}'"))?;
-
-    let styles = theme.palette.build_styles();
-    let mut guard = ACTIVE.write();
-    guard.id = theme.id.to_string();
-    guard.label = theme.label.to_string();
-    guard.palette = theme.palette.clone();
-    guard.styles = styles;
+    let active = if let Some(theme) = REGISTRY.get(id_lc.as_str()) {
+        ActiveTheme {
+            id: theme.id.to_string(),
+            label: theme.label.to_string(),
+            palette: theme.palette.clone(),
+            styles: theme.palette.build_styles(MIN_CONTRAST),
+            syntax_theme: None,
+            min_contrast: MIN_CONTRAST,
+        }
+    } else {
+        let custom = CUSTOM_THEMES.read();
+        let theme = custom
+            .get(id_lc.as_str())
+            .ok_or_else(|| anyhow!("Unknown theme '{theme_id}'"))?;
+        ActiveTheme {
+            id: id_lc.clone(),
+            label: theme.label.clone(),
+            palette: theme.palette.clone(),
+            styles: theme.palette.build_styles(theme.min_contrast),
+            syntax_theme: theme.syntax_theme.clone(),
+            min_contrast: theme.min_contrast,
+        }
+    };
+
+    *ACTIVE.write() = active;
+    Ok(())
+}
+
+/// Load every `*.toml` theme in `dir`, replacing previously loaded custom themes.
+/// Files that fail to parse or validate are skipped and reported in the result.
+pub fn load_custom_themes(dir: &Path) -> Vec<anyhow::Error> {
+    let mut errors = Vec::new();
+    let mut loaded = HashMap::new();
+    let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    paths.sort();
+
+    for path in paths {
+        let Some(id) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(|stem| stem.trim().to_lowercase())
+        else {
+            continue;
+        };
+        if REGISTRY.contains_key(id.as_str()) {
+            errors.push(anyhow!(
+                "{}: '{}' is a built-in theme id; rename the file",
+                path.display(),
+                id
+            ));
+            continue;
+        }
+        match load_custom_theme(&path) {
+            Ok(theme) => {
+                loaded.insert(id, theme);
+            }
+            Err(err) => errors.push(err),
+        }
+    }
+
+    *CUSTOM_THEMES.write() = loaded;
+    errors
+}
+
+fn load_custom_theme(path: &Path) -> Result<CustomTheme> {
+    let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read theme {}", path.display()))?;
+    let file: CustomThemeFile =
+        toml::from_str(&content).with_context(|| format!("invalid theme {}", path.display()))?;
+    let theme = custom_theme_from_file(file, path)
+        .with_context(|| format!("invalid theme {}", path.display()))?;
+    Ok(CustomTheme { modified, ..theme })
+}
+
+fn custom_theme_from_file(file: CustomThemeFile, path: &Path) -> Result<CustomTheme> {
+    let secondary_accent = parse_hex_color("secondary_accent", &file.secondary_accent)?;
+    let palette = ThemePalette {
+        primary_accent: parse_hex_color("primary_accent", &file.primary_accent)?,
+        background: parse_hex_color("background", &file.background)?,
+        foreground: parse_hex_color("foreground", &file.foreground)?,
+        secondary_accent,
+        alert: parse_hex_color("alert", &file.alert)?,
+        logo_accent: match file.logo_accent.as_deref() {
+            Some(value) => parse_hex_color("logo_accent", value)?,
+            None => secondary_accent,
+        },
+    };
+    let min_contrast = file.min_contrast.unwrap_or(MIN_CONTRAST);
+    if !(1.0..=21.0).contains(&min_contrast) {
+        bail!(
+            "min_contrast must be between 1 and 21, got {}",
+            min_contrast
+        );
+    }
+    validate_contrast(&palette, min_contrast)?;
+
+    let syntax_theme = file.syntax_theme.map(|name| {
+        if name.ends_with(".tmTheme") {
+            let base = path.parent().unwrap_or_else(|| Path::new("."));
+            base.join(name).to_string_lossy().into_owned()
+        } else {
+            name
+        }
+    });
+    let label = file.label.unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+
+    Ok(CustomTheme {
+        label,
+        palette,
+        syntax_theme,
+        min_contrast,
+        path: path.to_path_buf(),
+        modified: None,
+    })
+}
+
+fn parse_hex_color(field: &str, value: &str) -> Result<RgbColor> {
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        bail!("{} must be a #RRGGBB color, got '{}'", field, value);
+    }
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16);
+    Ok(RgbColor(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Reject palettes whose foreground misses `min_contrast` on the background, or
+/// whose derived colors still miss it after the adjustments `build_styles` makes.
+fn validate_contrast(palette: &ThemePalette, min_contrast: f64) -> Result<()> {
+    let ratio = contrast_ratio(palette.foreground, palette.background);
+    if ratio < min_contrast {
+        bail!(
+            "foreground contrast is {:.1}:1 against the background, below {:.1}:1",
+            ratio,
+            min_contrast
+        );
+    }
+    let styles = palette.build_styles(min_contrast);
+    let derived = [
+        ("text", styles.output),
+        ("response", styles.response),
+        ("info", styles.info),
+        ("error", styles.error),
+        ("reasoning", styles.reasoning),
+        ("tool", styles.tool),
+        ("tool detail", styles.tool_detail),
+        ("status", styles.status),
+        ("mcp", styles.mcp),
+        ("user", styles.user),
+    ];
+    let failing: Vec<String> = derived
+        .iter()
+        .filter_map(|(name, style)| match style.get_fg_color() {
+            Some(Color::Rgb(color)) => {
+                let ratio = contrast_ratio(color, palette.background);
+                (ratio < min_contrast).then(|| format!("{} ({:.1}:1)", name, ratio))
+            }
+            _ => None,
+        })
+        .collect();
+    if !failing.is_empty() {
+        bail!(
+            "contrast below {:.1}:1 against the background for {}; adjust foreground or background",
+            min_contrast,
+            failing.join(", ")
+        );
+    }
     Ok(())
 }
 
+/// Reload the active theme from disk if it is a custom theme whose file changed.
+/// Returns true when the active styles were updated.
+pub fn reload_active_custom_theme() -> Result<bool> {
+    let active_id = active_theme_id();
+    let (path, modified) = match CUSTOM_THEMES.read().get(active_id.as_str()) {
+        Some(theme) => (theme.path.clone(), theme.modified),
+        None => return Ok(false),
+    };
+    let current = fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+    if current.is_none() || current == modified {
+        return Ok(false);
+    }
+
+    let result = load_custom_theme(&path);
+    let mut custom = CUSTOM_THEMES.write();
+    match result {
+        Ok(theme) => {
+            custom.insert(active_id.clone(), theme);
+            drop(custom);
+            set_active_theme(&active_id)?;
+            Ok(true)
+        }
+        Err(err) => {
+            // Remember the broken revision so the error is reported once per save.
+            if let Some(theme) = custom.get_mut(active_id.as_str()) {
+                theme.modified = current;
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Syntax highlighting theme requested by the active theme, if any.
+pub fn active_syntax_theme() -> Option<String> {
+    ACTIVE.read().syntax_theme.clone()
+}
+
+/// Source file of a custom theme.
+pub fn custom_theme_path(theme_id: &str) -> Option<PathBuf> {
+    CUSTOM_THEMES
+        .read()
+        .get(theme_id)
+        .map(|theme| theme.path.clone())
+}
+
 /// Get the identifier of the active theme.
 pub fn active_theme_id() -> String {
     ACTIVE.read().id.clone()
@@ -324,13 +580,14 @@ pub fn banner_color() -> RgbColor {
     let accent = guard.palette.logo_accent;
     let secondary = guard.palette.secondary_accent;
     let background = guard.palette.background;
+    let min_contrast = guard.min_contrast;
     drop(guard);
 
     let candidate = lighten(accent, 0.35);
     ensure_contrast(
         candidate,
         background,
-        MIN_CONTRAST,
+        min_contrast,
         &[lighten(accent, 0.5), lighten(secondary, 0.25), accent],
     )
 }
@@ -346,16 +603,23 @@ pub fn logo_accent_color() -> RgbColor {
     ACTIVE.read().palette.logo_accent
 }
 
-/// Enumerate available theme identifiers.
-pub fn available_themes() -> Vec<&'static str> {
-    let mut keys: Vec<_> = REGISTRY.keys().copied().collect();
+/// Enumerate available theme identifiers, built-in and custom.
+pub fn available_themes() -> Vec<String> {
+    let mut keys: Vec<String> = REGISTRY.keys().map(|key| key.to_string()).collect();
+    keys.extend(CUSTOM_THEMES.read().keys().cloned());
     keys.sort();
     keys
 }
 
 /// Look up a theme label for display.
-pub fn theme_label(theme_id: &str) -> Option<&'static str> {
-    REGISTRY.get(theme_id).map(|definition| definition.label)
+pub fn theme_label(theme_id: &str) -> Option<String> {
+    match REGISTRY.get(theme_id) {
+        Some(definition) => Some(definition.label.to_string()),
+        None => CUSTOM_THEMES
+            .read()
+            .get(theme_id)
+            .map(|theme| theme.label.clone()),
+    }
 }
 
 fn relative_luminance(color: RgbColor) -> f64 {
@@ -422,7 +686,9 @@ pub fn resolve_theme(preferred: Option<String>) -> String {
             let trimmed = candidate.trim().to_lowercase();
             if trimmed.is_empty() {
                 None
-            } else if REGISTRY.contains_key(trimmed.as_str()) {
+            } else if REGISTRY.contains_key(trimmed.as_str())
+                || CUSTOM_THEMES.read().contains_key(trimmed.as_str())
+            {
                 Some(trimmed)
             } else {
                 None
@@ -432,9 +698,85 @@ pub fn resolve_theme(preferred: Option<String>) -> String {
 }
 
 /// Validate a theme and return its label for messaging.
-pub fn ensure_theme(theme_id: &str) -> Result<&'static str> {
-    REGISTRY
-        .get(theme_id)
-        .map(|definition| definition.label)
-        .context("Theme not found")
+pub fn ensure_theme(theme_id: &str) -> Result<String> {
+    theme_label(theme_id).context("Theme not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HIGH_CONTRAST: &str = r##"
+label = "High Contrast"
+syntax_theme = "InspiredGitHub"
+min_contrast = 7.0
+primary_accent = "#FFD700"
+background = "#000000"
+foreground = "#FFFFFF"
+secondary_accent = "#00FFFF"
+alert = "#FF6060"
+"##;
+
+    #[test]
+    fn loads_custom_themes_and_skips_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("high-contrast.toml"), HIGH_CONTRAST).unwrap();
+        fs::write(
+            dir.path().join("murky.toml"),
+            HIGH_CONTRAST.replace("#FFFFFF", "#202020"),
+        )
+        .unwrap();
+        fs::write(dir.path().join("ciapre-dark.toml"), HIGH_CONTRAST).unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let errors = load_custom_themes(dir.path());
+
+        assert_eq!(errors.len(), 2);
+        let available = available_themes();
+        assert!(available.contains(&"high-contrast".to_string()));
+        assert!(!available.contains(&"murky".to_string()));
+        assert_eq!(
+            theme_label("high-contrast").as_deref(),
+            Some("High Contrast")
+        );
+        assert_eq!(
+            custom_theme_path("high-contrast"),
+            Some(dir.path().join("high-contrast.toml"))
+        );
+    }
+
+    #[test]
+    fn custom_theme_contrast_follows_its_minimum() {
+        let path = Path::new("/themes/dim.toml");
+        let file: CustomThemeFile = toml::from_str(HIGH_CONTRAST).unwrap();
+        let theme = custom_theme_from_file(file, path).unwrap();
+        assert_eq!(theme.palette.logo_accent, theme.palette.secondary_accent);
+        assert_eq!(theme.syntax_theme.as_deref(), Some("InspiredGitHub"));
+
+        // Grey on black passes AA but not the AAA minimum this theme asks for.
+        let grey = HIGH_CONTRAST.replace("#FFFFFF", "#8A8A8A");
+        let file: CustomThemeFile = toml::from_str(&grey).unwrap();
+        let error = custom_theme_from_file(file, path).unwrap_err();
+        assert!(error.to_string().contains("foreground contrast"));
+
+        let relaxed = grey.replace("min_contrast = 7.0", "min_contrast = 4.5");
+        let file: CustomThemeFile = toml::from_str(&relaxed).unwrap();
+        assert!(custom_theme_from_file(file, path).is_ok());
+    }
+
+    #[test]
+    fn parses_hex_colors_and_relative_syntax_theme_files() {
+        assert_eq!(
+            parse_hex_color("alert", "#ff8A00").unwrap(),
+            RgbColor(0xFF, 0x8A, 0x00)
+        );
+        assert!(parse_hex_color("alert", "red").is_err());
+        assert!(parse_hex_color("alert", "#FFF").is_err());
+
+        let with_file = HIGH_CONTRAST.replace("InspiredGitHub", "code.tmTheme");
+        let file: CustomThemeFile = toml::from_str(&with_file).unwrap();
+        let theme = custom_theme_from_file(file, Path::new("/themes/mine.toml")).unwrap();
+        assert_eq!(theme.syntax_theme.as_deref(), Some("/themes/code.tmTheme"));
+        assert_eq!(theme.label, "High Contrast");
+    }
 }
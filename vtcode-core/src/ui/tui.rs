@@ -3,16 +3,21 @@ use tokio::sync::mpsc;
 
 use crate::config::types::UiSurfacePreference;
 
+mod history;
+mod keymap;
+mod mentions;
 mod session;
 mod style;
 mod tui;
 mod types;
 
+pub use history::PromptHistory;
+pub use keymap::{KeyAction, KeyBinding, Keymap};
 pub use style::{convert_style, theme_from_styles};
 pub use types::{
-    InlineCommand, InlineEvent, InlineHandle, InlineHeaderContext, InlineListItem,
-    InlineListSelection, InlineMessageKind, InlineSegment, InlineSession, InlineTextStyle,
-    InlineTheme,
+    InlineBlockKind, InlineCommand, InlineEvent, InlineHandle, InlineHeaderContext, InlineListItem,
+    InlineListSelection, InlineMentionCandidate, InlineMentionKind, InlineMessageKind,
+    InlineSegment, InlineSession, InlineTextStyle, InlineTheme,
 };
 
 use tui::run_tui;
@@ -23,6 +28,8 @@ pub fn spawn_session(
     surface_preference: UiSurfacePreference,
     inline_rows: u16,
     show_timeline_pane: bool,
+    history: PromptHistory,
+    keymap: Keymap,
 ) -> Result<InlineSession> {
     let (command_tx, command_rx) = mpsc::unbounded_channel();
     let (event_tx, event_rx) = mpsc::unbounded_channel();
@@ -36,6 +43,8 @@ pub fn spawn_session(
             surface_preference,
             inline_rows,
             show_timeline_pane,
+            history,
+            keymap,
         )
         .await
         {
@@ -44,7 +53,7 @@ pub fn spawn_session(
     });
 
     Ok(InlineSession {
-        handle: InlineHandle { sender: command_tx },
+        handle: InlineHandle::new(command_tx),
         events: event_rx,
     })
 }
@@ -0,0 +1,206 @@
+//! Per-workspace prompt history for the inline input
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::config::constants::ui;
+
+const HISTORY_DIR: &str = "history";
+const HISTORY_EXTENSION: &str = "jsonl";
+
+/// Submitted prompts, oldest first, persisted as one JSON string per line.
+#[derive(Debug, Clone, Default)]
+pub struct PromptHistory {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+    limit: usize,
+}
+
+impl PromptHistory {
+    /// History that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self {
+            entries: Vec::new(),
+            path: None,
+            limit: ui::PROMPT_HISTORY_LIMIT,
+        }
+    }
+
+    /// History stored under `~/.vtcode/history`, keyed by the workspace path.
+    pub fn for_workspace(workspace: &Path) -> Self {
+        match history_path(workspace) {
+            Some(path) => Self::load(path, ui::PROMPT_HISTORY_LIMIT),
+            None => Self::in_memory(),
+        }
+    }
+
+    pub fn load(path: PathBuf, limit: usize) -> Self {
+        let entries = match fs::read_to_string(&path) {
+            Ok(contents) => contents
+                .lines()
+                .filter_map(|line| serde_json::from_str::<String>(line).ok())
+                .filter(|entry| !entry.trim().is_empty())
+                .collect(),
+            Err(error) => {
+                if error.kind() != std::io::ErrorKind::NotFound {
+                    tracing::debug!(%error, path = %path.display(), "failed to read prompt history");
+                }
+                Vec::new()
+            }
+        };
+        let mut history = Self {
+            entries,
+            path: Some(path),
+            limit: limit.max(1),
+        };
+        if history.entries.len() > history.limit {
+            let excess = history.entries.len() - history.limit;
+            history.entries.drain(..excess);
+            history.rewrite();
+        }
+        history
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// Record a submitted prompt, skipping blanks and immediate repeats.
+    pub fn push(&mut self, entry: &str) {
+        if entry.trim().is_empty() || self.entries.last().map(String::as_str) == Some(entry) {
+            return;
+        }
+        self.entries.push(entry.to_string());
+        if self.entries.len() > self.limit {
+            let excess = self.entries.len() - self.limit;
+            self.entries.drain(..excess);
+            self.rewrite();
+        } else {
+            self.append(entry);
+        }
+    }
+
+    /// Newest entry before `before` that contains `query`, ignoring case.
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        let needle = query.to_lowercase();
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|entry| entry.to_lowercase().contains(&needle))
+    }
+
+    fn append(&self, entry: &str) {
+        let Some(path) = self.path.as_ref() else {
+            return;
+        };
+        let result = ensure_parent(path).and_then(|_| {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", encode(entry))
+        });
+        if let Err(error) = result {
+            tracing::debug!(%error, path = %path.display(), "failed to append prompt history");
+        }
+    }
+
+    fn rewrite(&self) {
+        let Some(path) = self.path.as_ref() else {
+            return;
+        };
+        let mut contents = String::new();
+        for entry in &self.entries {
+            contents.push_str(&encode(entry));
+            contents.push('\n');
+        }
+        if let Err(error) = ensure_parent(path).and_then(|_| fs::write(path, contents)) {
+            tracing::debug!(%error, path = %path.display(), "failed to rewrite prompt history");
+        }
+    }
+}
+
+fn encode(entry: &str) -> String {
+    serde_json::to_string(entry).unwrap_or_default()
+}
+
+fn ensure_parent(path: &Path) -> std::io::Result<()> {
+    match path.parent() {
+        Some(parent) => fs::create_dir_all(parent),
+        None => Ok(()),
+    }
+}
+
+fn history_path(workspace: &Path) -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    let workspace = workspace
+        .canonicalize()
+        .unwrap_or_else(|_| workspace.to_path_buf());
+    let digest = Sha256::digest(workspace.to_string_lossy().as_bytes());
+    let hash: String = digest
+        .iter()
+        .take(6)
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    let name: String = workspace
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("workspace")
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    Some(
+        home.join(".vtcode")
+            .join(HISTORY_DIR)
+            .join(format!("{}-{}.{}", name, hash, HISTORY_EXTENSION)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persists_multiline_entries_and_trims_to_limit() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("nested").join("history.jsonl");
+
+        let mut history = PromptHistory::load(path.clone(), 3);
+        history.push("first");
+        history.push("second\nwith a trace");
+        history.push("second\nwith a trace");
+        history.push("   ");
+        history.push("third");
+        history.push("fourth");
+        assert_eq!(history.len(), 3);
+
+        let reloaded = PromptHistory::load(path, 3);
+        assert_eq!(reloaded.get(0), Some("second\nwith a trace"));
+        assert_eq!(reloaded.get(2), Some("fourth"));
+    }
+
+    #[test]
+    fn search_walks_backwards_through_matches() {
+        let mut history = PromptHistory::in_memory();
+        for entry in ["cargo test", "git status", "Cargo build", "ls"] {
+            history.push(entry);
+        }
+        assert_eq!(history.search("cargo", history.len()), Some(2));
+        assert_eq!(history.search("cargo", 2), Some(0));
+        assert_eq!(history.search("cargo", 0), None);
+        assert_eq!(history.search("missing", history.len()), None);
+    }
+}
@@ -0,0 +1,499 @@
+use std::fmt;
+
+use anyhow::{Context, Result, anyhow, bail};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::UiConfig;
+
+/// Named input actions that can be rebound from `[ui.keybindings]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Submit,
+    Newline,
+    Cancel,
+    Interrupt,
+    Exit,
+    TogglePlanMode,
+    OpenModelPicker,
+    Steer,
+    EditQueued,
+    HistoryPrevious,
+    HistoryNext,
+    HistorySearch,
+    ScrollLineUp,
+    ScrollLineDown,
+    ScrollPageUp,
+    ScrollPageDown,
+    NextBlock,
+    PreviousBlock,
+    ToggleBlock,
+    CopyBlock,
+    ShowFullOutput,
+    EditMessage,
+    CursorLeft,
+    CursorRight,
+    CursorWordLeft,
+    CursorWordRight,
+    CursorLineStart,
+    CursorLineEnd,
+    DeleteBackward,
+    DeleteForward,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 30] = [
+        Self::Submit,
+        Self::Newline,
+        Self::Cancel,
+        Self::Interrupt,
+        Self::Exit,
+        Self::TogglePlanMode,
+        Self::OpenModelPicker,
+        Self::Steer,
+        Self::EditQueued,
+        Self::HistoryPrevious,
+        Self::HistoryNext,
+        Self::HistorySearch,
+        Self::ScrollLineUp,
+        Self::ScrollLineDown,
+        Self::ScrollPageUp,
+        Self::ScrollPageDown,
+        Self::NextBlock,
+        Self::PreviousBlock,
+        Self::ToggleBlock,
+        Self::CopyBlock,
+        Self::ShowFullOutput,
+        Self::EditMessage,
+        Self::CursorLeft,
+        Self::CursorRight,
+        Self::CursorWordLeft,
+        Self::CursorWordRight,
+        Self::CursorLineStart,
+        Self::CursorLineEnd,
+        Self::DeleteBackward,
+        Self::DeleteForward,
+    ];
+
+    /// The name used for this action in `[ui.keybindings]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Submit => "submit",
+            Self::Newline => "newline",
+            Self::Cancel => "cancel",
+            Self::Interrupt => "interrupt",
+            Self::Exit => "exit",
+            Self::TogglePlanMode => "toggle_plan_mode",
+            Self::OpenModelPicker => "open_model_picker",
+            Self::Steer => "steer",
+            Self::EditQueued => "edit_queued",
+            Self::HistoryPrevious => "history_previous",
+            Self::HistoryNext => "history_next",
+            Self::HistorySearch => "history_search",
+            Self::ScrollLineUp => "scroll_line_up",
+            Self::ScrollLineDown => "scroll_line_down",
+            Self::ScrollPageUp => "scroll_page_up",
+            Self::ScrollPageDown => "scroll_page_down",
+            Self::NextBlock => "next_block",
+            Self::PreviousBlock => "previous_block",
+            Self::ToggleBlock => "toggle_block",
+            Self::CopyBlock => "copy_block",
+            Self::ShowFullOutput => "show_full_output",
+            Self::EditMessage => "edit_message",
+            Self::CursorLeft => "cursor_left",
+            Self::CursorRight => "cursor_right",
+            Self::CursorWordLeft => "cursor_word_left",
+            Self::CursorWordRight => "cursor_word_right",
+            Self::CursorLineStart => "cursor_line_start",
+            Self::CursorLineEnd => "cursor_line_end",
+            Self::DeleteBackward => "delete_backward",
+            Self::DeleteForward => "delete_forward",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.name() == name.trim())
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Self::Submit => &["enter"],
+            Self::Newline => &["shift+enter", "alt+enter", "ctrl+j"],
+            Self::Cancel => &["esc"],
+            Self::Interrupt => &["ctrl+c"],
+            Self::Exit => &["ctrl+d"],
+            Self::TogglePlanMode => &["shift+tab"],
+            Self::OpenModelPicker => &[],
+            Self::Steer => &["ctrl+s"],
+            Self::EditQueued => &["alt+up"],
+            Self::HistoryPrevious => &["up"],
+            Self::HistoryNext => &["down"],
+            Self::HistorySearch => &["ctrl+r"],
+            Self::ScrollLineUp => &["ctrl+up", "shift+up"],
+            Self::ScrollLineDown => &["ctrl+down", "shift+down"],
+            Self::ScrollPageUp => &["pageup"],
+            Self::ScrollPageDown => &["pagedown"],
+            Self::NextBlock => &["alt+n"],
+            Self::PreviousBlock => &["alt+p"],
+            Self::ToggleBlock => &["alt+o"],
+            Self::CopyBlock => &["alt+y"],
+            Self::ShowFullOutput => &["alt+e"],
+            Self::EditMessage => &["alt+r"],
+            Self::CursorLeft => &["left"],
+            Self::CursorRight => &["right"],
+            Self::CursorWordLeft => &["alt+left", "alt+b"],
+            Self::CursorWordRight => &["alt+right", "alt+f"],
+            Self::CursorLineStart => &["home", "super+left", "super+a"],
+            Self::CursorLineEnd => &["end", "super+right", "super+e"],
+            Self::DeleteBackward => &["backspace"],
+            Self::DeleteForward => &["delete"],
+        }
+    }
+}
+
+/// A key together with the modifiers that must be held, normalised so that
+/// terminal differences (Meta vs Alt, Shift on letters and BackTab) compare equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers = modifiers
+            & (KeyModifiers::CONTROL
+                | KeyModifiers::SHIFT
+                | KeyModifiers::ALT
+                | KeyModifiers::SUPER);
+        let code = match code {
+            KeyCode::Char(ch) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                if modifiers.is_empty() {
+                    KeyCode::Char(ch)
+                } else {
+                    KeyCode::Char(ch.to_ascii_lowercase())
+                }
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            other => other,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        let mut modifiers = key.modifiers;
+        // macOS terminals report Option as Meta unless Command is also held.
+        if modifiers.contains(KeyModifiers::META) && !modifiers.contains(KeyModifiers::SUPER) {
+            modifiers.insert(KeyModifiers::ALT);
+        }
+        Self::new(key.code, modifiers)
+    }
+
+    /// Parse a spec such as `ctrl+s`, `alt+enter`, `shift+tab` or `f2`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            bail!("empty key binding");
+        }
+        let (modifier_part, key_part) = match spec.rsplit_once('+') {
+            // Allow binding the plus key itself, e.g. `ctrl++`.
+            Some((rest, "")) => match rest.strip_suffix('+') {
+                Some(modifiers) => (Some(modifiers), "+"),
+                None if rest.is_empty() => (None, "+"),
+                None => bail!("missing key after `{}+`", rest),
+            },
+            Some((rest, key)) => (Some(rest), key),
+            None => (None, spec),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for name in modifier_part.into_iter().flat_map(|part| part.split('+')) {
+            modifiers |= match name.trim().to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "option" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                "super" | "cmd" | "command" => KeyModifiers::SUPER,
+                other => bail!("unknown modifier `{}` in `{}`", other, spec),
+            };
+        }
+
+        let lowered = key_part.trim().to_ascii_lowercase();
+        let code = match lowered.as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" | "pgup" => KeyCode::PageUp,
+            "pagedown" | "pgdn" => KeyCode::PageDown,
+            "space" => KeyCode::Char(' '),
+            name => match name.strip_prefix('f').map(str::parse::<u8>) {
+                Some(Ok(number)) if (1..=24).contains(&number) => KeyCode::F(number),
+                _ => {
+                    let mut chars = key_part.trim().chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(ch), None) => KeyCode::Char(ch),
+                        _ => bail!("unknown key `{}` in `{}`", key_part.trim(), spec),
+                    }
+                }
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, label) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::ALT, "Alt+"),
+            (KeyModifiers::SUPER, "Super+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(label)?;
+            }
+        }
+        match self.code {
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::BackTab => f.write_str("Shift+Tab"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Delete => f.write_str("Delete"),
+            KeyCode::Insert => f.write_str("Insert"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(ch) => write!(f, "{}", ch.to_ascii_uppercase()),
+            KeyCode::F(number) => write!(f, "F{}", number),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Resolves key presses to actions. Later bindings for a key replace earlier ones.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, KeyAction)>,
+    vi_mode: bool,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = KeyAction::ALL
+            .into_iter()
+            .flat_map(|action| {
+                action.default_keys().iter().map(move |spec| {
+                    let binding = KeyBinding::parse(spec).expect("default key bindings are valid");
+                    (binding, action)
+                })
+            })
+            .collect();
+        Self {
+            bindings,
+            vi_mode: false,
+        }
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from `[ui]`, applying `keybindings` over the defaults.
+    /// An action listed there loses its default keys; an empty list unbinds it.
+    pub fn from_config(config: &UiConfig) -> Result<Self> {
+        let mut keymap = Self {
+            vi_mode: config.vi_mode,
+            ..Self::default()
+        };
+        for (name, spec) in &config.keybindings {
+            let action = KeyAction::from_name(name)
+                .ok_or_else(|| anyhow!("unknown key binding action `{}`", name))?;
+            let keys = spec
+                .keys()
+                .iter()
+                .map(|key| KeyBinding::parse(key))
+                .collect::<Result<Vec<_>>>()
+                .with_context(|| format!("invalid key binding for `{}`", name))?;
+            keymap.bind(action, keys);
+        }
+        Ok(keymap)
+    }
+
+    fn bind(&mut self, action: KeyAction, keys: Vec<KeyBinding>) {
+        self.bindings
+            .retain(|(binding, bound)| *bound != action && !keys.contains(binding));
+        self.bindings
+            .extend(keys.into_iter().map(|binding| (binding, action)));
+    }
+
+    pub fn action_for(&self, key: &KeyEvent) -> Option<KeyAction> {
+        let binding = KeyBinding::from_event(key);
+        self.bindings
+            .iter()
+            .find(|(candidate, _)| *candidate == binding)
+            .map(|(_, action)| *action)
+    }
+
+    /// Display label of the first key bound to `action`, for hints.
+    pub fn label(&self, action: KeyAction) -> Option<String> {
+        self.bindings
+            .iter()
+            .find(|(_, bound)| *bound == action)
+            .map(|(binding, _)| binding.to_string())
+    }
+
+    pub fn with_vi_mode(mut self, enabled: bool) -> Self {
+        self.vi_mode = enabled;
+        self
+    }
+
+    pub fn vi_mode(&self) -> bool {
+        self.vi_mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::KeyBindingSpec;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn defaults_match_built_in_shortcuts() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(KeyAction::Interrupt)
+        );
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Enter, KeyModifiers::SHIFT)),
+            Some(KeyAction::Newline)
+        );
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            Some(KeyAction::TogglePlanMode)
+        );
+        // Option reported as Meta behaves like Alt.
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Char('b'), KeyModifiers::META)),
+            Some(KeyAction::CursorWordLeft)
+        );
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Left, KeyModifiers::SUPER)),
+            Some(KeyAction::CursorLineStart)
+        );
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Char('x'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(keymap.label(KeyAction::Steer).as_deref(), Some("Ctrl+S"));
+        assert_eq!(
+            keymap.label(KeyAction::EditQueued).as_deref(),
+            Some("Alt+↑")
+        );
+    }
+
+    #[test]
+    fn parses_key_specs() {
+        assert_eq!(
+            KeyBinding::parse("Ctrl+Alt+Enter").unwrap(),
+            KeyBinding::new(KeyCode::Enter, KeyModifiers::CONTROL | KeyModifiers::ALT)
+        );
+        assert_eq!(
+            KeyBinding::parse("shift+tab").unwrap(),
+            KeyBinding::new(KeyCode::BackTab, KeyModifiers::NONE)
+        );
+        assert_eq!(
+            KeyBinding::parse("ctrl++").unwrap(),
+            KeyBinding::new(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            KeyBinding::parse("f5").unwrap(),
+            KeyBinding::new(KeyCode::F(5), KeyModifiers::NONE)
+        );
+        assert!(KeyBinding::parse("hyper+x").is_err());
+        assert!(KeyBinding::parse("ctrl+nope").is_err());
+    }
+
+    #[test]
+    fn config_overrides_replace_defaults_and_steal_keys() {
+        let mut config = UiConfig::default();
+        config.keybindings.insert(
+            "submit".to_string(),
+            KeyBindingSpec::Single("ctrl+enter".to_string()),
+        );
+        config.keybindings.insert(
+            "newline".to_string(),
+            KeyBindingSpec::Multiple(vec!["enter".to_string()]),
+        );
+        config
+            .keybindings
+            .insert("steer".to_string(), KeyBindingSpec::Multiple(Vec::new()));
+        config.keybindings.insert(
+            "open_model_picker".to_string(),
+            KeyBindingSpec::Single("ctrl+s".to_string()),
+        );
+        let keymap = Keymap::from_config(&config).unwrap();
+
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Enter, KeyModifiers::NONE)),
+            Some(KeyAction::Newline)
+        );
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Enter, KeyModifiers::CONTROL)),
+            Some(KeyAction::Submit)
+        );
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Enter, KeyModifiers::SHIFT)),
+            None
+        );
+        assert_eq!(
+            keymap.action_for(&key(KeyCode::Char('s'), KeyModifiers::CONTROL)),
+            Some(KeyAction::OpenModelPicker)
+        );
+        assert_eq!(keymap.label(KeyAction::Steer), None);
+    }
+
+    #[test]
+    fn config_rejects_unknown_actions_and_keys() {
+        let mut config = UiConfig::default();
+        config.keybindings.insert(
+            "launch_rockets".to_string(),
+            KeyBindingSpec::Single("ctrl+l".to_string()),
+        );
+        assert!(Keymap::from_config(&config).is_err());
+
+        let mut config = UiConfig::default();
+        config.keybindings.insert(
+            "submit".to_string(),
+            KeyBindingSpec::Single("ctrl+".to_string()),
+        );
+        assert!(Keymap::from_config(&config).is_err());
+    }
+}
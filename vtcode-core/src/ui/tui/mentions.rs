@@ -0,0 +1,265 @@
+//! `@` mention detection and fuzzy ranking for the inline input
+
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use ratatui::widgets::ListState;
+
+use super::types::InlineMentionCandidate;
+use crate::config::constants::ui;
+
+/// The `@token` under the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct MentionToken {
+    /// Byte offset of the `@`.
+    pub start: usize,
+    /// Byte offset just past the token.
+    pub end: usize,
+    /// Text typed between the `@` and the cursor.
+    pub query: String,
+}
+
+/// Locate a mention that the cursor is currently inside of.
+pub(super) fn mention_at(input: &str, cursor: usize) -> Option<MentionToken> {
+    let before = input.get(..cursor)?;
+    let start = before.rfind(|ch: char| ch.is_whitespace() || ch == '@')?;
+    if !before[start..].starts_with('@') {
+        return None;
+    }
+    // Ignore e-mail addresses and other `@` in the middle of a word.
+    let preceded_by_word = before[..start]
+        .chars()
+        .next_back()
+        .is_some_and(|ch| !ch.is_whitespace() && !matches!(ch, '(' | '[' | '"' | '\''));
+    if preceded_by_word {
+        return None;
+    }
+    let end = input[cursor..]
+        .find(char::is_whitespace)
+        .map(|offset| cursor + offset)
+        .unwrap_or(input.len());
+    Some(MentionToken {
+        start,
+        end,
+        query: before[start + 1..].to_string(),
+    })
+}
+
+/// Completion palette state for `@` mentions.
+pub(super) struct MentionPalette {
+    candidates: Vec<InlineMentionCandidate>,
+    matches: Vec<usize>,
+    selected: usize,
+    token: Option<MentionToken>,
+    dismissed: Option<usize>,
+    matcher: Matcher,
+    pub list_state: ListState,
+    pub visible_rows: usize,
+}
+
+impl MentionPalette {
+    pub fn new() -> Self {
+        Self {
+            candidates: Vec::new(),
+            matches: Vec::new(),
+            selected: 0,
+            token: None,
+            dismissed: None,
+            matcher: Matcher::new(Config::DEFAULT.match_paths()),
+            list_state: ListState::default(),
+            visible_rows: 0,
+        }
+    }
+
+    pub fn set_candidates(&mut self, candidates: Vec<InlineMentionCandidate>) {
+        self.candidates = candidates;
+        self.token = None;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.matches.is_empty()
+    }
+
+    pub fn matches(&self) -> impl Iterator<Item = &InlineMentionCandidate> {
+        self.matches.iter().map(|index| &self.candidates[*index])
+    }
+
+    /// Recompute matches for the current input. Returns true when the palette changed.
+    pub fn update(&mut self, input: &str, cursor: usize, enabled: bool) -> bool {
+        let token = mention_at(input, cursor).filter(|_| enabled && !self.candidates.is_empty());
+        if token.as_ref().map(|token| token.start) != self.dismissed {
+            self.dismissed = None;
+        }
+        if token == self.token {
+            return false;
+        }
+        let had_matches = !self.matches.is_empty();
+        self.matches = match token.as_ref() {
+            Some(token) if self.dismissed.is_none() => self.rank(&token.query),
+            _ => Vec::new(),
+        };
+        self.token = token;
+        self.selected = 0;
+        self.sync_list_state();
+        had_matches || !self.matches.is_empty()
+    }
+
+    fn rank(&mut self, query: &str) -> Vec<usize> {
+        let query = query.trim();
+        if query.is_empty() {
+            return (0..self.candidates.len())
+                .take(ui::MENTION_SUGGESTION_LIMIT)
+                .collect();
+        }
+        let pattern = Pattern::new(
+            query,
+            CaseMatching::Smart,
+            Normalization::Smart,
+            AtomKind::Fuzzy,
+        );
+        let mut buffer = Vec::new();
+        let mut scored: Vec<(u32, usize)> = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(index, candidate)| {
+                let haystack = Utf32Str::new(candidate.label.as_str(), &mut buffer);
+                pattern
+                    .score(haystack, &mut self.matcher)
+                    .map(|score| (score, index))
+            })
+            .collect();
+        scored.sort_by(|a, b| {
+            b.0.cmp(&a.0).then_with(|| {
+                let left = &self.candidates[a.1].label;
+                let right = &self.candidates[b.1].label;
+                left.len().cmp(&right.len()).then_with(|| left.cmp(right))
+            })
+        });
+        scored
+            .into_iter()
+            .take(ui::MENTION_SUGGESTION_LIMIT)
+            .map(|(_, index)| index)
+            .collect()
+    }
+
+    pub fn select_previous(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        self.selected = self
+            .selected
+            .checked_sub(1)
+            .unwrap_or(self.matches.len() - 1);
+        self.sync_list_state();
+    }
+
+    pub fn select_next(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        self.selected = (self.selected + 1) % self.matches.len();
+        self.sync_list_state();
+    }
+
+    /// Hide the palette until the cursor leaves the current mention.
+    pub fn dismiss(&mut self) {
+        self.dismissed = self.token.as_ref().map(|token| token.start);
+        self.matches.clear();
+        self.sync_list_state();
+    }
+
+    /// Input and cursor after replacing the current mention with the selected candidate.
+    pub fn apply(&self, input: &str) -> Option<(String, usize)> {
+        let token = self.token.as_ref()?;
+        let candidate = &self.candidates[*self.matches.get(self.selected)?];
+        let mut updated = String::with_capacity(input.len() + candidate.insert.len() + 2);
+        updated.push_str(&input[..token.start]);
+        updated.push('@');
+        updated.push_str(&candidate.insert);
+        let suffix = &input[token.end..];
+        if !suffix.starts_with(char::is_whitespace) {
+            updated.push(' ');
+        }
+        let cursor = updated.len();
+        updated.push_str(suffix);
+        Some((updated, cursor))
+    }
+
+    pub fn sync_list_state(&mut self) {
+        if self.matches.is_empty() {
+            self.list_state.select(None);
+            *self.list_state.offset_mut() = 0;
+            return;
+        }
+        self.list_state.select(Some(self.selected));
+        if self.visible_rows == 0 {
+            return;
+        }
+        let offset = self.list_state.offset_mut();
+        if self.selected < *offset {
+            *offset = self.selected;
+        } else if self.selected >= *offset + self.visible_rows {
+            *offset = self.selected + 1 - self.visible_rows;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::tui::types::InlineMentionKind;
+
+    fn candidate(kind: InlineMentionKind, label: &str, insert: &str) -> InlineMentionCandidate {
+        InlineMentionCandidate {
+            kind,
+            label: label.to_string(),
+            insert: insert.to_string(),
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn detects_mentions_only_at_word_starts() {
+        let token = mention_at("explain (@src/ma", 16).expect("mention");
+        assert_eq!((token.start, token.end), (9, 16));
+        assert_eq!(token.query, "src/ma");
+        assert!(mention_at("mail me@example.com", 19).is_none());
+        assert!(mention_at("@lib done", 9).is_none());
+    }
+
+    #[test]
+    fn ranks_fuzzy_matches_and_applies_selection() {
+        let mut palette = MentionPalette::new();
+        palette.set_candidates(vec![
+            candidate(
+                InlineMentionKind::File,
+                "docs/session.md",
+                "docs/session.md",
+            ),
+            candidate(
+                InlineMentionKind::File,
+                "src/ui/tui/session.rs",
+                "src/ui/tui/session.rs",
+            ),
+            candidate(
+                InlineMentionKind::Symbol,
+                "render_input",
+                "src/ui/tui/session.rs#render_input",
+            ),
+        ]);
+
+        let input = "look at @tuises please";
+        assert!(palette.update(input, 15, true));
+        let labels: Vec<&str> = palette.matches().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, vec!["src/ui/tui/session.rs"]);
+        let (updated, cursor) = palette.apply(input).expect("applied");
+        assert_eq!(updated, "look at @src/ui/tui/session.rs please");
+        assert_eq!(&updated[..cursor], "look at @src/ui/tui/session.rs");
+
+        assert!(palette.update("@rendin", 7, true));
+        palette.dismiss();
+        assert!(!palette.is_visible());
+        assert!(!palette.update("@rendinp", 8, true));
+        assert!(!palette.is_visible());
+    }
+}
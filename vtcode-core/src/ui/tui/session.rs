@@ -1,4 +1,4 @@
-use std::{cmp::min, mem, ptr, sync::OnceLock};
+use std::{cmp::min, fs, mem, path::PathBuf, ptr, sync::OnceLock};
 
 use anstyle::{AnsiColor, Color as AnsiColorEnum, RgbColor};
 use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
@@ -18,14 +18,23 @@ use tui_scrollview::ScrollViewState;
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
+use super::history::PromptHistory;
+use super::keymap::{KeyAction, Keymap};
+use super::mentions::MentionPalette;
 use super::types::{
-    InlineCommand, InlineEvent, InlineHeaderContext, InlineListItem, InlineListSelection,
-    InlineMessageKind, InlineSegment, InlineTextStyle, InlineTheme,
+    InlineBlockKind, InlineCommand, InlineEvent, InlineHeaderContext, InlineListItem,
+    InlineListSelection, InlineMentionKind, InlineMessageKind, InlineSegment, InlineTextStyle,
+    InlineTheme,
 };
 use crate::config::constants::ui;
 use crate::ui::slash::{SlashCommandInfo, suggestions_for};
 
 const USER_PREFIX: &str = "❯ ";
+const SEARCH_LABEL: &str = "(reverse-i-search)";
+const FAILED_SEARCH_LABEL: &str = "(failed reverse-i-search)";
+const QUEUE_PREFIX: &str = "  ↳ ";
+const VI_NORMAL_PREFIX: &str = "❮ ";
+const MODEL_PICKER_COMMAND: &str = "/model";
 const PLACEHOLDER_COLOR: RgbColor = RgbColor(0x88, 0x88, 0x88);
 
 #[derive(Clone)]
@@ -35,6 +44,100 @@ struct MessageLine {
     revision: u64,
 }
 
+/// A run of transcript lines that folds, copies and is navigated as a unit.
+struct TranscriptBlock {
+    kind: InlineBlockKind,
+    /// Index of the block's first line in `Session::lines`.
+    start: usize,
+    title: Option<String>,
+    full_output: Option<PathBuf>,
+    collapsed: bool,
+}
+
+/// Editing mode of the input buffer when vi mode is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ViMode {
+    Insert,
+    /// Normal mode, with the `d` or `c` operator awaiting a motion.
+    Normal {
+        operator: Option<char>,
+    },
+}
+
+/// State of an in-progress Ctrl+R search through the prompt history.
+struct HistorySearch {
+    query: String,
+    matched: Option<usize>,
+    failed: bool,
+    draft: String,
+    draft_cursor: usize,
+}
+
+impl HistorySearch {
+    fn label(&self) -> String {
+        let label = if self.failed {
+            FAILED_SEARCH_LABEL
+        } else {
+            SEARCH_LABEL
+        };
+        format!("{}`{}': ", label, self.query)
+    }
+}
+
+/// One wrapped row of the input area.
+struct InputRow {
+    prefix: String,
+    text: String,
+}
+
+/// Wrapped input rows plus the cursor's row and column within them.
+struct InputLayout {
+    rows: Vec<InputRow>,
+    cursor: (usize, usize),
+}
+
+/// Wrap `text` at `width` columns behind `prefix`, indenting continuation rows to match it.
+fn layout_input(prefix: &str, text: &str, cursor: usize, width: u16) -> InputLayout {
+    let indent_width = UnicodeWidthStr::width(prefix);
+    let indent = " ".repeat(indent_width);
+    let width = usize::from(width.max(1));
+    let mut rows = vec![InputRow {
+        prefix: prefix.to_string(),
+        text: String::new(),
+    }];
+    let mut column = indent_width;
+    let mut cursor_at = None;
+
+    for (index, grapheme) in text.grapheme_indices(true) {
+        let grapheme_width = UnicodeWidthStr::width(grapheme);
+        if grapheme != "\n" && column + grapheme_width > width && column > indent_width {
+            rows.push(InputRow {
+                prefix: indent.clone(),
+                text: String::new(),
+            });
+            column = indent_width;
+        }
+        if cursor_at.is_none() && index >= cursor {
+            cursor_at = Some((rows.len() - 1, column));
+        }
+        if grapheme == "\n" {
+            rows.push(InputRow {
+                prefix: indent.clone(),
+                text: String::new(),
+            });
+            column = indent_width;
+            continue;
+        }
+        if let Some(row) = rows.last_mut() {
+            row.text.push_str(grapheme);
+        }
+        column += grapheme_width;
+    }
+
+    let cursor = cursor_at.unwrap_or((rows.len() - 1, column));
+    InputLayout { rows, cursor }
+}
+
 #[derive(Clone, Default)]
 struct MessageLabels {
     agent: Option<String>,
@@ -350,6 +453,8 @@ struct TranscriptReflowCache {
     width: u16,
     flattened: Vec<Line<'static>>,
     messages: Vec<CachedMessage>,
+    /// Row in `flattened` where each message starts, or would start if folded away.
+    line_rows: Vec<usize>,
 }
 
 #[derive(Default)]
@@ -399,6 +504,9 @@ fn ratatui_style_from_inline(style: &InlineTextStyle, fallback: Option<AnsiColor
 
 pub struct Session {
     lines: Vec<MessageLine>,
+    blocks: Vec<TranscriptBlock>,
+    focused_block: Option<usize>,
+    clipboard: Option<String>,
     theme: InlineTheme,
     header_context: InlineHeaderContext,
     header_rows: u16,
@@ -409,12 +517,22 @@ pub struct Session {
     placeholder_style: Option<InlineTextStyle>,
     input: String,
     cursor: usize,
+    input_height: u16,
+    history: PromptHistory,
+    history_index: Option<usize>,
+    history_draft: String,
+    history_search: Option<HistorySearch>,
+    keymap: Keymap,
+    vi: Option<ViMode>,
+    mentions: MentionPalette,
     slash_suggestions: Vec<&'static SlashCommandInfo>,
     slash_selected: Option<usize>,
     slash_list_state: ListState,
     slash_visible_rows: usize,
     navigation_state: ListState,
     input_enabled: bool,
+    busy: bool,
+    queued: Vec<String>,
     cursor_visible: bool,
     needs_redraw: bool,
     should_exit: bool,
@@ -449,6 +567,9 @@ impl Session {
         let initial_transcript_rows = resolved_rows.saturating_sub(reserved_rows).max(1);
         let mut session = Self {
             lines: Vec::new(),
+            blocks: Vec::new(),
+            focused_block: None,
+            clipboard: None,
             theme,
             header_context: InlineHeaderContext::default(),
             labels: MessageLabels::default(),
@@ -458,12 +579,22 @@ impl Session {
             placeholder_style: None,
             input: String::new(),
             cursor: 0,
+            input_height: ui::INLINE_INPUT_HEIGHT,
+            history: PromptHistory::in_memory(),
+            history_index: None,
+            history_draft: String::new(),
+            history_search: None,
+            keymap: Keymap::default(),
+            vi: None,
+            mentions: MentionPalette::new(),
             slash_suggestions: Vec::new(),
             slash_selected: None,
             slash_list_state: ListState::default(),
             slash_visible_rows: 0,
             navigation_state: ListState::default(),
             input_enabled: true,
+            busy: false,
+            queued: Vec::new(),
             cursor_visible: true,
             needs_redraw: true,
             should_exit: false,
@@ -484,6 +615,17 @@ impl Session {
         session
     }
 
+    pub fn with_history(mut self, history: PromptHistory) -> Self {
+        self.history = history;
+        self
+    }
+
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.vi = keymap.vi_mode().then_some(ViMode::Insert);
+        self.keymap = keymap;
+        self
+    }
+
     pub fn should_exit(&self) -> bool {
         self.should_exit
     }
@@ -492,6 +634,11 @@ impl Session {
         self.should_exit = true;
     }
 
+    /// Text of the last copied block, to be sent to the terminal clipboard.
+    pub fn take_clipboard(&mut self) -> Option<String> {
+        self.clipboard.take()
+    }
+
     pub fn take_redraw(&mut self) -> bool {
         if self.needs_redraw {
             self.needs_redraw = false;
@@ -512,6 +659,15 @@ impl Session {
             InlineCommand::ReplaceLast { count, kind, lines } => {
                 self.replace_last(count, kind, lines);
             }
+            InlineCommand::BeginBlock {
+                kind,
+                title,
+                full_output,
+            } => {
+                self.begin_block(kind, title, full_output);
+                self.invalidate_scroll_metrics();
+                self.enforce_scroll_bounds();
+            }
             InlineCommand::SetPrompt { prefix, style } => {
                 self.prompt_prefix = prefix;
                 self.prompt_style = style;
@@ -527,7 +683,24 @@ impl Session {
                 self.invalidate_scroll_metrics();
             }
             InlineCommand::SetHeaderContext { context } => {
+                // Spend is pushed separately, so keep it across context rebuilds
+                let cost = std::mem::take(&mut self.header_context.cost);
                 self.header_context = context;
+                if self.header_context.cost.is_empty() {
+                    self.header_context.cost = cost;
+                }
+                self.needs_redraw = true;
+            }
+            InlineCommand::SetHeaderCost { cost } => {
+                self.header_context.cost = if cost.trim().is_empty() {
+                    String::new()
+                } else {
+                    format!("{}{}", ui::HEADER_COST_PREFIX, cost.trim())
+                };
+                self.needs_redraw = true;
+            }
+            InlineCommand::SetHeaderMode { mode } => {
+                self.header_context.mode = mode;
                 self.needs_redraw = true;
             }
             InlineCommand::SetTheme { theme } => {
@@ -540,16 +713,31 @@ impl Session {
             }
             InlineCommand::SetInputEnabled(value) => {
                 self.input_enabled = value;
-                self.update_slash_suggestions();
+                self.update_input_suggestions();
+            }
+            InlineCommand::SetBusy(value) => {
+                self.busy = value;
+            }
+            InlineCommand::SetQueuedMessages(messages) => {
+                self.queued = messages;
+                self.recalculate_transcript_rows();
             }
             InlineCommand::SetInput(content) => {
+                self.reset_history_navigation();
                 self.input = content;
                 self.cursor = self.input.len();
-                self.update_slash_suggestions();
+                self.update_input_suggestions();
             }
             InlineCommand::ClearInput => {
                 self.clear_input();
             }
+            InlineCommand::RecordHistory(entry) => {
+                self.history.push(&entry);
+            }
+            InlineCommand::SetMentionCandidates(candidates) => {
+                self.mentions.set_candidates(candidates);
+                self.update_input_suggestions();
+            }
             InlineCommand::ForceRedraw => {
                 self.mark_dirty();
             }
@@ -587,6 +775,15 @@ impl Session {
                 self.apply_view_rows(rows);
                 self.mark_dirty();
             }
+            CrosstermEvent::Paste(text) => {
+                if self.input_enabled && self.modal.is_none() {
+                    if self.history_search.is_some() {
+                        self.accept_history_search();
+                    }
+                    self.insert_text(&text);
+                    self.mark_dirty();
+                }
+            }
             _ => {}
         }
     }
@@ -606,8 +803,18 @@ impl Session {
             self.recalculate_transcript_rows();
         }
 
+        let input_height = self.desired_input_height(viewport.width);
+        if input_height != self.input_height {
+            self.input_height = input_height;
+            self.recalculate_transcript_rows();
+        }
+
+        let queue_height = self.queue_height();
         let mut constraints = vec![Constraint::Length(header_height), Constraint::Min(1)];
-        constraints.push(Constraint::Length(ui::INLINE_INPUT_HEIGHT));
+        if queue_height > 0 {
+            constraints.push(Constraint::Length(queue_height));
+        }
+        constraints.push(Constraint::Length(input_height));
 
         let segments = Layout::vertical(constraints).split(viewport);
 
@@ -615,6 +822,7 @@ impl Session {
         let main_area = segments[1];
         let input_index = segments.len().saturating_sub(1);
         let input_area = segments[input_index];
+        let queue_area = (queue_height > 0).then(|| segments[input_index - 1]);
 
         let available_width = main_area.width;
         let horizontal_minimum = ui::INLINE_CONTENT_MIN_WIDTH + ui::INLINE_NAVIGATION_MIN_WIDTH;
@@ -652,9 +860,13 @@ impl Session {
             self.render_navigation(frame, navigation_area);
         }
         self.render_transcript(frame, transcript_area);
+        if let Some(queue_area) = queue_area {
+            self.render_queue(frame, queue_area);
+        }
         self.render_input(frame, input_area);
         self.render_modal(frame, viewport);
         self.render_slash_palette(frame, viewport);
+        self.render_mention_palette(frame, viewport);
     }
 
     fn render_header(&self, frame: &mut Frame<'_>, area: Rect, lines: &[Line<'static>]) {
@@ -722,15 +934,17 @@ impl Session {
 
         let items = self.navigation_items();
         let item_count = items.len();
-        if self.lines.is_empty() {
+        if self.blocks.is_empty() {
             self.navigation_state.select(None);
             *self.navigation_state.offset_mut() = 0;
         } else {
-            let last_index = self.lines.len().saturating_sub(1);
-            self.navigation_state.select(Some(last_index));
-            let viewport = inner.height as usize;
+            let last_index = self.blocks.len().saturating_sub(1);
+            let selected = self.focused_block.unwrap_or(last_index);
+            self.navigation_state.select(Some(selected));
+            let viewport = (inner.height as usize).max(1);
             let max_offset = item_count.saturating_sub(viewport);
-            *self.navigation_state.offset_mut() = max_offset;
+            let offset = (selected + 1).saturating_sub(viewport).min(max_offset);
+            *self.navigation_state.offset_mut() = offset;
         }
 
         let list = List::new(items)
@@ -862,6 +1076,7 @@ impl Session {
             (&self.header_context.tools, defaults.tools),
             (&self.header_context.languages, defaults.languages),
             (&self.header_context.mcp, defaults.mcp),
+            (&self.header_context.cost, defaults.cost),
         ];
 
         fields
@@ -1013,30 +1228,48 @@ impl Session {
     }
 
     fn navigation_items(&self) -> Vec<ListItem<'static>> {
-        if self.lines.is_empty() {
+        if self.blocks.is_empty() {
             return vec![ListItem::new(Line::from(vec![Span::styled(
                 ui::NAVIGATION_EMPTY_LABEL.to_string(),
                 self.navigation_placeholder_style(),
             )]))];
         }
 
-        self.lines
-            .iter()
-            .enumerate()
-            .map(|(index, line)| ListItem::new(Line::from(self.navigation_spans(index, line))))
+        (0..self.blocks.len())
+            .map(|index| ListItem::new(Line::from(self.navigation_spans(index))))
             .collect()
     }
 
-    fn navigation_spans(&self, index: usize, line: &MessageLine) -> Vec<Span<'static>> {
+    fn navigation_spans(&self, index: usize) -> Vec<Span<'static>> {
+        let block = &self.blocks[index];
         let mut spans = Vec::new();
         let sequence = format!("{}{:02}", ui::NAVIGATION_INDEX_PREFIX, index + 1);
         spans.push(Span::styled(sequence, self.navigation_index_style()));
         spans.push(Span::raw(" "));
+        let kind = Self::block_message_kind(block.kind);
         spans.push(Span::styled(
-            self.navigation_label(line.kind).to_string(),
-            self.navigation_label_style(line.kind),
+            self.navigation_label(block.kind).to_string(),
+            self.navigation_label_style(kind),
         ));
-        let preview = self.navigation_preview_text(line);
+        if block.collapsed {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                ui::INLINE_BLOCK_FOLD_MARKER.to_string(),
+                self.navigation_index_style(),
+            ));
+        }
+        let preview = match &block.title {
+            Some(title) => self.navigation_preview_text(std::slice::from_ref(title)),
+            None => self.lines[block.start..self.block_end(index)]
+                .iter()
+                .find(|line| line.segments.iter().any(|seg| !seg.text.trim().is_empty()))
+                .map(|line| {
+                    let texts: Vec<String> =
+                        line.segments.iter().map(|seg| seg.text.clone()).collect();
+                    self.navigation_preview_text(&texts)
+                })
+                .unwrap_or_default(),
+        };
         if !preview.is_empty() {
             spans.push(Span::raw(" "));
             spans.push(Span::styled(preview, self.navigation_preview_style()));
@@ -1044,24 +1277,23 @@ impl Session {
         spans
     }
 
-    fn navigation_label(&self, kind: InlineMessageKind) -> &'static str {
+    fn navigation_label(&self, kind: InlineBlockKind) -> &'static str {
         match kind {
-            InlineMessageKind::Agent => ui::NAVIGATION_LABEL_AGENT,
-            InlineMessageKind::Error => ui::NAVIGATION_LABEL_ERROR,
-            InlineMessageKind::Info => ui::NAVIGATION_LABEL_INFO,
-            InlineMessageKind::Policy => ui::NAVIGATION_LABEL_POLICY,
-            InlineMessageKind::Tool => ui::NAVIGATION_LABEL_TOOL,
-            InlineMessageKind::User => ui::NAVIGATION_LABEL_USER,
-            InlineMessageKind::Pty => ui::NAVIGATION_LABEL_PTY,
+            InlineBlockKind::User => ui::NAVIGATION_LABEL_USER,
+            InlineBlockKind::Agent => ui::NAVIGATION_LABEL_AGENT,
+            InlineBlockKind::ToolCall => ui::NAVIGATION_LABEL_TOOL_CALL,
+            InlineBlockKind::ToolResult => ui::NAVIGATION_LABEL_TOOL_RESULT,
+            InlineBlockKind::Diff => ui::NAVIGATION_LABEL_DIFF,
+            InlineBlockKind::Reasoning => ui::NAVIGATION_LABEL_REASONING,
         }
     }
 
-    fn navigation_preview_text(&self, line: &MessageLine) -> String {
+    fn navigation_preview_text(&self, texts: &[String]) -> String {
         let mut preview = String::new();
         let mut char_count = 0usize;
         let mut truncated = false;
-        for segment in &line.segments {
-            let sanitized = segment.text.replace('\n', " ").replace('\r', " ");
+        for text in texts {
+            let sanitized = text.replace('\n', " ").replace('\r', " ");
             let trimmed = sanitized.trim();
             if trimmed.is_empty() {
                 continue;
@@ -1290,6 +1522,93 @@ impl Session {
         frame.render_stateful_widget(list, layout.list_area, &mut self.slash_list_state);
     }
 
+    fn render_mention_palette(&mut self, frame: &mut Frame<'_>, viewport: Rect) {
+        if viewport.height == 0
+            || viewport.width == 0
+            || self.modal.is_some()
+            || !self.mentions.is_visible()
+        {
+            self.mentions.visible_rows = 0;
+            return;
+        }
+
+        let mut width_hint = measure_text_width(ui::MENTION_PALETTE_HINT_PRIMARY);
+        width_hint = width_hint.max(measure_text_width(ui::MENTION_PALETTE_HINT_SECONDARY));
+        for candidate in self.mentions.matches() {
+            let mut label = format!("@{}", candidate.label);
+            if let Some(detail) = candidate.detail.as_deref() {
+                label.push(' ');
+                label.push_str(detail);
+            }
+            width_hint = width_hint.max(measure_text_width(&label));
+        }
+
+        let instructions = vec![
+            Line::from(Span::styled(
+                ui::MENTION_PALETTE_HINT_PRIMARY.to_string(),
+                self.default_style(),
+            )),
+            Line::from(Span::styled(
+                ui::MENTION_PALETTE_HINT_SECONDARY.to_string(),
+                self.default_style().add_modifier(Modifier::DIM),
+            )),
+        ];
+        let area = compute_modal_area(viewport, width_hint, instructions.len(), true);
+
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .title(Line::from(Span::styled(
+                ui::MENTION_BLOCK_TITLE.to_string(),
+                self.section_title_style(),
+            )))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .style(self.default_style())
+            .border_style(self.border_style());
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        if inner.height == 0 || inner.width == 0 {
+            self.mentions.visible_rows = 0;
+            return;
+        }
+
+        let layout = ModalListLayout::new(inner, instructions.len());
+        if let Some(text_area) = layout.text_area {
+            let paragraph = Paragraph::new(instructions).wrap(Wrap { trim: true });
+            frame.render_widget(paragraph, text_area);
+        }
+
+        self.mentions.visible_rows = layout.list_area.height as usize;
+        self.mentions.sync_list_state();
+
+        let name_style = self.slash_name_style();
+        let description_style = self.slash_description_style();
+        let items: Vec<ListItem<'static>> = self
+            .mentions
+            .matches()
+            .map(|candidate| {
+                let marker = match candidate.kind {
+                    InlineMentionKind::File => "@",
+                    InlineMentionKind::Symbol => "@#",
+                };
+                let mut spans = vec![Span::styled(
+                    format!("{}{}", marker, candidate.label),
+                    name_style,
+                )];
+                if let Some(detail) = candidate.detail.as_deref() {
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(detail.to_string(), description_style));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .style(self.default_style())
+            .highlight_style(self.slash_highlight_style());
+
+        frame.render_stateful_widget(list, layout.list_area, &mut self.mentions.list_state);
+    }
+
     fn slash_palette_instructions(&self) -> Vec<Line<'static>> {
         vec![
             Line::from(Span::styled(
@@ -1315,50 +1634,171 @@ impl Session {
             .style(self.default_style())
             .border_style(self.accent_style());
         let inner = block.inner(area);
-        let paragraph = Paragraph::new(self.render_input_line())
+        let layout = self.input_layout(inner.width);
+        let visible_rows = usize::from(inner.height.max(1));
+        let scroll = layout.cursor.0.saturating_sub(visible_rows - 1);
+        let paragraph = Paragraph::new(self.render_input_lines(&layout))
             .style(self.default_style())
-            .wrap(Wrap { trim: false })
+            .scroll((scroll as u16, 0))
             .block(block);
         frame.render_widget(paragraph, area);
 
-        if self.cursor_should_be_visible() && inner.width > 0 {
-            let (x, y) = self.cursor_position(inner);
+        if self.cursor_should_be_visible() && inner.width > 0 && inner.height > 0 {
+            let (row, column) = layout.cursor;
+            let x = inner.x + (column as u16).min(inner.width - 1);
+            let y = inner.y + (row - scroll) as u16;
             frame.set_cursor_position((x, y));
         }
     }
 
-    fn render_input_line(&self) -> Line<'static> {
-        let mut spans = Vec::new();
+    /// Join hints for the bound actions, naming whichever keys the keymap uses.
+    fn key_hints(&self, lead: &str, hints: &[(KeyAction, &str)]) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if !lead.is_empty() {
+            parts.push(lead.to_string());
+        }
+        for (action, hint) in hints {
+            if let Some(key) = self.keymap.label(*action) {
+                parts.push(format!("{} {}", key, hint));
+            }
+        }
+        parts.join(" • ")
+    }
+
+    fn queue_height(&self) -> u16 {
+        if self.queued.is_empty() {
+            return 0;
+        }
+        let shown = self.queued.len().min(ui::QUEUE_PREVIEW_LIMIT);
+        let overflow = usize::from(self.queued.len() > shown);
+        u16::try_from(1 + shown + overflow).unwrap_or(u16::MAX)
+    }
+
+    fn render_queue(&self, frame: &mut Frame<'_>, area: Rect) {
+        frame.render_widget(Clear, area);
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+
+        let hint_style = self.default_style().add_modifier(Modifier::DIM);
+        let message_style = self.slash_description_style();
+        let hint = self.key_hints(
+            ui::QUEUE_HINT,
+            &[
+                (KeyAction::EditQueued, ui::QUEUE_EDIT_HINT),
+                (KeyAction::Steer, ui::QUEUE_STEER_HINT),
+            ],
+        );
+        let mut lines = vec![Line::from(Span::styled(hint, hint_style))];
+        for message in self.queued.iter().take(ui::QUEUE_PREVIEW_LIMIT) {
+            let first_line = message.lines().next().unwrap_or_default();
+            let ellipsis = if message.lines().nth(1).is_some() {
+                " …"
+            } else {
+                ""
+            };
+            lines.push(Line::from(vec![
+                Span::styled(QUEUE_PREFIX.to_string(), hint_style),
+                Span::styled(format!("{}{}", first_line, ellipsis), message_style),
+            ]));
+        }
+        let hidden = self.queued.len().saturating_sub(ui::QUEUE_PREVIEW_LIMIT);
+        if hidden > 0 {
+            lines.push(Line::from(Span::styled(
+                format!("{}{} more", QUEUE_PREFIX, hidden),
+                hint_style,
+            )));
+        }
+        frame.render_widget(Paragraph::new(lines).style(self.default_style()), area);
+    }
+
+    fn input_layout(&self, width: u16) -> InputLayout {
+        if let Some(search) = self.history_search.as_ref() {
+            let label = search.label();
+            let matched = search
+                .matched
+                .and_then(|index| self.history.get(index))
+                .unwrap_or_default();
+            let mut layout = layout_input(&label, matched, 0, width);
+            // Keep the cursor after the query rather than on the match.
+            let query_end = UnicodeWidthStr::width(label.as_str()).saturating_sub("': ".len());
+            layout.cursor = (0, query_end);
+            return layout;
+        }
+        layout_input(self.active_prompt_prefix(), &self.input, self.cursor, width)
+    }
+
+    /// The prompt prefix, swapped for a distinct marker in vi normal mode.
+    fn active_prompt_prefix(&self) -> &str {
+        match self.vi {
+            Some(ViMode::Normal { .. }) => VI_NORMAL_PREFIX,
+            _ => &self.prompt_prefix,
+        }
+    }
+
+    fn desired_input_height(&self, width: u16) -> u16 {
+        let rows = self.input_layout(width).rows.len().max(1);
+        let rows = u16::try_from(rows)
+            .unwrap_or(u16::MAX)
+            .min(ui::INLINE_INPUT_MAX_LINES);
+        ui::INLINE_INPUT_HEIGHT + rows - 1
+    }
+
+    fn render_input_lines(&self, layout: &InputLayout) -> Vec<Line<'static>> {
         let mut prompt_style = self.prompt_style.clone();
         if prompt_style.color.is_none() {
             prompt_style.color = self.theme.primary.or(self.theme.foreground);
         }
         let prompt_style = ratatui_style_from_inline(&prompt_style, self.theme.foreground);
-        spans.push(Span::styled(self.prompt_prefix.clone(), prompt_style));
-
-        if self.input.is_empty() {
-            if let Some(placeholder) = &self.placeholder {
-                let placeholder_style =
-                    self.placeholder_style
-                        .clone()
-                        .unwrap_or_else(|| InlineTextStyle {
-                            color: Some(AnsiColorEnum::Rgb(PLACEHOLDER_COLOR)),
-                            italic: true,
-                            ..InlineTextStyle::default()
-                        });
-                let style = ratatui_style_from_inline(
-                    &placeholder_style,
-                    Some(AnsiColorEnum::Rgb(PLACEHOLDER_COLOR)),
+        let accent_style = self.accent_inline_style();
+        let text_style = ratatui_style_from_inline(&accent_style, self.theme.foreground);
+
+        if self.input.is_empty()
+            && self.history_search.is_none()
+            && let Some(placeholder) = &self.placeholder
+        {
+            let placeholder_style =
+                self.placeholder_style
+                    .clone()
+                    .unwrap_or_else(|| InlineTextStyle {
+                        color: Some(AnsiColorEnum::Rgb(PLACEHOLDER_COLOR)),
+                        italic: true,
+                        ..InlineTextStyle::default()
+                    });
+            let style = ratatui_style_from_inline(
+                &placeholder_style,
+                Some(AnsiColorEnum::Rgb(PLACEHOLDER_COLOR)),
+            );
+            let mut spans = vec![
+                Span::styled(self.active_prompt_prefix().to_string(), prompt_style),
+                Span::styled(placeholder.clone(), style),
+            ];
+            if self.busy {
+                // Input stays editable while the agent works; say what sending does now.
+                let hint = self.key_hints(
+                    "",
+                    &[
+                        (KeyAction::Submit, ui::BUSY_QUEUE_HINT),
+                        (KeyAction::Steer, ui::BUSY_STEER_HINT),
+                    ],
                 );
-                spans.push(Span::styled(placeholder.clone(), style));
+                if !hint.is_empty() {
+                    spans.push(Span::styled(format!(" • {}", hint), style));
+                }
             }
-        } else {
-            let accent_style = self.accent_inline_style();
-            let style = ratatui_style_from_inline(&accent_style, self.theme.foreground);
-            spans.push(Span::styled(self.input.clone(), style));
+            return vec![Line::from(spans)];
         }
 
-        Line::from(spans)
+        layout
+            .rows
+            .iter()
+            .map(|row| {
+                Line::from(vec![
+                    Span::styled(row.prefix.clone(), prompt_style),
+                    Span::styled(row.text.clone(), text_style),
+                ])
+            })
+            .collect()
     }
 
     fn visible_slash_suggestions(&self) -> &[&'static SlashCommandInfo] {
@@ -1421,7 +1861,7 @@ impl Session {
     }
 
     fn input_reserved_rows(&self) -> u16 {
-        self.header_reserved_rows() + ui::INLINE_INPUT_HEIGHT
+        self.header_reserved_rows() + self.queue_height() + self.input_height
     }
 
     fn recalculate_transcript_rows(&mut self) {
@@ -1480,6 +1920,36 @@ impl Session {
         }
     }
 
+    fn update_input_suggestions(&mut self) {
+        self.update_slash_suggestions();
+        let enabled = self.input_enabled && self.history_search.is_none();
+        if self.mentions.update(&self.input, self.cursor, enabled) {
+            self.mark_dirty();
+        }
+    }
+
+    /// Handle palette keys while `@` completions are shown.
+    fn try_handle_mention_navigation(&mut self, key: &KeyEvent, has_control: bool) -> bool {
+        if !self.input_enabled || !self.mentions.is_visible() || has_control {
+            return false;
+        }
+        match key.code {
+            KeyCode::Up | KeyCode::BackTab => self.mentions.select_previous(),
+            KeyCode::Down => self.mentions.select_next(),
+            KeyCode::Tab | KeyCode::Enter => {
+                if let Some((input, cursor)) = self.mentions.apply(&self.input) {
+                    self.input = input;
+                    self.cursor = cursor;
+                }
+                self.update_input_suggestions();
+            }
+            KeyCode::Esc => self.mentions.dismiss(),
+            _ => return false,
+        }
+        self.mark_dirty();
+        true
+    }
+
     fn current_slash_prefix(&self) -> Option<&str> {
         if !self.input.starts_with('/') || self.cursor == 0 {
             return None;
@@ -1683,7 +2153,7 @@ impl Session {
 
         self.input = new_input;
         self.cursor = cursor_position;
-        self.update_slash_suggestions();
+        self.update_input_suggestions();
         self.mark_dirty();
         true
     }
@@ -1936,13 +2406,6 @@ impl Session {
             .add_modifier(Modifier::DIM)
     }
 
-    fn cursor_position(&self, area: Rect) -> (u16, u16) {
-        let prompt_width = UnicodeWidthStr::width(self.prompt_prefix.as_str()) as u16;
-        let before_cursor = &self.input[..self.cursor];
-        let cursor_width = UnicodeWidthStr::width(before_cursor) as u16;
-        (area.x + prompt_width + cursor_width, area.y)
-    }
-
     fn cursor_should_be_visible(&self) -> bool {
         self.cursor_visible && self.input_enabled
     }
@@ -2051,9 +2514,10 @@ impl Session {
     }
 
     pub fn clear_input(&mut self) {
+        self.reset_history_navigation();
         self.input.clear();
         self.cursor = 0;
-        self.update_slash_suggestions();
+        self.update_input_suggestions();
         self.mark_dirty();
     }
 
@@ -2064,7 +2528,6 @@ impl Session {
         let raw_meta = modifiers.contains(KeyModifiers::META);
         let has_super = modifiers.contains(KeyModifiers::SUPER);
         let has_alt = raw_alt || (!has_super && raw_meta);
-        let has_command = has_super || (raw_meta && !has_alt);
 
         if let Some(modal) = self.modal.as_mut() {
             if let Some(list) = modal.list.as_mut() {
@@ -2096,20 +2559,64 @@ impl Session {
             }
         }
 
+        if self.history_search.is_some() && self.process_search_key(&key, has_control) {
+            return None;
+        }
+
+        if self.try_handle_mention_navigation(&key, has_control) {
+            return None;
+        }
+
         if self.try_handle_slash_navigation(&key, has_control, has_alt) {
             return None;
         }
 
+        if self.vi == Some(ViMode::Insert)
+            && self.modal.is_none()
+            && key.code == KeyCode::Esc
+            && !has_control
+            && !has_alt
+            && !has_super
+        {
+            self.vi = Some(ViMode::Normal { operator: None });
+            self.move_left();
+            self.mark_dirty();
+            return None;
+        }
+
+        if let Some(action) = self.keymap.action_for(&key) {
+            return self.perform_action(action);
+        }
+
         match key.code {
-            KeyCode::Char('c') if has_control => {
+            KeyCode::Char(ch) if self.input_enabled && !has_control && !has_alt && !has_super => {
+                if matches!(self.vi, Some(ViMode::Normal { .. })) {
+                    self.process_vi_normal_key(ch)
+                } else {
+                    self.insert_char(ch);
+                    self.mark_dirty();
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn perform_action(&mut self, action: KeyAction) -> Option<InlineEvent> {
+        match action {
+            KeyAction::Interrupt => {
                 self.mark_dirty();
                 Some(InlineEvent::Interrupt)
             }
-            KeyCode::Char('d') if has_control => {
+            KeyAction::Exit => {
                 self.mark_dirty();
                 Some(InlineEvent::Exit)
             }
-            KeyCode::Esc => {
+            KeyAction::TogglePlanMode => {
+                self.mark_dirty();
+                Some(InlineEvent::TogglePlanMode)
+            }
+            KeyAction::Cancel => {
                 if self.modal.is_some() {
                     self.close_modal();
                     None
@@ -2118,139 +2625,487 @@ impl Session {
                     Some(InlineEvent::Cancel)
                 }
             }
-            KeyCode::PageUp => {
+            KeyAction::ScrollPageUp => {
                 self.scroll_page_up();
                 self.mark_dirty();
                 Some(InlineEvent::ScrollPageUp)
             }
-            KeyCode::PageDown => {
+            KeyAction::ScrollPageDown => {
                 self.scroll_page_down();
                 self.mark_dirty();
                 Some(InlineEvent::ScrollPageDown)
             }
-            KeyCode::Up => {
+            KeyAction::ScrollLineUp => {
                 self.scroll_line_up();
                 self.mark_dirty();
                 Some(InlineEvent::ScrollLineUp)
             }
-            KeyCode::Down => {
+            KeyAction::ScrollLineDown => {
                 self.scroll_line_down();
                 self.mark_dirty();
                 Some(InlineEvent::ScrollLineDown)
             }
-            KeyCode::Enter => {
-                if self.input_enabled {
-                    let submitted = std::mem::take(&mut self.input);
-                    self.cursor = 0;
-                    self.update_slash_suggestions();
+            KeyAction::EditQueued => {
+                if self.input_enabled && self.input.is_empty() && !self.queued.is_empty() {
                     self.mark_dirty();
-                    Some(InlineEvent::Submit(submitted))
+                    Some(InlineEvent::EditQueued)
                 } else {
-                    None
+                    self.perform_action(KeyAction::HistoryPrevious)
                 }
             }
-            KeyCode::Backspace => {
-                if self.input_enabled {
-                    self.delete_char();
-                    self.mark_dirty();
+            KeyAction::HistoryPrevious => {
+                if !self.input_enabled {
+                    return self.perform_action(KeyAction::ScrollLineUp);
                 }
+                if !self.move_to_line(true) {
+                    self.recall_previous();
+                }
+                self.mark_dirty();
                 None
             }
-            KeyCode::Left => {
-                if self.input_enabled {
-                    if has_command {
-                        self.move_to_start();
-                    } else if has_alt {
-                        self.move_left_word();
-                    } else {
-                        self.move_left();
-                    }
-                    self.mark_dirty();
+            KeyAction::HistoryNext => {
+                if !self.input_enabled {
+                    return self.perform_action(KeyAction::ScrollLineDown);
+                }
+                if !self.move_to_line(false) {
+                    self.recall_next();
                 }
+                self.mark_dirty();
                 None
             }
-            KeyCode::Right => {
+            KeyAction::HistorySearch => {
                 if self.input_enabled {
-                    if has_command {
-                        self.move_to_end();
-                    } else if has_alt {
-                        self.move_right_word();
-                    } else {
-                        self.move_right();
-                    }
+                    self.start_history_search();
                     self.mark_dirty();
                 }
                 None
             }
-            KeyCode::Home => {
-                if self.input_enabled {
-                    self.move_to_start();
-                    self.mark_dirty();
+            KeyAction::Submit => {
+                if !self.input_enabled || (self.busy && self.input.trim().is_empty()) {
+                    return None;
+                }
+                let submitted = self.take_input();
+                if self.busy {
+                    Some(InlineEvent::Queue(submitted))
+                } else {
+                    Some(InlineEvent::Submit(submitted))
                 }
-                None
             }
-            KeyCode::End => {
-                if self.input_enabled {
-                    self.move_to_end();
-                    self.mark_dirty();
+            KeyAction::Steer => {
+                if !self.input_enabled
+                    || !self.busy
+                    || (self.input.trim().is_empty() && self.queued.is_empty())
+                {
+                    return None;
                 }
-                None
+                Some(InlineEvent::Steer(self.take_input()))
             }
-            KeyCode::Char(ch) => {
+            KeyAction::OpenModelPicker => {
                 if !self.input_enabled {
                     return None;
                 }
-
-                if has_command {
-                    match ch {
-                        'a' | 'A' => {
-                            self.move_to_start();
-                            self.mark_dirty();
-                            return None;
-                        }
-                        'e' | 'E' => {
-                            self.move_to_end();
-                            self.mark_dirty();
-                            return None;
-                        }
-                        _ => {
-                            return None;
-                        }
-                    }
+                self.mark_dirty();
+                let command = MODEL_PICKER_COMMAND.to_string();
+                if self.busy {
+                    Some(InlineEvent::Queue(command))
+                } else {
+                    Some(InlineEvent::Submit(command))
                 }
-
-                if has_alt {
-                    match ch {
-                        'b' | 'B' => {
-                            self.move_left_word();
-                            self.mark_dirty();
-                        }
-                        'f' | 'F' => {
-                            self.move_right_word();
-                            self.mark_dirty();
-                        }
-                        _ => {}
+            }
+            KeyAction::NextBlock => {
+                if let Some(index) = self.focused_block {
+                    if index + 1 < self.blocks.len() {
+                        self.focus_block(index + 1);
+                    } else {
+                        self.focused_block = None;
+                        self.scroll_offset = 0;
                     }
-                    return None;
-                }
-
-                if !has_control {
-                    self.insert_char(ch);
-                    self.mark_dirty();
                 }
+                self.mark_dirty();
                 None
             }
-            _ => None,
-        }
-    }
-
-    fn insert_char(&mut self, ch: char) {
-        if ch == '\u{7f}' {
-            return;
-        }
-        self.input.insert(self.cursor, ch);
-        self.cursor += ch.len_utf8();
-        self.update_slash_suggestions();
+            KeyAction::PreviousBlock => {
+                let target = match self.focused_block {
+                    Some(index) => index.saturating_sub(1),
+                    None => self.blocks.len().checked_sub(1)?,
+                };
+                self.focus_block(target);
+                self.mark_dirty();
+                None
+            }
+            KeyAction::ToggleBlock => {
+                let index = self.target_block()?;
+                let block = &mut self.blocks[index];
+                block.collapsed = !block.collapsed;
+                self.refresh_block_layout();
+                self.mark_dirty();
+                None
+            }
+            KeyAction::CopyBlock => {
+                let index = self.target_block()?;
+                self.clipboard = Some(self.block_text(index));
+                None
+            }
+            KeyAction::ShowFullOutput => {
+                let index = self.target_block()?;
+                self.show_full_output(index);
+                self.mark_dirty();
+                None
+            }
+            KeyAction::EditMessage => {
+                let index = self
+                    .focused_block
+                    .filter(|&index| self.blocks[index].kind == InlineBlockKind::User)?;
+                if !self.input_enabled || self.busy {
+                    return None;
+                }
+                let text = self.block_text(index).trim().to_string();
+                self.focused_block = None;
+                self.scroll_offset = 0;
+                self.mark_dirty();
+                Some(InlineEvent::EditMessage(text))
+            }
+            KeyAction::Newline
+            | KeyAction::CursorLeft
+            | KeyAction::CursorRight
+            | KeyAction::CursorWordLeft
+            | KeyAction::CursorWordRight
+            | KeyAction::CursorLineStart
+            | KeyAction::CursorLineEnd
+            | KeyAction::DeleteBackward
+            | KeyAction::DeleteForward => {
+                if self.input_enabled {
+                    self.perform_edit(action);
+                    self.mark_dirty();
+                }
+                None
+            }
+        }
+    }
+
+    fn perform_edit(&mut self, action: KeyAction) {
+        match action {
+            KeyAction::Newline => self.insert_char('\n'),
+            KeyAction::CursorLeft => self.move_left(),
+            KeyAction::CursorRight => self.move_right(),
+            KeyAction::CursorWordLeft => self.move_left_word(),
+            KeyAction::CursorWordRight => self.move_right_word(),
+            KeyAction::CursorLineStart => self.move_to_start(),
+            KeyAction::CursorLineEnd => self.move_to_end(),
+            KeyAction::DeleteBackward => self.delete_char(),
+            KeyAction::DeleteForward => self.delete_char_forward(),
+            _ => {}
+        }
+    }
+
+    /// Take the input for sending, leaving an empty buffer in insert mode.
+    fn take_input(&mut self) -> String {
+        self.reset_history_navigation();
+        let submitted = mem::take(&mut self.input);
+        self.cursor = 0;
+        if self.vi.is_some() {
+            self.vi = Some(ViMode::Insert);
+        }
+        self.update_input_suggestions();
+        self.mark_dirty();
+        submitted
+    }
+
+    /// Handle a character typed in vi normal mode: motions, `x`/`X`, the `d` and
+    /// `c` operators, and the commands that return to insert mode.
+    fn process_vi_normal_key(&mut self, ch: char) -> Option<InlineEvent> {
+        let Some(ViMode::Normal { operator }) = self.vi else {
+            return None;
+        };
+        self.vi = Some(ViMode::Normal { operator: None });
+        self.mark_dirty();
+
+        if let Some(operator) = operator {
+            if ch == operator {
+                self.input.clear();
+                self.cursor = 0;
+            } else {
+                let start = self.cursor;
+                if !self.apply_vi_motion(ch) {
+                    return None;
+                }
+                let (from, to) = (start.min(self.cursor), start.max(self.cursor));
+                self.input.drain(from..to);
+                self.cursor = from;
+            }
+            if operator == 'c' {
+                self.vi = Some(ViMode::Insert);
+            }
+            self.update_input_suggestions();
+            return None;
+        }
+
+        if self.apply_vi_motion(ch) {
+            return None;
+        }
+        match ch {
+            'i' => self.vi = Some(ViMode::Insert),
+            'a' => {
+                self.move_right();
+                self.vi = Some(ViMode::Insert);
+            }
+            'I' => {
+                self.move_to_start();
+                self.vi = Some(ViMode::Insert);
+            }
+            'A' => {
+                self.move_to_end();
+                self.vi = Some(ViMode::Insert);
+            }
+            'x' => self.delete_char_forward(),
+            'X' => self.delete_char(),
+            'd' | 'c' => self.vi = Some(ViMode::Normal { operator: Some(ch) }),
+            'D' | 'C' => {
+                self.input.truncate(self.cursor);
+                if ch == 'C' {
+                    self.vi = Some(ViMode::Insert);
+                }
+                self.update_input_suggestions();
+            }
+            'S' => {
+                self.input.clear();
+                self.cursor = 0;
+                self.vi = Some(ViMode::Insert);
+                self.update_input_suggestions();
+            }
+            'k' => return self.perform_action(KeyAction::HistoryPrevious),
+            'j' => return self.perform_action(KeyAction::HistoryNext),
+            _ => {}
+        }
+        None
+    }
+
+    fn apply_vi_motion(&mut self, ch: char) -> bool {
+        match ch {
+            'h' => self.move_left(),
+            'l' => self.move_right(),
+            'w' => self.move_to_next_word_start(),
+            'b' => self.move_left_word(),
+            '0' | '^' => self.move_to_start(),
+            '$' => self.move_to_end(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Handle a key while reverse search is active. Returns false when the key
+    /// accepted the match and should still be processed normally.
+    fn process_search_key(&mut self, key: &KeyEvent, has_control: bool) -> bool {
+        match key.code {
+            KeyCode::Char('r') if has_control => self.search_older(),
+            KeyCode::Char('g') if has_control => self.cancel_history_search(),
+            KeyCode::Esc => self.cancel_history_search(),
+            KeyCode::Enter => self.accept_history_search(),
+            KeyCode::Backspace => {
+                if let Some(search) = self.history_search.as_mut() {
+                    search.query.pop();
+                }
+                self.refresh_history_search(true);
+            }
+            KeyCode::Char(ch) if !has_control => {
+                if let Some(search) = self.history_search.as_mut() {
+                    search.query.push(ch);
+                }
+                self.refresh_history_search(false);
+            }
+            _ => {
+                self.accept_history_search();
+                return false;
+            }
+        }
+        self.mark_dirty();
+        true
+    }
+
+    fn start_history_search(&mut self) {
+        self.history_search = Some(HistorySearch {
+            query: String::new(),
+            matched: None,
+            failed: false,
+            draft: self.input.clone(),
+            draft_cursor: self.cursor,
+        });
+        self.clear_slash_suggestions();
+    }
+
+    /// Re-run the search after the query changed, from the newest entry when `restart`.
+    fn refresh_history_search(&mut self, restart: bool) {
+        let len = self.history.len();
+        let Some(search) = self.history_search.as_mut() else {
+            return;
+        };
+        if search.query.is_empty() {
+            search.matched = None;
+            search.failed = false;
+            return;
+        }
+        let before = match search.matched {
+            Some(index) if !restart => index + 1,
+            _ => len,
+        };
+        let found = self.history.search(&search.query, before);
+        search.failed = found.is_none();
+        if found.is_some() || restart {
+            search.matched = found;
+        }
+    }
+
+    fn search_older(&mut self) {
+        let len = self.history.len();
+        let Some(search) = self.history_search.as_mut() else {
+            return;
+        };
+        if search.query.is_empty() {
+            return;
+        }
+        let before = search.matched.unwrap_or(len);
+        match self.history.search(&search.query, before) {
+            Some(index) => {
+                search.matched = Some(index);
+                search.failed = false;
+            }
+            None => search.failed = true,
+        }
+    }
+
+    fn accept_history_search(&mut self) {
+        let Some(search) = self.history_search.take() else {
+            return;
+        };
+        match search.matched.and_then(|index| self.history.get(index)) {
+            Some(entry) => {
+                if self.history_index.is_none() {
+                    self.history_draft = search.draft;
+                }
+                self.input = entry.to_string();
+                self.cursor = self.input.len();
+                self.history_index = search.matched;
+            }
+            None => {
+                self.input = search.draft;
+                self.cursor = search.draft_cursor.min(self.input.len());
+            }
+        }
+        self.update_input_suggestions();
+    }
+
+    fn cancel_history_search(&mut self) {
+        if let Some(search) = self.history_search.take() {
+            self.input = search.draft;
+            self.cursor = search.draft_cursor.min(self.input.len());
+            self.update_input_suggestions();
+        }
+    }
+
+    fn reset_history_navigation(&mut self) {
+        self.history_index = None;
+        self.history_draft.clear();
+        self.history_search = None;
+    }
+
+    /// Replace the input with the previous history entry, keeping the draft to return to.
+    fn recall_previous(&mut self) -> bool {
+        let index = match self.history_index {
+            Some(0) => return false,
+            Some(index) => index - 1,
+            None => match self.history.len().checked_sub(1) {
+                Some(index) => index,
+                None => return false,
+            },
+        };
+        let Some(entry) = self.history.get(index).map(str::to_string) else {
+            return false;
+        };
+        if self.history_index.is_none() {
+            self.history_draft = mem::take(&mut self.input);
+        }
+        self.history_index = Some(index);
+        // Land on the first line so another Up keeps walking back.
+        self.cursor = entry.find('\n').unwrap_or(entry.len());
+        self.input = entry;
+        self.update_input_suggestions();
+        true
+    }
+
+    fn recall_next(&mut self) -> bool {
+        let Some(index) = self.history_index else {
+            return false;
+        };
+        if let Some(entry) = self.history.get(index + 1).map(str::to_string) {
+            self.history_index = Some(index + 1);
+            self.input = entry;
+        } else {
+            self.history_index = None;
+            self.input = mem::take(&mut self.history_draft);
+        }
+        self.cursor = self.input.len();
+        self.update_input_suggestions();
+        true
+    }
+
+    /// Move the cursor to the same column on the previous or next line of a
+    /// multiline input. Returns false when already on the first or last line.
+    fn move_to_line(&mut self, up: bool) -> bool {
+        let line_start = self.input[..self.cursor]
+            .rfind('\n')
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        let column = self.input[line_start..self.cursor].chars().count();
+        let (target_start, target_end) = if up {
+            if line_start == 0 {
+                return false;
+            }
+            let previous_end = line_start - 1;
+            let previous_start = self.input[..previous_end]
+                .rfind('\n')
+                .map(|index| index + 1)
+                .unwrap_or(0);
+            (previous_start, previous_end)
+        } else {
+            let Some(offset) = self.input[self.cursor..].find('\n') else {
+                return false;
+            };
+            let next_start = self.cursor + offset + 1;
+            let next_end = self.input[next_start..]
+                .find('\n')
+                .map(|index| next_start + index)
+                .unwrap_or(self.input.len());
+            (next_start, next_end)
+        };
+        self.cursor = self.input[target_start..target_end]
+            .char_indices()
+            .nth(column)
+            .map(|(index, _)| target_start + index)
+            .unwrap_or(target_end);
+        self.update_input_suggestions();
+        true
+    }
+
+    /// Insert pasted text, normalising line endings and dropping control characters.
+    fn insert_text(&mut self, text: &str) {
+        let normalized: String = text
+            .replace("\r\n", "\n")
+            .replace('\r', "\n")
+            .chars()
+            .filter(|ch| !ch.is_control() || matches!(ch, '\n' | '\t'))
+            .collect();
+        self.input.insert_str(self.cursor, &normalized);
+        self.cursor += normalized.len();
+        self.update_input_suggestions();
+    }
+
+    fn insert_char(&mut self, ch: char) {
+        if ch == '\u{7f}' {
+            return;
+        }
+        self.input.insert(self.cursor, ch);
+        self.cursor += ch.len_utf8();
+        self.update_input_suggestions();
     }
 
     fn delete_char(&mut self) {
@@ -2265,7 +3120,14 @@ impl Session {
         {
             self.input.drain(index..self.cursor);
             self.cursor = index;
-            self.update_slash_suggestions();
+            self.update_input_suggestions();
+        }
+    }
+
+    fn delete_char_forward(&mut self) {
+        if let Some(ch) = self.input[self.cursor..].chars().next() {
+            self.input.drain(self.cursor..self.cursor + ch.len_utf8());
+            self.update_input_suggestions();
         }
     }
 
@@ -2280,7 +3142,7 @@ impl Session {
             .last()
         {
             self.cursor = index;
-            self.update_slash_suggestions();
+            self.update_input_suggestions();
         }
     }
 
@@ -2291,10 +3153,10 @@ impl Session {
         let slice = &self.input[self.cursor..];
         if let Some((_, ch)) = slice.char_indices().next() {
             self.cursor += ch.len_utf8();
-            self.update_slash_suggestions();
+            self.update_input_suggestions();
         } else {
             self.cursor = self.input.len();
-            self.update_slash_suggestions();
+            self.update_input_suggestions();
         }
     }
 
@@ -2337,6 +3199,16 @@ impl Session {
         }
     }
 
+    /// Vi `w`: move to the start of the next word.
+    fn move_to_next_word_start(&mut self) {
+        let rest = &self.input[self.cursor..];
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let next = rest[word_end..]
+            .find(|ch: char| !ch.is_whitespace())
+            .map_or(rest.len(), |offset| word_end + offset);
+        self.cursor += next;
+    }
+
     fn move_right_word(&mut self) {
         if self.cursor >= self.input.len() {
             return;
@@ -2441,14 +3313,19 @@ impl Session {
 
     fn push_line(&mut self, kind: InlineMessageKind, segments: Vec<InlineSegment>) {
         let previous_max_offset = self.current_max_scroll_offset();
+        self.push_message(kind, segments);
+        self.invalidate_scroll_metrics();
+        self.adjust_scroll_after_change(previous_max_offset);
+    }
+
+    fn push_message(&mut self, kind: InlineMessageKind, segments: Vec<InlineSegment>) {
+        self.track_block(kind);
         let revision = self.next_revision();
         self.lines.push(MessageLine {
             kind,
             segments,
             revision,
         });
-        self.invalidate_scroll_metrics();
-        self.adjust_scroll_after_change(previous_max_offset);
     }
 
     fn append_inline(&mut self, kind: InlineMessageKind, segment: InlineSegment) {
@@ -2505,13 +3382,9 @@ impl Session {
         for _ in 0..remove_count {
             self.lines.pop();
         }
+        self.drop_blocks_past_end();
         for segments in lines {
-            let revision = self.next_revision();
-            self.lines.push(MessageLine {
-                kind,
-                segments,
-                revision,
-            });
+            self.push_message(kind, segments);
         }
         self.invalidate_scroll_metrics();
         self.adjust_scroll_after_change(previous_max_offset);
@@ -2557,15 +3430,13 @@ impl Session {
         }
 
         if !appended {
-            let revision = self.next_revision();
-            self.lines.push(MessageLine {
+            self.push_message(
                 kind,
-                segments: vec![InlineSegment {
+                vec![InlineSegment {
                     text: text.to_string(),
                     style: style.clone(),
                 }],
-                revision,
-            });
+            );
         }
 
         self.invalidate_scroll_metrics();
@@ -2598,6 +3469,221 @@ impl Session {
         self.start_line(kind);
     }
 
+    /// Close the current block and start a new one at the next appended line.
+    fn begin_block(
+        &mut self,
+        kind: InlineBlockKind,
+        title: Option<String>,
+        full_output: Option<PathBuf>,
+    ) {
+        let next_line = self.lines.len();
+        if self
+            .blocks
+            .last()
+            .is_some_and(|block| block.start == next_line)
+        {
+            self.blocks.pop();
+        } else if let Some(block) = self.blocks.last_mut()
+            && (block.kind == InlineBlockKind::Reasoning
+                || (block.kind.is_tool()
+                    && next_line - block.start > ui::INLINE_BLOCK_AUTO_COLLAPSE_LINES))
+        {
+            block.collapsed = true;
+        }
+        self.blocks.push(TranscriptBlock {
+            kind,
+            start: next_line,
+            title,
+            full_output,
+            collapsed: false,
+        });
+    }
+
+    /// Start a block for a line whose kind does not belong to the current one.
+    /// Notices, errors and reasoning stay with the block they interrupt.
+    fn track_block(&mut self, kind: InlineMessageKind) {
+        let block_kind = match kind {
+            InlineMessageKind::User => InlineBlockKind::User,
+            InlineMessageKind::Agent => InlineBlockKind::Agent,
+            InlineMessageKind::Tool | InlineMessageKind::Pty => InlineBlockKind::ToolResult,
+            InlineMessageKind::Info | InlineMessageKind::Error | InlineMessageKind::Policy => {
+                return;
+            }
+        };
+        let continues = self.blocks.last().is_some_and(|block| {
+            block.kind == block_kind || (block.kind.is_tool() && block_kind.is_tool())
+        });
+        if !continues {
+            self.begin_block(block_kind, None, None);
+        }
+    }
+
+    fn drop_blocks_past_end(&mut self) {
+        let end = self.lines.len();
+        self.blocks.retain(|block| block.start <= end);
+        while self.blocks.len() > 1 && self.blocks[self.blocks.len() - 2].start == end {
+            self.blocks.remove(self.blocks.len() - 2);
+        }
+        if self
+            .focused_block
+            .is_some_and(|index| index >= self.blocks.len())
+        {
+            self.focused_block = None;
+        }
+    }
+
+    fn block_end(&self, index: usize) -> usize {
+        self.blocks
+            .get(index + 1)
+            .map(|block| block.start)
+            .unwrap_or(self.lines.len())
+    }
+
+    fn block_message_kind(kind: InlineBlockKind) -> InlineMessageKind {
+        match kind {
+            InlineBlockKind::User => InlineMessageKind::User,
+            InlineBlockKind::Agent => InlineMessageKind::Agent,
+            InlineBlockKind::ToolCall | InlineBlockKind::ToolResult | InlineBlockKind::Diff => {
+                InlineMessageKind::Tool
+            }
+            InlineBlockKind::Reasoning => InlineMessageKind::Policy,
+        }
+    }
+
+    /// The focused block, or the latest one when nothing is focused.
+    fn target_block(&self) -> Option<usize> {
+        self.focused_block
+            .or_else(|| self.blocks.len().checked_sub(1))
+            .filter(|&index| self.block_end(index) > self.blocks[index].start)
+    }
+
+    /// Focus a block and scroll so that its first line sits at the top of the transcript.
+    fn focus_block(&mut self, index: usize) {
+        self.focused_block = Some(index);
+        let start = self.blocks[index].start;
+        let row = if self.transcript_width == 0 {
+            start
+        } else {
+            let width = self.transcript_width;
+            self.cached_transcript_lines(width);
+            self.transcript_cache
+                .as_ref()
+                .and_then(|cache| cache.line_rows.get(start).copied())
+                .unwrap_or(start)
+        };
+        let max_offset = self.current_max_scroll_offset();
+        self.scroll_offset = max_offset.saturating_sub(row);
+    }
+
+    fn refresh_block_layout(&mut self) {
+        self.invalidate_scroll_metrics();
+        match self.focused_block {
+            Some(index) => self.focus_block(index),
+            None => self.enforce_scroll_bounds(),
+        }
+    }
+
+    fn block_text(&self, index: usize) -> String {
+        self.lines[self.blocks[index].start..self.block_end(index)]
+            .iter()
+            .map(|line| {
+                line.segments
+                    .iter()
+                    .map(|segment| segment.text.as_str())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Replace a truncated block with the full output saved on disk.
+    fn show_full_output(&mut self, index: usize) {
+        let Some(path) = self.blocks[index].full_output.clone() else {
+            return;
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(error) => {
+                let message = format!(
+                    "Failed to read full output from {}: {}",
+                    path.display(),
+                    error
+                );
+                self.push_line(
+                    InlineMessageKind::Error,
+                    vec![InlineSegment {
+                        text: message,
+                        style: InlineTextStyle::default(),
+                    }],
+                );
+                return;
+            }
+        };
+
+        let start = self.blocks[index].start;
+        let end = self.block_end(index);
+        let mut replacement = Vec::new();
+        replacement.push(MessageLine {
+            kind: InlineMessageKind::Tool,
+            segments: vec![InlineSegment {
+                text: format!("[full output] {}", path.display()),
+                style: InlineTextStyle::default(),
+            }],
+            revision: self.next_revision(),
+        });
+        for text in content.lines() {
+            replacement.push(MessageLine {
+                kind: InlineMessageKind::Pty,
+                segments: vec![InlineSegment {
+                    text: format!("  {text}"),
+                    style: InlineTextStyle::default(),
+                }],
+                revision: self.next_revision(),
+            });
+        }
+
+        let added = replacement.len();
+        self.lines.splice(start..end, replacement);
+        for block in &mut self.blocks[index + 1..] {
+            block.start = block.start - (end - start) + added;
+        }
+        let block = &mut self.blocks[index];
+        block.full_output = None;
+        block.collapsed = false;
+        self.refresh_block_layout();
+    }
+
+    /// Dimmed rows shown after a block's last visible line: how much is folded away
+    /// and which keys expand it or load the full output.
+    fn block_footer_lines(&self, index: usize, width: u16) -> Vec<Line<'static>> {
+        let block = &self.blocks[index];
+        let hidden = self.block_end(index).saturating_sub(block.start + 1);
+        let folded = block.collapsed && hidden > 0;
+        if !folded && block.full_output.is_none() {
+            return Vec::new();
+        }
+
+        let lead = if folded {
+            format!("  {} {} more lines", ui::INLINE_BLOCK_FOLD_MARKER, hidden)
+        } else {
+            format!("  {}", ui::INLINE_BLOCK_FOLD_MARKER)
+        };
+        let mut hints = Vec::new();
+        if folded {
+            hints.push((KeyAction::ToggleBlock, ui::INLINE_BLOCK_EXPAND_HINT));
+        }
+        if block.full_output.is_some() {
+            hints.push((KeyAction::ShowFullOutput, ui::INLINE_BLOCK_FULL_OUTPUT_HINT));
+        }
+        let style = self.default_style().add_modifier(Modifier::DIM);
+        let line = Line::from(Span::styled(self.key_hints(&lead, &hints), style));
+        if width == 0 {
+            vec![line]
+        } else {
+            self.wrap_line(line, usize::from(width))
+        }
+    }
+
     fn scroll_line_up(&mut self) {
         let max_offset = self.current_max_scroll_offset();
         if max_offset == 0 {
@@ -2701,12 +3787,38 @@ impl Session {
             }
         }
 
+        let mut hidden = vec![false; self.lines.len()];
+        let mut dimmed = vec![false; self.lines.len()];
+        let mut footers: Vec<(usize, Vec<Line<'static>>)> = Vec::new();
+        for index in 0..self.blocks.len() {
+            let start = self.blocks[index].start;
+            let end = self.block_end(index);
+            if end <= start {
+                continue;
+            }
+            if self.blocks[index].kind == InlineBlockKind::Reasoning {
+                dimmed[start..end].fill(true);
+            }
+            let last_visible = if self.blocks[index].collapsed {
+                hidden[start + 1..end].fill(true);
+                start
+            } else {
+                end - 1
+            };
+            let footer = self.block_footer_lines(index, width);
+            if !footer.is_empty() {
+                footers.push((last_visible, footer));
+            }
+        }
+        let mut footers = footers.into_iter().peekable();
+
         let cache = self
             .transcript_cache
             .get_or_insert_with(|| TranscriptReflowCache {
                 width,
                 flattened: Vec::new(),
                 messages: Vec::new(),
+                line_rows: Vec::new(),
             });
 
         cache.width = width;
@@ -2721,14 +3833,34 @@ impl Session {
         }
 
         cache.flattened.clear();
+        cache.line_rows.clear();
         for (index, line) in self.lines.iter().enumerate() {
             if let Some(new_lines) = updates[index].take() {
                 let message_cache = &mut cache.messages[index];
                 message_cache.revision = line.revision;
                 message_cache.lines = new_lines;
             }
+            cache.line_rows.push(cache.flattened.len());
+            if hidden[index] {
+                continue;
+            }
             let message_cache = &cache.messages[index];
-            cache.flattened.extend(message_cache.lines.iter().cloned());
+            if dimmed[index] {
+                let dim = Style::default().add_modifier(Modifier::DIM);
+                cache.flattened.extend(
+                    message_cache
+                        .lines
+                        .iter()
+                        .map(|line| line.clone().patch_style(dim)),
+                );
+            } else {
+                cache.flattened.extend(message_cache.lines.iter().cloned());
+            }
+            if footers.peek().is_some_and(|(after, _)| *after == index)
+                && let Some((_, footer)) = footers.next()
+            {
+                cache.flattened.extend(footer);
+            }
         }
 
         if cache.flattened.is_empty() {
@@ -2975,7 +4107,7 @@ mod tests {
         let width = session.transcript_width;
         let viewport = session.viewport_height();
         let offset = usize::from(session.transcript_scroll.offset().y);
-        let lines = session.reflow_transcript_lines(width);
+        let lines = session.cached_transcript_lines(width).to_vec();
 
         let start = offset.min(lines.len());
         let mut collected: Vec<String> = lines
@@ -3419,7 +4551,7 @@ mod tests {
     #[test]
     fn timeline_visible_selects_latest_item() {
         let mut session = Session::new(InlineTheme::default(), None, VIEW_ROWS, true);
-        session.push_line(InlineMessageKind::Agent, vec![make_segment("First")]);
+        session.push_line(InlineMessageKind::User, vec![make_segment("First")]);
         session.push_line(InlineMessageKind::Agent, vec![make_segment("Second")]);
 
         let backend = TestBackend::new(VIEW_WIDTH, VIEW_ROWS);
@@ -3494,4 +4626,391 @@ mod tests {
         assert!(body_span.style.add_modifier.contains(Modifier::ITALIC));
         assert_eq!(body_span.content.clone().into_owned(), "result line");
     }
+
+    fn session_with_history(entries: &[&str]) -> Session {
+        let mut session = Session::new(InlineTheme::default(), None, VIEW_ROWS, true);
+        for entry in entries {
+            session.handle_command(InlineCommand::RecordHistory(entry.to_string()));
+        }
+        session
+    }
+
+    fn press(session: &mut Session, code: KeyCode, modifiers: KeyModifiers) -> Option<InlineEvent> {
+        session.process_key(KeyEvent::new(code, modifiers))
+    }
+
+    fn type_text(session: &mut Session, text: &str) {
+        for ch in text.chars() {
+            press(session, KeyCode::Char(ch), KeyModifiers::NONE);
+        }
+    }
+
+    #[test]
+    fn shift_enter_inserts_newline_and_enter_submits_all_lines() {
+        let mut session = session_with_input("first", 5);
+        assert!(press(&mut session, KeyCode::Enter, KeyModifiers::SHIFT).is_none());
+        type_text(&mut session, "second");
+        assert_eq!(session.input, "first\nsecond");
+
+        let layout = session.input_layout(VIEW_WIDTH);
+        assert_eq!(layout.rows.len(), 2);
+        assert_eq!(layout.rows[1].prefix, " ".repeat(USER_PREFIX.width()));
+        assert_eq!(
+            session.desired_input_height(VIEW_WIDTH),
+            ui::INLINE_INPUT_HEIGHT + 1
+        );
+
+        match press(&mut session, KeyCode::Enter, KeyModifiers::NONE) {
+            Some(InlineEvent::Submit(text)) => assert_eq!(text, "first\nsecond"),
+            _ => panic!("enter should submit the whole input"),
+        }
+    }
+
+    #[test]
+    fn up_and_down_walk_history_from_the_input_edges() {
+        let mut session = session_with_history(&["older", "two\nlines"]);
+        type_text(&mut session, "draft");
+
+        press(&mut session, KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(session.input, "two\nlines");
+        assert_eq!(session.cursor, "two".len());
+
+        press(&mut session, KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(session.input, "older");
+
+        press(&mut session, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(session.input, "two\nlines");
+        assert_eq!(session.cursor, session.input.len());
+
+        // Within a multiline entry, Up moves between lines before recalling.
+        press(&mut session, KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(session.cursor, "two".len());
+        press(&mut session, KeyCode::Down, KeyModifiers::NONE);
+        press(&mut session, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(session.input, "draft");
+    }
+
+    #[test]
+    fn ctrl_r_searches_history_backwards() {
+        let mut session = session_with_history(&["cargo test", "git status", "cargo build"]);
+        press(&mut session, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        type_text(&mut session, "cargo");
+        let search = session.history_search.as_ref().expect("search active");
+        assert_eq!(search.matched, Some(2));
+
+        press(&mut session, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        press(&mut session, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        let search = session.history_search.as_ref().expect("search active");
+        assert_eq!(search.matched, Some(0));
+        assert!(search.failed);
+
+        assert!(press(&mut session, KeyCode::Enter, KeyModifiers::NONE).is_none());
+        assert!(session.history_search.is_none());
+        assert_eq!(session.input, "cargo test");
+
+        press(&mut session, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        type_text(&mut session, "status");
+        press(&mut session, KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(session.input, "cargo test");
+    }
+
+    #[test]
+    fn busy_input_queues_steers_and_edits_queued_messages() {
+        let mut session = session_with_input("", 0);
+        session.handle_command(InlineCommand::SetBusy(true));
+        assert!(press(&mut session, KeyCode::Enter, KeyModifiers::NONE).is_none());
+
+        type_text(&mut session, "also check the tests dir");
+        match press(&mut session, KeyCode::Enter, KeyModifiers::NONE) {
+            Some(InlineEvent::Queue(text)) => assert_eq!(text, "also check the tests dir"),
+            _ => panic!("enter should queue while busy"),
+        }
+        assert!(session.input.is_empty());
+
+        session.handle_command(InlineCommand::SetQueuedMessages(vec![
+            "also check the tests dir".to_string(),
+        ]));
+        assert_eq!(session.queue_height(), 2);
+        assert!(matches!(
+            press(&mut session, KeyCode::Up, KeyModifiers::ALT),
+            Some(InlineEvent::EditQueued)
+        ));
+
+        type_text(&mut session, "stop, use main");
+        match press(&mut session, KeyCode::Char('s'), KeyModifiers::CONTROL) {
+            Some(InlineEvent::Steer(text)) => assert_eq!(text, "stop, use main"),
+            _ => panic!("ctrl+s should steer while busy"),
+        }
+
+        session.handle_command(InlineCommand::SetBusy(false));
+        type_text(&mut session, "next");
+        assert!(matches!(
+            press(&mut session, KeyCode::Enter, KeyModifiers::NONE),
+            Some(InlineEvent::Submit(_))
+        ));
+    }
+
+    #[test]
+    fn configured_keybindings_replace_default_actions() {
+        let mut config = crate::config::UiConfig::default();
+        config.keybindings.insert(
+            "submit".to_string(),
+            crate::config::KeyBindingSpec::Single("ctrl+enter".to_string()),
+        );
+        config.keybindings.insert(
+            "newline".to_string(),
+            crate::config::KeyBindingSpec::Single("enter".to_string()),
+        );
+        config.keybindings.insert(
+            "open_model_picker".to_string(),
+            crate::config::KeyBindingSpec::Single("ctrl+o".to_string()),
+        );
+        let mut session = Session::new(InlineTheme::default(), None, VIEW_ROWS, true)
+            .with_keymap(Keymap::from_config(&config).unwrap());
+
+        type_text(&mut session, "first");
+        assert!(press(&mut session, KeyCode::Enter, KeyModifiers::NONE).is_none());
+        type_text(&mut session, "second");
+        match press(&mut session, KeyCode::Enter, KeyModifiers::CONTROL) {
+            Some(InlineEvent::Submit(text)) => assert_eq!(text, "first\nsecond"),
+            _ => panic!("ctrl+enter should submit"),
+        }
+
+        match press(&mut session, KeyCode::Char('o'), KeyModifiers::CONTROL) {
+            Some(InlineEvent::Submit(text)) => assert_eq!(text, "/model"),
+            _ => panic!("ctrl+o should open the model picker"),
+        }
+    }
+
+    #[test]
+    fn vi_mode_switches_between_insert_and_normal_editing() {
+        let keymap = Keymap::default().with_vi_mode(true);
+        let mut session =
+            Session::new(InlineTheme::default(), None, VIEW_ROWS, true).with_keymap(keymap);
+
+        type_text(&mut session, "fix the parser");
+        assert!(press(&mut session, KeyCode::Esc, KeyModifiers::NONE).is_none());
+        assert_eq!(session.active_prompt_prefix(), VI_NORMAL_PREFIX);
+
+        // Typing in normal mode runs commands instead of inserting text.
+        type_text(&mut session, "0wdw");
+        assert_eq!(session.input, "fix parser");
+        type_text(&mut session, "x");
+        assert_eq!(session.input, "fix arser");
+        type_text(&mut session, "ip");
+        assert_eq!(session.input, "fix parser");
+
+        assert!(press(&mut session, KeyCode::Esc, KeyModifiers::NONE).is_none());
+        type_text(&mut session, "A now");
+        assert_eq!(session.input, "fix parser now");
+
+        assert!(press(&mut session, KeyCode::Esc, KeyModifiers::NONE).is_none());
+        type_text(&mut session, "cc");
+        assert!(session.input.is_empty());
+        assert_eq!(session.vi, Some(ViMode::Insert));
+
+        // Esc in normal mode still cancels.
+        assert!(press(&mut session, KeyCode::Esc, KeyModifiers::NONE).is_none());
+        assert!(matches!(
+            press(&mut session, KeyCode::Esc, KeyModifiers::NONE),
+            Some(InlineEvent::Cancel)
+        ));
+    }
+
+    fn append(session: &mut Session, kind: InlineMessageKind, text: &str) {
+        session.handle_command(InlineCommand::AppendLine {
+            kind,
+            segments: vec![make_segment(text)],
+        });
+    }
+
+    fn transcript_text(session: &mut Session) -> String {
+        session
+            .cached_transcript_lines(VIEW_WIDTH)
+            .iter()
+            .map(|line| line_text(line).trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn begin(session: &mut Session, kind: InlineBlockKind, full_output: Option<PathBuf>) {
+        session.handle_command(InlineCommand::BeginBlock {
+            kind,
+            title: None,
+            full_output,
+        });
+    }
+
+    #[test]
+    fn reasoning_folds_once_the_response_starts() {
+        let mut session = Session::new(InlineTheme::default(), None, VIEW_ROWS, true);
+        append(
+            &mut session,
+            InlineMessageKind::User,
+            "why is the build slow?",
+        );
+        begin(&mut session, InlineBlockKind::Reasoning, None);
+        append(
+            &mut session,
+            InlineMessageKind::Policy,
+            "Looking at the profile",
+        );
+        append(&mut session, InlineMessageKind::Policy, "Linking dominates");
+        assert!(!session.blocks[1].collapsed);
+
+        append(
+            &mut session,
+            InlineMessageKind::Agent,
+            "Linking takes most of it.",
+        );
+        assert_eq!(session.blocks[1].kind, InlineBlockKind::Reasoning);
+        assert!(session.blocks[1].collapsed);
+
+        let view = transcript_text(&mut session);
+        assert!(view.contains("Looking at the profile"));
+        assert!(view.contains("1 more lines"));
+        assert!(!view.contains("Linking dominates"));
+
+        let lines = session.cached_transcript_lines(VIEW_WIDTH).to_vec();
+        let thinking = lines
+            .iter()
+            .find(|line| line_text(line).contains("Looking at the profile"))
+            .unwrap();
+        assert!(thinking.style.add_modifier.contains(Modifier::DIM));
+    }
+
+    #[test]
+    fn long_tool_output_folds_into_a_navigable_block() {
+        let mut session = Session::new(InlineTheme::default(), None, VIEW_ROWS, true);
+        append(&mut session, InlineMessageKind::User, "run the tests");
+        begin(&mut session, InlineBlockKind::ToolCall, None);
+        append(&mut session, InlineMessageKind::Info, "→ cargo test");
+        begin(&mut session, InlineBlockKind::ToolResult, None);
+        append(&mut session, InlineMessageKind::Tool, "[STDOUT]");
+        for index in 0..40 {
+            append(
+                &mut session,
+                InlineMessageKind::Pty,
+                &format!("test case {index} ok"),
+            );
+        }
+        append(&mut session, InlineMessageKind::Agent, "All tests pass.");
+
+        let kinds: Vec<InlineBlockKind> = session.blocks.iter().map(|block| block.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                InlineBlockKind::User,
+                InlineBlockKind::ToolCall,
+                InlineBlockKind::ToolResult,
+                InlineBlockKind::Agent,
+            ]
+        );
+        assert!(session.blocks[2].collapsed);
+
+        let view = transcript_text(&mut session);
+        assert!(view.contains("[STDOUT]"));
+        assert!(view.contains("40 more lines"));
+        assert!(!view.contains("test case 39 ok"));
+        assert!(view.contains("All tests pass."));
+
+        visible_transcript(&mut session);
+        press(&mut session, KeyCode::Char('p'), KeyModifiers::ALT);
+        press(&mut session, KeyCode::Char('p'), KeyModifiers::ALT);
+        assert_eq!(session.focused_block, Some(2));
+        press(&mut session, KeyCode::Char('o'), KeyModifiers::ALT);
+        assert!(!session.blocks[2].collapsed);
+
+        let view = visible_transcript(&mut session);
+        assert_eq!(view[0], "[STDOUT]");
+        assert!(view.iter().any(|line| line == "test case 0 ok"));
+
+        press(&mut session, KeyCode::Char('y'), KeyModifiers::ALT);
+        let copied = session.take_clipboard().expect("block should be copied");
+        assert!(copied.starts_with("[STDOUT]\ntest case 0 ok\n"));
+        assert!(copied.ends_with("test case 39 ok"));
+    }
+
+    #[test]
+    fn focused_prompt_can_be_sent_back_for_editing() {
+        let mut session = Session::new(InlineTheme::default(), None, VIEW_ROWS, true);
+        append(&mut session, InlineMessageKind::User, "rename foo");
+        append(
+            &mut session,
+            InlineMessageKind::Agent,
+            "Renamed foo to bar.",
+        );
+        visible_transcript(&mut session);
+
+        press(&mut session, KeyCode::Char('p'), KeyModifiers::ALT);
+        assert!(press(&mut session, KeyCode::Char('r'), KeyModifiers::ALT).is_none());
+
+        press(&mut session, KeyCode::Char('p'), KeyModifiers::ALT);
+        match press(&mut session, KeyCode::Char('r'), KeyModifiers::ALT) {
+            Some(InlineEvent::EditMessage(text)) => assert_eq!(text, "rename foo"),
+            _ => panic!("alt+r should edit the focused prompt"),
+        }
+        assert_eq!(session.focused_block, None);
+    }
+
+    #[test]
+    fn truncated_block_loads_full_output_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stdout.log");
+        fs::write(&path, "first\nsecond\nthird\n").unwrap();
+
+        let mut session = Session::new(InlineTheme::default(), None, VIEW_ROWS, true);
+        begin(
+            &mut session,
+            InlineBlockKind::ToolResult,
+            Some(path.clone()),
+        );
+        append(
+            &mut session,
+            InlineMessageKind::Info,
+            "... showing last 1/3 lines",
+        );
+        append(&mut session, InlineMessageKind::Pty, "third");
+        append(&mut session, InlineMessageKind::Agent, "Done.");
+
+        assert!(transcript_text(&mut session).contains("for full output"));
+
+        press(&mut session, KeyCode::Char('p'), KeyModifiers::ALT);
+        press(&mut session, KeyCode::Char('p'), KeyModifiers::ALT);
+        press(&mut session, KeyCode::Char('e'), KeyModifiers::ALT);
+
+        let block_lines: Vec<String> = session.lines[..session.block_end(0)]
+            .iter()
+            .map(|line| line.segments.iter().map(|seg| seg.text.as_str()).collect())
+            .collect();
+        assert_eq!(
+            block_lines,
+            vec![
+                format!("[full output] {}", path.display()),
+                "  first".to_string(),
+                "  second".to_string(),
+                "  third".to_string(),
+            ]
+        );
+        assert!(session.blocks[0].full_output.is_none());
+        assert_eq!(session.blocks[1].start, 4);
+        assert!(
+            !visible_transcript(&mut session)
+                .join("\n")
+                .contains("for full output")
+        );
+    }
+
+    #[test]
+    fn bracketed_paste_inserts_block_without_submitting() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut session = session_with_input("trace: ", 7);
+        session.handle_event(
+            CrosstermEvent::Paste("panicked at src/main.rs\r\n  0: main\r\n".to_string()),
+            &sender,
+        );
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(session.input, "trace: panicked at src/main.rs\n  0: main\n");
+        assert_eq!(session.cursor, session.input.len());
+    }
 }
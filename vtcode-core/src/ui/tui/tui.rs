@@ -1,10 +1,12 @@
-use std::io::{self, IsTerminal};
+use std::io::{self, IsTerminal, Write};
 use std::sync::mpsc;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use crossterm::{
-    event::{self, Event as CrosstermEvent},
+    event::{self, DisableBracketedPaste, EnableBracketedPaste, Event as CrosstermEvent},
     execute,
     terminal::{
         self, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
@@ -20,6 +22,8 @@ use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, error::TryRecvError}
 use crate::config::{constants::ui, types::UiSurfacePreference};
 
 use super::{
+    history::PromptHistory,
+    keymap::Keymap,
     session::Session,
     types::{InlineCommand, InlineEvent, InlineTheme},
 };
@@ -187,6 +191,7 @@ impl TerminalSurface {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run_tui(
     mut commands: UnboundedReceiver<InlineCommand>,
     events: UnboundedSender<InlineEvent>,
@@ -195,9 +200,13 @@ pub async fn run_tui(
     surface_preference: UiSurfacePreference,
     inline_rows: u16,
     show_timeline_pane: bool,
+    history: PromptHistory,
+    keymap: Keymap,
 ) -> Result<()> {
     let surface = TerminalSurface::detect(surface_preference, inline_rows)?;
-    let mut session = Session::new(theme, placeholder, surface.rows(), show_timeline_pane);
+    let mut session = Session::new(theme, placeholder, surface.rows(), show_timeline_pane)
+        .with_history(history)
+        .with_keymap(keymap);
     let mut inputs = InputListener::spawn();
 
     let mut stdout = io::stdout();
@@ -205,6 +214,10 @@ pub async fn run_tui(
     if surface.use_alternate() {
         execute!(stdout, EnterAlternateScreen).context(ALTERNATE_SCREEN_ERROR)?;
     }
+    // Pastes arrive as one event instead of a burst of key presses that would submit early.
+    if let Err(error) = execute!(stdout, EnableBracketedPaste) {
+        tracing::debug!(%error, "failed to enable bracketed paste");
+    }
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend).context("failed to initialize inline terminal")?;
@@ -219,6 +232,9 @@ pub async fn run_tui(
     )
     .await;
     let finalize_result = finalize_terminal(&mut terminal);
+    if let Err(error) = execute!(terminal.backend_mut(), DisableBracketedPaste) {
+        tracing::debug!(%error, "failed to disable bracketed paste");
+    }
 
     let leave_alternate_result = if surface.use_alternate() {
         Some(execute!(terminal.backend_mut(), LeaveAlternateScreen))
@@ -287,6 +303,9 @@ async fn drive_terminal<B: Backend>(
                 match result {
                     Some(event) => {
                         session.handle_event(event, events);
+                        if let Some(text) = session.take_clipboard() {
+                            copy_to_clipboard(&text);
+                        }
                         if session.take_redraw() {
                             terminal
                                 .draw(|frame| session.render(frame))
@@ -311,6 +330,19 @@ async fn drive_terminal<B: Backend>(
     Ok(())
 }
 
+/// Ask the terminal to put `text` on the system clipboard with an OSC 52 sequence,
+/// which also works over SSH. Terminals without support ignore it.
+fn copy_to_clipboard(text: &str) {
+    let sequence = format!("\x1b]52;c;{}\x07", BASE64.encode(text));
+    let mut stdout = io::stdout();
+    if let Err(error) = stdout
+        .write_all(sequence.as_bytes())
+        .and_then(|_| stdout.flush())
+    {
+        tracing::debug!(%error, "failed to write clipboard escape sequence");
+    }
+}
+
 fn measure_terminal_dimensions() -> Option<(u16, u16)> {
     let (Width(columns), Height(rows)) = terminal_size()?;
     if rows == 0 {
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use anstyle::{Color as AnsiColorEnum, Style as AnsiStyle};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
@@ -14,6 +16,8 @@ pub struct InlineHeaderContext {
     pub tools: String,
     pub languages: String,
     pub mcp: String,
+    /// Session spend summary; empty when cost tracking is off
+    pub cost: String,
 }
 
 impl Default for InlineHeaderContext {
@@ -63,6 +67,7 @@ impl Default for InlineHeaderContext {
             tools,
             languages,
             mcp,
+            cost: String::new(),
         }
     }
 }
@@ -122,6 +127,9 @@ pub enum InlineListSelection {
     Theme(String),
     Session(String),
     SlashCommand(String),
+    McpChoice(String),
+    PlanChoice(String),
+    HunkChoice(String),
 }
 
 #[derive(Clone, Debug)]
@@ -133,6 +141,23 @@ pub struct InlineListItem {
     pub selection: Option<InlineListSelection>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InlineMentionKind {
+    File,
+    Symbol,
+}
+
+/// A workspace file or symbol offered by `@` completion.
+#[derive(Clone, Debug)]
+pub struct InlineMentionCandidate {
+    pub kind: InlineMentionKind,
+    /// Text matched against the query, such as a relative path or symbol name.
+    pub label: String,
+    /// Text inserted after `@` when the candidate is chosen.
+    pub insert: String,
+    pub detail: Option<String>,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum InlineMessageKind {
     Agent,
@@ -144,6 +169,25 @@ pub enum InlineMessageKind {
     User,
 }
 
+/// Transcript blocks that lines are grouped into for folding and navigation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InlineBlockKind {
+    User,
+    Agent,
+    ToolCall,
+    ToolResult,
+    Diff,
+    /// Reasoning streamed ahead of a response, dimmed and folded once it ends.
+    Reasoning,
+}
+
+impl InlineBlockKind {
+    /// Whether the block belongs to a tool invocation, so tool output lines continue it.
+    pub fn is_tool(self) -> bool {
+        matches!(self, Self::ToolCall | Self::ToolResult | Self::Diff)
+    }
+}
+
 pub enum InlineCommand {
     AppendLine {
         kind: InlineMessageKind,
@@ -158,6 +202,13 @@ pub enum InlineCommand {
         kind: InlineMessageKind,
         lines: Vec<Vec<InlineSegment>>,
     },
+    /// Start a new transcript block with the next appended line.
+    BeginBlock {
+        kind: InlineBlockKind,
+        title: Option<String>,
+        /// File holding the untruncated output when the block shows only part of it.
+        full_output: Option<PathBuf>,
+    },
     SetPrompt {
         prefix: String,
         style: InlineTextStyle,
@@ -173,13 +224,23 @@ pub enum InlineCommand {
     SetHeaderContext {
         context: InlineHeaderContext,
     },
+    SetHeaderCost {
+        cost: String,
+    },
+    SetHeaderMode {
+        mode: String,
+    },
     SetTheme {
         theme: InlineTheme,
     },
     SetCursorVisible(bool),
     SetInputEnabled(bool),
+    SetBusy(bool),
+    SetQueuedMessages(Vec<String>),
     SetInput(String),
     ClearInput,
+    RecordHistory(String),
+    SetMentionCandidates(Vec<InlineMentionCandidate>),
     ForceRedraw,
     ShowModal {
         title: String,
@@ -198,11 +259,20 @@ pub enum InlineCommand {
 #[derive(Debug, Clone)]
 pub enum InlineEvent {
     Submit(String),
+    /// Input sent with Enter while the agent is working.
+    Queue(String),
+    /// Input sent with the steer key while the agent is working.
+    Steer(String),
+    /// Move the most recently queued message back into the input.
+    EditQueued,
+    /// Edit an earlier prompt, given by its transcript text, and regenerate from it.
+    EditMessage(String),
     ListModalSubmit(InlineListSelection),
     ListModalCancel,
     Cancel,
     Exit,
     Interrupt,
+    TogglePlanMode,
     ScrollLineUp,
     ScrollLineDown,
     ScrollPageUp,
@@ -215,6 +285,11 @@ pub struct InlineHandle {
 }
 
 impl InlineHandle {
+    /// Wrap a command channel, for driving the UI from something other than `spawn_session`.
+    pub fn new(sender: UnboundedSender<InlineCommand>) -> Self {
+        Self { sender }
+    }
+
     pub fn append_line(&self, kind: InlineMessageKind, segments: Vec<InlineSegment>) {
         let segments = if segments.is_empty() {
             vec![InlineSegment::default()]
@@ -241,6 +316,19 @@ impl InlineHandle {
             .send(InlineCommand::ReplaceLast { count, kind, lines });
     }
 
+    pub fn begin_block(
+        &self,
+        kind: InlineBlockKind,
+        title: Option<String>,
+        full_output: Option<PathBuf>,
+    ) {
+        let _ = self.sender.send(InlineCommand::BeginBlock {
+            kind,
+            title,
+            full_output,
+        });
+    }
+
     pub fn set_prompt(&self, prefix: String, style: InlineTextStyle) {
         let _ = self.sender.send(InlineCommand::SetPrompt { prefix, style });
     }
@@ -267,6 +355,14 @@ impl InlineHandle {
             .send(InlineCommand::SetHeaderContext { context });
     }
 
+    pub fn set_header_cost(&self, cost: String) {
+        let _ = self.sender.send(InlineCommand::SetHeaderCost { cost });
+    }
+
+    pub fn set_header_mode(&self, mode: String) {
+        let _ = self.sender.send(InlineCommand::SetHeaderMode { mode });
+    }
+
     pub fn set_theme(&self, theme: InlineTheme) {
         let _ = self.sender.send(InlineCommand::SetTheme { theme });
     }
@@ -279,6 +375,15 @@ impl InlineHandle {
         let _ = self.sender.send(InlineCommand::SetInputEnabled(enabled));
     }
 
+    /// Keep the input editable while the agent works, queueing submissions instead.
+    pub fn set_busy(&self, busy: bool) {
+        let _ = self.sender.send(InlineCommand::SetBusy(busy));
+    }
+
+    pub fn set_queued_messages(&self, messages: Vec<String>) {
+        let _ = self.sender.send(InlineCommand::SetQueuedMessages(messages));
+    }
+
     pub fn set_input(&self, content: String) {
         let _ = self.sender.send(InlineCommand::SetInput(content));
     }
@@ -287,6 +392,17 @@ impl InlineHandle {
         let _ = self.sender.send(InlineCommand::ClearInput);
     }
 
+    pub fn set_mention_candidates(&self, candidates: Vec<InlineMentionCandidate>) {
+        let _ = self
+            .sender
+            .send(InlineCommand::SetMentionCandidates(candidates));
+    }
+
+    /// Add a submitted prompt to the persistent input history.
+    pub fn record_history(&self, entry: String) {
+        let _ = self.sender.send(InlineCommand::RecordHistory(entry));
+    }
+
     pub fn force_redraw(&self) {
         let _ = self.sender.send(InlineCommand::ForceRedraw);
     }
@@ -2,8 +2,8 @@ use crate::config::loader::SyntaxHighlightingConfig;
 use crate::ui::markdown::{MarkdownLine, MarkdownSegment, render_markdown_to_lines};
 use crate::ui::theme;
 use crate::ui::tui::{
-    InlineHandle, InlineListItem, InlineListSelection, InlineMessageKind, InlineSegment,
-    InlineTextStyle, convert_style as convert_to_inline_style, theme_from_styles,
+    InlineBlockKind, InlineHandle, InlineListItem, InlineListSelection, InlineMessageKind,
+    InlineSegment, InlineTextStyle, convert_style as convert_to_inline_style, theme_from_styles,
 };
 use crate::utils::transcript;
 use anstream::{AutoStream, ColorChoice};
@@ -11,6 +11,7 @@ use anstyle::{Reset, Style};
 use anstyle_query::{clicolor, clicolor_force, no_color, term_supports_color};
 use anyhow::{Result, anyhow};
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 /// Styles available for rendering messages
 #[derive(Clone, Copy)]
@@ -152,6 +153,34 @@ impl AnsiRenderer {
         }
     }
 
+    /// Group the following lines into a transcript block in the inline UI.
+    /// `full_output` points at the untruncated text when the block shows only part of it.
+    pub fn begin_block(
+        &mut self,
+        kind: InlineBlockKind,
+        title: Option<&str>,
+        full_output: Option<&Path>,
+    ) {
+        if let Some(sink) = &self.sink {
+            sink.begin_block(
+                kind,
+                title.map(str::to_string),
+                full_output.map(Path::to_path_buf),
+            );
+        }
+    }
+
+    /// Remove the last `count` lines from the inline transcript, e.g. a live preview
+    /// that final output replaces. Lines already printed to a plain terminal stay.
+    pub fn retract_lines(&mut self, count: usize) {
+        if count == 0 {
+            return;
+        }
+        if let Some(sink) = &mut self.sink {
+            sink.replace_lines(count, &[], &[], InlineMessageKind::Pty);
+        }
+    }
+
     /// Push text into the buffer
     pub fn push(&mut self, text: &str) {
         self.buffer.push_str(text);
@@ -407,6 +436,15 @@ impl InlineSink {
         self.handle.close_modal();
     }
 
+    fn begin_block(
+        &self,
+        kind: InlineBlockKind,
+        title: Option<String>,
+        full_output: Option<PathBuf>,
+    ) {
+        self.handle.begin_block(kind, title, full_output);
+    }
+
     fn resolve_fallback_style(&self, style: Style) -> InlineTextStyle {
         let mut text_style = convert_to_inline_style(style);
         if text_style.color.is_none() {
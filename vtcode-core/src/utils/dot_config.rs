@@ -190,9 +190,11 @@ impl DotManager {
             "cache/prompts",
             "cache/context",
             "cache/models",
+            "cache/tool-output",
             "logs",
             "sessions",
             "backups",
+            "themes",
         ];
 
         for subdir in &subdirs {
@@ -262,6 +264,11 @@ impl DotManager {
         self.config_dir.join("backups")
     }
 
+    /// Get directory holding user-defined UI themes
+    pub fn themes_dir(&self) -> PathBuf {
+        self.config_dir.join("themes")
+    }
+
     /// Clean up old cache files
     pub fn cleanup_cache(&self) -> Result<CacheCleanupStats, DotError> {
         let config = self.load_config()?;
@@ -285,6 +292,10 @@ impl DotManager {
         // Clean model cache
         stats.models_cleaned = self.cleanup_directory(&self.cache_dir("models"), max_age, now)?;
 
+        // Clean full tool output spilled from truncated transcript blocks
+        stats.tool_output_cleaned =
+            self.cleanup_directory(&self.cache_dir("tool-output"), max_age, now)?;
+
         Ok(stats)
     }
 
@@ -428,6 +439,7 @@ pub struct CacheCleanupStats {
     pub prompts_cleaned: u64,
     pub context_cleaned: u64,
     pub models_cleaned: u64,
+    pub tool_output_cleaned: u64,
 }
 
 #[derive(Debug, Default)]
@@ -96,6 +96,7 @@ pub mod colors;
 pub mod dot_config;
 pub mod safety;
 pub mod session_archive;
+pub mod session_export;
 pub mod transcript;
 pub mod utils;
 pub mod vtcodegitignore;
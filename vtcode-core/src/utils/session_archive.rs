@@ -1,4 +1,4 @@
-use crate::llm::provider::{Message, MessageRole};
+use crate::llm::provider::{Message, MessageRole, ToolCall};
 use crate::utils::dot_config::DotManager;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
@@ -42,12 +42,27 @@ impl SessionArchiveMetadata {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// Token usage and cost of the model response that produced an assistant message.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SessionUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SessionMessage {
     pub role: MessageRole,
     pub content: String,
     #[serde(default)]
     pub tool_call_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<SessionUsage>,
 }
 
 impl SessionMessage {
@@ -56,6 +71,9 @@ impl SessionMessage {
             role,
             content: content.into(),
             tool_call_id: None,
+            tool_calls: None,
+            reasoning: None,
+            usage: None,
         }
     }
 
@@ -68,8 +86,22 @@ impl SessionMessage {
             role,
             content: content.into(),
             tool_call_id,
+            tool_calls: None,
+            reasoning: None,
+            usage: None,
         }
     }
+
+    /// Attach the reasoning trace and usage of the response behind this message.
+    pub fn with_response_details(
+        mut self,
+        reasoning: Option<String>,
+        usage: Option<SessionUsage>,
+    ) -> Self {
+        self.reasoning = reasoning;
+        self.usage = usage;
+        self
+    }
 }
 
 impl From<&Message> for SessionMessage {
@@ -78,11 +110,42 @@ impl From<&Message> for SessionMessage {
             role: message.role.clone(),
             content: message.content.clone(),
             tool_call_id: message.tool_call_id.clone(),
+            tool_calls: message.tool_calls.clone(),
+            reasoning: None,
+            usage: None,
         }
     }
 }
 
+/// A file write made on a conversation branch. `None` means the file did not exist.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SessionFileEdit {
+    /// Identifier shared by the copies of this edit on branches forked after it
+    pub id: u64,
+    /// Prompt turn during which the edit was made
+    pub turn: u64,
+    pub path: PathBuf,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// One line of a conversation whose earlier prompt was edited and regenerated.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionBranch {
+    pub id: usize,
+    #[serde(default)]
+    pub parent: Option<usize>,
+    /// Number of leading messages shared with the parent branch
+    #[serde(default)]
+    pub fork_at: usize,
+    #[serde(default)]
+    pub active: bool,
+    pub messages: Vec<SessionMessage>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub file_edits: Vec<SessionFileEdit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SessionSnapshot {
     pub metadata: SessionArchiveMetadata,
     pub started_at: DateTime<Utc>,
@@ -92,6 +155,10 @@ pub struct SessionSnapshot {
     pub transcript: Vec<String>,
     #[serde(default)]
     pub messages: Vec<SessionMessage>,
+    /// Every branch of the conversation, when an earlier prompt was edited.
+    /// `messages` holds the active one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<SessionBranch>,
 }
 
 #[derive(Debug, Clone)]
@@ -200,6 +267,24 @@ impl SessionArchive {
         total_messages: usize,
         distinct_tools: Vec<String>,
         messages: Vec<SessionMessage>,
+    ) -> Result<PathBuf> {
+        self.finalize_with_branches(
+            transcript,
+            total_messages,
+            distinct_tools,
+            messages,
+            Vec::new(),
+        )
+    }
+
+    /// Like [`Self::finalize`], also storing the conversation's branches.
+    pub fn finalize_with_branches(
+        &self,
+        transcript: Vec<String>,
+        total_messages: usize,
+        distinct_tools: Vec<String>,
+        messages: Vec<SessionMessage>,
+        branches: Vec<SessionBranch>,
     ) -> Result<PathBuf> {
         let snapshot = SessionSnapshot {
             metadata: self.metadata.clone(),
@@ -209,6 +294,7 @@ impl SessionArchive {
             distinct_tools,
             transcript,
             messages,
+            branches,
         };
 
         let payload = serde_json::to_string_pretty(&snapshot)
@@ -271,6 +357,25 @@ pub fn list_recent_sessions(limit: usize) -> Result<Vec<SessionListing>> {
     Ok(listings)
 }
 
+/// Find an archived session by the identifier shown in `/sessions`, or by its file path.
+pub fn find_session(identifier: &str) -> Result<Option<SessionListing>> {
+    let candidate = Path::new(identifier);
+    if is_session_file(candidate) && candidate.is_file() {
+        let data = fs::read_to_string(candidate)
+            .with_context(|| format!("failed to read session file: {}", candidate.display()))?;
+        let snapshot: SessionSnapshot = serde_json::from_str(&data)
+            .with_context(|| format!("failed to parse session file: {}", candidate.display()))?;
+        return Ok(Some(SessionListing {
+            path: candidate.to_path_buf(),
+            snapshot,
+        }));
+    }
+
+    Ok(list_recent_sessions(0)?
+        .into_iter()
+        .find(|listing| listing.identifier() == identifier))
+}
+
 fn resolve_sessions_dir() -> Result<PathBuf> {
     if let Some(custom) = env::var_os(SESSION_DIR_ENV) {
         let path = PathBuf::from(custom);
@@ -401,6 +506,70 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn session_archive_persists_branches() -> Result<()> {
+        let temp_dir = tempfile::tempdir().context("failed to create temp dir")?;
+        let _guard = EnvGuard::set(SESSION_DIR_ENV, temp_dir.path());
+
+        let metadata = SessionArchiveMetadata::new(
+            "ExampleWorkspace",
+            "/tmp/example",
+            "model-x",
+            "provider-y",
+            "dark",
+            "medium",
+        );
+        let archive = SessionArchive::new(metadata)?;
+        let original = vec![
+            SessionMessage::new(MessageRole::User, "Rename foo"),
+            SessionMessage::new(MessageRole::Assistant, "Renamed foo to bar"),
+        ];
+        let edited = vec![
+            SessionMessage::new(MessageRole::User, "Rename foo to baz"),
+            SessionMessage::new(MessageRole::Assistant, "Renamed foo to baz"),
+        ];
+        let branches = vec![
+            SessionBranch {
+                id: 1,
+                parent: None,
+                fork_at: 0,
+                active: false,
+                messages: original,
+                file_edits: vec![SessionFileEdit {
+                    id: 1,
+                    turn: 1,
+                    path: PathBuf::from("/tmp/example/src/lib.rs"),
+                    before: Some("fn foo() {}\n".to_string()),
+                    after: Some("fn bar() {}\n".to_string()),
+                }],
+            },
+            SessionBranch {
+                id: 2,
+                parent: Some(1),
+                fork_at: 0,
+                active: true,
+                messages: edited.clone(),
+                file_edits: Vec::new(),
+            },
+        ];
+        let path = archive.finalize_with_branches(
+            Vec::new(),
+            2,
+            Vec::new(),
+            edited.clone(),
+            branches.clone(),
+        )?;
+
+        let stored = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read stored session: {}", path.display()))?;
+        let snapshot: SessionSnapshot =
+            serde_json::from_str(&stored).context("failed to deserialize stored snapshot")?;
+
+        assert_eq!(snapshot.messages, edited);
+        assert_eq!(snapshot.branches, branches);
+        Ok(())
+    }
+
     #[test]
     fn session_archive_path_collision_adds_suffix() -> Result<()> {
         let temp_dir = tempfile::tempdir().context("failed to create temp dir")?;
@@ -536,6 +705,7 @@ mod tests {
                 SessionMessage::new(MessageRole::User, "  prompt line\nsecond"),
                 SessionMessage::new(MessageRole::Assistant, long_response.clone()),
             ],
+            branches: Vec::new(),
         };
         let listing = SessionListing {
             path: PathBuf::from("session-workspace.json"),
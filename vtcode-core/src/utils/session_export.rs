@@ -0,0 +1,767 @@
+//! Render archived sessions as Markdown, HTML or JSON documents.
+//!
+//! Conversations are grouped into turns, one per user prompt. Each turn lists the
+//! assistant responses with their reasoning, every tool call with its arguments,
+//! diff and output, and the token usage and cost of the turn. Markdown and HTML
+//! collapse tool calls and truncate long outputs; JSON keeps everything.
+
+use crate::config::constants::tools;
+use crate::core::cost_tracker::format_usd;
+use crate::llm::provider::MessageRole;
+use crate::ui::diff_renderer::DiffRenderer;
+use crate::utils::session_archive::{SessionArchiveMetadata, SessionSnapshot, SessionUsage};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use pulldown_cmark::escape::escape_html;
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, html};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const OUTPUT_PREVIEW_LINES: usize = 40;
+const OUTPUT_PREVIEW_CHARS: usize = 4_000;
+const DIFF_CONTEXT_LINES: usize = 3;
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S UTC";
+
+const HTML_STYLE: &str = "body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;max-width:960px;margin:2rem auto;padding:0 1rem;line-height:1.5;color:#1f2328}\
+header dl{display:grid;grid-template-columns:max-content 1fr;gap:.25rem 1rem}\
+header dt{font-weight:600}\
+section.turn{border-top:1px solid #d0d7de;margin-top:2rem}\
+blockquote.prompt{margin:1rem 0;padding:.5rem 1rem;border-left:4px solid #0969da;background:#f6f8fa}\
+details{margin:.5rem 0;border:1px solid #d0d7de;border-radius:6px;padding:.25rem .75rem}\
+summary{cursor:pointer}\
+pre{background:#f6f8fa;padding:.75rem;overflow-x:auto;white-space:pre-wrap}\
+pre.diff .add{color:#1a7f37}\
+pre.diff .del{color:#cf222e}\
+pre.diff .hunk{color:#8250df}\
+p.usage,p.truncated{color:#57606a;font-size:.9em}";
+
+/// Output formats accepted by `/export` and `vtcode sessions export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    #[value(name = "md", alias = "markdown")]
+    Markdown,
+    #[value(alias = "htm")]
+    Html,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        <Self as ValueEnum>::from_str(value, true).ok()
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Token usage summed over the responses of a turn or a whole session.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct ExportUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Only present when every response was priced
+    pub cost_usd: Option<f64>,
+    #[serde(skip)]
+    responses: usize,
+}
+
+impl ExportUsage {
+    fn add(&mut self, usage: &SessionUsage) {
+        self.prompt_tokens += u64::from(usage.prompt_tokens);
+        self.completion_tokens += u64::from(usage.completion_tokens);
+        self.cost_usd = match (self.responses, self.cost_usd, usage.cost_usd) {
+            (0, _, cost) => cost,
+            (_, Some(total), Some(cost)) => Some(total + cost),
+            _ => None,
+        };
+        self.responses += 1;
+    }
+
+    fn merge(&mut self, other: &ExportUsage) {
+        if other.responses == 0 {
+            return;
+        }
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.cost_usd = match (self.responses, self.cost_usd, other.cost_usd) {
+            (0, _, cost) => cost,
+            (_, Some(total), Some(cost)) => Some(total + cost),
+            _ => None,
+        };
+        self.responses += other.responses;
+    }
+
+    fn describe(&self) -> Option<String> {
+        if self.responses == 0 {
+            return None;
+        }
+        let mut label = format!(
+            "{} prompt + {} completion tokens",
+            self.prompt_tokens, self.completion_tokens
+        );
+        if let Some(cost) = self.cost_usd {
+            label.push_str(" · ");
+            label.push_str(&format_usd(cost));
+        }
+        Some(label)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExportStep {
+    Response {
+        text: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reasoning: Option<String>,
+    },
+    ToolCall {
+        id: String,
+        name: String,
+        arguments: Value,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        diff: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportTurn {
+    pub prompt: String,
+    pub steps: Vec<ExportStep>,
+    pub usage: ExportUsage,
+}
+
+/// A session regrouped into turns, ready to render in any [`ExportFormat`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionExport {
+    pub metadata: SessionArchiveMetadata,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub usage: ExportUsage,
+    pub turns: Vec<ExportTurn>,
+}
+
+impl SessionExport {
+    pub fn from_snapshot(snapshot: &SessionSnapshot) -> Self {
+        let outputs: HashMap<&str, &str> = snapshot
+            .messages
+            .iter()
+            .filter(|message| message.role == MessageRole::Tool)
+            .filter_map(|message| {
+                message
+                    .tool_call_id
+                    .as_deref()
+                    .map(|id| (id, message.content.as_str()))
+            })
+            .collect();
+
+        let mut turns: Vec<ExportTurn> = Vec::new();
+        for message in &snapshot.messages {
+            match message.role {
+                MessageRole::User => turns.push(ExportTurn {
+                    prompt: message.content.clone(),
+                    steps: Vec::new(),
+                    usage: ExportUsage::default(),
+                }),
+                MessageRole::Assistant => {
+                    if turns.is_empty() {
+                        turns.push(ExportTurn {
+                            prompt: String::new(),
+                            steps: Vec::new(),
+                            usage: ExportUsage::default(),
+                        });
+                    }
+                    let Some(turn) = turns.last_mut() else {
+                        continue;
+                    };
+                    if let Some(usage) = message.usage.as_ref() {
+                        turn.usage.add(usage);
+                    }
+
+                    let text = message.content.trim();
+                    let response = ExportStep::Response {
+                        text: text.to_string(),
+                        reasoning: message
+                            .reasoning
+                            .as_deref()
+                            .map(str::trim)
+                            .filter(|reasoning| !reasoning.is_empty())
+                            .map(str::to_string),
+                    };
+                    let has_reasoning = matches!(
+                        &response,
+                        ExportStep::Response {
+                            reasoning: Some(_),
+                            ..
+                        }
+                    );
+                    // The runloop can record the same final answer twice in a row
+                    let repeated = turn.steps.last() == Some(&response);
+                    if (!text.is_empty() || has_reasoning) && !repeated {
+                        turn.steps.push(response);
+                    }
+
+                    for call in message.tool_calls.iter().flatten() {
+                        let arguments = serde_json::from_str(&call.function.arguments)
+                            .unwrap_or_else(|_| Value::String(call.function.arguments.clone()));
+                        let output = outputs.get(call.id.as_str()).copied();
+                        turn.steps.push(ExportStep::ToolCall {
+                            id: call.id.clone(),
+                            name: call.function.name.clone(),
+                            diff: tool_diff(&call.function.name, &arguments, output),
+                            arguments,
+                            output: output.map(str::to_string),
+                        });
+                    }
+                }
+                MessageRole::System | MessageRole::Tool => {}
+            }
+        }
+
+        let mut usage = ExportUsage::default();
+        for turn in &turns {
+            usage.merge(&turn.usage);
+        }
+
+        Self {
+            metadata: snapshot.metadata.clone(),
+            started_at: snapshot.started_at,
+            ended_at: snapshot.ended_at,
+            usage,
+            turns,
+        }
+    }
+
+    pub fn render(&self, format: ExportFormat) -> Result<String> {
+        match format {
+            ExportFormat::Markdown => Ok(self.render_markdown()),
+            ExportFormat::Html => Ok(self.render_html()),
+            ExportFormat::Json => {
+                serde_json::to_string_pretty(self).context("failed to serialize session export")
+            }
+        }
+    }
+
+    fn render_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "# VTCode session · {}\n",
+            self.metadata.workspace_label
+        );
+        for (label, value) in self.header_fields() {
+            let _ = writeln!(out, "- **{}:** {}", label, value);
+        }
+
+        for (index, turn) in self.turns.iter().enumerate() {
+            let _ = writeln!(out, "\n## Turn {}\n", index + 1);
+            if !turn.prompt.trim().is_empty() {
+                for line in turn.prompt.trim().lines() {
+                    let _ = writeln!(out, "> {}", line);
+                }
+                out.push('\n');
+            }
+
+            for step in &turn.steps {
+                match step {
+                    ExportStep::Response { text, reasoning } => {
+                        if let Some(reasoning) = reasoning {
+                            out.push_str("<details>\n<summary>Reasoning</summary>\n\n");
+                            push_fenced(&mut out, "text", reasoning);
+                            out.push_str("</details>\n\n");
+                        }
+                        if !text.is_empty() {
+                            out.push_str(text);
+                            out.push_str("\n\n");
+                        }
+                    }
+                    ExportStep::ToolCall {
+                        name,
+                        arguments,
+                        diff,
+                        output,
+                        ..
+                    } => {
+                        let _ = writeln!(
+                            out,
+                            "<details>\n<summary>Tool call: <code>{}</code></summary>\n",
+                            escape(name)
+                        );
+                        out.push_str("**Arguments**\n\n");
+                        push_fenced(&mut out, "json", &pretty_json(arguments));
+                        if let Some(diff) = diff {
+                            out.push_str("**Diff**\n\n");
+                            push_fenced(&mut out, "diff", diff);
+                        }
+                        if let Some(output) = output {
+                            let (preview, omitted) = truncate_output(&display_output(output));
+                            out.push_str("**Output**\n\n");
+                            push_fenced(&mut out, "text", &preview);
+                            if let Some(omitted) = omitted {
+                                let _ = writeln!(out, "_{}_\n", omitted);
+                            }
+                        }
+                        out.push_str("</details>\n\n");
+                    }
+                }
+            }
+
+            if let Some(usage) = turn.usage.describe() {
+                let _ = writeln!(out, "_Usage: {}_", usage);
+            }
+        }
+
+        out
+    }
+
+    fn render_html(&self) -> String {
+        let title = format!("VTCode session · {}", self.metadata.workspace_label);
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<header>\n<h1>{}</h1>\n<dl>\n",
+            escape(&title),
+            HTML_STYLE,
+            escape(&title)
+        );
+        for (label, value) in self.header_fields() {
+            let _ = writeln!(out, "<dt>{}</dt><dd>{}</dd>", label, escape(&value));
+        }
+        out.push_str("</dl>\n</header>\n");
+
+        for (index, turn) in self.turns.iter().enumerate() {
+            let _ = writeln!(out, "<section class=\"turn\">\n<h2>Turn {}</h2>", index + 1);
+            if !turn.prompt.trim().is_empty() {
+                let _ = writeln!(
+                    out,
+                    "<blockquote class=\"prompt\">{}</blockquote>",
+                    markdown_to_html(&turn.prompt)
+                );
+            }
+
+            for step in &turn.steps {
+                match step {
+                    ExportStep::Response { text, reasoning } => {
+                        if let Some(reasoning) = reasoning {
+                            let _ = writeln!(
+                                out,
+                                "<details class=\"reasoning\"><summary>Reasoning</summary><pre>{}</pre></details>",
+                                escape(reasoning)
+                            );
+                        }
+                        if !text.is_empty() {
+                            out.push_str(&markdown_to_html(text));
+                        }
+                    }
+                    ExportStep::ToolCall {
+                        name,
+                        arguments,
+                        diff,
+                        output,
+                        ..
+                    } => {
+                        let _ = writeln!(
+                            out,
+                            "<details class=\"tool\"><summary>Tool call: <code>{}</code></summary>",
+                            escape(name)
+                        );
+                        let _ = writeln!(
+                            out,
+                            "<h4>Arguments</h4><pre><code>{}</code></pre>",
+                            escape(&pretty_json(arguments))
+                        );
+                        if let Some(diff) = diff {
+                            let _ = writeln!(
+                                out,
+                                "<h4>Diff</h4><pre class=\"diff\">{}</pre>",
+                                diff_to_html(diff)
+                            );
+                        }
+                        if let Some(output) = output {
+                            let (preview, omitted) = truncate_output(&display_output(output));
+                            let _ = writeln!(out, "<h4>Output</h4><pre>{}</pre>", escape(&preview));
+                            if let Some(omitted) = omitted {
+                                let _ = writeln!(
+                                    out,
+                                    "<p class=\"truncated\">{}</p>",
+                                    escape(&omitted)
+                                );
+                            }
+                        }
+                        out.push_str("</details>\n");
+                    }
+                }
+            }
+
+            if let Some(usage) = turn.usage.describe() {
+                let _ = writeln!(out, "<p class=\"usage\">Usage: {}</p>", escape(&usage));
+            }
+            out.push_str("</section>\n");
+        }
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    fn header_fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            (
+                "Model",
+                format!("{} ({})", self.metadata.model, self.metadata.provider),
+            ),
+            ("Workspace", self.metadata.workspace_path.clone()),
+            (
+                "Started",
+                self.started_at.format(TIMESTAMP_FORMAT).to_string(),
+            ),
+            ("Ended", self.ended_at.format(TIMESTAMP_FORMAT).to_string()),
+            ("Turns", self.turns.len().to_string()),
+        ];
+        if let Some(usage) = self.usage.describe() {
+            fields.push(("Usage", usage));
+        }
+        fields
+    }
+}
+
+pub fn render_session(snapshot: &SessionSnapshot, format: ExportFormat) -> Result<String> {
+    SessionExport::from_snapshot(snapshot).render(format)
+}
+
+/// Render a session and write it to `path`, creating parent directories as needed.
+pub fn write_session(snapshot: &SessionSnapshot, format: ExportFormat, path: &Path) -> Result<()> {
+    let rendered = render_session(snapshot, format)?;
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create export directory: {}", parent.display()))?;
+    }
+    fs::write(path, rendered)
+        .with_context(|| format!("failed to write session export: {}", path.display()))
+}
+
+pub fn default_file_name(snapshot: &SessionSnapshot, format: ExportFormat) -> String {
+    format!(
+        "vtcode-session-{}.{}",
+        snapshot.started_at.format("%Y%m%dT%H%M%SZ"),
+        format.extension()
+    )
+}
+
+fn tool_diff(name: &str, arguments: &Value, output: Option<&str>) -> Option<String> {
+    match name {
+        tools::EDIT_FILE => {
+            let path = arguments.get("path")?.as_str()?;
+            let old = arguments.get("old_str")?.as_str()?;
+            let new = arguments.get("new_str")?.as_str()?;
+            let renderer = DiffRenderer::new(false, DIFF_CONTEXT_LINES, false);
+            let diff = renderer.generate_diff(old, new, path);
+            Some(renderer.render_diff(&diff))
+        }
+        tools::APPLY_PATCH => arguments
+            .get("input")?
+            .as_str()
+            .map(|patch| patch.trim_end().to_string()),
+        _ => {
+            let output: Value = serde_json::from_str(output?).ok()?;
+            let content = output.get("diff_preview")?.get("content")?.as_str()?;
+            (!content.trim().is_empty()).then(|| content.trim_end().to_string())
+        }
+    }
+}
+
+/// Tool outputs are usually JSON; pretty print them so they read well in a document.
+fn display_output(output: &str) -> String {
+    match serde_json::from_str::<Value>(output) {
+        Ok(value @ (Value::Object(_) | Value::Array(_))) => pretty_json(&value),
+        _ => output.trim_end().to_string(),
+    }
+}
+
+fn pretty_json(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+fn truncate_output(output: &str) -> (String, Option<String>) {
+    let total_lines = output.lines().count();
+    let mut preview = String::new();
+    let mut kept_lines = 0usize;
+    for line in output.lines().take(OUTPUT_PREVIEW_LINES) {
+        if preview.len() + line.len() > OUTPUT_PREVIEW_CHARS {
+            break;
+        }
+        if kept_lines > 0 {
+            preview.push('\n');
+        }
+        preview.push_str(line);
+        kept_lines += 1;
+    }
+    if kept_lines == 0 && total_lines > 0 {
+        preview = output.chars().take(OUTPUT_PREVIEW_CHARS).collect();
+        kept_lines = 1;
+    }
+
+    let omitted = total_lines.saturating_sub(kept_lines);
+    let note = (omitted > 0).then(|| format!("… {} more lines truncated", omitted));
+    (preview, note)
+}
+
+/// Fence `content` with more backticks than it contains in a row.
+fn push_fenced(out: &mut String, language: &str, content: &str) {
+    let mut longest = 0usize;
+    let mut current = 0usize;
+    for ch in content.chars() {
+        if ch == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    let fence = "`".repeat(longest.max(2) + 1);
+    let _ = writeln!(out, "{}{}\n{}\n{}\n", fence, language, content, fence);
+}
+
+/// URL schemes links and images in exported messages may use.
+const ALLOWED_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Render message Markdown, showing any raw HTML it contains as text and dropping
+/// link and image targets that use a scheme outside `ALLOWED_URL_SCHEMES`.
+fn markdown_to_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let parser = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(raw) => Event::Text(raw),
+        Event::Start(tag) => Event::Start(sanitize_tag(tag)),
+        Event::End(tag) => Event::End(sanitize_tag(tag)),
+        other => other,
+    });
+    let mut out = String::new();
+    html::push_html(&mut out, parser);
+    out
+}
+
+fn sanitize_tag(tag: Tag<'_>) -> Tag<'_> {
+    match tag {
+        Tag::Link(kind, url, title) if !is_safe_url(&url) => {
+            Tag::Link(kind, CowStr::Borrowed(""), title)
+        }
+        Tag::Image(kind, url, title) if !is_safe_url(&url) => {
+            Tag::Image(kind, CowStr::Borrowed(""), title)
+        }
+        other => other,
+    }
+}
+
+/// Whether `url` is relative or uses an allowed scheme. Browsers ignore
+/// whitespace and control characters inside a scheme, so those are skipped too.
+fn is_safe_url(url: &str) -> bool {
+    let url: String = url
+        .chars()
+        .filter(|ch| !ch.is_ascii_whitespace() && !ch.is_ascii_control())
+        .collect();
+    let Some(end) = url.find([':', '/', '?', '#']) else {
+        return true;
+    };
+    if !url[end..].starts_with(':') {
+        return true;
+    }
+    let scheme = url[..end].to_ascii_lowercase();
+    ALLOWED_URL_SCHEMES.contains(&scheme.as_str())
+}
+
+fn diff_to_html(diff: &str) -> String {
+    diff.lines()
+        .map(|line| {
+            let class = if line.starts_with("+++") || line.starts_with("---") {
+                None
+            } else if line.starts_with('+') {
+                Some("add")
+            } else if line.starts_with('-') {
+                Some("del")
+            } else if line.starts_with("@@") {
+                Some("hunk")
+            } else {
+                None
+            };
+            match class {
+                Some(class) => format!("<span class=\"{}\">{}</span>", class, escape(line)),
+                None => escape(line),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::new();
+    let _ = escape_html(&mut out, text);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::provider::ToolCall;
+    use crate::utils::session_archive::SessionMessage;
+
+    fn snapshot(messages: Vec<SessionMessage>) -> SessionSnapshot {
+        SessionSnapshot {
+            metadata: SessionArchiveMetadata::new(
+                "demo",
+                "/tmp/demo",
+                "model-x",
+                "provider-y",
+                "dark",
+                "medium",
+            ),
+            started_at: Utc::now(),
+            ended_at: Utc::now(),
+            total_messages: messages.len(),
+            distinct_tools: Vec::new(),
+            transcript: Vec::new(),
+            messages,
+            branches: Vec::new(),
+        }
+    }
+
+    fn edit_session() -> SessionSnapshot {
+        let call = ToolCall::function(
+            "call_1".to_string(),
+            tools::EDIT_FILE.to_string(),
+            r#"{"path":"src/lib.rs","old_str":"fn a() {}\n","new_str":"fn b() {}\n"}"#.to_string(),
+        );
+        let mut with_tools = SessionMessage::new(MessageRole::Assistant, "Renaming it.")
+            .with_response_details(
+                Some("The function name is wrong.".to_string()),
+                Some(SessionUsage {
+                    prompt_tokens: 100,
+                    completion_tokens: 20,
+                    cost_usd: Some(0.01),
+                }),
+            );
+        with_tools.tool_calls = Some(vec![call]);
+        let output = (0..60)
+            .map(|index| format!("line {index}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        snapshot(vec![
+            SessionMessage::new(MessageRole::User, "Rename a to b <script>"),
+            with_tools,
+            SessionMessage::with_tool_call_id(MessageRole::Tool, output, Some("call_1".into())),
+            SessionMessage::new(MessageRole::Assistant, "Done.").with_response_details(
+                None,
+                Some(SessionUsage {
+                    prompt_tokens: 150,
+                    completion_tokens: 5,
+                    cost_usd: Some(0.02),
+                }),
+            ),
+            SessionMessage::new(MessageRole::Assistant, "Done."),
+        ])
+    }
+
+    #[test]
+    fn groups_messages_into_turns_with_tool_details() {
+        let export = SessionExport::from_snapshot(&edit_session());
+        assert_eq!(export.turns.len(), 1);
+        let turn = &export.turns[0];
+        assert_eq!(turn.steps.len(), 3);
+        match &turn.steps[1] {
+            ExportStep::ToolCall {
+                name, diff, output, ..
+            } => {
+                assert_eq!(name, tools::EDIT_FILE);
+                let diff = diff.as_deref().expect("edit diff");
+                assert!(diff.contains("- fn a() {}"));
+                assert!(diff.contains("+ fn b() {}"));
+                assert!(output.as_deref().unwrap().contains("line 59"));
+            }
+            other => panic!("expected tool call, got {other:?}"),
+        }
+        assert_eq!(turn.usage.prompt_tokens, 250);
+        assert_eq!(turn.usage.completion_tokens, 25);
+        assert!((turn.usage.cost_usd.unwrap() - 0.03).abs() < 1e-9);
+        assert_eq!(export.usage.prompt_tokens, 250);
+    }
+
+    #[test]
+    fn markdown_collapses_tool_calls_and_truncates_output() {
+        let markdown = render_session(&edit_session(), ExportFormat::Markdown).unwrap();
+        assert!(markdown.contains("<summary>Tool call: <code>edit_file</code></summary>"));
+        assert!(markdown.contains("<summary>Reasoning</summary>"));
+        assert!(markdown.contains("```diff"));
+        assert!(markdown.contains("… 20 more lines truncated"));
+        assert!(!markdown.contains("line 59"));
+        assert!(markdown.contains("_Usage: 250 prompt + 25 completion tokens · $0.0300"));
+    }
+
+    #[test]
+    fn html_escapes_message_markup() {
+        let html = render_session(&edit_session(), ExportFormat::Html).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("<span class=\"add\">+ fn b() {}</span>"));
+    }
+
+    #[test]
+    fn html_drops_unsafe_link_targets() {
+        let html = markdown_to_html(
+            "[a](javascript:alert(1)) [b](<JaVa\tScript:alert(1)>) ![c](data:image/svg+xml,x) \
+             [d](https://example.com) [e](mailto:me@example.com) [f](docs/guide.md#setup) \
+             <vbscript:msgbox>",
+        );
+        assert!(!html.to_ascii_lowercase().contains("script:alert"));
+        assert!(!html.contains("data:"));
+        assert!(html.contains("<a href=\"\">a</a>"));
+        assert!(html.contains("<a href=\"\">b</a>"));
+        assert!(html.contains("<img src=\"\" alt=\"c\" />"));
+        assert!(html.contains("href=\"https://example.com\""));
+        assert!(html.contains("href=\"mailto:me@example.com\""));
+        assert!(html.contains("href=\"docs/guide.md#setup\""));
+    }
+
+    #[test]
+    fn json_keeps_full_tool_output() {
+        let json = render_session(&edit_session(), ExportFormat::Json).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        let steps = value["turns"][0]["steps"].as_array().unwrap();
+        assert_eq!(steps[1]["kind"], "tool_call");
+        assert!(steps[1]["output"].as_str().unwrap().contains("line 59"));
+        assert_eq!(steps[0]["reasoning"], "The function name is wrong.");
+    }
+
+    #[test]
+    fn parses_format_names() {
+        assert_eq!(ExportFormat::parse("md"), Some(ExportFormat::Markdown));
+        assert_eq!(
+            ExportFormat::parse("Markdown"),
+            Some(ExportFormat::Markdown)
+        );
+        assert_eq!(ExportFormat::parse("html"), Some(ExportFormat::Html));
+        assert_eq!(ExportFormat::parse("json"), Some(ExportFormat::Json));
+        assert_eq!(ExportFormat::parse("pdf"), None);
+    }
+
+    #[test]
+    fn fences_outgrow_backticks_in_content() {
+        let mut out = String::new();
+        push_fenced(&mut out, "text", "```rust\nfn main() {}\n```");
+        assert!(out.starts_with("````text\n"));
+    }
+}
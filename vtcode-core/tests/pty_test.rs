@@ -1,10 +1,11 @@
 use serde_json::json;
-use std::path::PathBuf;
+use tempfile::TempDir;
 use vtcode_core::tools::ToolRegistry;
 
 #[tokio::test]
 async fn test_pty_functionality() {
-    let mut registry = ToolRegistry::new(PathBuf::from("."));
+    let temp_dir = TempDir::new().unwrap();
+    let mut registry = ToolRegistry::new(temp_dir.path().to_path_buf());
 
     // Test a simple echo command
     let result = registry
@@ -27,7 +28,8 @@ async fn test_pty_functionality() {
 
 #[tokio::test]
 async fn test_pty_functionality_with_exit_code() {
-    let mut registry = ToolRegistry::new(PathBuf::from("."));
+    let temp_dir = TempDir::new().unwrap();
+    let mut registry = ToolRegistry::new(temp_dir.path().to_path_buf());
 
     // Test a command that exits with code 1
     let result = registry